@@ -0,0 +1,134 @@
+//! AI auto-play off the UI thread: each move is searched on a background
+//! thread, so the screen keeps redrawing (with a spinner showing the
+//! search's progress) and the search can be cancelled at any time.
+
+use rusty2048_core::{
+    AIAlgorithm, AIGameController, AIPlayer, CancellationToken, Game, GameConfig, GameResult,
+    SearchProgress,
+};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+type Search = JoinHandle<(AIGameController, GameResult<bool>)>;
+
+/// An AI controller whose moves are searched in the background
+pub struct BackgroundAi {
+    algorithm: Option<AIAlgorithm>,
+    name: String,
+    /// The controller, while no search is running
+    controller: Option<AIGameController>,
+    /// The running search, which owns the controller until it finishes
+    search: Option<Search>,
+    cancellation: CancellationToken,
+    progress: Arc<Mutex<Option<SearchProgress>>>,
+}
+
+impl BackgroundAi {
+    /// Play new games of `config` with `player`
+    pub fn new(config: GameConfig, player: AIPlayer) -> GameResult<Self> {
+        let cancellation = CancellationToken::new();
+        let progress = Arc::new(Mutex::new(None));
+        let sink = progress.clone();
+        let player = player
+            .with_cancellation(cancellation.clone())
+            .with_progress(move |report| {
+                if let Ok(mut progress) = sink.lock() {
+                    *progress = Some(*report);
+                }
+            });
+        let controller = AIGameController::with_player(config, player)?;
+
+        Ok(Self {
+            algorithm: controller.algorithm(),
+            name: controller.strategy_name().to_string(),
+            controller: Some(controller),
+            search: None,
+            cancellation,
+            progress,
+        })
+    }
+
+    /// Built-in algorithm being used, `None` for a custom strategy
+    pub fn algorithm(&self) -> Option<AIAlgorithm> {
+        self.algorithm
+    }
+
+    /// Name of the strategy being used
+    pub fn strategy_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether a search is running
+    pub fn is_thinking(&self) -> bool {
+        self.search.is_some()
+    }
+
+    /// Start searching a move for `game`, unless a search is running
+    pub fn start(&mut self, game: &Game) {
+        let Some(mut controller) = self.controller.take() else {
+            return;
+        };
+        *controller.game_mut() = game.clone();
+        self.cancellation.reset();
+        if let Ok(mut progress) = self.progress.lock() {
+            *progress = None;
+        }
+        self.search = Some(std::thread::spawn(move || {
+            let moved = controller.make_ai_move();
+            (controller, moved)
+        }));
+    }
+
+    /// The game after the AI's move, once a search has finished and
+    /// moved. `None` while thinking, or when the AI could not move.
+    pub fn poll(&mut self) -> Option<Option<Game>> {
+        if !self.search.as_ref()?.is_finished() {
+            return None;
+        }
+        let moved = self.join()?;
+        let controller = self.controller.as_ref()?;
+        Some(moved.then(|| controller.game().clone()))
+    }
+
+    /// Stop the running search, if any, and discard its move
+    pub fn cancel(&mut self) {
+        self.cancellation.cancel();
+        self.join();
+    }
+
+    /// Wait for the search to end and take the controller back. Returns
+    /// whether the AI moved.
+    fn join(&mut self) -> Option<bool> {
+        let (controller, moved) = self.search.take()?.join().ok()?;
+        self.controller = Some(controller);
+        Some(moved.unwrap_or(false))
+    }
+
+    /// Spinner and progress of the running search, if any
+    pub fn status(&self) -> Option<String> {
+        self.search.as_ref()?;
+        let frame = SPINNER[(rusty2048_core::get_current_time_ms() / 100) as usize % SPINNER.len()];
+        let progress = self.progress.lock().ok().and_then(|progress| *progress);
+        Some(match progress {
+            Some(progress) => match progress.best {
+                Some(best) => format!(
+                    "{} Thinking: depth {}, {} nodes, best so far {}",
+                    frame, progress.depth, progress.nodes, best
+                ),
+                None => format!(
+                    "{} Thinking: depth {}, {} nodes",
+                    frame, progress.depth, progress.nodes
+                ),
+            },
+            None => format!("{} Thinking", frame),
+        })
+    }
+}
+
+impl Drop for BackgroundAi {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
@@ -0,0 +1,121 @@
+//! `rusty2048 bench`: play seeded games headlessly with an AI and report
+//! how it did, to compare algorithms and heuristics objectively.
+
+use rusty2048_core::{
+    AIAlgorithm, AIBenchmark, AIPlayer, BenchmarkResults, DepthPolicy, GameConfig, HeuristicConfig,
+};
+use std::fmt::Write;
+use std::time::Duration;
+
+pub const USAGE: &str =
+    "Usage: rusty2048 bench [--algorithm greedy|expectimax|mcts|minimax|ntuple] [--games <N>] [--seed <N>] [--size <N>] [--heuristic classic|snake] [--adaptive-depth <MIN>-<MAX>] [--prune <PROBABILITY>] [--budget-ms <MS>]";
+
+/// Run `bench` with the arguments after it; returns the report to print
+pub fn run(args: &[String]) -> Result<String, String> {
+    let mut algorithm = AIAlgorithm::Expectimax;
+    let mut games = 10;
+    let mut seed = 0;
+    let mut config = GameConfig::default();
+    let mut heuristic = HeuristicConfig::classic();
+    let mut depth = DepthPolicy::Fixed;
+    let mut prune = None;
+    let mut budget = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--algorithm" | "-a" => algorithm = parse_algorithm(value()?)?,
+            "--games" | "-n" => games = parse(arg, value()?)?,
+            "--seed" => seed = parse(arg, value()?)?,
+            "--size" => config.board_size = parse(arg, value()?)?,
+            "--heuristic" => heuristic = parse_heuristic(value()?)?,
+            "--adaptive-depth" => {
+                let value = value()?;
+                let (min_depth, max_depth) = value
+                    .split_once('-')
+                    .ok_or_else(|| format!("Invalid {}: {}", arg, value))?;
+                depth = DepthPolicy::ByEmptyCells {
+                    min_depth: parse(arg, min_depth)?,
+                    max_depth: parse(arg, max_depth)?,
+                };
+            }
+            "--prune" => prune = Some(parse(arg, value()?)?),
+            "--budget-ms" => budget = Some(Duration::from_millis(parse(arg, value()?)?)),
+            _ => return Err(format!("Unknown argument: {}\n{}", arg, USAGE)),
+        }
+    }
+
+    let mut player =
+        AIPlayer::new(algorithm).with_heuristic(HeuristicConfig { depth, ..heuristic });
+    if let Some(threshold) = prune {
+        player = player.with_probability_threshold(threshold);
+    }
+    if let Some(budget) = budget {
+        player = player.with_time_budget(budget);
+    }
+    let results =
+        AIBenchmark::run_player(&config, &mut player, games, seed).map_err(|e| e.to_string())?;
+    Ok(report(&results))
+}
+
+/// Parse an AI algorithm name
+pub fn parse_algorithm(name: &str) -> Result<AIAlgorithm, String> {
+    match name {
+        "greedy" => Ok(AIAlgorithm::Greedy),
+        "expectimax" => Ok(AIAlgorithm::Expectimax),
+        "mcts" => Ok(AIAlgorithm::MCTS),
+        "minimax" => Ok(AIAlgorithm::Minimax),
+        "ntuple" => Ok(AIAlgorithm::NTuple),
+        _ => Err(format!("Unknown algorithm: {}", name)),
+    }
+}
+
+/// Parse a heuristic name
+pub fn parse_heuristic(name: &str) -> Result<HeuristicConfig, String> {
+    match name {
+        "classic" => Ok(HeuristicConfig::classic()),
+        "snake" => Ok(HeuristicConfig::snake()),
+        _ => Err(format!("Unknown heuristic: {}", name)),
+    }
+}
+
+fn parse<T: std::str::FromStr>(arg: &str, value: &str) -> Result<T, String>
+where
+    T::Err: std::fmt::Display,
+{
+    value.parse().map_err(|e| format!("Invalid {}: {}", arg, e))
+}
+
+/// Human-readable summary of a benchmark
+fn report(results: &BenchmarkResults) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "🤖 {} over {} games", results.strategy, results.games);
+    let _ = writeln!(
+        out,
+        "Win rate:     {:.1}% ({} won)",
+        results.win_rate * 100.0,
+        results.wins
+    );
+    let _ = writeln!(out, "Mean score:   {:.0}", results.mean_score);
+    let _ = writeln!(out, "Median score: {:.0}", results.median_score);
+    let _ = writeln!(out, "Best score:   {}", results.best_score);
+    let _ = writeln!(
+        out,
+        "Speed:        {:.0} moves/s ({} moves in {:.1}s)",
+        results.moves_per_second,
+        results.total_moves,
+        results.duration_ms as f64 / 1000.0
+    );
+    let _ = writeln!(out, "Max tiles:");
+    for (tile, count) in results.max_tiles.iter().rev() {
+        let _ = writeln!(
+            out,
+            "  {:>6}  {:>4}  {}",
+            tile,
+            count,
+            "█".repeat((count * 40).div_ceil(results.games.max(1)))
+        );
+    }
+    out
+}
@@ -0,0 +1,132 @@
+//! Drawing of the board, sized to the space the terminal leaves for it.
+//!
+//! Cells grow with the terminal up to a comfortable size and shrink down
+//! to one line without borders; the grid is centered in its area. A
+//! terminal too small even for that gets a notice instead.
+
+use crate::language::LanguageManager;
+use crate::theme::{get_tile_color, get_tile_text_color};
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Color, Style},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use rusty2048_shared::{Theme, TranslationKey};
+
+/// Smallest cell: a tile of up to six digits on one line
+const MIN_CELL_WIDTH: u16 = 6;
+const MIN_CELL_HEIGHT: u16 = 1;
+/// Cells stop growing here, keeping the board square-ish on big screens
+const MAX_CELL_WIDTH: u16 = 14;
+const MAX_CELL_HEIGHT: u16 = 7;
+/// Smallest cell that still gets a border around its number
+const BORDERED_CELL_WIDTH: u16 = 8;
+const BORDERED_CELL_HEIGHT: u16 = 3;
+
+/// Width and height of a board of `size` x `size` cells at its smallest
+pub fn min_board_size(size: usize) -> (u16, u16) {
+    let size = size as u16;
+    (size * MIN_CELL_WIDTH, size * MIN_CELL_HEIGHT)
+}
+
+/// Width of a board of `size` x `size` cells at its largest
+pub fn max_board_width(size: usize) -> u16 {
+    size as u16 * MAX_CELL_WIDTH
+}
+
+/// Draw the rows of tile values `cells` centered in `area`, with cells as
+/// large as fit. `area` must hold at least `min_board_size`.
+pub fn render_board(f: &mut Frame, area: Rect, cells: &[Vec<u64>], theme: &Theme) {
+    let size = cells.len() as u16;
+    if size == 0 {
+        return;
+    }
+
+    // Terminal cells are about twice as tall as wide, so keep the cells'
+    // width about twice their height
+    let mut width = (area.width / size).clamp(MIN_CELL_WIDTH, MAX_CELL_WIDTH);
+    let mut height = (area.height / size).clamp(MIN_CELL_HEIGHT, MAX_CELL_HEIGHT);
+    height = height.min(width.div_ceil(2));
+    width = width.min((height * 2 + 2).max(MIN_CELL_WIDTH));
+    let bordered = width >= BORDERED_CELL_WIDTH && height >= BORDERED_CELL_HEIGHT;
+
+    let left = area.x + area.width.saturating_sub(width * size) / 2;
+    let top = area.y + area.height.saturating_sub(height * size) / 2;
+    for (row, values) in cells.iter().enumerate() {
+        for (col, &value) in values.iter().enumerate() {
+            let cell = Rect::new(
+                left + col as u16 * width,
+                top + row as u16 * height,
+                width,
+                height,
+            )
+            .intersection(area);
+            render_cell(f, cell, value, theme, bordered);
+        }
+    }
+}
+
+fn render_cell(f: &mut Frame, cell: Rect, value: u64, theme: &Theme, bordered: bool) {
+    let style = Style::default()
+        .fg(get_tile_text_color(value, theme))
+        .bg(get_tile_color(value, theme));
+    let block = if bordered {
+        Block::default().borders(Borders::ALL)
+    } else {
+        Block::default()
+    };
+    let inner = block.inner(cell);
+    f.render_widget(block.style(style), cell);
+
+    if value == 0 || inner.height == 0 {
+        return;
+    }
+    let label = Rect {
+        y: inner.y + (inner.height - 1) / 2,
+        height: 1,
+        ..inner
+    };
+    let text = tile_label(value, label.width as usize);
+    f.render_widget(
+        Paragraph::new(text)
+            .alignment(Alignment::Center)
+            .style(style),
+        label,
+    );
+}
+
+/// The value of a tile, shortened to `k` or `M` if it is wider than `width`
+fn tile_label(value: u64, width: usize) -> String {
+    let full = value.to_string();
+    if full.len() <= width {
+        full
+    } else if value < 1 << 20 {
+        format!("{}k", value >> 10)
+    } else {
+        format!("{}M", value >> 20)
+    }
+}
+
+/// Draw over the whole frame a notice that the terminal is smaller than
+/// `needed` (width, height)
+pub fn render_too_small(f: &mut Frame, needed: (u16, u16), language: &LanguageManager) {
+    let area = f.size();
+    let text = language.t_with_params(
+        &TranslationKey::TerminalTooSmall,
+        &[
+            ("width", &area.width.to_string()),
+            ("height", &area.height.to_string()),
+            ("needed_width", &needed.0.to_string()),
+            ("needed_height", &needed.1.to_string()),
+        ],
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(text)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .style(Style::default().fg(Color::Yellow)),
+        area,
+    );
+}
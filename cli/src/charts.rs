@@ -5,7 +5,10 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph, Row, Table},
     Frame,
 };
-use rusty2048_core::StatisticsManager;
+use rusty2048_core::{Game, Leaderboard, LeaderboardEntry, StatisticsManager, StatsPeriod};
+use std::path::PathBuf;
+
+use crate::config::PathSettings;
 
 /// Chart display mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -18,6 +21,12 @@ pub enum ChartMode {
     EfficiencyTrend,
     /// Tile achievements chart
     TileAchievements,
+    /// Where tiles ended up on the board
+    PositionHeatmap,
+    /// Games per day and totals of the current period
+    Activity,
+    /// Local high scores
+    Leaderboard,
     /// Recent games table
     RecentGames,
 }
@@ -25,15 +34,21 @@ pub enum ChartMode {
 /// Statistics charts display
 pub struct ChartsDisplay {
     stats_manager: StatisticsManager,
+    leaderboard: Leaderboard,
+    /// Where the leaderboard is saved
+    leaderboard_file: PathBuf,
     current_mode: ChartMode,
 }
 
 impl ChartsDisplay {
     /// Create a new charts display
-    pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
-        let stats_manager = StatisticsManager::new("cli/stats.json")?;
+    pub fn new(paths: &PathSettings) -> Result<Self, Box<dyn std::error::Error>> {
+        let stats_manager = StatisticsManager::new(&paths.stats.to_string_lossy())?;
+        let leaderboard = Leaderboard::load(&paths.leaderboard)?;
         Ok(Self {
             stats_manager,
+            leaderboard,
+            leaderboard_file: paths.leaderboard.clone(),
             current_mode: ChartMode::Summary,
         })
     }
@@ -44,7 +59,10 @@ impl ChartsDisplay {
             ChartMode::Summary => ChartMode::ScoreTrend,
             ChartMode::ScoreTrend => ChartMode::EfficiencyTrend,
             ChartMode::EfficiencyTrend => ChartMode::TileAchievements,
-            ChartMode::TileAchievements => ChartMode::RecentGames,
+            ChartMode::TileAchievements => ChartMode::PositionHeatmap,
+            ChartMode::PositionHeatmap => ChartMode::Activity,
+            ChartMode::Activity => ChartMode::Leaderboard,
+            ChartMode::Leaderboard => ChartMode::RecentGames,
             ChartMode::RecentGames => ChartMode::Summary,
         };
     }
@@ -56,7 +74,10 @@ impl ChartsDisplay {
             ChartMode::ScoreTrend => ChartMode::Summary,
             ChartMode::EfficiencyTrend => ChartMode::ScoreTrend,
             ChartMode::TileAchievements => ChartMode::EfficiencyTrend,
-            ChartMode::RecentGames => ChartMode::TileAchievements,
+            ChartMode::PositionHeatmap => ChartMode::TileAchievements,
+            ChartMode::Activity => ChartMode::PositionHeatmap,
+            ChartMode::Leaderboard => ChartMode::Activity,
+            ChartMode::RecentGames => ChartMode::Leaderboard,
         };
     }
 
@@ -67,6 +88,9 @@ impl ChartsDisplay {
             ChartMode::ScoreTrend => "Score Trend",
             ChartMode::EfficiencyTrend => "Efficiency Trend",
             ChartMode::TileAchievements => "Tile Achievements",
+            ChartMode::PositionHeatmap => "Position Heatmap",
+            ChartMode::Activity => "Activity",
+            ChartMode::Leaderboard => "Leaderboard",
             ChartMode::RecentGames => "Recent Games",
         }
     }
@@ -78,6 +102,9 @@ impl ChartsDisplay {
             ChartMode::ScoreTrend => self.render_score_trend(f, area),
             ChartMode::EfficiencyTrend => self.render_efficiency_trend(f, area),
             ChartMode::TileAchievements => self.render_tile_achievements(f, area),
+            ChartMode::PositionHeatmap => self.render_position_heatmap(f, area),
+            ChartMode::Activity => self.render_activity(f, area),
+            ChartMode::Leaderboard => self.render_leaderboard(f, area),
             ChartMode::RecentGames => self.render_recent_games(f, area),
         }
     }
@@ -167,13 +194,34 @@ impl ChartsDisplay {
                 ),
             ]));
 
-            summary_text.push(Line::from(vec![
+            let mut tile_line = vec![
                 Span::styled("Highest Tile: ", Style::default().fg(Color::White)),
                 Span::styled(
                     summary.highest_tile.to_string(),
                     Style::default().fg(Color::Red),
                 ),
-            ]));
+            ];
+            if let Some(average_move_ms) = summary.average_move_ms {
+                tile_line.push(Span::styled(
+                    " | Avg Move Time: ",
+                    Style::default().fg(Color::White),
+                ));
+                tile_line.push(Span::styled(
+                    format!("{:.1}s", average_move_ms / 1000.0),
+                    Style::default().fg(Color::Magenta),
+                ));
+            }
+            if let Some(average_accuracy) = summary.average_accuracy {
+                tile_line.push(Span::styled(
+                    " | Avg Accuracy: ",
+                    Style::default().fg(Color::White),
+                ));
+                tile_line.push(Span::styled(
+                    format!("{:.1}%", average_accuracy),
+                    Style::default().fg(Color::Magenta),
+                ));
+            }
+            summary_text.push(Line::from(tile_line));
 
             // Score distribution
             summary_text.push(Line::from(vec![Span::styled(
@@ -420,6 +468,144 @@ impl ChartsDisplay {
         f.render_widget(chart_widget, chunks[1]);
     }
 
+    /// Render where the max tile ended up and how often each cell was occupied
+    fn render_position_heatmap(&self, f: &mut Frame, area: Rect) {
+        let heatmaps = self.stats_manager.get_position_heatmap();
+
+        let chunks = Layout::default()
+            .direction(LayoutDirection::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+            .split(area);
+
+        // Title
+        let title = Paragraph::new("🗺️ Position Heatmap")
+            .style(
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .alignment(ratatui::layout::Alignment::Center);
+        f.render_widget(title, chunks[0]);
+
+        if heatmaps.is_empty() {
+            let message = Paragraph::new("No data available")
+                .style(Style::default().fg(Color::Yellow))
+                .alignment(ratatui::layout::Alignment::Center);
+            f.render_widget(message, chunks[1]);
+            return;
+        }
+
+        let mut lines = Vec::new();
+        for heatmap in &heatmaps {
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "{}x{} board, {} games — max tile | occupied",
+                    heatmap.board_size, heatmap.board_size, heatmap.games
+                ),
+                Style::default().add_modifier(Modifier::BOLD),
+            )));
+            for (max_row, occupied_row) in heatmap.max_tile.iter().zip(&heatmap.occupancy) {
+                let mut spans = heat_cells(max_row, heatmap.games);
+                spans.push(Span::raw("   "));
+                spans.extend(heat_cells(occupied_row, heatmap.games));
+                lines.push(Line::from(spans));
+            }
+            lines.push(Line::from(""));
+        }
+
+        let chart_widget = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title("Final Board Positions")
+                    .borders(Borders::ALL),
+            )
+            .style(Style::default().fg(Color::White));
+        f.render_widget(chart_widget, chunks[1]);
+    }
+
+    /// Render this day's, week's and month's totals and the games per day
+    fn render_activity(&self, f: &mut Frame, area: Rect) {
+        let daily_totals = self.stats_manager.get_daily_totals();
+
+        let chunks = Layout::default()
+            .direction(LayoutDirection::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+            .split(area);
+
+        // Title
+        let title = Paragraph::new("📅 Activity")
+            .style(
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .alignment(ratatui::layout::Alignment::Center);
+        f.render_widget(title, chunks[0]);
+
+        if daily_totals.is_empty() {
+            let message = Paragraph::new("No data available")
+                .style(Style::default().fg(Color::Yellow))
+                .alignment(ratatui::layout::Alignment::Center);
+            f.render_widget(message, chunks[1]);
+            return;
+        }
+
+        let now = rusty2048_core::get_current_time();
+        let mut lines = Vec::new();
+        for (label, period) in [
+            ("Today", StatsPeriod::Day),
+            ("This Week", StatsPeriod::Week),
+            ("This Month", StatsPeriod::Month),
+        ] {
+            let (start, end) = period.range_containing(now);
+            let summary = self.stats_manager.get_summary_for_range(start, end);
+            lines.push(Line::from(vec![
+                Span::styled(format!("{:<11}", label), Style::default().fg(Color::White)),
+                Span::styled(
+                    format!("{:>4} games", summary.total_games),
+                    Style::default().fg(Color::Green),
+                ),
+                Span::styled(" | Best Score: ", Style::default().fg(Color::White)),
+                Span::styled(
+                    summary.highest_score.to_string(),
+                    Style::default().fg(Color::Yellow),
+                ),
+            ]));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![Span::styled(
+            "Games per Day:",
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )]));
+
+        let recent_days = &daily_totals[daily_totals.len().saturating_sub(14)..];
+        let max_games = recent_days.iter().map(|day| day.games).max().unwrap_or(1);
+        for day in recent_days {
+            let bar_length = day
+                .games
+                .saturating_mul(20)
+                .checked_div(max_games)
+                .unwrap_or(0);
+            lines.push(Line::from(vec![Span::styled(
+                format!(
+                    "{} | {:>3} | {}",
+                    day.date,
+                    day.games,
+                    "█".repeat(bar_length as usize)
+                ),
+                Style::default().fg(Color::Blue),
+            )]));
+        }
+
+        let chart_widget = Paragraph::new(lines)
+            .block(Block::default().title("Activity").borders(Borders::ALL))
+            .style(Style::default().fg(Color::White));
+        f.render_widget(chart_widget, chunks[1]);
+    }
+
     /// Render recent games table
     fn render_recent_games(&self, f: &mut Frame, area: Rect) {
         let summary = self.stats_manager.get_summary();
@@ -489,12 +675,117 @@ impl ChartsDisplay {
         f.render_widget(table, chunks[1]);
     }
 
+    /// Submit a finished game to the local leaderboard under the player's
+    /// name (`RUSTY2048_PLAYER`, else the login name). Returns its rank if it
+    /// made the list.
+    pub fn submit_score(&mut self, game: &Game, daily: bool) -> Option<usize> {
+        let name = std::env::var("RUSTY2048_PLAYER")
+            .or_else(|_| std::env::var("USER"))
+            .or_else(|_| std::env::var("USERNAME"))
+            .unwrap_or_else(|_| "Player".to_string());
+        let mode = if daily { "daily" } else { "classic" };
+        let rank = self
+            .leaderboard
+            .submit(LeaderboardEntry::for_game(&name, game, mode))?;
+        if let Err(e) = self.leaderboard.save(&self.leaderboard_file) {
+            eprintln!("Failed to save leaderboard: {}", e);
+        }
+        Some(rank)
+    }
+
+    /// Render the local leaderboard, best first within each board size and mode
+    fn render_leaderboard(&self, f: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(LayoutDirection::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+            .split(area);
+
+        // Title
+        let title = Paragraph::new("🏅 Leaderboard")
+            .style(
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .alignment(ratatui::layout::Alignment::Center);
+        f.render_widget(title, chunks[0]);
+
+        let categories = self.leaderboard.categories();
+        if categories.is_empty() {
+            let message = Paragraph::new("No scores yet")
+                .style(Style::default().fg(Color::Yellow))
+                .alignment(ratatui::layout::Alignment::Center);
+            f.render_widget(message, chunks[1]);
+            return;
+        }
+
+        let rows: Vec<Row> = categories
+            .iter()
+            .flat_map(|(board_size, mode)| {
+                self.leaderboard
+                    .top(*board_size, mode)
+                    .into_iter()
+                    .enumerate()
+                    .map(move |(i, entry)| {
+                        Row::new(vec![
+                            format!("#{}", i + 1),
+                            entry.name.clone(),
+                            entry.score.to_string(),
+                            entry.max_tile.to_string(),
+                            format!("{}x{} {}", board_size, board_size, mode),
+                            entry.date(),
+                        ])
+                    })
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            &[
+                Constraint::Length(4),  // Rank
+                Constraint::Length(20), // Name
+                Constraint::Length(8),  // Score
+                Constraint::Length(8),  // Max Tile
+                Constraint::Length(12), // Board
+                Constraint::Length(10), // Date
+            ],
+        )
+        .header(Row::new(vec![
+            "#", "Name", "Score", "Max Tile", "Board", "Date",
+        ]))
+        .block(Block::default().title("Top Scores").borders(Borders::ALL));
+
+        f.render_widget(table, chunks[1]);
+    }
+
     /// Get statistics manager reference
     pub fn stats_manager(&mut self) -> &mut StatisticsManager {
         &mut self.stats_manager
     }
 }
 
+/// One heatmap row: each cell shows its count on a background that gets
+/// hotter the larger the count's share of `games`
+fn heat_cells(counts: &[u32], games: u32) -> Vec<Span<'static>> {
+    counts
+        .iter()
+        .map(|&count| {
+            let color = match count.saturating_mul(100).checked_div(games).unwrap_or(0) {
+                0 => Color::DarkGray,
+                1..=20 => Color::Blue,
+                21..=40 => Color::Cyan,
+                41..=60 => Color::Green,
+                61..=80 => Color::Yellow,
+                _ => Color::Red,
+            };
+            Span::styled(
+                format!("{:^6}", count),
+                Style::default().fg(Color::Black).bg(color),
+            )
+        })
+        .collect()
+}
+
 /// Format duration in seconds to human readable format
 fn format_duration(seconds: u64) -> String {
     let hours = seconds / 3600;
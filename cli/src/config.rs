@@ -0,0 +1,384 @@
+//! The CLI's settings file, `config.toml` in the platform's config
+//! directory (`$XDG_CONFIG_HOME/rusty2048`, `~/.config/rusty2048` by
+//! default on Linux). Every key is optional: options on the command line
+//! override the file, and the file overrides the built-in defaults.
+//!
+//! Data is kept in the platform's data directory (`$XDG_DATA_HOME/rusty2048`,
+//! `~/.local/share/rusty2048` by default on Linux) unless `[paths]` says
+//! otherwise. `[keys]` remaps the keys of the game screen, see
+//! [`crate::keymap`].
+//!
+//! ```toml
+//! board_size = 4
+//! theme = "Dark"
+//! language = "zh"
+//!
+//! [ai]
+//! algorithm = "expectimax"
+//! heuristic = "snake"
+//! time_budget_ms = 100
+//! speed_ms = 800
+//!
+//! [paths]
+//! stats = "/home/me/2048/stats.json"
+//! leaderboard = "/home/me/2048/leaderboard.json"
+//! replays = "/home/me/2048/replays"
+//!
+//! [keys]
+//! move_up = ["Up", "comma"]
+//! undo = ["Ctrl+z"]
+//! ```
+
+use crate::keymap::Keymap;
+use directories::ProjectDirs;
+use rusty2048_core::{
+    AIAlgorithm, HeuristicConfig, Leaderboard, ReplayData, SavedGame, StatisticsManager,
+};
+use rusty2048_shared::{Language, Theme};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Settings of the CLI
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CliConfig {
+    /// Size of new games
+    pub board_size: usize,
+    /// Theme at startup
+    pub theme: String,
+    /// Language code, the system's language if unset
+    pub language: Option<String>,
+    pub ai: AiSettings,
+    pub paths: PathSettings,
+    /// Key bindings of the game screen
+    pub keys: Keymap,
+}
+
+impl Default for CliConfig {
+    fn default() -> Self {
+        Self {
+            board_size: 4,
+            theme: "Classic".to_string(),
+            language: None,
+            ai: AiSettings::default(),
+            paths: PathSettings::default(),
+            keys: Keymap::default(),
+        }
+    }
+}
+
+/// How AI mode plays
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AiSettings {
+    /// Algorithm AI mode starts with
+    pub algorithm: String,
+    /// `classic` or `snake` board evaluation
+    pub heuristic: String,
+    /// Time the AI may think about each move
+    pub time_budget_ms: u64,
+    /// Delay between auto-played moves
+    pub speed_ms: u64,
+}
+
+impl Default for AiSettings {
+    fn default() -> Self {
+        Self {
+            algorithm: "greedy".to_string(),
+            heuristic: "snake".to_string(),
+            time_budget_ms: 100,
+            speed_ms: 800,
+        }
+    }
+}
+
+impl AiSettings {
+    pub fn algorithm(&self) -> Result<AIAlgorithm, String> {
+        crate::bench::parse_algorithm(&self.algorithm)
+    }
+
+    pub fn heuristic(&self) -> Result<HeuristicConfig, String> {
+        crate::bench::parse_heuristic(&self.heuristic)
+    }
+
+    pub fn time_budget(&self) -> Duration {
+        Duration::from_millis(self.time_budget_ms)
+    }
+}
+
+/// Where the CLI keeps its data
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PathSettings {
+    /// Statistics of finished games
+    pub stats: PathBuf,
+    /// Local high scores
+    pub leaderboard: PathBuf,
+    /// Directory of saved replays
+    pub replays: PathBuf,
+}
+
+impl Default for PathSettings {
+    fn default() -> Self {
+        let dir = data_dir();
+        Self {
+            stats: dir.join("stats.json"),
+            leaderboard: dir.join("leaderboard.json"),
+            replays: dir.join("replays"),
+        }
+    }
+}
+
+impl PathSettings {
+    /// Create the directories the data files go in
+    pub fn create_dirs(&self) -> std::io::Result<()> {
+        fs::create_dir_all(data_dir())?;
+        for file in [&self.stats, &self.leaderboard] {
+            if let Some(parent) = file.parent() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn project_dirs() -> Option<ProjectDirs> {
+    ProjectDirs::from("", "", "rusty2048")
+}
+
+/// Where the settings file is looked for by default
+pub fn default_path() -> Option<PathBuf> {
+    project_dirs().map(|dirs| dirs.config_dir().join("config.toml"))
+}
+
+/// Where the CLI keeps its data by default; the working directory when
+/// the platform has no data directory (no home directory is known)
+pub fn data_dir() -> PathBuf {
+    project_dirs()
+        .map(|dirs| dirs.data_dir().to_path_buf())
+        .unwrap_or_default()
+}
+
+/// Move the files older versions kept relative to the working directory
+/// to where they are kept now. A file is only moved if nothing is at its
+/// new place yet, and only if it holds rusty2048 data: running from a
+/// folder that happens to have a `replays` directory leaves it alone.
+/// Returns a note per file moved or failed to move.
+pub fn migrate_legacy_files(paths: &PathSettings) -> Vec<String> {
+    let moves = [
+        (
+            PathBuf::from("cli/stats.json"),
+            paths.stats.clone(),
+            is_stats_file as fn(&Path) -> bool,
+        ),
+        (
+            PathBuf::from("cli/leaderboard.json"),
+            paths.leaderboard.clone(),
+            is_leaderboard_file,
+        ),
+        (
+            PathBuf::from("replays"),
+            paths.replays.clone(),
+            is_replay_dir,
+        ),
+        (
+            PathBuf::from(crate::save::SAVE_FILE),
+            crate::save::save_path(),
+            is_saved_game,
+        ),
+        (
+            PathBuf::from(crate::save::BEST_SCORE_FILE),
+            crate::save::best_score_path(),
+            is_best_score_file,
+        ),
+    ];
+
+    let mut notes = Vec::new();
+    for (old, new, is_ours) in moves {
+        if !old.exists() || new.exists() || old == new || !is_ours(&old) {
+            continue;
+        }
+        let old = std::env::current_dir()
+            .map(|dir| dir.join(&old))
+            .unwrap_or(old);
+        match move_path(&old, &new) {
+            Ok(()) => notes.push(format!("Moved {} to {}", old.display(), new.display())),
+            Err(e) => notes.push(format!(
+                "Failed to move {} to {}, left it in place: {}",
+                old.display(),
+                new.display(),
+                e
+            )),
+        }
+    }
+    notes
+}
+
+fn is_stats_file(path: &Path) -> bool {
+    fs::read_to_string(path).is_ok_and(|content| {
+        StatisticsManager::in_memory()
+            .import_statistics(&content)
+            .is_ok()
+    })
+}
+
+fn is_leaderboard_file(path: &Path) -> bool {
+    path.is_file() && Leaderboard::load(path).is_ok()
+}
+
+/// A directory of nothing but replays and the recording stream
+fn is_replay_dir(path: &Path) -> bool {
+    let Ok(entries) = fs::read_dir(path) else {
+        return false;
+    };
+    let mut any = false;
+    for entry in entries {
+        let Ok(entry) = entry else {
+            return false;
+        };
+        let path = entry.path();
+        let ours = if entry.file_name() == crate::replay::STREAM_FILE {
+            path.is_file()
+        } else {
+            fs::read(&path).is_ok_and(|bytes| ReplayData::decode(&bytes).is_ok())
+        };
+        if !ours {
+            return false;
+        }
+        any = true;
+    }
+    any
+}
+
+fn is_saved_game(path: &Path) -> bool {
+    fs::read_to_string(path).is_ok_and(|json| serde_json::from_str::<SavedGame>(&json).is_ok())
+}
+
+fn is_best_score_file(path: &Path) -> bool {
+    fs::read_to_string(path).is_ok_and(|content| content.trim().parse::<u64>().is_ok())
+}
+
+/// Rename a file or a directory, copying it if it has to cross file
+/// systems. A failed copy is removed again, leaving the original as it was.
+fn move_path(from: &Path, to: &Path) -> std::io::Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    if let Err(e) = copy_path(from, to) {
+        let _ = if to.is_dir() {
+            fs::remove_dir_all(to)
+        } else {
+            fs::remove_file(to)
+        };
+        return Err(e);
+    }
+    if from.is_dir() {
+        fs::remove_dir_all(from)
+    } else {
+        fs::remove_file(from)
+    }
+}
+
+/// Copy a file, or a directory with everything in it
+fn copy_path(from: &Path, to: &Path) -> std::io::Result<()> {
+    if !from.is_dir() {
+        return fs::copy(from, to).map(|_| ());
+    }
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        copy_path(&entry.path(), &to.join(entry.file_name()))?;
+    }
+    Ok(())
+}
+
+impl CliConfig {
+    /// Read the settings file at `path`; the defaults if there is none
+    pub fn load(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let config: Self =
+            toml::from_str(&content).map_err(|e| format!("Invalid {}: {}", path.display(), e))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Apply the options at the start of `args`, before any command.
+    /// Returns the arguments after them, and whether a board size was
+    /// given.
+    pub fn apply_options<'a>(
+        &mut self,
+        args: &'a [String],
+    ) -> Result<(&'a [String], bool), String> {
+        let mut size_given = false;
+        let mut rest = args;
+        while let [option, value, tail @ ..] = rest {
+            match option.as_str() {
+                "--size" => {
+                    self.board_size = value
+                        .parse()
+                        .map_err(|e| format!("Invalid --size: {}", e))?;
+                    size_given = true;
+                }
+                "--theme" => self.theme = value.clone(),
+                "--language" => self.language = Some(value.clone()),
+                "--ai" => self.ai.algorithm = value.clone(),
+                _ => break,
+            }
+            rest = tail;
+        }
+        if let [option] = rest {
+            if ["--size", "--theme", "--language", "--ai"].contains(&option.as_str()) {
+                return Err(format!("{} needs a value", option));
+            }
+        }
+        self.validate()?;
+        Ok((rest, size_given))
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if Theme::by_name(&self.theme).is_none() {
+            return Err(format!("Unknown theme: {}", self.theme));
+        }
+        if let Some(code) = &self.language {
+            Language::from_code(code).ok_or_else(|| format!("Unknown language: {}", code))?;
+        }
+        self.ai.algorithm()?;
+        self.ai.heuristic()?;
+        Ok(())
+    }
+
+    /// The configured language, if any
+    pub fn language(&self) -> Option<Language> {
+        self.language.as_deref().and_then(Language::from_code)
+    }
+}
+
+/// Store `language` in the settings file at `path`, keeping its other keys
+pub fn save_language(path: &Path, language: Language) -> Result<(), String> {
+    let mut table = match fs::read_to_string(path) {
+        Ok(content) => content
+            .parse::<toml::Table>()
+            .map_err(|e| format!("Invalid {}: {}", path.display(), e))?,
+        Err(_) => toml::Table::new(),
+    };
+    table.insert(
+        "language".to_string(),
+        toml::Value::String(language.code().to_string()),
+    );
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::write(path, table.to_string())
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
@@ -0,0 +1,99 @@
+//! `rusty2048 replay export`: render a saved replay to an animated GIF or
+//! an asciinema cast.
+
+use rusty2048_core::ReplayData;
+use rusty2048_shared::export::{self, Frame};
+use rusty2048_shared::Theme;
+use std::fs;
+use std::path::PathBuf;
+
+/// Time each move is shown, in milliseconds
+const DEFAULT_FRAME_MS: u32 = 300;
+
+pub const USAGE: &str =
+    "Usage: rusty2048 replay export <FILE> [--format gif|cast] [--output <FILE>] [--theme <NAME>] [--frame-ms <MS>]";
+
+/// Output format of an export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExportFormat {
+    Gif,
+    /// asciinema cast, version 2
+    Cast,
+}
+
+impl ExportFormat {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "gif" => Ok(Self::Gif),
+            "cast" | "asciicast" => Ok(Self::Cast),
+            _ => Err(format!("Unknown export format: {} (use gif or cast)", name)),
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Gif => "gif",
+            Self::Cast => "cast",
+        }
+    }
+}
+
+/// Run `replay export` with the arguments after `export`; returns the path written
+pub fn run(args: &[String]) -> Result<PathBuf, String> {
+    let mut input = None;
+    let mut format = ExportFormat::Gif;
+    let mut output = None;
+    let mut theme = Theme::default();
+    let mut frame_ms = DEFAULT_FRAME_MS;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--format" | "-f" => format = ExportFormat::parse(value()?)?,
+            "--output" | "-o" => output = Some(PathBuf::from(value()?)),
+            "--theme" => {
+                let name = value()?;
+                theme = Theme::by_name(name).ok_or_else(|| format!("Unknown theme: {}", name))?;
+            }
+            "--frame-ms" => {
+                frame_ms = value()?
+                    .parse::<u32>()
+                    .map_err(|e| format!("Invalid --frame-ms: {}", e))?
+                    .max(20);
+            }
+            _ if input.is_none() && !arg.starts_with('-') => input = Some(PathBuf::from(arg)),
+            _ => return Err(format!("Unknown argument: {}", arg)),
+        }
+    }
+    let input = input.ok_or(USAGE)?;
+    let output = output.unwrap_or_else(|| input.with_extension(format.extension()));
+
+    let bytes =
+        fs::read(&input).map_err(|e| format!("Failed to read {}: {}", input.display(), e))?;
+    let replay = ReplayData::decode(&bytes).map_err(|e| e.to_string())?;
+    let frames = replay_frames(&replay);
+
+    let contents = match format {
+        ExportFormat::Gif => export::encode_gif(&export::render_frames(&frames, &theme), frame_ms)?,
+        ExportFormat::Cast => {
+            export::encode_asciicast(&frames, frame_ms, &theme, &replay.metadata.name)?.into_bytes()
+        }
+    };
+    fs::write(&output, contents)
+        .map_err(|e| format!("Failed to write {}: {}", output.display(), e))?;
+    Ok(output)
+}
+
+/// Every position of a replay, starting with the initial board
+fn replay_frames(replay: &ReplayData) -> Vec<Frame> {
+    let mut frames = vec![Frame {
+        board: replay.initial_board.clone(),
+        caption: "Score 0".to_string(),
+    }];
+    frames.extend(replay.moves.iter().map(|m| Frame {
+        board: m.board_after.clone(),
+        caption: format!("Move {}  Score {}", m.move_number, m.score_after),
+    }));
+    frames
+}
@@ -0,0 +1,151 @@
+//! `rusty2048 --headless`: play without a terminal UI, for bots and test
+//! harnesses. Commands are read from stdin one per line, either a letter
+//! (`u`, `d`, `l`, `r`) or word (`up`, `undo`, `new`, ...) or a JSON object
+//! such as `{"type": "move", "direction": "left"}`. Each command is
+//! answered with one JSON line on stdout: the state of the game, or an
+//! error. Headless games are neither saved nor counted in the statistics.
+
+use rusty2048_core::{Direction, Game, GameConfig, GameState};
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, Write};
+
+pub const USAGE: &str = "Usage: rusty2048 --headless [--size <N>] [--seed <N>]";
+
+/// A command of the JSON form
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Command {
+    Move { direction: String },
+    Undo,
+    NewGame,
+    ContinueAfterWin,
+    State,
+    Quit,
+}
+
+/// A line written to stdout
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Output {
+    State {
+        board: Vec<Vec<u64>>,
+        score: u64,
+        best_score: u64,
+        moves: u32,
+        max_tile: u64,
+        state: GameState,
+        /// Whether the last command changed the board
+        moved: bool,
+    },
+    Error {
+        message: String,
+    },
+}
+
+impl Output {
+    fn state(game: &Game, moved: bool) -> Self {
+        Output::State {
+            board: game.board().to_vec(),
+            score: game.score().current(),
+            best_score: game.score().best(),
+            moves: game.moves(),
+            max_tile: game.board().max_tile(),
+            state: game.state(),
+            moved,
+        }
+    }
+}
+
+/// Run the headless game with the arguments after `--headless`, until
+/// stdin ends or a quit command
+pub fn run(args: &[String]) -> Result<(), String> {
+    let mut config = GameConfig::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--size" => config.board_size = parse(arg, value()?)?,
+            "--seed" => config.seed = Some(parse(arg, value()?)?),
+            _ => return Err(format!("Unknown argument: {}\n{}", arg, USAGE)),
+        }
+    }
+
+    let mut game = Game::new(config).map_err(|e| e.to_string())?;
+    let mut stdout = io::stdout().lock();
+    write_line(&mut stdout, &Output::state(&game, false))?;
+
+    for line in io::stdin().lock().lines() {
+        let line = line.map_err(|e| e.to_string())?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let output = match parse_command(line) {
+            Ok(Command::Quit) => break,
+            Ok(command) => execute(&mut game, command),
+            Err(message) => Output::Error { message },
+        };
+        write_line(&mut stdout, &output)?;
+    }
+    Ok(())
+}
+
+/// Parse a command in either form
+fn parse_command(line: &str) -> Result<Command, String> {
+    if line.starts_with('{') {
+        return serde_json::from_str(line).map_err(|e| format!("Invalid command: {}", e));
+    }
+    match line.to_ascii_lowercase().as_str() {
+        "undo" | "z" => Ok(Command::Undo),
+        "new" | "n" => Ok(Command::NewGame),
+        "continue" | "c" => Ok(Command::ContinueAfterWin),
+        "state" | "s" => Ok(Command::State),
+        "quit" | "q" => Ok(Command::Quit),
+        direction => Ok(Command::Move {
+            direction: direction.to_string(),
+        }),
+    }
+}
+
+fn parse_direction(name: &str) -> Result<Direction, String> {
+    match name.to_ascii_lowercase().as_str() {
+        "u" | "up" => Ok(Direction::Up),
+        "d" | "down" => Ok(Direction::Down),
+        "l" | "left" => Ok(Direction::Left),
+        "r" | "right" => Ok(Direction::Right),
+        _ => Err(format!("Unknown command: {}", name)),
+    }
+}
+
+fn execute(game: &mut Game, command: Command) -> Output {
+    let result = match command {
+        Command::Move { direction } => match parse_direction(&direction) {
+            Ok(direction) => game.make_move(direction).map(|result| result.moved),
+            Err(message) => return Output::Error { message },
+        },
+        Command::Undo => game.undo().map(|()| true),
+        Command::NewGame => game.new_game().map(|()| true),
+        Command::ContinueAfterWin => game.continue_after_win().map(|()| false),
+        Command::State | Command::Quit => Ok(false),
+    };
+    match result {
+        Ok(moved) => Output::state(game, moved),
+        Err(e) => Output::Error {
+            message: e.to_string(),
+        },
+    }
+}
+
+fn write_line(out: &mut impl Write, output: &Output) -> Result<(), String> {
+    let json = serde_json::to_string(output).map_err(|e| e.to_string())?;
+    writeln!(out, "{}", json)
+        .and_then(|()| out.flush())
+        .map_err(|e| e.to_string())
+}
+
+fn parse<T: std::str::FromStr>(arg: &str, value: &str) -> Result<T, String>
+where
+    T::Err: std::fmt::Display,
+{
+    value.parse().map_err(|e| format!("Invalid {}: {}", arg, e))
+}
@@ -0,0 +1,143 @@
+//! Key bindings of the game screen. Each action is bound to one or more
+//! key chords, remappable in the `[keys]` table of the settings file:
+//!
+//! ```toml
+//! [keys]
+//! # Dvorak
+//! move_up = ["Up", "comma"]
+//! move_left = ["Left", "a"]
+//! move_down = ["Down", "o"]
+//! move_right = ["Right", "e"]
+//! toggle_auto_play = ["Ctrl+o"]
+//! ```
+//!
+//! An action listed there gets exactly the keys listed; the others keep
+//! their defaults. A chord is a key name (a character, `Up`, `Enter`,
+//! `Space`, `Esc`, `Tab`, `Backspace`, `F1`...) optionally prefixed with
+//! `Ctrl+` or `Alt+`. Letters match either case.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use rusty2048_shared::{Action, Key, KeyBindings, KeyChord};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+
+/// Default keys of each action
+const DEFAULT_BINDINGS: [(Action, &[&str]); 29] = [
+    (Action::Quit, &["q"]),
+    (Action::Menu, &["Esc"]),
+    (Action::MoveUp, &["Up", "w", "k"]),
+    (Action::MoveDown, &["Down", "s", "j"]),
+    (Action::MoveLeft, &["Left", "a"]),
+    (Action::MoveRight, &["Right", "d"]),
+    (Action::NewGame, &["r"]),
+    (Action::Undo, &["u"]),
+    (Action::ContinueAfterWin, &["Enter"]),
+    (Action::Pause, &["Space"]),
+    (Action::NextTheme, &["t"]),
+    (Action::ThemeClassic, &["1"]),
+    (Action::ThemeDark, &["2"]),
+    (Action::ThemeNeon, &["3"]),
+    (Action::ThemeRetro, &["4"]),
+    (Action::ThemePastel, &["5"]),
+    (Action::ToggleThemeHelp, &["h"]),
+    (Action::NextLanguage, &["l"]),
+    (Action::ReplayMode, &["p"]),
+    (Action::ToggleAssist, &["g"]),
+    (Action::ToggleCharts, &["c"]),
+    (Action::PrevChart, &["x"]),
+    (Action::NextChart, &["z"]),
+    (Action::ToggleAi, &["i"]),
+    (Action::ToggleAutoPlay, &["o"]),
+    (Action::PrevAlgorithm, &["["]),
+    (Action::NextAlgorithm, &["]"]),
+    (Action::SpeedUp, &["+", "="]),
+    (Action::SlowDown, &["-"]),
+];
+
+/// The key bindings in effect
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keymap {
+    bindings: KeyBindings,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            bindings: KeyBindings::from_defaults(&DEFAULT_BINDINGS),
+        }
+    }
+}
+
+impl Keymap {
+    /// The defaults with the actions in `overrides` bound to their keys
+    /// instead. Fails on an unknown key or a key bound to two actions.
+    pub fn with_overrides(overrides: &BTreeMap<Action, Vec<String>>) -> Result<Self, String> {
+        Ok(Self {
+            bindings: Self::default().bindings.with_overrides(overrides)?,
+        })
+    }
+
+    /// The action bound to a key press, if any
+    pub fn action_for(&self, event: &KeyEvent) -> Option<Action> {
+        self.bindings.action_for_chord(&chord_of(event)?)
+    }
+
+    /// The first key of an action, for hints on screen
+    pub fn key_label(&self, action: Action) -> String {
+        let chord = self
+            .bindings
+            .keys_for(action)
+            .first()
+            .and_then(|key| key.parse::<KeyChord>().ok());
+        match chord {
+            Some(KeyChord {
+                key: Key::Char(c),
+                ctrl: false,
+                alt: false,
+            }) if c != ' ' => c.to_uppercase().to_string(),
+            Some(chord) => chord.to_string(),
+            None => "unbound".to_string(),
+        }
+    }
+}
+
+/// The chord of a key press; `None` for keys that cannot be bound
+fn chord_of(event: &KeyEvent) -> Option<KeyChord> {
+    let key = match event.code {
+        KeyCode::Char(c) => Key::Char(c),
+        KeyCode::Up => Key::Up,
+        KeyCode::Down => Key::Down,
+        KeyCode::Left => Key::Left,
+        KeyCode::Right => Key::Right,
+        KeyCode::Enter => Key::Enter,
+        KeyCode::Esc => Key::Esc,
+        KeyCode::Tab => Key::Tab,
+        KeyCode::Backspace => Key::Backspace,
+        KeyCode::Delete => Key::Delete,
+        KeyCode::Home => Key::Home,
+        KeyCode::End => Key::End,
+        KeyCode::PageUp => Key::PageUp,
+        KeyCode::PageDown => Key::PageDown,
+        KeyCode::F(n) => Key::F(n),
+        _ => return None,
+    };
+    Some(KeyChord::new(
+        key,
+        event.modifiers.contains(KeyModifiers::CONTROL),
+        event.modifiers.contains(KeyModifiers::ALT),
+    ))
+}
+
+impl Serialize for Keymap {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.bindings.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Keymap {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let overrides = BTreeMap::<Action, Vec<String>>::deserialize(deserializer)?;
+        Self::with_overrides(&overrides).map_err(D::Error::custom)
+    }
+}
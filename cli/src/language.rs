@@ -1,25 +1,28 @@
+use crate::config;
 use rusty2048_shared::{I18n, Language, TranslationKey};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// Where older versions saved the language, before it moved to the
+/// settings file
+const LEGACY_LANGUAGE_FILE: &str = "language_config.json";
 
 /// Language manager for CLI version
 pub struct LanguageManager {
     i18n: I18n,
-    config_file: String,
+    /// Settings file the language is saved to
+    config_file: Option<PathBuf>,
 }
 
 impl LanguageManager {
-    /// Create a new language manager
-    pub fn new() -> Self {
-        let mut manager = Self {
-            i18n: I18n::new(),
-            config_file: "language_config.json".to_string(),
-        };
-
-        // Load saved language preference
-        manager.load_language_preference();
-
-        manager
+    /// Start in `language`, or the default one, and save changes to the
+    /// settings file at `config_file`
+    pub fn new(language: Option<Language>, config_file: Option<PathBuf>) -> Self {
+        let mut i18n = I18n::new();
+        if let Some(language) = language {
+            i18n.set_language(language);
+        }
+        Self { i18n, config_file }
     }
 
     /// Get current language
@@ -39,7 +42,6 @@ impl LanguageManager {
     }
 
     /// Get translation with parameters
-    #[allow(dead_code)]
     pub fn t_with_params(&self, key: &TranslationKey, params: &[(&str, &str)]) -> String {
         self.i18n.t_with_params(key, params)
     }
@@ -67,7 +69,6 @@ impl LanguageManager {
     }
 
     /// Cycle to previous language
-    #[allow(dead_code)]
     pub fn prev_language(&mut self) {
         let languages = self.supported_languages();
         let current_index = languages
@@ -82,32 +83,10 @@ impl LanguageManager {
         self.set_language(languages[prev_index]);
     }
 
-    /// Load language preference from file
-    fn load_language_preference(&mut self) {
-        if !Path::new(&self.config_file).exists() {
-            return;
-        }
-
-        if let Ok(content) = fs::read_to_string(&self.config_file) {
-            if let Ok(language_code) = serde_json::from_str::<String>(&content) {
-                if let Some(language) = Language::from_code(&language_code) {
-                    self.i18n.set_language(language);
-                }
-            }
-        }
-    }
-
-    /// Save language preference to file
+    /// Save language preference to the settings file
     fn save_language_preference(&self) {
-        // Ensure directory exists
-        if let Some(parent) = Path::new(&self.config_file).parent() {
-            let _ = fs::create_dir_all(parent);
-        }
-
-        // Save language code
-        let language_code = self.current_language().code();
-        if let Ok(json) = serde_json::to_string(&language_code) {
-            let _ = fs::write(&self.config_file, json);
+        if let Some(path) = &self.config_file {
+            let _ = config::save_language(path, self.current_language());
         }
     }
 
@@ -122,8 +101,17 @@ impl LanguageManager {
     }
 }
 
-impl Default for LanguageManager {
-    fn default() -> Self {
-        Self::new()
+/// The language saved by an older version, if any. It moves into the
+/// settings file at `config_file`, when there is one.
+pub fn migrate_legacy_language(config_file: Option<&Path>) -> Option<Language> {
+    let content = fs::read_to_string(LEGACY_LANGUAGE_FILE).ok()?;
+    let language = serde_json::from_str::<String>(&content)
+        .ok()
+        .and_then(|code| Language::from_code(&code))?;
+    if let Some(path) = config_file {
+        if config::save_language(path, language).is_ok() {
+            let _ = fs::remove_file(LEGACY_LANGUAGE_FILE);
+        }
     }
+    Some(language)
 }
@@ -6,30 +6,94 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph},
     Terminal,
 };
-use rusty2048_core::{AIAlgorithm, AIGameController, Direction, Game, GameConfig, GameState};
+use rusty2048_core::{
+    register_network, AIAlgorithm, AIPlayer, DailyChallenge, Direction, Game, GameConfig,
+    GameResult, GameState, HeuristicConfig, MoveAnalysis, MoveGrade, MoveGrader, NTupleNetwork,
+    PlayerType, StatisticsManager,
+};
 
+mod background_ai;
+mod bench;
+mod board_view;
 mod charts;
+mod config;
+mod export;
+mod headless;
+mod keymap;
 mod language;
+mod menu;
 mod replay;
+mod save;
 mod theme;
+use background_ai::BackgroundAi;
 use charts::ChartsDisplay;
+use config::{AiSettings, CliConfig};
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use language::LanguageManager;
-use replay::ReplayMode;
-use rusty2048_shared::TranslationKey;
+use menu::{MenuCommand, MenuStatus, PauseMenu};
+use replay::{AutoRecording, ReplayMode};
+use rusty2048_shared::{Action, TranslationKey};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::{io, panic};
-use theme::{get_tile_color, get_tile_text_color, hex_to_color, ThemeManager};
+use theme::{hex_to_color, ThemeManager};
+
+/// AI controller for a new game. It searches within the configured time
+/// budget, short by default so the screen stays responsive.
+fn new_ai_controller(
+    config: GameConfig,
+    algorithm: AIAlgorithm,
+    settings: &AiSettings,
+) -> GameResult<BackgroundAi> {
+    BackgroundAi::new(
+        config,
+        AIPlayer::new(algorithm)
+            .with_time_budget(settings.time_budget())
+            .with_heuristic(
+                settings
+                    .heuristic()
+                    .unwrap_or_else(|_| HeuristicConfig::snake()),
+            ),
+    )
+}
 
 fn print_help() {
     println!("🎮 Rusty2048 CLI - Modern 2048 Game");
     println!("====================================");
     println!();
     println!("Usage:");
-    println!("  rusty2048              Start the game");
+    println!("  rusty2048 [OPTIONS] [COMMAND]");
+    println!();
+    println!("Options (override the settings file):");
+    println!("  --config <FILE>        Settings file, given first");
+    println!("                         (default ~/.config/rusty2048/config.toml)");
+    println!("  --size <N>             Board size; starts a new game");
+    println!("  --theme <NAME>         Theme at startup");
+    println!("  --language <CODE>      Language (en, zh)");
+    println!("  --ai <ALGORITHM>       Algorithm AI mode starts with");
+    println!();
+    println!("Commands:");
+    println!("  rusty2048              Start the game (resumes the last unfinished game)");
+    println!("  rusty2048 --new        Start a new game, discarding the saved one");
+    println!("  rusty2048 --daily [YYYY-MM-DD]");
+    println!("                         Play the daily challenge (today's by default)");
+    println!("  rusty2048 --import-stats <FILE>");
+    println!("                         Merge statistics from another device into these");
+    println!("  rusty2048 replay export <FILE> [--format gif|cast] [--output <FILE>]");
+    println!("                         [--theme <NAME>] [--frame-ms <MS>]");
+    println!("                         Render a replay to an animated GIF or asciinema cast");
+    println!("  rusty2048 bench [--algorithm <NAME>] [--games <N>] [--seed <N>]");
+    println!("                         [--size <N>] [--heuristic classic|snake]");
+    println!("                         [--adaptive-depth <MIN>-<MAX>] [--prune <PROBABILITY>]");
+    println!("                         [--budget-ms <MS>]");
+    println!("                         Play seeded games with an AI and report its results");
+    println!("  rusty2048 --headless [--size <N>] [--seed <N>]");
+    println!("                         Play without a UI: moves from stdin (u/d/l/r or JSON),");
+    println!("                         game state to stdout as JSON lines");
     println!("  rusty2048 --help       Show this help message");
     println!("  rusty2048 --version    Show version information");
     println!();
@@ -40,6 +104,10 @@ fn print_help() {
     println!("  A                     Toggle AI mode");
     println!("  S                     Show statistics charts");
     println!("  R                     Start/stop replay");
+    println!("  G                     Toggle assist mode (grades each move)");
+    println!("  Enter                 Keep going after winning");
+    println!("  Space                 Pause/resume the game clock");
+    println!("  Esc                   Pause menu (new game, board size, settings, quit)");
     println!("  Q                     Quit game");
     println!();
     println!("Features:");
@@ -49,14 +117,70 @@ fn print_help() {
     println!("  🔄 Game replay system");
     println!("  🌍 English/Chinese support");
     println!();
+    println!("Environment:");
+    println!("  RUSTY2048_PLAYER      Name used on the local leaderboard");
+    println!("  RUSTY2048_AUTO_RECORD Save a replay of every finished game (set to 1)");
+    println!("  RUSTY2048_KEEP_BEST   Automatic replays kept by score (default 10)");
+    println!("  RUSTY2048_KEEP_RECENT Automatic replays kept by date (default 20)");
+    println!("  RUSTY2048_NTUPLE_WEIGHTS");
+    println!("                        N-tuple weights file for the N-Tuple AI");
+    println!();
     println!("More info: https://github.com/honkinglin/rusty2048");
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Check command line arguments
     let args: Vec<String> = std::env::args().collect();
-    if args.len() > 1 {
-        match args[1].as_str() {
+    let mut resume = true;
+    let mut daily = None;
+
+    // Settings: the defaults, overridden by the settings file, overridden
+    // by the options before any command
+    let (config_path, options) = match args.get(1..3) {
+        Some([flag, path]) if flag == "--config" => (Some(PathBuf::from(path)), &args[3..]),
+        _ => (config::default_path(), args.get(1..).unwrap_or_default()),
+    };
+    let mut settings = match &config_path {
+        Some(path) => CliConfig::load(path),
+        None => Ok(CliConfig::default()),
+    }
+    .unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    let (args, size_given) = settings.apply_options(options).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    if size_given {
+        resume = false;
+    }
+    if settings.language.is_none() {
+        settings.language = language::migrate_legacy_language(config_path.as_deref())
+            .map(|language| language.code().to_string());
+    }
+    if let Err(e) = settings.paths.create_dirs() {
+        eprintln!("Failed to create data directories: {}", e);
+        std::process::exit(1);
+    }
+    for note in config::migrate_legacy_files(&settings.paths) {
+        eprintln!("{}", note);
+    }
+
+    // Load the n-tuple network before the terminal is taken over, so a bad
+    // file is reported readably; `bench` plays with it too
+    if let Ok(path) = std::env::var("RUSTY2048_NTUPLE_WEIGHTS") {
+        match NTupleNetwork::load(&path) {
+            Ok(network) => register_network(Arc::new(network)),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    if let Some(command) = args.first() {
+        match command.as_str() {
             "--help" | "-h" => {
                 print_help();
                 return Ok(());
@@ -65,8 +189,67 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 println!("rusty2048-cli {}", env!("CARGO_PKG_VERSION"));
                 return Ok(());
             }
+            "--new" => {
+                resume = false;
+                save::discard_saved_game();
+            }
+            "--daily" => {
+                let challenge = match args.get(1) {
+                    Some(date) => DailyChallenge::for_date(date).unwrap_or_else(|e| {
+                        eprintln!("{}", e);
+                        std::process::exit(1);
+                    }),
+                    None => DailyChallenge::today(),
+                };
+                resume = false;
+                daily = Some(challenge);
+            }
+            "--import-stats" => {
+                let Some(path) = args.get(1) else {
+                    eprintln!("Usage: rusty2048 --import-stats <FILE>");
+                    std::process::exit(1);
+                };
+                let report = StatisticsManager::new(&settings.paths.stats.to_string_lossy())
+                    .and_then(|mut stats| stats.import_and_merge(path))
+                    .unwrap_or_else(|e| {
+                        eprintln!("Failed to import statistics: {}", e);
+                        std::process::exit(1);
+                    });
+                println!(
+                    "Imported {} games ({} already recorded, {} renumbered, {} with corrected times)",
+                    report.added, report.duplicates, report.renumbered, report.adjusted
+                );
+                return Ok(());
+            }
+            "--headless" => {
+                headless::run(&args[1..]).unwrap_or_else(|e| {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                });
+                return Ok(());
+            }
+            "bench" => {
+                let report = bench::run(&args[1..]).unwrap_or_else(|e| {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                });
+                print!("{}", report);
+                return Ok(());
+            }
+            "replay" => {
+                if args.get(1).map(String::as_str) != Some("export") {
+                    eprintln!("{}", export::USAGE);
+                    std::process::exit(1);
+                }
+                let output = export::run(&args[2..]).unwrap_or_else(|e| {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                });
+                println!("Exported replay to {}", output.display());
+                return Ok(());
+            }
             _ => {
-                eprintln!("Unknown argument: {}", args[1]);
+                eprintln!("Unknown argument: {}", command);
                 eprintln!("Use --help to see available options");
                 std::process::exit(1);
             }
@@ -88,12 +271,26 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         original_hook(panic_info);
     }));
 
-    // Create game
-    let config = GameConfig::default();
-    let mut game = Game::new(config)?;
+    // Resume the saved game or create a new one
+    let mut game = match (&daily, resume.then(save::load_game).flatten()) {
+        (Some(challenge), _) => challenge.new_game()?,
+        (None, Some(game)) => game,
+        (None, None) => Game::new(GameConfig {
+            board_size: settings.board_size,
+            ..GameConfig::default()
+        })?,
+    };
+    save::persist_best_score(&mut game);
 
     // Run the game
-    let res = run_game(&mut terminal, &mut game);
+    let res = run_game(
+        &mut terminal,
+        &mut game,
+        daily.as_ref(),
+        &settings,
+        config_path,
+    );
+    save::save_game(&game);
 
     // Restore terminal
     disable_raw_mode()?;
@@ -114,26 +311,52 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 fn run_game<B: ratatui::backend::Backend>(
     terminal: &mut Terminal<B>,
     game: &mut Game,
+    daily: Option<&DailyChallenge>,
+    settings: &CliConfig,
+    config_path: Option<PathBuf>,
 ) -> io::Result<()> {
     let mut show_game_over = false;
     let mut show_win = false;
     let mut last_score = game.score().current();
     let mut score_animation = 0;
     let mut theme_manager = ThemeManager::new();
+    theme_manager.set_theme(&settings.theme);
     let mut show_theme_help = false;
+    let mut menu: Option<PauseMenu> = None;
+    // Whether opening the menu paused the game, so closing it resumes it
+    let mut menu_paused = false;
     let mut ai_mode = false;
-    let mut ai_controller: Option<AIGameController> = None;
+    let mut ai_controller: Option<BackgroundAi> = None;
     let mut ai_auto_play = false;
-    let mut ai_speed = 800; // AI移动延迟，单位毫秒
-    let mut charts_display = ChartsDisplay::new().unwrap_or_else(|_| {
-        eprintln!("Failed to initialize charts display");
+    let mut ai_speed = settings.ai.speed_ms; // AI移动延迟，单位毫秒
+                                             // Whether the AI made moves in the current game
+    let mut ai_played = false;
+    // When auto-play may search the next move, in ms since the epoch
+    let mut next_ai_move_at = 0;
+    let mut charts_display = ChartsDisplay::new(&settings.paths).unwrap_or_else(|e| {
+        eprintln!("Failed to initialize charts display: {}", e);
         std::process::exit(1);
     });
     let mut show_charts = false;
-    let mut game_start_time = rusty2048_core::get_current_time();
-    let mut language_manager = LanguageManager::new();
+    // Rank of the finished game on the local leaderboard, if it made the list
+    let mut leaderboard_rank = None;
+    // Id of the session the current game was recorded as, once it ended
+    let mut recorded_session: Option<u64> = None;
+    // A resumed game keeps its play time
+    let mut game_start_time = rusty2048_core::get_current_time() - game.stats().duration;
+    let mut language_manager = LanguageManager::new(settings.language(), config_path);
+    let mut auto_recording = AutoRecording::from_env(game, &settings.paths.replays);
+    // Assist mode grades each move against the AI's choice
+    let mut grader: Option<MoveGrader> = None;
+    let mut last_grade: Option<MoveAnalysis> = None;
 
     loop {
+        if let Some(recording) = &mut auto_recording {
+            if let Err(e) = recording.observe(game) {
+                eprintln!("Failed to save replay: {}", e);
+            }
+        }
+
         terminal.draw(|f| {
             let size = f.size();
             let chunks = Layout::default()
@@ -155,8 +378,12 @@ fn run_game<B: ratatui::backend::Backend>(
                     .direction(LayoutDirection::Horizontal)
                     .constraints(
                         [
-                            Constraint::Length(40), // Game area
-                            Constraint::Min(0),     // Charts area
+                            // Game area, half the width at most
+                            Constraint::Length(
+                                board_view::max_board_width(game.board().size())
+                                    .min(chunks[1].width / 2),
+                            ),
+                            Constraint::Min(0), // Charts area
                         ]
                         .as_ref(),
                     )
@@ -177,44 +404,16 @@ fn run_game<B: ratatui::backend::Backend>(
             .block(Block::default().borders(Borders::NONE));
             f.render_widget(title, title_area);
 
-            // Game board
-            let board_chunks = Layout::default()
-                .direction(LayoutDirection::Vertical)
-                .constraints(
-                    (0..game.board().size())
-                        .map(|_| Constraint::Length(3))
-                        .collect::<Vec<_>>(),
-                )
-                .split(game_area);
-
-            for (row, &chunk) in board_chunks.iter().enumerate() {
-                let row_chunks = Layout::default()
-                    .direction(LayoutDirection::Horizontal)
-                    .constraints(
-                        (0..game.board().size())
-                            .map(|_| Constraint::Length(8))
-                            .collect::<Vec<_>>(),
-                    )
-                    .split(chunk);
-
-                for (col, &cell) in row_chunks.iter().enumerate() {
-                    let tile = game.board().get_tile(row, col).unwrap();
-                    let text = if tile.is_empty() {
-                        " ".to_string()
-                    } else {
-                        tile.value.to_string()
-                    };
-
-                    let tile_color = get_tile_color(tile.value, &theme_manager.current_theme);
-                    let text_color = get_tile_text_color(tile.value, &theme_manager.current_theme);
-
-                    let style = Style::default().fg(text_color).bg(tile_color);
-
-                    let cell_widget = Paragraph::new(text)
-                        .block(Block::default().borders(Borders::ALL))
-                        .style(style);
-                    f.render_widget(cell_widget, cell);
-                }
+            // Game board, or a notice instead if it does not fit
+            let min_board = board_view::min_board_size(game.board().size());
+            let too_small = game_area.width < min_board.0 || game_area.height < min_board.1;
+            if !too_small {
+                board_view::render_board(
+                    f,
+                    game_area,
+                    &game.board().to_vec(),
+                    &theme_manager.current_theme,
+                );
             }
 
             // Render charts if enabled
@@ -318,7 +517,12 @@ fn run_game<B: ratatui::backend::Backend>(
                     Span::styled("H", Style::default().fg(Color::White)),
                     Span::raw(format!(" {} | ", language_manager.t(&TranslationKey::Help))),
                     Span::styled("Q", Style::default().fg(Color::White)),
-                    Span::raw(format!(" {}", language_manager.t(&TranslationKey::Quit))),
+                    Span::raw(format!(" {} | ", language_manager.t(&TranslationKey::Quit))),
+                    Span::styled(
+                        settings.keys.key_label(Action::Menu),
+                        Style::default().fg(Color::White),
+                    ),
+                    Span::raw(" Menu"),
                 ]),
             ];
 
@@ -328,29 +532,23 @@ fn run_game<B: ratatui::backend::Backend>(
                     if !show_win {
                         show_win = true;
 
-                        // Record game statistics
-                        let end_time = rusty2048_core::get_current_time();
-                        let session_stats = rusty2048_core::create_session_stats(
-                            game.score().current(),
-                            game.moves(),
-                            game.stats().duration,
-                            game.board().max_tile(),
-                            true, // Won
+                        record_game(
+                            &mut charts_display,
+                            game,
+                            daily,
+                            ai_played,
                             game_start_time,
-                            end_time,
+                            &mut recorded_session,
                         );
-
-                        if let Err(e) = charts_display.stats_manager().record_session(session_stats)
-                        {
-                            eprintln!("Failed to record game statistics: {}", e);
-                        }
+                        leaderboard_rank = charts_display.submit_score(game, daily.is_some());
                     }
                     status_text.push(Line::from(vec![Span::styled(
                         format!(
-                            "{} {} {}",
+                            "{} {} {} {}",
                             language_manager.t(&TranslationKey::Congratulations),
                             language_manager.t(&TranslationKey::YouWon),
-                            language_manager.t(&TranslationKey::PressRToRestart)
+                            language_manager.t(&TranslationKey::PressRToRestart),
+                            language_manager.t(&TranslationKey::ContinuePlaying)
                         ),
                         Style::default()
                             .fg(Color::Yellow)
@@ -360,22 +558,17 @@ fn run_game<B: ratatui::backend::Backend>(
                 GameState::GameOver => {
                     if !show_game_over {
                         show_game_over = true;
-
-                        // Record game statistics
-                        let end_time = rusty2048_core::get_current_time();
-                        let session_stats = rusty2048_core::create_session_stats(
-                            game.score().current(),
-                            game.moves(),
-                            game.stats().duration,
-                            game.board().max_tile(),
-                            false, // Game over, not won
+                        record_game(
+                            &mut charts_display,
+                            game,
+                            daily,
+                            ai_played,
                             game_start_time,
-                            end_time,
+                            &mut recorded_session,
                         );
-
-                        if let Err(e) = charts_display.stats_manager().record_session(session_stats)
-                        {
-                            eprintln!("Failed to record game statistics: {}", e);
+                        // A continued game entered the leaderboard when it was won
+                        if !game.keep_playing() {
+                            leaderboard_rank = charts_display.submit_score(game, daily.is_some());
                         }
                     }
                     status_text.push(Line::from(vec![Span::styled(
@@ -425,20 +618,22 @@ fn run_game<B: ratatui::backend::Backend>(
                 GameState::Playing => {
                     show_game_over = false;
                     show_win = false;
+                    if game.is_paused() {
+                        status_text.push(Line::from(vec![Span::styled(
+                            format!("⏸ {}", language_manager.t(&TranslationKey::Paused)),
+                            Style::default()
+                                .fg(Color::Yellow)
+                                .add_modifier(Modifier::BOLD),
+                        )]));
+                    }
                 }
             }
 
             // Add AI mode status
             if ai_mode {
-                let algo_name = if let Some(controller) = &ai_controller {
-                    match controller.algorithm() {
-                        AIAlgorithm::Greedy => "Greedy",
-                        AIAlgorithm::Expectimax => "Expectimax",
-                        AIAlgorithm::MCTS => "MCTS",
-                    }
-                } else {
-                    "None"
-                };
+                let algo_name = ai_controller
+                    .as_ref()
+                    .map_or("None", |controller| controller.strategy_name());
 
                 status_text.push(Line::from(vec![Span::styled(
                     format!(
@@ -451,12 +646,30 @@ fn run_game<B: ratatui::backend::Backend>(
                         .fg(Color::Magenta)
                         .add_modifier(Modifier::BOLD),
                 )]));
+                if let Some(status) = ai_controller.as_ref().and_then(BackgroundAi::status) {
+                    status_text.push(Line::from(vec![Span::styled(
+                        status,
+                        Style::default().fg(Color::Magenta),
+                    )]));
+                }
+                let keys = &settings.keys;
                 status_text.push(Line::from(vec![Span::styled(
-                    "AI Controls: O=Auto-play, []=Prev Algo, ]=Next Algo, +/-=Speed",
+                    format!(
+                        "AI Controls: {}=Auto-play, {}=Prev Algo, {}=Next Algo, {}/{}=Speed",
+                        keys.key_label(Action::ToggleAutoPlay),
+                        keys.key_label(Action::PrevAlgorithm),
+                        keys.key_label(Action::NextAlgorithm),
+                        keys.key_label(Action::SpeedUp),
+                        keys.key_label(Action::SlowDown)
+                    ),
                     Style::default().fg(Color::Magenta),
                 )]));
             }
 
+            if grader.is_some() {
+                status_text.push(assist_line(last_grade.as_ref()));
+            }
+
             // Add theme help if requested
             if show_theme_help {
                 status_text.push(Line::from(vec![Span::styled(
@@ -469,6 +682,15 @@ fn run_game<B: ratatui::backend::Backend>(
                 )]));
             }
 
+            if let Some(rank) = leaderboard_rank {
+                status_text.push(Line::from(vec![Span::styled(
+                    format!("🏅 New high score: #{} on the leaderboard", rank),
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                )]));
+            }
+
             // Add charts status if enabled
             if show_charts {
                 status_text.push(Line::from(vec![Span::styled(
@@ -496,27 +718,78 @@ fn run_game<B: ratatui::backend::Backend>(
 
             let status = Paragraph::new(status_text).block(Block::default().borders(Borders::NONE));
             f.render_widget(status, status_area);
+
+            if let Some(menu) = &menu {
+                menu.render(
+                    f,
+                    size,
+                    &MenuStatus {
+                        theme: theme_manager.current_theme_name(),
+                        language: language_manager.language_name(),
+                        assist: grader.is_some(),
+                        charts: show_charts,
+                        can_resize: daily.is_none(),
+                    },
+                    &language_manager,
+                );
+            }
+
+            if too_small {
+                board_view::render_too_small(
+                    f,
+                    (
+                        size.width + min_board.0.saturating_sub(game_area.width),
+                        size.height + min_board.1.saturating_sub(game_area.height),
+                    ),
+                    &language_manager,
+                );
+            }
         })?;
 
         // Check for user input with timeout
 
         // Use non-blocking event polling for AI mode
         if ai_mode && ai_auto_play && game.state() == GameState::Playing {
+            // Search the next move in the background once the delay after
+            // the last one has passed
+            if let Some(ai) = &mut ai_controller {
+                if !ai.is_thinking() && rusty2048_core::get_current_time_ms() >= next_ai_move_at {
+                    ai.start(game);
+                }
+            }
+
             // Check for immediate exit
             if event::poll(std::time::Duration::from_millis(100))? {
-                if let Event::Key(key) = event::read()? {
-                    match key.code {
-                        KeyCode::Char('q') | KeyCode::Esc => {
-                            return Ok(());
+                let event = event::read()?;
+                if let Event::Resize(..) = event {
+                    terminal.autoresize()?;
+                }
+                if let Event::Key(key) = event {
+                    match settings.keys.action_for(&key) {
+                        Some(Action::Quit) => {
+                            break;
                         }
-                        KeyCode::Char('o') => {
+                        Some(Action::Menu) => {
+                            // Stop auto-play; the menu is handled as in manual play
                             ai_auto_play = false;
+                            if let Some(ai) = &mut ai_controller {
+                                ai.cancel();
+                            }
+                            menu = Some(PauseMenu::new(game.board().size()));
+                            menu_paused = !game.is_paused();
+                            game.pause();
                         }
-                        KeyCode::Char('+') | KeyCode::Char('=') => {
+                        Some(Action::ToggleAutoPlay) => {
+                            ai_auto_play = false;
+                            if let Some(ai) = &mut ai_controller {
+                                ai.cancel();
+                            }
+                        }
+                        Some(Action::SpeedUp) => {
                             // Increase AI speed (decrease delay)
                             ai_speed = (ai_speed as i32 - 100).max(100) as u64;
                         }
-                        KeyCode::Char('-') => {
+                        Some(Action::SlowDown) => {
                             // Decrease AI speed (increase delay)
                             ai_speed = (ai_speed + 100).min(2000);
                         }
@@ -525,91 +798,180 @@ fn run_game<B: ratatui::backend::Backend>(
                 }
             }
 
-            // Make AI move if no exit was requested
-            if ai_auto_play {
-                if let Some(controller) = &mut ai_controller {
-                    // Sync AI controller with current game state
-                    *controller.game_mut() = game.clone();
-
-                    if let Ok(moved) = controller.make_ai_move() {
-                        if moved {
-                            // Update the main game with AI's move
-                            *game = controller.game().clone();
-
-                            // Add delay for AI speed control
-                            std::thread::sleep(std::time::Duration::from_millis(ai_speed));
-                        }
+            // Apply the AI's move once its search has finished
+            if let Some(outcome) = ai_controller.as_mut().and_then(BackgroundAi::poll) {
+                if let Some(mut next) = outcome {
+                    // The AI played on a copy, which leaves saving the best
+                    // score to the game it replaces
+                    if let Some(store) = game.best_score_store() {
+                        let _ = next.set_best_score_store(store);
                     }
+                    *game = next;
+                    ai_played = true;
                 }
+                // Add delay for AI speed control
+                next_ai_move_at = rusty2048_core::get_current_time_ms() + ai_speed;
             }
         } else {
-            // Normal blocking event read for manual mode
-            if let Event::Key(key) = event::read()? {
-                match key.code {
-                    KeyCode::Char('q') | KeyCode::Esc => {
-                        return Ok(());
-                    }
-                    KeyCode::Up | KeyCode::Char('w') | KeyCode::Char('k')
-                        if game.state() == GameState::Playing =>
-                    {
-                        let _ = game.make_move(Direction::Up);
-                    }
-                    KeyCode::Down | KeyCode::Char('s') | KeyCode::Char('j')
-                        if game.state() == GameState::Playing =>
-                    {
-                        let _ = game.make_move(Direction::Down);
-                    }
-                    KeyCode::Left | KeyCode::Char('a') if game.state() == GameState::Playing => {
-                        let _ = game.make_move(Direction::Left);
-                    }
-                    KeyCode::Right | KeyCode::Char('d') if game.state() == GameState::Playing => {
-                        let _ = game.make_move(Direction::Right);
-                    }
-                    KeyCode::Char('r') => {
-                        let _ = game.new_game();
-                        show_game_over = false;
-                        show_win = false;
-                        game_start_time = rusty2048_core::get_current_time();
-                    }
-                    KeyCode::Char('u') if game.state() == GameState::Playing => {
+            // Normal blocking event read for manual mode; a resize
+            // only redraws the screen at its new size
+            let event = event::read()?;
+            if let Event::Resize(..) = event {
+                terminal.autoresize()?;
+            }
+            if let Event::Key(key) = event {
+                let action = settings.keys.action_for(&key);
+                let mut restarted = false;
+                match action {
+                    // An open menu takes every key
+                    _ if menu.is_some() => {
+                        let command = menu.as_mut().and_then(|menu| menu.handle_key(&key, action));
+                        match command {
+                            Some(MenuCommand::NewGame) => {
+                                restart_game(
+                                    game,
+                                    None,
+                                    &mut auto_recording,
+                                    &settings.paths.replays,
+                                )?;
+                                restarted = true;
+                            }
+                            Some(MenuCommand::ChangeBoardSize(board_size)) if daily.is_none() => {
+                                let config = GameConfig {
+                                    board_size,
+                                    ..game.config().clone()
+                                };
+                                match Game::new(config) {
+                                    Ok(mut next) => {
+                                        save::persist_best_score(&mut next);
+                                        restart_game(
+                                            game,
+                                            Some(next),
+                                            &mut auto_recording,
+                                            &settings.paths.replays,
+                                        )?;
+                                        if let Some(controller) = &ai_controller {
+                                            let algorithm = controller
+                                                .algorithm()
+                                                .unwrap_or(AIAlgorithm::Greedy);
+                                            ai_controller = new_ai_controller(
+                                                game.config().clone(),
+                                                algorithm,
+                                                &settings.ai,
+                                            )
+                                            .map_err(|e| {
+                                                eprintln!("Failed to initialize AI: {}", e)
+                                            })
+                                            .ok();
+                                        }
+                                        restarted = true;
+                                    }
+                                    Err(e) => eprintln!("Failed to change board size: {}", e),
+                                }
+                            }
+                            Some(MenuCommand::PrevTheme) => theme_manager.prev_theme(),
+                            Some(MenuCommand::NextTheme) => theme_manager.next_theme(),
+                            Some(MenuCommand::PrevLanguage) => language_manager.prev_language(),
+                            Some(MenuCommand::NextLanguage) => language_manager.next_language(),
+                            Some(MenuCommand::ToggleAssist) => {
+                                grader = match grader {
+                                    Some(_) => None,
+                                    None => Some(MoveGrader::default()),
+                                };
+                                last_grade = None;
+                            }
+                            Some(MenuCommand::ToggleCharts) => show_charts = !show_charts,
+                            Some(MenuCommand::SaveAndQuit) => break,
+                            _ => {}
+                        }
+                        if restarted || command == Some(MenuCommand::Resume) {
+                            menu = None;
+                            if menu_paused {
+                                game.resume();
+                            }
+                        }
+                    }
+                    Some(Action::Quit) => {
+                        break;
+                    }
+                    Some(Action::Menu) => {
+                        menu = Some(PauseMenu::new(game.board().size()));
+                        menu_paused = !game.is_paused();
+                        game.pause();
+                    }
+                    Some(Action::MoveUp) if game.state() == GameState::Playing => {
+                        play_graded(game, Direction::Up, grader.as_mut(), &mut last_grade);
+                    }
+                    Some(Action::MoveDown) if game.state() == GameState::Playing => {
+                        play_graded(game, Direction::Down, grader.as_mut(), &mut last_grade);
+                    }
+                    Some(Action::MoveLeft) if game.state() == GameState::Playing => {
+                        play_graded(game, Direction::Left, grader.as_mut(), &mut last_grade);
+                    }
+                    Some(Action::MoveRight) if game.state() == GameState::Playing => {
+                        play_graded(game, Direction::Right, grader.as_mut(), &mut last_grade);
+                    }
+                    Some(Action::NewGame) => {
+                        restart_game(game, None, &mut auto_recording, &settings.paths.replays)?;
+                        restarted = true;
+                    }
+                    Some(Action::Undo) if game.state() == GameState::Playing => {
                         let _ = game.undo();
                     }
-                    KeyCode::Char('t') => {
+                    Some(Action::ContinueAfterWin) if game.state() == GameState::Won => {
+                        let _ = game.continue_after_win();
+                    }
+                    Some(Action::Pause) if game.state() == GameState::Playing => {
+                        if game.is_paused() {
+                            game.resume();
+                        } else {
+                            game.pause();
+                        }
+                    }
+                    Some(Action::NextTheme) => {
                         theme_manager.next_theme();
                     }
-                    KeyCode::Char('1') => {
+                    Some(Action::ThemeClassic) => {
                         theme_manager.set_theme("Classic");
                     }
-                    KeyCode::Char('2') => {
+                    Some(Action::ThemeDark) => {
                         theme_manager.set_theme("Dark");
                     }
-                    KeyCode::Char('3') => {
+                    Some(Action::ThemeNeon) => {
                         theme_manager.set_theme("Neon");
                     }
-                    KeyCode::Char('4') => {
+                    Some(Action::ThemeRetro) => {
                         theme_manager.set_theme("Retro");
                     }
-                    KeyCode::Char('5') => {
+                    Some(Action::ThemePastel) => {
                         theme_manager.set_theme("Pastel");
                     }
-                    KeyCode::Char('h') => {
+                    Some(Action::ToggleThemeHelp) => {
                         show_theme_help = !show_theme_help;
                     }
-                    KeyCode::Char('l') => {
+                    Some(Action::NextLanguage) => {
                         // Switch language
                         language_manager.next_language();
                     }
-                    KeyCode::Char('p') => {
+                    Some(Action::ReplayMode) => {
                         // Enter replay mode
-                        if let Err(e) = ReplayMode::new()?.run(terminal) {
+                        if let Err(e) = ReplayMode::new(&settings.paths.replays)?.run(terminal) {
                             eprintln!("Replay mode error: {}", e);
                         }
                     }
-                    KeyCode::Char('c') => {
+                    Some(Action::ToggleAssist) => {
+                        // Toggle assist mode
+                        grader = match grader {
+                            Some(_) => None,
+                            None => Some(MoveGrader::default()),
+                        };
+                        last_grade = None;
+                    }
+                    Some(Action::ToggleCharts) => {
                         // Toggle charts display
                         show_charts = !show_charts;
                     }
-                    KeyCode::Char('i') => {
+                    Some(Action::ToggleAi) => {
                         // Toggle AI mode
                         if ai_mode {
                             ai_mode = false;
@@ -617,68 +979,206 @@ fn run_game<B: ratatui::backend::Backend>(
                             ai_auto_play = false;
                         } else {
                             ai_mode = true;
-                            match AIGameController::new(game.config().clone(), AIAlgorithm::Greedy)
+                            let algorithm = settings.ai.algorithm().unwrap_or(AIAlgorithm::Greedy);
+                            match new_ai_controller(game.config().clone(), algorithm, &settings.ai)
                             {
                                 Ok(controller) => ai_controller = Some(controller),
                                 Err(e) => eprintln!("Failed to initialize AI: {}", e),
                             }
                         }
                     }
-                    KeyCode::Char('o') if ai_mode && ai_controller.is_some() => {
+                    Some(Action::ToggleAutoPlay) if ai_mode && ai_controller.is_some() => {
                         // Toggle AI auto-play
                         ai_auto_play = !ai_auto_play;
                     }
-                    KeyCode::Char('[') if ai_mode => {
+                    Some(Action::PrevAlgorithm) if ai_mode => {
                         // Switch to previous AI algorithm
                         if let Some(controller) = &mut ai_controller {
                             let current_algo = controller.algorithm();
-                            let new_algo = match current_algo {
-                                AIAlgorithm::Greedy => AIAlgorithm::MCTS,
+                            let new_algo = match current_algo.unwrap_or(AIAlgorithm::Greedy) {
+                                AIAlgorithm::Greedy => AIAlgorithm::NTuple,
                                 AIAlgorithm::Expectimax => AIAlgorithm::Greedy,
                                 AIAlgorithm::MCTS => AIAlgorithm::Expectimax,
+                                AIAlgorithm::Minimax => AIAlgorithm::MCTS,
+                                AIAlgorithm::NTuple => AIAlgorithm::Minimax,
                             };
-                            match AIGameController::new(game.config().clone(), new_algo) {
+                            match new_ai_controller(game.config().clone(), new_algo, &settings.ai) {
                                 Ok(new_controller) => ai_controller = Some(new_controller),
                                 Err(e) => eprintln!("Failed to switch AI algorithm: {}", e),
                             }
                         }
                     }
-                    KeyCode::Char(']') if ai_mode => {
+                    Some(Action::NextAlgorithm) if ai_mode => {
                         // Switch to next AI algorithm
                         if let Some(controller) = &mut ai_controller {
                             let current_algo = controller.algorithm();
-                            let new_algo = match current_algo {
+                            let new_algo = match current_algo.unwrap_or(AIAlgorithm::Greedy) {
                                 AIAlgorithm::Greedy => AIAlgorithm::Expectimax,
                                 AIAlgorithm::Expectimax => AIAlgorithm::MCTS,
-                                AIAlgorithm::MCTS => AIAlgorithm::Greedy,
+                                AIAlgorithm::MCTS => AIAlgorithm::Minimax,
+                                AIAlgorithm::Minimax => AIAlgorithm::NTuple,
+                                AIAlgorithm::NTuple => AIAlgorithm::Greedy,
                             };
-                            match AIGameController::new(game.config().clone(), new_algo) {
+                            match new_ai_controller(game.config().clone(), new_algo, &settings.ai) {
                                 Ok(new_controller) => ai_controller = Some(new_controller),
                                 Err(e) => eprintln!("Failed to switch AI algorithm: {}", e),
                             }
                         }
                     }
-                    KeyCode::Char('+') | KeyCode::Char('=') if ai_mode => {
+                    Some(Action::SpeedUp) if ai_mode => {
                         // Increase AI speed (decrease delay)
                         ai_speed = (ai_speed as i32 - 100).max(100) as u64;
                     }
-                    KeyCode::Char('-') if ai_mode => {
+                    Some(Action::SlowDown) if ai_mode => {
                         // Decrease AI speed (increase delay)
                         ai_speed = (ai_speed + 100).min(2000);
                     }
-                    KeyCode::Char('x') if show_charts => {
+                    Some(Action::PrevChart) if show_charts => {
                         // Previous chart mode
                         charts_display.prev_mode();
                     }
-                    KeyCode::Char('z') if show_charts => {
+                    Some(Action::NextChart) if show_charts => {
                         // Next chart mode
                         charts_display.next_mode();
                     }
                     _ => {}
                 }
+                if restarted {
+                    show_game_over = false;
+                    show_win = false;
+                    ai_played = false;
+                    leaderboard_rank = None;
+                    recorded_session = None;
+                    last_grade = None;
+                    game_start_time = rusty2048_core::get_current_time();
+                }
             }
         }
     }
+
+    // A won game counts as finished even if it was never continued
+    if let Some(recording) = &mut auto_recording {
+        recording.finish()?;
+    }
+    Ok(())
+}
+
+/// Start over on `next`, or on a new game with the same settings. The game
+/// left is abandoned: its auto-recording is finished (saving it if it was
+/// won) and recording starts again on the new game.
+fn restart_game(
+    game: &mut Game,
+    next: Option<Game>,
+    auto_recording: &mut Option<AutoRecording>,
+    replays_dir: &Path,
+) -> io::Result<()> {
+    if let Some(recording) = auto_recording {
+        recording.observe(game)?;
+        recording.finish()?;
+    }
+    match next {
+        Some(next) => *game = next,
+        None => {
+            let _ = game.new_game();
+        }
+    }
+    *auto_recording = AutoRecording::from_env(game, replays_dir);
+    Ok(())
+}
+
+/// Record the finished game in the statistics. A game continued after the
+/// win was recorded then; its session is updated when it really ends.
+fn record_game(
+    charts_display: &mut ChartsDisplay,
+    game: &Game,
+    daily: Option<&DailyChallenge>,
+    ai_played: bool,
+    game_start_time: u64,
+    recorded_session: &mut Option<u64>,
+) {
+    let player = if ai_played {
+        PlayerType::Ai
+    } else {
+        PlayerType::Human
+    };
+    let end_time = rusty2048_core::get_current_time();
+    let session_stats = rusty2048_core::create_session_stats(
+        game.score().current(),
+        game.moves(),
+        game.stats().duration,
+        game.board().max_tile(),
+        game.has_won(),
+        game_start_time,
+        end_time,
+    )
+    .with_move_timing(&game.stats())
+    .with_config(game.config())
+    .with_final_board(game.board())
+    .with_player(player);
+    let session_stats = match daily {
+        Some(challenge) => session_stats.with_daily(challenge),
+        None => session_stats,
+    };
+
+    let stats = charts_display.stats_manager();
+    let result = match *recorded_session {
+        Some(session_id) => stats.update_session(session_id, session_stats),
+        None => {
+            *recorded_session = Some(session_stats.session_id);
+            stats.record_session(session_stats)
+        }
+    };
+    if let Err(e) = result {
+        eprintln!("Failed to record game statistics: {}", e);
+    }
+}
+
+/// Play a move, grading it first when assist mode is on. The grade of a
+/// move that changes nothing is dropped.
+fn play_graded(
+    game: &mut Game,
+    direction: Direction,
+    grader: Option<&mut MoveGrader>,
+    last_grade: &mut Option<MoveAnalysis>,
+) {
+    let analysis = grader.and_then(|grader| grader.grade(game, direction));
+    if game.make_move(direction).is_ok_and(|result| result.moved) {
+        *last_grade = analysis;
+    }
+}
+
+/// Analysis bar of assist mode
+fn assist_line(last_grade: Option<&MoveAnalysis>) -> Line<'static> {
+    let Some(analysis) = last_grade else {
+        return Line::from(Span::styled(
+            "🎓 Assist: make a move to have it graded | G to turn off",
+            Style::default().fg(Color::Cyan),
+        ));
+    };
+    let color = match analysis.grade() {
+        MoveGrade::Best => Color::Green,
+        MoveGrade::Good => Color::Cyan,
+        MoveGrade::Inaccuracy => Color::Yellow,
+        MoveGrade::Blunder => Color::Red,
+    };
+    let detail = if analysis.grade() == MoveGrade::Best {
+        format!("{} was the best move", analysis.played)
+    } else {
+        format!(
+            "{} lost {:.0}%, {} was best",
+            analysis.played,
+            analysis.loss * 100.0,
+            analysis.best
+        )
+    };
+    Line::from(vec![
+        Span::raw("🎓 Assist: "),
+        Span::styled(
+            analysis.grade().to_string(),
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(format!(" ({})", detail)),
+    ])
 }
 
 fn format_duration(seconds: u64) -> String {
@@ -0,0 +1,260 @@
+//! The pause menu of the game screen, opened with Esc. It holds the
+//! commands that should not be one stray key press away: starting over,
+//! changing the board size, settings and quitting.
+
+use crate::language::LanguageManager;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use rusty2048_shared::{Action, TranslationKey};
+
+/// Board sizes the menu offers
+const BOARD_SIZES: std::ops::RangeInclusive<usize> = 3..=8;
+
+const MAIN_ITEMS: [TranslationKey; 5] = [
+    TranslationKey::Resume,
+    TranslationKey::NewGame,
+    TranslationKey::ChangeBoardSize,
+    TranslationKey::Settings,
+    TranslationKey::SaveAndQuit,
+];
+const SETTINGS_ITEMS: [TranslationKey; 5] = [
+    TranslationKey::Theme,
+    TranslationKey::Language,
+    TranslationKey::AssistMode,
+    TranslationKey::Charts,
+    TranslationKey::Back,
+];
+
+/// What the player picked in the menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuCommand {
+    /// Close the menu
+    Resume,
+    NewGame,
+    /// Start a new game on a board of this size
+    ChangeBoardSize(usize),
+    PrevTheme,
+    NextTheme,
+    PrevLanguage,
+    NextLanguage,
+    ToggleAssist,
+    ToggleCharts,
+    SaveAndQuit,
+}
+
+/// Current settings, shown next to their entries
+pub struct MenuStatus<'a> {
+    pub theme: &'a str,
+    pub language: &'a str,
+    pub assist: bool,
+    pub charts: bool,
+    /// Whether the board size may change; a daily challenge's may not
+    pub can_resize: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Page {
+    Main,
+    BoardSize,
+    Settings,
+}
+
+/// State of the open menu
+#[derive(Debug, Clone)]
+pub struct PauseMenu {
+    page: Page,
+    selected: usize,
+    /// Size picked on the board size page
+    board_size: usize,
+}
+
+impl PauseMenu {
+    pub fn new(board_size: usize) -> Self {
+        Self {
+            page: Page::Main,
+            selected: 0,
+            board_size,
+        }
+    }
+
+    /// Handle a key press. `action` is what the key is bound to on the
+    /// game screen, so remapped movement keys move through the menu too.
+    pub fn handle_key(&mut self, key: &KeyEvent, action: Option<Action>) -> Option<MenuCommand> {
+        let items = match self.page {
+            Page::Main => MAIN_ITEMS.len(),
+            Page::BoardSize => 1,
+            Page::Settings => SETTINGS_ITEMS.len(),
+        };
+        match (key.code, action) {
+            (KeyCode::Esc, _) | (_, Some(Action::Menu)) => {
+                if self.page == Page::Main {
+                    return Some(MenuCommand::Resume);
+                }
+                self.open(Page::Main);
+            }
+            (KeyCode::Up, _) | (_, Some(Action::MoveUp)) => {
+                self.selected = (self.selected + items - 1) % items;
+            }
+            (KeyCode::Down, _) | (_, Some(Action::MoveDown)) => {
+                self.selected = (self.selected + 1) % items;
+            }
+            (KeyCode::Left, _) | (_, Some(Action::MoveLeft)) => return self.adjust(false),
+            (KeyCode::Right, _) | (_, Some(Action::MoveRight)) => return self.adjust(true),
+            (KeyCode::Enter | KeyCode::Char(' '), _) => return self.select(),
+            _ => {}
+        }
+        None
+    }
+
+    fn open(&mut self, page: Page) {
+        // Back on the main page, the entry of the page left is selected
+        self.selected = match (self.page, page) {
+            (Page::BoardSize, Page::Main) => 2,
+            (Page::Settings, Page::Main) => 3,
+            _ => 0,
+        };
+        self.page = page;
+    }
+
+    fn select(&mut self) -> Option<MenuCommand> {
+        match (self.page, self.selected) {
+            (Page::Main, 0) => Some(MenuCommand::Resume),
+            (Page::Main, 1) => Some(MenuCommand::NewGame),
+            (Page::Main, 2) => {
+                self.open(Page::BoardSize);
+                None
+            }
+            (Page::Main, 3) => {
+                self.open(Page::Settings);
+                None
+            }
+            (Page::Main, _) => Some(MenuCommand::SaveAndQuit),
+            (Page::BoardSize, _) => Some(MenuCommand::ChangeBoardSize(self.board_size)),
+            (Page::Settings, 4) => {
+                self.open(Page::Main);
+                None
+            }
+            (Page::Settings, _) => self.adjust(true),
+        }
+    }
+
+    /// Change the selected value one step back or forward
+    fn adjust(&mut self, forward: bool) -> Option<MenuCommand> {
+        match (self.page, self.selected) {
+            (Page::BoardSize, _) => {
+                let size = if forward {
+                    self.board_size + 1
+                } else {
+                    self.board_size.saturating_sub(1)
+                };
+                self.board_size = size.clamp(*BOARD_SIZES.start(), *BOARD_SIZES.end());
+                None
+            }
+            (Page::Settings, 0) if forward => Some(MenuCommand::NextTheme),
+            (Page::Settings, 0) => Some(MenuCommand::PrevTheme),
+            (Page::Settings, 1) if forward => Some(MenuCommand::NextLanguage),
+            (Page::Settings, 1) => Some(MenuCommand::PrevLanguage),
+            (Page::Settings, 2) => Some(MenuCommand::ToggleAssist),
+            (Page::Settings, 3) => Some(MenuCommand::ToggleCharts),
+            _ => None,
+        }
+    }
+
+    /// Draw the menu centered over `area`
+    pub fn render(
+        &self,
+        f: &mut Frame,
+        area: Rect,
+        status: &MenuStatus,
+        language: &LanguageManager,
+    ) {
+        let (title, entries): (TranslationKey, Vec<String>) = match self.page {
+            Page::Main => (
+                TranslationKey::MenuPaused,
+                MAIN_ITEMS.iter().map(|item| language.t(item)).collect(),
+            ),
+            Page::BoardSize if status.can_resize => (
+                TranslationKey::BoardSize,
+                vec![format!("◀ {0}x{0} ▶", self.board_size)],
+            ),
+            Page::BoardSize => (
+                TranslationKey::BoardSize,
+                vec![language.t(&TranslationKey::BoardSizeFixed)],
+            ),
+            Page::Settings => {
+                let on_off = |on: bool| {
+                    language.t(if on {
+                        &TranslationKey::On
+                    } else {
+                        &TranslationKey::Off
+                    })
+                };
+                let values = [
+                    status.theme.to_string(),
+                    status.language.to_string(),
+                    on_off(status.assist),
+                    on_off(status.charts),
+                ];
+                let entries = SETTINGS_ITEMS
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| match values.get(i) {
+                        Some(value) => format!("{:<12} ◀ {} ▶", language.t(item), value),
+                        None => language.t(item),
+                    })
+                    .collect();
+                (TranslationKey::Settings, entries)
+            }
+        };
+
+        let mut lines: Vec<Line> = entries
+            .into_iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                if i == self.selected {
+                    Line::from(Span::styled(
+                        format!("> {} <", entry),
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD),
+                    ))
+                } else {
+                    Line::from(entry)
+                }
+            })
+            .collect();
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            language.t(match self.page {
+                Page::Main => &TranslationKey::MenuMainHelp,
+                _ => &TranslationKey::MenuPageHelp,
+            }),
+            Style::default().fg(Color::DarkGray),
+        )));
+
+        let width = 46.min(area.width);
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let popup = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        );
+        f.render_widget(Clear, popup);
+        f.render_widget(
+            Paragraph::new(lines).alignment(Alignment::Center).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(language.t(&title))
+                    .title_alignment(Alignment::Center),
+            ),
+            popup,
+        );
+    }
+}
@@ -1,10 +1,14 @@
 use rusty2048_core::{
-    Direction, GameConfig, ReplayData, ReplayMetadata, ReplayPlayer, ReplayRecorder,
+    analyze_replay, daily, recover_replay, AutoRecorder, Direction, Game, GameConfig, GameState,
+    PlaybackTiming, ReplayAnalysis, ReplayData, ReplayFile, ReplayManager, ReplayMetadata,
+    ReplayPlayer, ReplayRecorder, ReplayRetention, ReplaySort,
 };
 
 use crate::theme::ThemeManager;
 
-const REPLAY_DIR: &str = "replays";
+/// Recording in progress in the replay directory, kept so it survives a
+/// crash
+pub const STREAM_FILE: &str = "recording.jsonl";
 use crossterm::event::{self, Event, KeyCode, KeyEvent};
 use ratatui::{
     layout::{Constraint, Direction as LayoutDirection, Layout},
@@ -15,16 +19,85 @@ use ratatui::{
 };
 
 use std::{
-    fs, io,
+    io,
+    path::{Path, PathBuf},
     time::{Duration, Instant},
 };
 
+/// Saves a replay of every finished game to the replay directory, when
+/// enabled with `RUSTY2048_AUTO_RECORD`
+pub struct AutoRecording {
+    recorder: AutoRecorder,
+    manager: ReplayManager,
+    retention: ReplayRetention,
+}
+
+impl AutoRecording {
+    /// Start recording `game` if `RUSTY2048_AUTO_RECORD` is set (to anything
+    /// but `0`). `RUSTY2048_KEEP_BEST` and `RUSTY2048_KEEP_RECENT` override
+    /// how many automatic replays are kept.
+    pub fn from_env(game: &mut Game, dir: &Path) -> Option<Self> {
+        if std::env::var("RUSTY2048_AUTO_RECORD").map_or(true, |value| value == "0") {
+            return None;
+        }
+        let manager = ReplayManager::in_dir(dir).ok()?;
+        let mut retention = ReplayRetention::default();
+        let count = |name: &str| std::env::var(name).ok()?.parse::<usize>().ok();
+        if let Some(keep_best) = count("RUSTY2048_KEEP_BEST") {
+            retention.keep_best = keep_best;
+        }
+        if let Some(keep_recent) = count("RUSTY2048_KEEP_RECENT") {
+            retention.keep_recent = keep_recent;
+        }
+
+        Some(Self {
+            recorder: AutoRecorder::new(game),
+            manager,
+            retention,
+        })
+    }
+
+    /// Record the last action on `game`, saving the replay of a game that
+    /// finished
+    pub fn observe(&mut self, game: &mut Game) -> io::Result<()> {
+        let finished = self.recorder.observe(game).map_err(io::Error::other)?;
+        self.save(finished)
+    }
+
+    /// Save the game in progress if it was won, before quitting
+    pub fn finish(&mut self) -> io::Result<()> {
+        let finished = self.recorder.finish().into_iter().collect();
+        self.save(finished)
+    }
+
+    fn save(&mut self, replays: Vec<ReplayData>) -> io::Result<()> {
+        if replays.is_empty() {
+            return Ok(());
+        }
+        for replay in &replays {
+            self.manager.save_file(replay).map_err(io::Error::other)?;
+        }
+        self.manager
+            .apply_retention(self.retention)
+            .map_err(io::Error::other)?;
+        Ok(())
+    }
+}
+
 /// Replay mode for CLI
 pub struct ReplayMode {
     /// Current replay recorder (if recording)
     recorder: Option<ReplayRecorder>,
     /// Current replay player (if playing)
     player: Option<ReplayPlayer>,
+    /// Analysis of the replay being played, once requested
+    analysis: Option<ReplayAnalysis>,
+    /// Replay files in the replay directory
+    manager: ReplayManager,
+    /// Where the recording in progress is streamed
+    stream_file: PathBuf,
+    /// Replay file highlighted in the load screen
+    selected: usize,
     /// Theme manager
     theme_manager: ThemeManager,
     /// Current mode
@@ -45,13 +118,27 @@ enum ReplayModeState {
 
 impl ReplayMode {
     /// Create a new replay mode
-    pub fn new() -> io::Result<Self> {
-        // Ensure replay directory exists
-        Self::ensure_replay_dir()?;
+    pub fn new(dir: &Path) -> io::Result<Self> {
+        let mut manager = ReplayManager::in_dir(dir).map_err(io::Error::other)?;
+        let stream_file = dir.join(STREAM_FILE);
+
+        // A recording left behind by a crash is saved with the others
+        if stream_file.exists() {
+            if let Ok(replay_data) = recover_replay(&stream_file) {
+                if !replay_data.moves.is_empty() {
+                    manager.save_file(&replay_data).map_err(io::Error::other)?;
+                }
+            }
+            let _ = std::fs::remove_file(&stream_file);
+        }
 
         Ok(Self {
             recorder: None,
             player: None,
+            analysis: None,
+            manager,
+            stream_file,
+            selected: 0,
             theme_manager: ThemeManager::new(),
             mode: ReplayModeState::Menu,
             auto_play_interval: Duration::from_millis(500),
@@ -59,14 +146,6 @@ impl ReplayMode {
         })
     }
 
-    /// Ensure the replay directory exists
-    fn ensure_replay_dir() -> io::Result<()> {
-        if fs::metadata(REPLAY_DIR).is_err() {
-            fs::create_dir(REPLAY_DIR)?;
-        }
-        Ok(())
-    }
-
     /// Run the replay mode
     pub fn run<B: ratatui::backend::Backend>(
         &mut self,
@@ -186,9 +265,12 @@ impl ReplayMode {
                         self.mode = ReplayModeState::Recording;
                     }
                     KeyCode::Char('2') => {
+                        self.manager.scan().map_err(io::Error::other)?;
+                        self.selected = 0;
                         self.mode = ReplayModeState::LoadReplay;
                     }
                     KeyCode::Char('3') => {
+                        self.manager.scan().map_err(io::Error::other)?;
                         self.list_replays(terminal)?;
                     }
                     KeyCode::Char('4') | KeyCode::Char('q') => {
@@ -205,11 +287,17 @@ impl ReplayMode {
     /// Start recording a new game
     fn start_recording(&mut self) -> io::Result<()> {
         let config = GameConfig::default();
-        self.recorder = Some(
-            ReplayRecorder::new(config)
-                .map_err(|e| io::Error::other(format!("Failed to create recorder: {}", e)))?,
-        );
+        let recorder = ReplayRecorder::new(config)
+            .map_err(|e| io::Error::other(format!("Failed to create recorder: {}", e)))?;
+        self.stream_recording(recorder)
+    }
 
+    /// Record with `recorder`, streaming the recording to `STREAM_FILE`
+    fn stream_recording(&mut self, mut recorder: ReplayRecorder) -> io::Result<()> {
+        recorder
+            .stream_to(&self.stream_file)
+            .map_err(|e| io::Error::other(format!("Failed to start recording: {}", e)))?;
+        self.recorder = Some(recorder);
         Ok(())
     }
 
@@ -339,23 +427,50 @@ impl ReplayMode {
         Ok(true)
     }
 
+    /// Continue playing from the replay's current position, recording the
+    /// new line as a replay of its own
+    fn take_over(&mut self) -> io::Result<()> {
+        let Some(player) = self.player.as_mut() else {
+            return Ok(());
+        };
+        player.pause();
+        let mut game = player
+            .take_over()
+            .map_err(|e| io::Error::other(format!("Failed to take over: {}", e)))?;
+        if game.state() == GameState::Won {
+            let _ = game.continue_after_win();
+        }
+
+        let source = &player.replay_data().metadata.name;
+        let mut recorder = ReplayRecorder::from_game(game);
+        recorder.set_metadata(ReplayMetadata::default().with_notes(format!(
+            "Taken over at move {} of {}",
+            player.current_move_index(),
+            source
+        )));
+        self.stream_recording(recorder)?;
+        self.mode = ReplayModeState::Recording;
+        Ok(())
+    }
+
     /// Stop recording and save replay
     fn stop_recording(&mut self) -> io::Result<()> {
         if let Some(mut recorder) = self.recorder.take() {
             let replay_data = recorder.stop_recording();
 
             // Use default name for now (can be enhanced later with TUI input)
-            let metadata = ReplayMetadata::default();
+            let metadata = ReplayMetadata {
+                notes: replay_data.metadata.notes.clone(),
+                ..ReplayMetadata::default()
+            };
             let mut replay_data = replay_data;
             replay_data.metadata = metadata;
 
             // Save replay
-            let filename = format!("replay_{}.json", replay_data.metadata.created_at);
-            let filepath = format!("{}/{}", REPLAY_DIR, filename);
-            let json = serde_json::to_string_pretty(&replay_data)
-                .map_err(|e| io::Error::other(format!("Failed to serialize replay: {}", e)))?;
-
-            fs::write(&filepath, json)?;
+            self.manager
+                .save_file(&replay_data)
+                .map_err(|e| io::Error::other(format!("Failed to save replay: {}", e)))?;
+            let _ = std::fs::remove_file(&self.stream_file);
 
             // Show success message in TUI
             // Note: This will be called from within a terminal context
@@ -446,27 +561,22 @@ impl ReplayMode {
             f.render_widget(title, chunks[0]);
 
             // File list
-            let files = self.get_replay_files();
-            let rows: Vec<Row> = files
-                .iter()
-                .enumerate()
-                .map(|(i, file)| Row::new(vec![format!("{}", i + 1), file.clone()]))
-                .collect();
-
-            let table = Table::new(rows, &[Constraint::Length(3), Constraint::Min(0)])
-                .header(Row::new(vec!["#", "Filename"]))
-                .block(
-                    Block::default()
-                        .title("Available Replays")
-                        .borders(Borders::ALL)
-                        .style(Style::default().fg(crate::theme::hex_to_color(&theme.text_color))),
-                );
+            let sort = match self.manager.sort() {
+                ReplaySort::Newest => "newest first",
+                ReplaySort::Score => "best score first",
+            };
+            let table = replay_table(self.manager.files(), Some(self.selected)).block(
+                Block::default()
+                    .title(format!("Available Replays ({})", sort))
+                    .borders(Borders::ALL)
+                    .style(Style::default().fg(crate::theme::hex_to_color(&theme.text_color))),
+            );
             f.render_widget(table, chunks[1]);
 
             // Instructions
             let instructions = Paragraph::new(vec![
                 Line::from(vec![Span::styled(
-                    "Enter replay number to load",
+                    "Up/Down: Select, Enter or 1-9: Load, o: Sort, x: Delete",
                     Style::default().fg(Color::Yellow),
                 )]),
                 Line::from(vec![Span::styled(
@@ -480,20 +590,36 @@ impl ReplayMode {
         // Handle input
         if event::poll(Duration::from_millis(100))? {
             if let Event::Key(KeyEvent { code, .. }) = event::read()? {
+                let count = self.manager.files().len();
                 match code {
                     KeyCode::Char('q') => {
                         return Ok(false);
                     }
-                    KeyCode::Char(c) if c.is_ascii_digit() => {
-                        let index = c.to_digit(10).unwrap() as usize - 1;
-                        let files = self.get_replay_files();
-                        if index < files.len() {
-                            if let Err(e) = self.load_replay(&files[index]) {
-                                println!("Error loading replay: {}", e);
-                            } else {
-                                self.mode = ReplayModeState::Playing;
-                            }
-                        }
+                    KeyCode::Up => {
+                        self.selected = self.selected.saturating_sub(1);
+                    }
+                    KeyCode::Down => {
+                        self.selected = (self.selected + 1).min(count.saturating_sub(1));
+                    }
+                    KeyCode::Enter => {
+                        self.open_replay(self.selected);
+                    }
+                    KeyCode::Char(c @ '1'..='9') => {
+                        self.open_replay(c.to_digit(10).unwrap() as usize - 1);
+                    }
+                    KeyCode::Char('o') => {
+                        let sort = match self.manager.sort() {
+                            ReplaySort::Newest => ReplaySort::Score,
+                            ReplaySort::Score => ReplaySort::Newest,
+                        };
+                        self.manager.sort_files(sort);
+                        self.selected = 0;
+                    }
+                    KeyCode::Char('x') if self.selected < count => {
+                        self.manager
+                            .delete_file(self.selected)
+                            .map_err(io::Error::other)?;
+                        self.selected = self.selected.min(count.saturating_sub(2));
                     }
                     _ => {}
                 }
@@ -511,11 +637,17 @@ impl ReplayMode {
         // Handle auto-play logic first
         {
             let player = self.player.as_mut().unwrap();
-            if player.is_playing() && self.last_auto_play.elapsed() >= self.auto_play_interval {
-                if let Ok(true) = player.next_move() {
-                    self.last_auto_play = Instant::now();
-                } else {
-                    player.pause();
+            if player.is_playing() {
+                match player.next_move_delay(self.auto_play_interval) {
+                    Some(delay) if self.last_auto_play.elapsed() >= delay => {
+                        if let Ok(true) = player.next_move() {
+                            self.last_auto_play = Instant::now();
+                        } else {
+                            player.pause();
+                        }
+                    }
+                    Some(_) => {}
+                    None => player.pause(),
                 }
             }
         }
@@ -531,6 +663,7 @@ impl ReplayMode {
                 player.total_moves(),
                 player.speed(),
                 player.is_playing(),
+                player.timing(),
             )
         };
 
@@ -547,7 +680,7 @@ impl ReplayMode {
                     [
                         Constraint::Length(3),
                         Constraint::Length(16), // Increased from 10 to 16 for better square display
-                        Constraint::Length(5),
+                        Constraint::Length(8),
                         Constraint::Min(0),
                     ]
                     .as_ref(),
@@ -568,15 +701,23 @@ impl ReplayMode {
             self.render_game_board_from_data(f, &player_state.0, chunks[1]);
 
             // Replay controls
-            let controls = [
+            let mut controls = vec![
                 format!("Progress: {:.1}%", player_state.1),
                 format!("Move: {}/{}", player_state.2, player_state.3),
-                format!("Speed: {}x", player_state.4),
+                format!(
+                    "Speed: {}x ({})",
+                    player_state.4,
+                    match player_state.6 {
+                        PlaybackTiming::Fixed => "fixed interval",
+                        PlaybackTiming::RealTime => "real time",
+                    }
+                ),
                 format!(
                     "Status: {}",
                     if player_state.5 { "Playing" } else { "Paused" }
                 ),
             ];
+            controls.extend(analysis_lines(self.analysis.as_ref(), player_state.2));
 
             let controls_text: Vec<Line> = controls
                 .iter()
@@ -600,7 +741,7 @@ impl ReplayMode {
 
             // Instructions
             let instructions = Paragraph::new(vec![Line::from(vec![Span::styled(
-                "Space: Play/Pause, Left/Right: Step, +/-: Speed, q: Quit",
+                "Space: Play/Pause, Left/Right: Step, +/-: Speed, r: Real time, a: Analyze, t: Take over, q: Quit",
                 Style::default().fg(Color::Yellow),
             )])]);
             f.render_widget(instructions, chunks[3]);
@@ -613,8 +754,12 @@ impl ReplayMode {
                 match code {
                     KeyCode::Char('q') => {
                         self.player = None;
+                        self.analysis = None;
                         return Ok(false);
                     }
+                    KeyCode::Char('a') if self.analysis.is_none() => {
+                        self.analysis = analyze_replay(player.replay_data()).ok();
+                    }
                     KeyCode::Char(' ') => {
                         if player.is_playing() {
                             player.pause();
@@ -634,6 +779,15 @@ impl ReplayMode {
                     KeyCode::Char('-') => {
                         player.set_speed((player.speed() - 0.5).max(0.1));
                     }
+                    KeyCode::Char('r') => {
+                        player.set_timing(match player.timing() {
+                            PlaybackTiming::Fixed => PlaybackTiming::RealTime,
+                            PlaybackTiming::RealTime => PlaybackTiming::Fixed,
+                        });
+                    }
+                    KeyCode::Char('t') => {
+                        self.take_over()?;
+                    }
                     _ => {}
                 }
             }
@@ -646,43 +800,10 @@ impl ReplayMode {
     fn render_game_board_from_data(
         &self,
         f: &mut ratatui::Frame,
-        board_data: &[Vec<u32>],
+        board_data: &[Vec<u64>],
         area: ratatui::layout::Rect,
     ) {
-        let size = board_data.len();
-        let theme = &self.theme_manager.current_theme;
-
-        // Create layout for the game board
-        let board_chunks = Layout::default()
-            .direction(LayoutDirection::Vertical)
-            .constraints((0..size).map(|_| Constraint::Length(3)).collect::<Vec<_>>())
-            .split(area);
-
-        for (row, &chunk) in board_chunks.iter().enumerate() {
-            let row_chunks = Layout::default()
-                .direction(LayoutDirection::Horizontal)
-                .constraints((0..size).map(|_| Constraint::Length(8)).collect::<Vec<_>>())
-                .split(chunk);
-
-            for (col, &cell) in row_chunks.iter().enumerate() {
-                let value = board_data[row][col];
-                let text = if value == 0 {
-                    " ".to_string()
-                } else {
-                    value.to_string()
-                };
-
-                let tile_color = crate::theme::get_tile_color(value, theme);
-                let text_color = crate::theme::get_tile_text_color(value, theme);
-
-                let style = Style::default().fg(text_color).bg(tile_color);
-
-                let cell_widget = Paragraph::new(text)
-                    .block(Block::default().borders(Borders::ALL))
-                    .style(style);
-                f.render_widget(cell_widget, cell);
-            }
-        }
+        crate::board_view::render_board(f, area, board_data, &self.theme_manager.current_theme);
     }
 
     /// Render game board
@@ -697,33 +818,29 @@ impl ReplayMode {
         self.render_game_board_from_data(f, &board.to_vec(), area);
     }
 
-    /// Get list of replay files
-    fn get_replay_files(&self) -> Vec<String> {
-        let mut files = Vec::new();
-        if let Ok(entries) = fs::read_dir(REPLAY_DIR) {
-            for entry in entries.flatten() {
-                if let Some(name) = entry.file_name().to_str() {
-                    if name.ends_with(".json") && name.starts_with("replay_") {
-                        files.push(name.to_string());
-                    }
-                }
-            }
+    /// Load a replay file and start playing it
+    fn open_replay(&mut self, index: usize) {
+        if index >= self.manager.files().len() {
+            return;
+        }
+        match self.load_replay(index) {
+            Ok(()) => self.mode = ReplayModeState::Playing,
+            Err(e) => println!("Error loading replay: {}", e),
         }
-        files.sort();
-        files
     }
 
-    /// Load a replay file
-    fn load_replay(&mut self, filename: &str) -> io::Result<()> {
-        let filepath = format!("{}/{}", REPLAY_DIR, filename);
-        let content = fs::read_to_string(&filepath)?;
-        let replay_data: ReplayData = serde_json::from_str(&content)
+    /// Load a replay file, binary or JSON
+    fn load_replay(&mut self, index: usize) -> io::Result<()> {
+        let replay_data = self
+            .manager
+            .load_file(index)
             .map_err(|e| io::Error::other(format!("Failed to parse replay: {}", e)))?;
 
         self.player = Some(
             ReplayPlayer::new(replay_data)
                 .map_err(|e| io::Error::other(format!("Failed to create player: {}", e)))?,
         );
+        self.analysis = None;
 
         Ok(())
     }
@@ -761,29 +878,19 @@ impl ReplayMode {
             f.render_widget(title, chunks[0]);
 
             // File list
-            let files = self.get_replay_files();
+            let files = self.manager.files();
             if files.is_empty() {
                 let message = Paragraph::new("No replay files found.")
                     .style(Style::default().fg(crate::theme::hex_to_color(&theme.text_color)))
                     .alignment(ratatui::layout::Alignment::Center);
                 f.render_widget(message, chunks[1]);
             } else {
-                let rows: Vec<Row> = files
-                    .iter()
-                    .enumerate()
-                    .map(|(i, file)| Row::new(vec![format!("{}", i + 1), file.clone()]))
-                    .collect();
-
-                let table = Table::new(rows, &[Constraint::Length(3), Constraint::Min(0)])
-                    .header(Row::new(vec!["#", "Filename"]))
-                    .block(
-                        Block::default()
-                            .title("Available Replays")
-                            .borders(Borders::ALL)
-                            .style(
-                                Style::default().fg(crate::theme::hex_to_color(&theme.text_color)),
-                            ),
-                    );
+                let table = replay_table(files, None).block(
+                    Block::default()
+                        .title("Available Replays")
+                        .borders(Borders::ALL)
+                        .style(Style::default().fg(crate::theme::hex_to_color(&theme.text_color))),
+                );
                 f.render_widget(table, chunks[1]);
             }
 
@@ -802,3 +909,70 @@ impl ReplayMode {
         Ok(())
     }
 }
+
+/// Table of replay files, with the selected one highlighted
+fn replay_table(files: &[ReplayFile], selected: Option<usize>) -> Table<'static> {
+    let rows: Vec<Row> = files
+        .iter()
+        .enumerate()
+        .map(|(i, file)| {
+            let row = Row::new(vec![
+                format!("{}", i + 1),
+                file.metadata.name.clone(),
+                daily::date_string(file.metadata.created_at / daily::SECONDS_PER_DAY),
+                file.final_score.to_string(),
+                file.total_moves.to_string(),
+            ]);
+            if selected == Some(i) {
+                row.style(Style::default().add_modifier(Modifier::REVERSED))
+            } else {
+                row
+            }
+        })
+        .collect();
+
+    Table::new(
+        rows,
+        &[
+            Constraint::Length(3),
+            Constraint::Min(16),
+            Constraint::Length(10),
+            Constraint::Length(8),
+            Constraint::Length(6),
+        ],
+    )
+    .header(Row::new(vec!["#", "Name", "Date", "Score", "Moves"]))
+}
+
+/// Accuracy of an analyzed replay and how the last move played compares
+/// with the best one
+fn analysis_lines(analysis: Option<&ReplayAnalysis>, current_move: usize) -> Vec<String> {
+    let Some(analysis) = analysis else {
+        return vec!["Accuracy: press a to analyze".to_string()];
+    };
+    let mut lines = vec![format!(
+        "Accuracy: {:.1}% ({} mistakes)",
+        analysis.accuracy,
+        analysis.mistakes().count()
+    )];
+    if let Some(last) = current_move
+        .checked_sub(1)
+        .and_then(|index| analysis.move_at(index))
+    {
+        lines.push(if last.is_mistake() {
+            format!(
+                "Mistake: {:?} lost {:.0}%, {:?} was best",
+                last.played,
+                last.loss * 100.0,
+                last.best
+            )
+        } else {
+            format!(
+                "Last move: {:?} ({:.0}% lost)",
+                last.played,
+                last.loss * 100.0
+            )
+        });
+    }
+    lines
+}
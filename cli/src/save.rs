@@ -0,0 +1,55 @@
+use rusty2048_core::{FileBestScoreStore, Game, GameState, SavedGame};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// File holding the game in progress between runs, in the data directory
+pub const SAVE_FILE: &str = "saved_game.json";
+/// File holding the best score across runs, in the data directory
+pub const BEST_SCORE_FILE: &str = "best_score.txt";
+
+/// Where the game in progress is saved
+pub fn save_path() -> PathBuf {
+    crate::config::data_dir().join(SAVE_FILE)
+}
+
+/// Where the best score is kept
+pub fn best_score_path() -> PathBuf {
+    crate::config::data_dir().join(BEST_SCORE_FILE)
+}
+
+/// Load the game saved by the last run, if there is one
+pub fn load_game() -> Option<Game> {
+    let path = save_path();
+    if !path.exists() {
+        return None;
+    }
+
+    let saved = fs::read_to_string(path)
+        .ok()
+        .and_then(|json| serde_json::from_str::<SavedGame>(&json).ok())?;
+    Game::from_saved_state(saved).ok()
+}
+
+/// Save the game so the next run resumes it; finished games are not kept
+pub fn save_game(game: &Game) {
+    if game.state() != GameState::Playing {
+        discard_saved_game();
+        return;
+    }
+
+    if let Ok(json) = serde_json::to_string(&game.to_saved_state()) {
+        let _ = fs::write(save_path(), json);
+    }
+}
+
+/// Keep the game's best score in the best score file. An unreadable file
+/// is ignored and overwritten by the next best score.
+pub fn persist_best_score(game: &mut Game) {
+    let _ = game.set_best_score_store(Arc::new(FileBestScoreStore::new(best_score_path())));
+}
+
+/// Remove the saved game
+pub fn discard_saved_game() {
+    let _ = fs::remove_file(save_path());
+}
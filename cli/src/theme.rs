@@ -16,23 +16,12 @@ pub fn hex_to_color(hex: &str) -> Color {
 }
 
 /// Get tile color based on value and theme
-pub fn get_tile_color(value: u32, theme: &Theme) -> Color {
-    if value == 0 {
-        return hex_to_color(&theme.tile_colors[0]);
-    }
-
-    let color_index = value.trailing_zeros() as usize;
-    if color_index < theme.tile_colors.len() {
-        hex_to_color(&theme.tile_colors[color_index])
-    } else {
-        // For values beyond our color palette, cycle through colors
-        let index = (color_index - theme.tile_colors.len()) % (theme.tile_colors.len() - 1) + 1;
-        hex_to_color(&theme.tile_colors[index])
-    }
+pub fn get_tile_color(value: u64, theme: &Theme) -> Color {
+    hex_to_color(&theme.tile_colors[theme.tile_color_index(value)])
 }
 
 /// Get text color for tile based on value and theme
-pub fn get_tile_text_color(value: u32, theme: &Theme) -> Color {
+pub fn get_tile_text_color(value: u64, theme: &Theme) -> Color {
     if value == 0 {
         return hex_to_color(&theme.text_color);
     }
@@ -77,7 +66,6 @@ impl ThemeManager {
     }
 
     /// Switch to previous theme
-    #[allow(dead_code)]
     pub fn prev_theme(&mut self) {
         self.current_index = if self.current_index == 0 {
             self.themes.len() - 1
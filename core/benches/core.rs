@@ -0,0 +1,133 @@
+//! Benchmarks for the core hot paths: moves, move detection, board cloning
+//! and AI search. Compare runs with `scripts/bench-compare.sh`.
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use rusty2048_core::{AIAlgorithm, AIPlayer, Board, Direction, Game, GameConfig, GameState, Score};
+
+const SIZES: [usize; 3] = [4, 6, 8];
+
+/// A mid-game position: a mix of tiles, merges and empty cells
+fn mid_game_values(size: usize) -> Vec<u64> {
+    (0..size * size)
+        .map(|i| match i % 7 {
+            0 | 4 => 0,
+            n => 1 << (n + (i / size) % 3),
+        })
+        .collect()
+}
+
+/// A full board where no merge is possible (worst case for move detection)
+fn stuck_values(size: usize) -> Vec<u64> {
+    (0..size * size)
+        .map(|i| {
+            let (row, col) = (i / size, i % size);
+            if (row + col) % 2 == 0 {
+                2
+            } else {
+                4
+            }
+        })
+        .collect()
+}
+
+fn game_with(size: usize, values: Vec<u64>) -> Game {
+    let mut game = Game::new(GameConfig {
+        board_size: size,
+        seed: Some(42),
+        ..GameConfig::default()
+    })
+    .unwrap();
+    game.load_from_state(values, Score::new(), 0, GameState::Playing)
+        .unwrap();
+    game
+}
+
+fn board_with(size: usize, values: Vec<u64>) -> Board {
+    Board::from_vec(values.chunks(size).map(<[u64]>::to_vec).collect()).unwrap()
+}
+
+fn bench_moves(c: &mut Criterion) {
+    let mut group = c.benchmark_group("make_move");
+    for size in SIZES {
+        let game = game_with(size, mid_game_values(size));
+        for direction in [
+            Direction::Up,
+            Direction::Down,
+            Direction::Left,
+            Direction::Right,
+        ] {
+            group.bench_with_input(
+                BenchmarkId::new(format!("{:?}", direction), size),
+                &game,
+                |b, game| {
+                    b.iter_batched(
+                        || game.clone(),
+                        |mut game| game.make_move(black_box(direction)),
+                        BatchSize::SmallInput,
+                    )
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+fn bench_has_valid_moves(c: &mut Criterion) {
+    let mut group = c.benchmark_group("has_valid_moves");
+    for size in SIZES {
+        let open = board_with(size, mid_game_values(size));
+        let stuck = board_with(size, stuck_values(size));
+        group.bench_with_input(BenchmarkId::new("open", size), &open, |b, board| {
+            b.iter(|| black_box(board).has_valid_moves())
+        });
+        group.bench_with_input(BenchmarkId::new("stuck", size), &stuck, |b, board| {
+            b.iter(|| black_box(board).has_valid_moves())
+        });
+    }
+    group.finish();
+}
+
+fn bench_board_clone(c: &mut Criterion) {
+    let mut group = c.benchmark_group("clone_board");
+    for size in SIZES {
+        let board = board_with(size, mid_game_values(size));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &board, |b, board| {
+            b.iter(|| black_box(board).clone_board())
+        });
+    }
+    group.finish();
+}
+
+fn bench_ai(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ai_best_move");
+    group.sample_size(10);
+    let mut players = [
+        ("greedy", AIPlayer::new(AIAlgorithm::Greedy)),
+        (
+            "expectimax",
+            AIPlayer::new(AIAlgorithm::Expectimax).with_max_depth(2),
+        ),
+        (
+            "mcts",
+            AIPlayer::new(AIAlgorithm::MCTS).with_simulation_count(20),
+        ),
+    ];
+    for size in [4, 6] {
+        let game = game_with(size, mid_game_values(size));
+        for (name, player) in &mut players {
+            group.bench_with_input(BenchmarkId::new(*name, size), &game, |b, game| {
+                b.iter(|| player.get_best_move(black_box(game)))
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_moves,
+    bench_has_valid_moves,
+    bench_board_clone,
+    bench_ai
+);
+criterion_main!(benches);
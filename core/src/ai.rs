@@ -1,5 +1,56 @@
+//! Bots that play 2048.
+//!
+//! Every bot implements [`AIStrategy`]. The built-in ones are
+//! [`GreedyStrategy`], [`ExpectimaxStrategy`] and [`MctsStrategy`], named
+//! by [`AIAlgorithm`]; downstream crates plug in their own by implementing
+//! the trait and handing a boxed strategy to [`AIPlayer::with_strategy`] or
+//! [`AIGameController::with_strategy`].
+
 use crate::board::Tile;
-use crate::{Board, Direction, Game, GameConfig, GameResult};
+use crate::game::line_cells;
+use crate::ntuple::NTupleStrategy;
+use crate::rules::{self, GameRules};
+use crate::search::{CancellationToken, SearchControl, SearchProgress, SearchStatus};
+use crate::transposition::{TranspositionStats, TranspositionTable};
+use crate::{
+    Board, Direction, Game, GameConfig, GameError, GameResult, GameRng, GameState, MoveAnalysis,
+    MoveResult, SpawnedTile,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Empty cells a chance node considers before sampling, by default
+pub const DEFAULT_SAMPLE_THRESHOLD: usize = 8;
+
+/// Chance of a line of spawns below which Expectimax stops searching it,
+/// by default
+pub const DEFAULT_PROBABILITY_THRESHOLD: f64 = 1e-4;
+
+/// Tiles a chance node spawns, with their probabilities
+const SPAWN_PROBABILITIES: [(u64, f64); 2] = [(2, 0.9), (4, 0.1)];
+
+/// Deepest search of an Expectimax strategy with a time budget
+pub const MAX_ITERATIVE_DEPTH: usize = 12;
+
+/// Plies the adversary of `Game::make_adversarial_move` searches: its
+/// placement and the player's reply
+pub const ADVERSARY_DEPTH: usize = 2;
+
+/// Nodes a search visits between checks for cancellation and progress
+/// reports
+const CHECK_INTERVAL: u64 = 256;
+
+/// Longest random rollout of an MCTS simulation
+const MAX_ROLLOUT_MOVES: usize = 1000;
+
+/// Every direction, in the order the searches try them
+const DIRECTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Down,
+    Direction::Left,
+    Direction::Right,
+];
 
 /// AI algorithm types
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -10,72 +61,147 @@ pub enum AIAlgorithm {
     Expectimax,
     /// Monte Carlo Tree Search
     MCTS,
+    /// Minimax against an adversary placing the most harmful tile
+    Minimax,
+    /// N-tuple network evaluation, with the network given to
+    /// `ntuple::register_network`
+    NTuple,
 }
 
-/// AI player for 2048 game
-pub struct AIPlayer {
-    algorithm: AIAlgorithm,
-    max_depth: usize,
-    simulation_count: usize,
+impl AIAlgorithm {
+    /// The built-in strategy with its default settings
+    pub fn strategy(self) -> Box<dyn AIStrategy> {
+        match self {
+            AIAlgorithm::Greedy => Box::new(GreedyStrategy),
+            AIAlgorithm::Expectimax => Box::new(ExpectimaxStrategy::default()),
+            AIAlgorithm::MCTS => Box::new(MctsStrategy::default()),
+            AIAlgorithm::Minimax => Box::new(MinimaxStrategy::default()),
+            AIAlgorithm::NTuple => Box::new(NTupleStrategy::registered()),
+        }
+    }
 }
 
-impl AIPlayer {
-    /// Create a new AI player
-    pub fn new(algorithm: AIAlgorithm) -> Self {
-        let max_depth = match algorithm {
-            AIAlgorithm::Greedy => 1,
-            AIAlgorithm::Expectimax => 4,
-            AIAlgorithm::MCTS => 1000,
-        };
+/// Weights of the terms of the board evaluation used by Expectimax and
+/// MCTS. A weight of 0 turns its term off.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HeuristicConfig {
+    /// Tile values weighted by position, corners and edges highest
+    pub position: f64,
+    /// Sum of the corner tiles
+    pub corner: f64,
+    /// Penalty per tile of 8 or less
+    pub scattered: f64,
+    /// `Board::smoothness`
+    pub smoothness: f64,
+    /// `Board::monotonicity`, scaled by the largest tile
+    pub monotonicity: f64,
+    /// `Board::snake_weighting`
+    pub snake: f64,
+    /// How deep Expectimax searches each position
+    #[serde(default)]
+    pub depth: DepthPolicy,
+}
 
-        let simulation_count = match algorithm {
-            AIAlgorithm::Greedy => 1,
-            AIAlgorithm::Expectimax => 1,
-            AIAlgorithm::MCTS => 100,
-        };
+/// How deep Expectimax searches a position, in plies (a move and a spawn
+/// are two). A time budget replaces either policy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DepthPolicy {
+    /// Always the strategy's maximum depth
+    #[default]
+    Fixed,
+    /// Deeper the fewer cells are empty: `min_depth` on an empty board,
+    /// rising evenly to `max_depth` on a full one. Open boards are
+    /// forgiving and have many spawns to weigh, crowded ones are where
+    /// games are lost and branch little.
+    ByEmptyCells { min_depth: usize, max_depth: usize },
+}
 
-        Self {
-            algorithm,
-            max_depth,
-            simulation_count,
+impl DepthPolicy {
+    /// Depth to search `board` at; `fixed_depth` for `Fixed`
+    pub fn depth_for(&self, board: &Board, fixed_depth: usize) -> usize {
+        match *self {
+            DepthPolicy::Fixed => fixed_depth,
+            DepthPolicy::ByEmptyCells {
+                min_depth,
+                max_depth,
+            } => {
+                let cells = board.size() * board.size();
+                let filled = cells - board.empty_positions().len();
+                min_depth + max_depth.saturating_sub(min_depth) * filled / cells
+            }
         }
     }
+}
 
-    /// Set the maximum search depth
-    pub fn with_max_depth(mut self, depth: usize) -> Self {
-        self.max_depth = depth;
-        self
+impl Default for HeuristicConfig {
+    fn default() -> Self {
+        Self::classic()
     }
+}
 
-    /// Set the number of simulations for MCTS
-    pub fn with_simulation_count(mut self, count: usize) -> Self {
-        self.simulation_count = count;
-        self
+impl HeuristicConfig {
+    /// The original evaluation: corners, edges and smoothness
+    pub fn classic() -> Self {
+        Self {
+            position: 1.0,
+            corner: 2.0,
+            scattered: 0.5,
+            smoothness: 0.1,
+            monotonicity: 0.0,
+            snake: 0.0,
+            depth: DepthPolicy::Fixed,
+        }
     }
 
-    /// Get the best move for the current game state
-    pub fn get_best_move(&self, game: &Game) -> GameResult<Direction> {
-        match self.algorithm {
-            AIAlgorithm::Greedy => self.greedy_move(game),
-            AIAlgorithm::Expectimax => self.expectimax_move(game),
-            AIAlgorithm::MCTS => self.mcts_move(game),
+    /// Keep the tiles sorted along a snake from one corner, the usual
+    /// strategy of strong players
+    pub fn snake() -> Self {
+        Self {
+            position: 0.0,
+            corner: 0.0,
+            scattered: 0.5,
+            smoothness: 0.1,
+            monotonicity: 1.0,
+            snake: 4.0,
+            depth: DepthPolicy::Fixed,
         }
     }
+}
+
+/// A bot choosing moves
+pub trait AIStrategy: Send {
+    /// Move to play in the current position. It may leave the board
+    /// unchanged when nothing looks better; `Game::hint` skips such moves.
+    fn choose(&mut self, game: &Game) -> Direction;
+
+    /// Human-readable name
+    fn name(&self) -> &str {
+        "Custom"
+    }
+
+    /// Transposition table counters of a searching strategy
+    fn cache_stats(&self) -> Option<TranspositionStats> {
+        None
+    }
+
+    /// Let searches be cancelled and report their progress. Strategies
+    /// that decide quickly can ignore it.
+    fn set_control(&mut self, _control: SearchControl) {}
+}
+
+/// Simple greedy algorithm - choose the move that gives the highest immediate score
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GreedyStrategy;
 
-    /// Simple greedy algorithm - choose the move that gives the highest immediate score
-    fn greedy_move(&self, game: &Game) -> GameResult<Direction> {
+impl AIStrategy for GreedyStrategy {
+    fn choose(&mut self, game: &Game) -> Direction {
         let mut best_score = 0;
         let mut best_direction = Direction::Up;
 
-        for &direction in &[
-            Direction::Up,
-            Direction::Down,
-            Direction::Left,
-            Direction::Right,
-        ] {
+        for direction in DIRECTIONS {
             let mut game_copy = game.clone();
-            if let Ok(moved) = game_copy.make_move(direction) {
-                if moved {
+            if let Ok(result) = game_copy.make_move(direction) {
+                if result.moved {
                     let score = game_copy.score().current();
                     if score > best_score {
                         best_score = score;
@@ -85,289 +211,1013 @@ impl AIPlayer {
             }
         }
 
-        Ok(best_direction)
+        best_direction
     }
 
-    /// Expectimax algorithm - considers both player moves and random tile placements
-    fn expectimax_move(&self, game: &Game) -> GameResult<Direction> {
-        let mut best_score = f64::NEG_INFINITY;
-        let mut best_direction = Direction::Up;
+    fn name(&self) -> &str {
+        "Greedy"
+    }
+}
 
-        for &direction in &[
-            Direction::Up,
-            Direction::Down,
-            Direction::Left,
-            Direction::Right,
-        ] {
-            let mut game_copy = game.clone();
-            if let Ok(moved) = game_copy.make_move(direction) {
-                if moved {
-                    let score = self.expectimax_search(&game_copy, self.max_depth - 1, false);
-                    if score > best_score {
-                        best_score = score;
-                        best_direction = direction;
-                    }
-                }
+/// Expectimax algorithm - considers both player moves and random tile placements
+#[derive(Debug, Clone)]
+pub struct ExpectimaxStrategy {
+    max_depth: usize,
+    sample_threshold: usize,
+    /// Chance below which a position is evaluated instead of searched
+    probability_threshold: f64,
+    heuristic: HeuristicConfig,
+    table: TranspositionTable,
+    /// Rules the table's values were searched under
+    rules_id: String,
+    /// Time allowed per move, searching deeper until it runs out
+    time_budget: Option<Duration>,
+    /// When the current search must stop, in ms since the epoch
+    deadline_ms: Option<u64>,
+    /// Nodes searched by the current search
+    nodes: u64,
+    /// Depth of the current search, for progress reports
+    depth: usize,
+    /// Best move of the deepest finished search, for progress reports
+    best: Option<Direction>,
+    /// Whether the current search ran out of time or was cancelled
+    aborted: bool,
+    control: SearchControl,
+}
+
+impl Default for ExpectimaxStrategy {
+    fn default() -> Self {
+        Self {
+            max_depth: 4,
+            sample_threshold: DEFAULT_SAMPLE_THRESHOLD,
+            probability_threshold: DEFAULT_PROBABILITY_THRESHOLD,
+            heuristic: HeuristicConfig::default(),
+            table: TranspositionTable::default(),
+            rules_id: String::new(),
+            time_budget: None,
+            deadline_ms: None,
+            nodes: 0,
+            depth: 0,
+            best: None,
+            aborted: false,
+            control: SearchControl::default(),
+        }
+    }
+}
+
+impl ExpectimaxStrategy {
+    /// Set the maximum search depth, searched unless the heuristic's
+    /// `DepthPolicy` adapts it
+    pub fn with_max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Search each move by iterative deepening for at most `budget`,
+    /// playing the best move of the deepest search that finished (at
+    /// least one ply deep, up to `MAX_ITERATIVE_DEPTH`). Replaces the
+    /// fixed depth.
+    pub fn with_time_budget(mut self, budget: Duration) -> Self {
+        self.time_budget = Some(budget);
+        self
+    }
+
+    /// Set how many empty cells a chance node considers before it samples
+    /// an evenly spread subset of them instead of all of them
+    pub fn with_sample_threshold(mut self, threshold: usize) -> Self {
+        self.sample_threshold = threshold.max(1);
+        self.table.clear();
+        self
+    }
+
+    /// Stop searching positions whose spawns are less likely than
+    /// `threshold` together, evaluating them instead. The search keeps
+    /// looking deep along likely lines only, so deeper searches become
+    /// affordable; 0 searches every position to the full depth.
+    pub fn with_probability_threshold(mut self, threshold: f64) -> Self {
+        self.probability_threshold = threshold.max(0.0);
+        self.table.clear();
+        self
+    }
+
+    /// Set how boards are evaluated at the leaves of the search
+    pub fn with_heuristic(mut self, heuristic: HeuristicConfig) -> Self {
+        self.heuristic = heuristic;
+        self.table.clear();
+        self
+    }
+
+    /// Set the number of slots of the transposition table; 0 disables it
+    pub fn with_cache_capacity(mut self, capacity: usize) -> Self {
+        self.table = TranspositionTable::new(capacity);
+        self
+    }
+
+    /// Transposition table counters, for tuning its capacity
+    pub fn cache_stats(&self) -> TranspositionStats {
+        self.table.stats()
+    }
+
+    /// Expectimax evaluation of every move that changes the board. Higher
+    /// is better.
+    pub fn evaluate_moves(&mut self, game: &Game) -> Vec<(Direction, f64)> {
+        self.deadline_ms = None;
+        self.start_search();
+        let depth = self.heuristic.depth.depth_for(game.board(), self.max_depth);
+        let values = self.evaluate_at_depth(game, depth).unwrap_or_default();
+        self.best = best_move(&values);
+        self.report();
+        values
+    }
+
+    /// Evaluate the moves searching `max_depth` plies, or `None` if the
+    /// deadline passed first
+    fn evaluate_at_depth(
+        &mut self,
+        game: &Game,
+        max_depth: usize,
+    ) -> Option<Vec<(Direction, f64)>> {
+        let rules = game.rules();
+        if self.rules_id != rules.id() {
+            self.table.clear();
+            self.rules_id = rules.id().to_string();
+        }
+        self.table.new_search();
+        self.depth = max_depth;
+
+        let depth = max_depth.saturating_sub(1);
+        let values = DIRECTIONS
+            .into_iter()
+            .filter_map(|direction| {
+                let board = slide_board(rules, game.board(), direction)?;
+                Some((direction, self.search(rules, &board, depth, 1.0, false)))
+            })
+            .collect();
+        (!self.aborted).then_some(values)
+    }
+
+    /// Best move of each deeper search until the time budget runs out
+    fn choose_within(&mut self, game: &Game, budget: Duration) -> Direction {
+        // One ply only evaluates boards, so it always finishes in time
+        self.deadline_ms = None;
+        self.start_search();
+        self.best = self
+            .evaluate_at_depth(game, 1)
+            .and_then(|values| best_move(&values));
+        self.report();
+
+        self.deadline_ms = Some(crate::get_current_time_ms() + budget.as_millis() as u64);
+        for depth in 2..=MAX_ITERATIVE_DEPTH {
+            match self.evaluate_at_depth(game, depth) {
+                Some(values) => self.best = best_move(&values).or(self.best),
+                None => break,
             }
+            self.report();
         }
+        self.deadline_ms = None;
+        self.best.unwrap_or(Direction::Up)
+    }
 
-        Ok(best_direction)
+    fn start_search(&mut self) {
+        self.nodes = 0;
+        self.best = None;
+        self.aborted = false;
     }
 
-    /// Expectimax search implementation
-    fn expectimax_search(&self, game: &Game, depth: usize, is_maximizing: bool) -> f64 {
-        if depth == 0 || game.state() != crate::GameState::Playing {
-            return self.evaluate_board(game.board());
+    fn report(&self) {
+        self.control.report(SearchProgress {
+            nodes: self.nodes,
+            depth: self.depth,
+            best: self.best,
+        });
+    }
+
+    /// Whether the search must stop because it was cancelled or its time
+    /// is up. Both are checked, and progress reported, every few hundred
+    /// nodes only.
+    fn out_of_time(&mut self) -> bool {
+        if self.aborted {
+            return true;
+        }
+        self.nodes += 1;
+        if self.nodes.is_multiple_of(CHECK_INTERVAL) {
+            self.aborted = self.control.is_cancelled()
+                || self
+                    .deadline_ms
+                    .is_some_and(|deadline_ms| crate::get_current_time_ms() >= deadline_ms);
+            self.report();
+        }
+        self.aborted
+    }
+
+    /// Expectimax search, looking nodes up in the transposition table.
+    /// `probability` is the chance of the spawns leading to `board`.
+    fn search(
+        &mut self,
+        rules: &dyn GameRules,
+        board: &Board,
+        depth: usize,
+        probability: f64,
+        is_maximizing: bool,
+    ) -> f64 {
+        // Unlikely positions are evaluated instead of searched
+        if depth == 0 || probability < self.probability_threshold {
+            return evaluate_board(board, &self.heuristic);
+        }
+
+        if self.out_of_time() {
+            return 0.0;
+        }
+
+        let hash = board.zobrist_hash();
+        if let Some(value) = self.table.get(hash, depth, is_maximizing) {
+            return value;
         }
+        let value = self.search_node(rules, board, depth, probability, is_maximizing);
+        // An interrupted node has no meaningful value
+        if !self.aborted {
+            self.table.insert(hash, depth, is_maximizing, value);
+        }
+        value
+    }
 
+    /// Search one node. Player nodes take the best slide; chance nodes
+    /// average over a 2 (90%) or a 4 (10%) spawning in each empty cell.
+    fn search_node(
+        &mut self,
+        rules: &dyn GameRules,
+        board: &Board,
+        depth: usize,
+        probability: f64,
+        is_maximizing: bool,
+    ) -> f64 {
         if is_maximizing {
-            // Player's turn - maximize score
-            let mut max_score = f64::NEG_INFINITY;
-            for &direction in &[
-                Direction::Up,
-                Direction::Down,
-                Direction::Left,
-                Direction::Right,
-            ] {
-                let mut game_copy = game.clone();
-                if let Ok(moved) = game_copy.make_move(direction) {
-                    if moved {
-                        let score = self.expectimax_search(&game_copy, depth - 1, false);
-                        max_score = max_score.max(score);
-                    }
-                }
-            }
-            max_score
+            // Player's turn - maximize score; a board with no move is lost
+            DIRECTIONS
+                .into_iter()
+                .filter_map(|direction| slide_board(rules, board, direction))
+                .map(|next| self.search(rules, &next, depth - 1, probability, false))
+                .reduce(f64::max)
+                .unwrap_or_else(|| evaluate_board(board, &self.heuristic))
         } else {
-            // Random tile placement - expect average score
-            let empty_positions = game.board().empty_positions();
+            // Tile placement - expect the weighted average score
+            let empty_positions = board.empty_positions();
             if empty_positions.is_empty() {
-                return self.evaluate_board(game.board());
+                return self.search(rules, board, depth - 1, probability, true);
             }
 
+            let step = empty_positions.len().div_ceil(self.sample_threshold);
+            let cells = empty_positions.len().div_ceil(step) as f64;
             let mut total_score = 0.0;
-            let mut count = 0;
-
-            // Sample a few random tile placements
-            for _ in 0..self.simulation_count.min(empty_positions.len()) {
-                let mut game_copy = game.clone();
-                if let Ok(()) = self.add_random_tile_simulation(&mut game_copy) {
-                    let score = self.expectimax_search(&game_copy, depth - 1, true);
-                    total_score += score;
-                    count += 1;
+            for &(row, col) in empty_positions.iter().step_by(step) {
+                for (value, spawn_probability) in SPAWN_PROBABILITIES {
+                    let mut next = board.clone();
+                    if next.set_tile(row, col, Tile::new(value)).is_ok() {
+                        let next_probability = probability * spawn_probability / cells;
+                        total_score += spawn_probability
+                            * self.search(rules, &next, depth - 1, next_probability, true);
+                    }
                 }
             }
+            total_score / cells
+        }
+    }
+}
+
+impl AIStrategy for ExpectimaxStrategy {
+    fn choose(&mut self, game: &Game) -> Direction {
+        match self.time_budget {
+            Some(budget) => self.choose_within(game, budget),
+            None => best_move(&self.evaluate_moves(game)).unwrap_or(Direction::Up),
+        }
+    }
+
+    fn name(&self) -> &str {
+        "Expectimax"
+    }
+
+    fn cache_stats(&self) -> Option<TranspositionStats> {
+        Some(self.table.stats())
+    }
+
+    fn set_control(&mut self, control: SearchControl) {
+        self.control = control;
+    }
+}
+
+/// An Expectimax search that runs in steps of a time budget each, for
+/// targets such as wasm where a long search must not block the thread.
+/// It deepens iteratively up to the strategy's depth (`MAX_ITERATIVE_DEPTH`
+/// with a time budget); work cut short by a step's budget is kept in the
+/// transposition table, so the next step picks up where it stopped.
+#[derive(Debug, Clone)]
+pub struct IncrementalSearch {
+    game: Game,
+    strategy: ExpectimaxStrategy,
+    /// Depth to search next
+    depth: usize,
+    /// Depth at which the search is done
+    target_depth: usize,
+    /// Best move of the deepest finished depth
+    best: Option<Direction>,
+    done: bool,
+}
+
+impl IncrementalSearch {
+    /// Search a move for `game` with `strategy`
+    pub fn new(game: &Game, mut strategy: ExpectimaxStrategy) -> Self {
+        let target_depth = match strategy.time_budget {
+            Some(_) => MAX_ITERATIVE_DEPTH,
+            None => strategy
+                .heuristic
+                .depth
+                .depth_for(game.board(), strategy.max_depth),
+        };
+        strategy.start_search();
+        Self {
+            game: game.clone(),
+            strategy,
+            depth: 1,
+            target_depth: target_depth.max(1),
+            best: None,
+            done: false,
+        }
+    }
+
+    /// The game being searched
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// Best move found so far
+    pub fn best(&self) -> Option<Direction> {
+        self.best
+    }
 
-            if count > 0 {
-                total_score / count as f64
+    /// Whether the search reached its full depth
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// How far the search got: the depth being searched, or the full
+    /// depth once done
+    pub fn progress(&self) -> SearchProgress {
+        SearchProgress {
+            nodes: self.strategy.nodes,
+            depth: if self.done {
+                self.depth - 1
             } else {
-                self.evaluate_board(game.board())
+                self.depth
+            },
+            best: self.best,
+        }
+    }
+
+    /// Search for at most about `budget`, deeper for each finished depth
+    pub fn step(&mut self, budget: Duration) -> SearchStatus {
+        if self.done {
+            return SearchStatus::Done(self.best);
+        }
+
+        let strategy = &mut self.strategy;
+        strategy.aborted = false;
+        strategy.deadline_ms = Some(crate::get_current_time_ms() + budget.as_millis() as u64);
+        while self.depth <= self.target_depth {
+            let Some(values) = strategy.evaluate_at_depth(&self.game, self.depth) else {
+                break;
+            };
+            self.best = best_move(&values).or(self.best);
+            strategy.best = self.best;
+            self.depth += 1;
+        }
+        strategy.deadline_ms = None;
+        strategy.report();
+
+        if self.depth > self.target_depth {
+            self.done = true;
+            return SearchStatus::Done(self.best);
+        }
+        SearchStatus::Searching(self.progress())
+    }
+
+    /// Take the strategy back, keeping its transposition table for the
+    /// next move's search
+    pub fn into_strategy(self) -> ExpectimaxStrategy {
+        self.strategy
+    }
+}
+
+/// Minimax with alpha-beta pruning: instead of spawning at random, an
+/// adversary places whichever 2 or 4 is worst for the player. The values
+/// are what the player can guarantee, so it plays pessimistically; the
+/// adversary alone makes a "cruel computer" opponent through
+/// `Game::make_adversarial_move`.
+#[derive(Debug, Clone)]
+pub struct MinimaxStrategy {
+    max_depth: usize,
+    heuristic: HeuristicConfig,
+    /// Nodes searched by the current search
+    nodes: u64,
+    /// Whether the current search was cancelled
+    cancelled: bool,
+    control: SearchControl,
+}
+
+impl Default for MinimaxStrategy {
+    fn default() -> Self {
+        Self {
+            max_depth: 4,
+            heuristic: HeuristicConfig::default(),
+            nodes: 0,
+            cancelled: false,
+            control: SearchControl::default(),
+        }
+    }
+}
+
+impl MinimaxStrategy {
+    /// Set the maximum search depth, counting the player's and the
+    /// adversary's plies
+    pub fn with_max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Set how boards are evaluated at the leaves of the search
+    pub fn with_heuristic(mut self, heuristic: HeuristicConfig) -> Self {
+        self.heuristic = heuristic;
+        self
+    }
+
+    /// Worst-case evaluation of every move that changes the board. Higher
+    /// is better. Empty if the search was cancelled.
+    pub fn evaluate_moves(&mut self, game: &Game) -> Vec<(Direction, f64)> {
+        let rules = game.rules();
+        let depth = self.max_depth.saturating_sub(1);
+        self.nodes = 0;
+        self.cancelled = false;
+
+        let mut values = Vec::new();
+        for direction in DIRECTIONS {
+            let Some(board) = slide_board(rules, game.board(), direction) else {
+                continue;
+            };
+            let value = self.search(
+                rules,
+                &board,
+                depth,
+                f64::NEG_INFINITY,
+                f64::INFINITY,
+                false,
+            );
+            if self.cancelled {
+                return Vec::new();
+            }
+            values.push((direction, value));
+            self.report(best_move(&values));
+        }
+        values
+    }
+
+    /// The tile the adversary places on `board`, the one after which the
+    /// player's best outcome is lowest. `None` when the board is full.
+    pub fn worst_spawn(&mut self, rules: &dyn GameRules, board: &Board) -> Option<SpawnedTile> {
+        let depth = self.max_depth.saturating_sub(1);
+        let mut worst: Option<(SpawnedTile, f64)> = None;
+        for (tile, next) in spawns(board) {
+            let beta = worst.map_or(f64::INFINITY, |(_, value)| value);
+            let value = self.search(rules, &next, depth, f64::NEG_INFINITY, beta, true);
+            if worst.is_none_or(|(_, worst_value)| value < worst_value) {
+                worst = Some((tile, value));
             }
         }
+        worst.map(|(tile, _)| tile)
+    }
+
+    fn report(&self, best: Option<Direction>) {
+        self.control.report(SearchProgress {
+            nodes: self.nodes,
+            depth: self.max_depth,
+            best,
+        });
+    }
+
+    /// Alpha-beta search. The player takes the best slide; the adversary
+    /// the worst spawn.
+    fn search(
+        &mut self,
+        rules: &dyn GameRules,
+        board: &Board,
+        depth: usize,
+        mut alpha: f64,
+        mut beta: f64,
+        is_maximizing: bool,
+    ) -> f64 {
+        if depth == 0 {
+            return evaluate_board(board, &self.heuristic);
+        }
+
+        self.nodes += 1;
+        if self.nodes.is_multiple_of(CHECK_INTERVAL) {
+            self.cancelled |= self.control.is_cancelled();
+        }
+        if self.cancelled {
+            return 0.0;
+        }
+
+        if is_maximizing {
+            // A board with no move is lost
+            let mut best = None;
+            for next in DIRECTIONS
+                .into_iter()
+                .filter_map(|direction| slide_board(rules, board, direction))
+            {
+                let value = self.search(rules, &next, depth - 1, alpha, beta, false);
+                best = Some(best.map_or(value, |best: f64| best.max(value)));
+                alpha = alpha.max(value);
+                if alpha >= beta {
+                    break;
+                }
+            }
+            best.unwrap_or_else(|| evaluate_board(board, &self.heuristic))
+        } else {
+            let mut worst = None;
+            for (_, next) in spawns(board) {
+                let value = self.search(rules, &next, depth - 1, alpha, beta, true);
+                worst = Some(worst.map_or(value, |worst: f64| worst.min(value)));
+                beta = beta.min(value);
+                if alpha >= beta {
+                    break;
+                }
+            }
+            // Nothing spawns on a full board
+            worst.unwrap_or_else(|| self.search(rules, board, depth - 1, alpha, beta, true))
+        }
+    }
+}
+
+impl AIStrategy for MinimaxStrategy {
+    fn choose(&mut self, game: &Game) -> Direction {
+        best_move(&self.evaluate_moves(game)).unwrap_or(Direction::Up)
+    }
+
+    fn name(&self) -> &str {
+        "Minimax"
+    }
+
+    fn set_control(&mut self, control: SearchControl) {
+        self.control = control;
+    }
+}
+
+/// Every tile a spawn could add to `board`, with the board it makes
+fn spawns(board: &Board) -> impl Iterator<Item = (SpawnedTile, Board)> + '_ {
+    board
+        .empty_positions()
+        .into_iter()
+        .flat_map(|(row, col)| {
+            SPAWN_PROBABILITIES
+                .into_iter()
+                .map(move |(value, _)| SpawnedTile { row, col, value })
+        })
+        .filter_map(|tile| {
+            let mut next = board.clone();
+            next.set_tile(tile.row, tile.col, Tile::new(tile.value))
+                .ok()?;
+            Some((tile, next))
+        })
+}
+
+/// Monte Carlo Tree Search algorithm
+#[derive(Debug, Clone)]
+pub struct MctsStrategy {
+    simulations: usize,
+    heuristic: HeuristicConfig,
+    /// Drives the random rollouts
+    rng: GameRng,
+    control: SearchControl,
+}
+
+impl Default for MctsStrategy {
+    fn default() -> Self {
+        Self {
+            simulations: 100,
+            heuristic: HeuristicConfig::default(),
+            rng: GameRng::new(None),
+            control: SearchControl::default(),
+        }
+    }
+}
+
+impl MctsStrategy {
+    /// Seed the rollouts, so the same positions get the same moves
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = GameRng::new(Some(seed));
+        self
     }
 
-    /// Monte Carlo Tree Search algorithm
-    fn mcts_move(&self, game: &Game) -> GameResult<Direction> {
+    /// Set how simulated games are scored
+    pub fn with_heuristic(mut self, heuristic: HeuristicConfig) -> Self {
+        self.heuristic = heuristic;
+        self
+    }
+
+    /// Set the number of simulations per move
+    pub fn with_simulations(mut self, simulations: usize) -> Self {
+        self.simulations = simulations;
+        self
+    }
+}
+
+impl AIStrategy for MctsStrategy {
+    fn choose(&mut self, game: &Game) -> Direction {
         let mut root = MCTSNode::new(game.clone());
+        // Deepest node selected so far, for progress reports
+        let mut deepest = 0;
 
-        for _ in 0..self.simulation_count {
+        for simulation in 1..=self.simulations {
+            if self.control.is_cancelled() {
+                break;
+            }
             let mut current = &mut root;
             let mut game_state = game.clone();
 
             // Selection
+            let mut depth = 0;
             while !current.children.is_empty() && current.visits > 0 {
                 current = current.select_child();
+                depth += 1;
                 if let Some(direction) = current.last_move {
                     let _ = game_state.make_move(direction);
                 }
             }
+            deepest = deepest.max(depth);
 
             // Expansion
-            if current.visits > 0 && game_state.state() == crate::GameState::Playing {
+            if current.visits > 0 && game_state.state() == GameState::Playing {
                 current.expand(&game_state);
             }
 
             // Simulation
-            let mut simulation_game = game_state.clone();
-            let simulation_result = self.simulate_random_game(&mut simulation_game);
+            let simulation_result = random_rollout(
+                game_state.rules(),
+                game_state.board(),
+                &mut self.rng,
+                &self.heuristic,
+            );
 
             // Backpropagation
             current.backpropagate(simulation_result);
+
+            self.control.report(SearchProgress {
+                nodes: simulation as u64,
+                depth: deepest,
+                best: root.most_visited(),
+            });
         }
 
         // Choose the best move
-        let best_child = root
-            .children
-            .iter()
-            .max_by(|a, b| a.visits.cmp(&b.visits))
-            .ok_or_else(|| crate::GameError::InvalidOperation("No valid moves".to_string()))?;
-
-        Ok(best_child.last_move.unwrap_or(Direction::Up))
-    }
-
-    /// Simulate a random game to completion
-    fn simulate_random_game(&self, game: &mut Game) -> f64 {
-        let mut moves = 0;
-        let max_moves = 1000; // Prevent infinite loops
-
-        while game.state() == crate::GameState::Playing && moves < max_moves {
-            let directions = [
-                Direction::Up,
-                Direction::Down,
-                Direction::Left,
-                Direction::Right,
-            ];
-            let mut moved = false;
-
-            for &direction in &directions {
-                if let Ok(did_move) = game.make_move(direction) {
-                    if did_move {
-                        moved = true;
-                        break;
-                    }
-                }
-            }
+        root.most_visited().unwrap_or(Direction::Up)
+    }
 
-            if !moved {
-                break; // No valid moves
-            }
+    fn name(&self) -> &str {
+        "MCTS"
+    }
 
-            moves += 1;
-        }
+    fn set_control(&mut self, control: SearchControl) {
+        self.control = control;
+    }
+}
+
+/// AI player for 2048 game
+pub struct AIPlayer {
+    /// Built-in algorithm, `None` for a custom strategy
+    algorithm: Option<AIAlgorithm>,
+    max_depth: usize,
+    simulation_count: usize,
+    time_budget: Option<Duration>,
+    probability_threshold: f64,
+    heuristic: HeuristicConfig,
+    seed: Option<u64>,
+    control: SearchControl,
+    strategy: Box<dyn AIStrategy>,
+}
 
-        self.evaluate_board(game.board())
+impl AIPlayer {
+    /// Create a new AI player
+    pub fn new(algorithm: AIAlgorithm) -> Self {
+        let max_depth = match algorithm {
+            AIAlgorithm::Greedy | AIAlgorithm::NTuple => 1,
+            AIAlgorithm::Expectimax | AIAlgorithm::Minimax => 4,
+            AIAlgorithm::MCTS => 1000,
+        };
+
+        let simulation_count = match algorithm {
+            AIAlgorithm::Greedy | AIAlgorithm::NTuple => 1,
+            AIAlgorithm::Expectimax | AIAlgorithm::Minimax => 1,
+            AIAlgorithm::MCTS => 100,
+        };
+
+        Self {
+            algorithm: Some(algorithm),
+            max_depth,
+            simulation_count,
+            time_budget: None,
+            probability_threshold: DEFAULT_PROBABILITY_THRESHOLD,
+            heuristic: HeuristicConfig::default(),
+            seed: None,
+            control: SearchControl::default(),
+            strategy: algorithm.strategy(),
+        }
+        .rebuild()
     }
 
-    /// Add a random tile for simulation purposes
-    fn add_random_tile_simulation(&self, game: &mut Game) -> GameResult<()> {
-        let empty_positions = game.board().empty_positions();
-        if empty_positions.is_empty() {
-            return Ok(());
+    /// Create an AI player driven by any strategy, e.g. a custom bot
+    pub fn with_strategy(strategy: Box<dyn AIStrategy>) -> Self {
+        Self {
+            algorithm: None,
+            max_depth: 4,
+            simulation_count: 1,
+            time_budget: None,
+            probability_threshold: DEFAULT_PROBABILITY_THRESHOLD,
+            heuristic: HeuristicConfig::default(),
+            seed: None,
+            control: SearchControl::default(),
+            strategy,
         }
+    }
 
-        // Use a simple random selection for simulation
-        let random_index = (empty_positions.len() as f64 * 0.5) as usize; // Simplified
-        let (row, col) = empty_positions[random_index];
-        let value = if rand::random::<u64>() % 10 < 9 { 2 } else { 4 };
+    /// Set the maximum search depth. Custom strategies ignore it.
+    pub fn with_max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self.rebuild()
+    }
 
-        game.board_mut().set_tile(row, col, Tile::new(value))?;
-        Ok(())
+    /// Set the number of simulations for MCTS. Custom strategies ignore it.
+    pub fn with_simulation_count(mut self, count: usize) -> Self {
+        self.simulation_count = count;
+        self.rebuild()
     }
 
-    /// Evaluate the current board state
-    fn evaluate_board(&self, board: &Board) -> f64 {
-        let mut score = 0.0;
-        let size = board.size();
+    /// Get the best move for the current game state. Fails with
+    /// `GameError::Cancelled` if the search was cancelled.
+    pub fn get_best_move(&mut self, game: &Game) -> GameResult<Direction> {
+        let direction = self.strategy.choose(game);
+        if self.control.is_cancelled() {
+            return Err(GameError::Cancelled);
+        }
+        Ok(direction)
+    }
 
-        // Weight matrix for position importance (corner and edge tiles are more valuable)
-        let weights = [
-            vec![4.0, 2.0, 2.0, 4.0],
-            vec![2.0, 1.0, 1.0, 2.0],
-            vec![2.0, 1.0, 1.0, 2.0],
-            vec![4.0, 2.0, 2.0, 4.0],
-        ];
+    /// Built-in algorithm of the player, `None` for a custom strategy
+    pub fn algorithm(&self) -> Option<AIAlgorithm> {
+        self.algorithm
+    }
 
-        // Evaluate each tile
-        for row in 0..size {
-            for col in 0..size {
-                if let Ok(tile) = board.get_tile(row, col) {
-                    if !tile.is_empty() {
-                        let weight = if row < weights.len() && col < weights[row].len() {
-                            weights[row][col]
-                        } else {
-                            1.0
-                        };
-                        score += (tile.value as f64) * weight;
-                    }
-                }
-            }
-        }
+    /// Name of the strategy
+    pub fn name(&self) -> &str {
+        self.strategy.name()
+    }
+
+    /// Transposition table counters of the strategy, if it keeps a table
+    pub fn cache_stats(&self) -> Option<TranspositionStats> {
+        self.strategy.cache_stats()
+    }
 
-        // Bonus for keeping high values in corners
-        score += self.corner_bonus(board);
+    /// Expectimax evaluation of every move that changes the board, whatever
+    /// the strategy of the player. Higher is better.
+    pub fn evaluate_moves(&self, game: &Game) -> Vec<(Direction, f64)> {
+        ExpectimaxStrategy::default()
+            .with_max_depth(self.max_depth)
+            .with_heuristic(self.heuristic)
+            .evaluate_moves(game)
+    }
 
-        // Penalty for having many small tiles scattered
-        score -= self.scattered_penalty(board);
+    /// Search each move for at most `budget` by iterative deepening
+    /// instead of to a fixed depth. Only Expectimax deepens; other
+    /// strategies ignore it.
+    pub fn with_time_budget(mut self, budget: Duration) -> Self {
+        self.time_budget = Some(budget);
+        self.rebuild()
+    }
 
-        // Bonus for smoothness (adjacent tiles with similar values)
-        score += self.smoothness_bonus(board);
+    /// Stop Expectimax from searching lines of spawns less likely than
+    /// `threshold`; 0 disables pruning. Other strategies ignore it.
+    pub fn with_probability_threshold(mut self, threshold: f64) -> Self {
+        self.probability_threshold = threshold;
+        self.rebuild()
+    }
 
-        score
+    /// Set how Expectimax and MCTS evaluate boards. Greedy and custom
+    /// strategies ignore it.
+    pub fn with_heuristic(mut self, heuristic: HeuristicConfig) -> Self {
+        self.heuristic = heuristic;
+        self.rebuild()
     }
 
-    /// Bonus for keeping high values in corners
-    fn corner_bonus(&self, board: &Board) -> f64 {
-        let size = board.size();
-        let mut bonus = 0.0;
+    /// Seed the random rollouts of MCTS, making its moves reproducible.
+    /// Other strategies ignore it.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self.rebuild()
+    }
 
-        let corners = [(0, 0), (0, size - 1), (size - 1, 0), (size - 1, size - 1)];
+    /// Stop searches when `token` is cancelled, e.g. from another thread
+    /// when the user moves while the AI is still thinking
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.control = self.control.with_cancellation(token);
+        self.rebuild()
+    }
+
+    /// Report the progress of searches to `callback` while they run. It is
+    /// called on the searching thread, so it should return quickly.
+    pub fn with_progress(
+        mut self,
+        callback: impl Fn(&SearchProgress) + Send + Sync + 'static,
+    ) -> Self {
+        self.control = self.control.with_progress(Arc::new(callback));
+        self.rebuild()
+    }
 
-        for (row, col) in corners {
-            if let Ok(tile) = board.get_tile(row, col) {
-                if !tile.is_empty() {
-                    bonus += tile.value as f64 * 2.0;
+    /// Apply the depth, time budget, heuristic, seed and simulation count
+    /// to a built-in strategy, and the search control to any strategy
+    fn rebuild(mut self) -> Self {
+        match self.algorithm {
+            Some(AIAlgorithm::Expectimax) => {
+                let mut strategy = ExpectimaxStrategy::default()
+                    .with_max_depth(self.max_depth)
+                    .with_probability_threshold(self.probability_threshold)
+                    .with_heuristic(self.heuristic);
+                if let Some(budget) = self.time_budget {
+                    strategy = strategy.with_time_budget(budget);
+                }
+                self.strategy = Box::new(strategy)
+            }
+            Some(AIAlgorithm::Minimax) => {
+                self.strategy = Box::new(
+                    MinimaxStrategy::default()
+                        .with_max_depth(self.max_depth)
+                        .with_heuristic(self.heuristic),
+                )
+            }
+            Some(AIAlgorithm::MCTS) => {
+                let mut strategy = MctsStrategy::default()
+                    .with_simulations(self.simulation_count)
+                    .with_heuristic(self.heuristic);
+                if let Some(seed) = self.seed {
+                    strategy = strategy.with_seed(seed);
                 }
+                self.strategy = Box::new(strategy)
             }
+            Some(AIAlgorithm::Greedy) | Some(AIAlgorithm::NTuple) | None => {}
         }
+        self.strategy.set_control(self.control.clone());
+        self
+    }
+}
 
-        bonus
+/// Move with the highest evaluation, the first one on ties
+fn best_move(values: &[(Direction, f64)]) -> Option<Direction> {
+    let mut best: Option<(Direction, f64)> = None;
+    for &(direction, score) in values {
+        if best.is_none_or(|(_, best_score)| score > best_score) {
+            best = Some((direction, score));
+        }
     }
+    best.map(|(direction, _)| direction)
+}
 
-    /// Penalty for having many small tiles scattered
-    fn scattered_penalty(&self, board: &Board) -> f64 {
-        let size = board.size();
-        let mut penalty = 0.0;
-        let mut small_tiles = 0;
+/// Play random moves from `board` until none is left (or
+/// `MAX_ROLLOUT_MOVES`), spawning tiles with `rng`, and evaluate the
+/// final board
+fn random_rollout(
+    rules: &dyn GameRules,
+    board: &Board,
+    rng: &mut GameRng,
+    heuristic: &HeuristicConfig,
+) -> f64 {
+    let mut board = board.clone();
+
+    for _ in 0..MAX_ROLLOUT_MOVES {
+        // Try the directions in a random order
+        let mut directions = DIRECTIONS;
+        for i in (1..directions.len()).rev() {
+            directions.swap(i, rng.gen_range(i + 1));
+        }
+        let Some(next) = directions
+            .into_iter()
+            .find_map(|direction| slide_board(rules, &board, direction))
+        else {
+            break; // No valid moves
+        };
+        board = next;
 
-        for row in 0..size {
-            for col in 0..size {
-                if let Ok(tile) = board.get_tile(row, col) {
-                    if !tile.is_empty() && tile.value <= 8 {
-                        small_tiles += 1;
-                    }
-                }
+        let empty_positions = board.empty_positions();
+        if let Some(&(row, col)) = empty_positions.get(rng.gen_range(empty_positions.len())) {
+            let value = rules.spawn_value(rng);
+            if board.set_tile(row, col, Tile::new(value)).is_err() {
+                break;
             }
         }
-
-        penalty += small_tiles as f64 * 0.5;
-        penalty
     }
 
-    /// Bonus for smoothness (adjacent tiles with similar values)
-    fn smoothness_bonus(&self, board: &Board) -> f64 {
-        let size = board.size();
-        let mut bonus = 0.0;
+    evaluate_board(&board, heuristic)
+}
 
-        // Check horizontal smoothness
-        for row in 0..size {
-            for col in 0..size - 1 {
-                if let (Ok(tile1), Ok(tile2)) =
-                    (board.get_tile(row, col), board.get_tile(row, col + 1))
-                {
-                    if !tile1.is_empty() && !tile2.is_empty() {
-                        let diff = (tile1.value as f64 - tile2.value as f64).abs();
-                        bonus -= diff * 0.1;
-                    }
-                }
-            }
+/// The board after sliding its tiles in `direction` under `rules`, before
+/// any tile spawns (gravity is not applied), or `None` if nothing moves
+fn slide_board(rules: &dyn GameRules, board: &Board, direction: Direction) -> Option<Board> {
+    slide_board_scored(rules, board, direction).map(|(next, _)| next)
+}
+
+/// `slide_board` with the points its merges score
+pub(crate) fn slide_board_scored(
+    rules: &dyn GameRules,
+    board: &Board,
+    direction: Direction,
+) -> Option<(Board, u64)> {
+    let size = board.size();
+    let tiles = board.as_flat_slice();
+    let mut next = board.clone();
+    let mut moved = false;
+    let mut points = 0;
+
+    for line in 0..size {
+        let cells = line_cells(size, line, direction);
+        let values: Vec<u64> = cells
+            .iter()
+            .map(|&(row, col)| tiles[row * size + col].value)
+            .collect();
+        let slid = rules::slide_line(rules, &values);
+        if slid.values == values {
+            continue;
+        }
+        moved = true;
+        points += slid
+            .merged_values()
+            .map(|value| rules.merge_score(value))
+            .sum::<u64>();
+        for (&(row, col), &value) in cells.iter().zip(&slid.values) {
+            next.set_tile(row, col, Tile::new(value)).ok()?;
         }
+    }
 
-        // Check vertical smoothness
-        for row in 0..size - 1 {
-            for col in 0..size {
-                if let (Ok(tile1), Ok(tile2)) =
-                    (board.get_tile(row, col), board.get_tile(row + 1, col))
-                {
-                    if !tile1.is_empty() && !tile2.is_empty() {
-                        let diff = (tile1.value as f64 - tile2.value as f64).abs();
-                        bonus -= diff * 0.1;
-                    }
-                }
-            }
+    moved.then_some((next, points))
+}
+
+/// Evaluate the current board state
+pub(crate) fn evaluate_board(board: &Board, heuristic: &HeuristicConfig) -> f64 {
+    let mut score = 0.0;
+
+    // Weight matrix for position importance (corner and edge tiles are more valuable)
+    if heuristic.position != 0.0 {
+        let weights = [
+            vec![4.0, 2.0, 2.0, 4.0],
+            vec![2.0, 1.0, 1.0, 2.0],
+            vec![2.0, 1.0, 1.0, 2.0],
+            vec![4.0, 2.0, 2.0, 4.0],
+        ];
+
+        // Evaluate each tile
+        for (row, col, tile) in board.iter_tiles().filter(|(_, _, tile)| !tile.is_empty()) {
+            let weight = if row < weights.len() && col < weights[row].len() {
+                weights[row][col]
+            } else {
+                1.0
+            };
+            score += (tile.value as f64) * weight * heuristic.position;
         }
+    }
+
+    // Bonus for keeping high values in corners
+    score += board.corner_weighting() * heuristic.corner;
+
+    // Penalty for having many small tiles scattered
+    score -= scattered_penalty(board) * heuristic.scattered;
 
-        bonus
+    // Bonus for smoothness (adjacent tiles with similar values)
+    score += board.smoothness() * heuristic.smoothness;
+
+    // Bonus for tiles ordered along a snake from a corner, and penalty for
+    // unsorted rows and columns. Monotonicity is measured in log2 steps, so
+    // it is scaled by the largest tile to keep pace with the other terms.
+    if heuristic.snake != 0.0 {
+        score += board.snake_weighting() * heuristic.snake;
+    }
+    if heuristic.monotonicity != 0.0 {
+        score += board.monotonicity() * board.max_tile() as f64 * heuristic.monotonicity;
     }
+
+    score
+}
+
+/// Number of small tiles scattered over the board
+fn scattered_penalty(board: &Board) -> f64 {
+    board
+        .as_flat_slice()
+        .iter()
+        .filter(|tile| !tile.is_empty() && tile.value <= 8)
+        .count() as f64
 }
 
 /// MCTS Node for Monte Carlo Tree Search
@@ -416,15 +1266,10 @@ impl MCTSNode {
     }
 
     fn expand(&mut self, game: &Game) {
-        for &direction in &[
-            Direction::Up,
-            Direction::Down,
-            Direction::Left,
-            Direction::Right,
-        ] {
+        for direction in DIRECTIONS {
             let mut game_copy = game.clone();
-            if let Ok(moved) = game_copy.make_move(direction) {
-                if moved {
+            if let Ok(result) = game_copy.make_move(direction) {
+                if result.moved {
                     let mut child = MCTSNode::new(game_copy);
                     child.last_move = Some(direction);
                     self.children.push(child);
@@ -433,12 +1278,71 @@ impl MCTSNode {
         }
     }
 
+    /// Move of the child visited most, the best so far
+    fn most_visited(&self) -> Option<Direction> {
+        self.children
+            .iter()
+            .max_by(|a, b| a.visits.cmp(&b.visits))
+            .and_then(|child| child.last_move)
+    }
+
     fn backpropagate(&mut self, score: f64) {
         self.visits += 1;
         self.total_score += score;
     }
 }
 
+impl Game {
+    /// Suggest a move for the current position using the given AI algorithm.
+    /// The suggestion always changes the board; fails with
+    /// `GameError::GameOver` when the game is not in play.
+    pub fn hint(&self, algorithm: AIAlgorithm) -> GameResult<Direction> {
+        self.hint_with(&mut AIPlayer::new(algorithm))
+    }
+
+    /// Like `hint`, with a configured player, e.g. one that can be
+    /// cancelled
+    pub fn hint_with(&self, player: &mut AIPlayer) -> GameResult<Direction> {
+        if self.state() != GameState::Playing {
+            return Err(GameError::GameOver);
+        }
+
+        // The searches can settle on a move that changes nothing when no
+        // move scores; fall back to the first one that does something
+        let suggested = player.get_best_move(self)?;
+        std::iter::once(suggested)
+            .chain(DIRECTIONS)
+            .find(|&direction| {
+                self.clone()
+                    .make_move(direction)
+                    .is_ok_and(|result| result.moved)
+            })
+            .ok_or(GameError::GameOver)
+    }
+
+    /// Make a move and let an adversary place the new tiles where they
+    /// hurt most, instead of at random: a "cruel computer" variant. The
+    /// adversary looks `ADVERSARY_DEPTH` plies ahead with the classic
+    /// heuristic.
+    pub fn make_adversarial_move(&mut self, direction: Direction) -> GameResult<MoveResult> {
+        let Some(mut board) = self.board_after_move(direction)? else {
+            // Nothing slides, so nothing spawns either
+            return self.make_move(direction);
+        };
+
+        let mut adversary = MinimaxStrategy::default().with_max_depth(ADVERSARY_DEPTH);
+        let mut spawned = Vec::with_capacity(self.config().tiles_per_spawn);
+        for _ in 0..self.config().tiles_per_spawn {
+            let Some(tile) = adversary.worst_spawn(self.rules(), &board) else {
+                break;
+            };
+            board.set_tile(tile.row, tile.col, Tile::new(tile.value))?;
+            spawned.push(tile);
+        }
+        self.make_move_with_spawns(direction, &spawned)
+    }
+}
+
 /// AI Game Controller - manages AI gameplay
 pub struct AIGameController {
     ai_player: AIPlayer,
@@ -450,7 +1354,18 @@ pub struct AIGameController {
 impl AIGameController {
     /// Create a new AI game controller
     pub fn new(config: GameConfig, algorithm: AIAlgorithm) -> GameResult<Self> {
-        let ai_player = AIPlayer::new(algorithm);
+        Self::with_player(config, AIPlayer::new(algorithm))
+    }
+
+    /// Create an AI game controller driven by any strategy, e.g. a custom
+    /// bot
+    pub fn with_strategy(config: GameConfig, strategy: Box<dyn AIStrategy>) -> GameResult<Self> {
+        Self::with_player(config, AIPlayer::with_strategy(strategy))
+    }
+
+    /// Create an AI game controller driven by a configured player, e.g.
+    /// one with a time budget
+    pub fn with_player(config: GameConfig, ai_player: AIPlayer) -> GameResult<Self> {
         let game = Game::new(config)?;
 
         Ok(Self {
@@ -483,12 +1398,12 @@ impl AIGameController {
 
     /// Make an AI move
     pub fn make_ai_move(&mut self) -> GameResult<bool> {
-        if self.game.state() != crate::GameState::Playing {
+        if self.game.state() != GameState::Playing {
             return Ok(false);
         }
 
         let best_move = self.ai_player.get_best_move(&self.game)?;
-        self.game.make_move(best_move)
+        Ok(self.game.make_move(best_move)?.moved)
     }
 
     /// Start a new AI game
@@ -496,25 +1411,490 @@ impl AIGameController {
         self.game.new_game()
     }
 
-    /// Get the AI algorithm being used
-    pub fn algorithm(&self) -> AIAlgorithm {
-        self.ai_player.algorithm
+    /// Get the AI algorithm being used, `None` for a custom strategy
+    pub fn algorithm(&self) -> Option<AIAlgorithm> {
+        self.ai_player.algorithm()
+    }
+
+    /// Name of the strategy being used
+    pub fn strategy_name(&self) -> &str {
+        self.ai_player.name()
+    }
+
+    /// Transposition table counters of the strategy, if it keeps a table
+    pub fn cache_stats(&self) -> Option<TranspositionStats> {
+        self.ai_player.cache_stats()
+    }
+
+    /// Compare `direction` with the move the AI's evaluation prefers in
+    /// the current position, before it is played. `None` when the move
+    /// does not change the board.
+    pub fn rate_move(&self, direction: Direction) -> Option<MoveAnalysis> {
+        let values = self.ai_player.evaluate_moves(&self.game);
+        MoveAnalysis::compare(self.game.moves() as usize, direction, &values)
     }
 }
 
-// Add rand dependency for simulation
-mod rand {
-    use std::collections::hash_map::DefaultHasher;
-    use std::hash::{Hash, Hasher};
-    use std::time::SystemTime;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Score;
+
+    #[test]
+    fn test_hint_changes_the_board() {
+        let mut game = Game::new(GameConfig::default()).unwrap();
+        // Only moving right or down changes this board, and nothing merges
+        game.load_from_state(
+            vec![2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            Score::new(),
+            0,
+            GameState::Playing,
+        )
+        .unwrap();
+
+        for algorithm in [AIAlgorithm::Greedy, AIAlgorithm::Expectimax] {
+            let direction = game.hint(algorithm).unwrap();
+            assert!(matches!(direction, Direction::Right | Direction::Down));
+        }
+
+        game.load_from_state(vec![0; 16], Score::new(), 0, GameState::GameOver)
+            .unwrap();
+        assert!(matches!(
+            game.hint(AIAlgorithm::Greedy),
+            Err(GameError::GameOver)
+        ));
+    }
+
+    /// Always plays the first direction that changes the board
+    struct FirstMove;
+
+    impl AIStrategy for FirstMove {
+        fn choose(&mut self, game: &Game) -> Direction {
+            DIRECTIONS
+                .into_iter()
+                .find(|&direction| {
+                    game.clone()
+                        .make_move(direction)
+                        .is_ok_and(|result| result.moved)
+                })
+                .unwrap_or(Direction::Up)
+        }
+    }
+
+    #[test]
+    fn test_custom_strategy() {
+        let mut controller =
+            AIGameController::with_strategy(GameConfig::default(), Box::new(FirstMove)).unwrap();
+        assert_eq!(controller.algorithm(), None);
+        assert_eq!(controller.strategy_name(), "Custom");
+        assert!(controller.make_ai_move().unwrap());
+        assert_eq!(controller.game().moves(), 1);
+
+        // Built-in algorithms are strategies too
+        let controller = AIGameController::new(GameConfig::default(), AIAlgorithm::MCTS).unwrap();
+        assert_eq!(controller.algorithm(), Some(AIAlgorithm::MCTS));
+        assert_eq!(controller.strategy_name(), "MCTS");
+    }
+
+    #[test]
+    fn test_expectimax_weighs_every_spawn() {
+        let mut game = Game::new(GameConfig::default()).unwrap();
+        game.load_from_state(
+            vec![2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            Score::new(),
+            0,
+            GameState::Playing,
+        )
+        .unwrap();
+
+        // One ply and a chance node: the average over every empty cell of a
+        // 2 (90%) or a 4 (10%) spawning there
+        let mut board = Board::new(4).unwrap();
+        board.set_tile(0, 3, Tile::new(2)).unwrap();
+        let empty = board.empty_positions();
+        let expected = empty
+            .iter()
+            .map(|&(row, col)| {
+                SPAWN_PROBABILITIES
+                    .iter()
+                    .map(|&(value, probability)| {
+                        let mut next = board.clone();
+                        next.set_tile(row, col, Tile::new(value)).unwrap();
+                        probability * evaluate_board(&next, &HeuristicConfig::default())
+                    })
+                    .sum::<f64>()
+            })
+            .sum::<f64>()
+            / empty.len() as f64;
+
+        let mut strategy = ExpectimaxStrategy::default()
+            .with_max_depth(2)
+            .with_sample_threshold(16);
+        let values = strategy.evaluate_moves(&game);
+        let right = values
+            .iter()
+            .find(|(direction, _)| *direction == Direction::Right)
+            .unwrap()
+            .1;
+        assert!((right - expected).abs() < 1e-9);
+        // Deterministic, unlike sampling random placements, and the second
+        // search finds its positions in the transposition table
+        let misses = strategy.cache_stats().misses;
+        assert_eq!(strategy.evaluate_moves(&game), values);
+        let stats = strategy.cache_stats();
+        assert_eq!(stats.misses, misses);
+        assert!(stats.hits >= values.len() as u64);
+    }
+
+    #[test]
+    fn test_time_budget() {
+        let game = Game::new(GameConfig {
+            seed: Some(5),
+            ..GameConfig::default()
+        })
+        .unwrap();
+
+        // A tiny budget still returns a move that changes the board
+        let mut player =
+            AIPlayer::new(AIAlgorithm::Expectimax).with_time_budget(Duration::from_millis(1));
+        let direction = player.get_best_move(&game).unwrap();
+        assert!(game
+            .clone()
+            .make_move(direction)
+            .is_ok_and(|result| result.moved));
+
+        // Deepening stops once the budget is spent
+        let mut strategy =
+            ExpectimaxStrategy::default().with_time_budget(Duration::from_millis(50));
+        let start = std::time::Instant::now();
+        strategy.choose(&game);
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_snake_heuristic() {
+        let sorted = Board::from_vec(vec![
+            vec![2, 0, 0, 0],
+            vec![4, 0, 0, 0],
+            vec![64, 32, 16, 8],
+            vec![128, 256, 512, 1024],
+        ])
+        .unwrap();
+        let scattered = Board::from_vec(vec![
+            vec![2, 0, 0, 0],
+            vec![4, 512, 0, 0],
+            vec![64, 32, 1024, 8],
+            vec![128, 256, 16, 0],
+        ])
+        .unwrap();
+
+        let snake = HeuristicConfig::snake();
+        assert!(evaluate_board(&sorted, &snake) > evaluate_board(&scattered, &snake));
+
+        // Without weights nothing counts
+        let none = HeuristicConfig {
+            scattered: 0.0,
+            smoothness: 0.0,
+            monotonicity: 0.0,
+            snake: 0.0,
+            ..snake
+        };
+        assert_eq!(evaluate_board(&scattered, &none), 0.0);
+    }
+
+    #[test]
+    fn test_seeded_mcts_is_reproducible() {
+        let mut game = Game::new(GameConfig {
+            seed: Some(21),
+            ..GameConfig::default()
+        })
+        .unwrap();
+        let mut first = AIPlayer::new(AIAlgorithm::MCTS)
+            .with_simulation_count(30)
+            .with_seed(7);
+        let mut second = AIPlayer::new(AIAlgorithm::MCTS)
+            .with_simulation_count(30)
+            .with_seed(7);
+
+        for _ in 0..10 {
+            let direction = first.get_best_move(&game).unwrap();
+            assert_eq!(second.get_best_move(&game).unwrap(), direction);
+            if game.make_move(direction).is_err() {
+                break;
+            }
+        }
+    }
+
+    #[test]
+    fn test_rate_move() {
+        let mut controller =
+            AIGameController::new(GameConfig::default(), AIAlgorithm::Expectimax).unwrap();
+        controller
+            .game_mut()
+            .load_from_state(
+                vec![4, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+                Score::new(),
+                0,
+                GameState::Playing,
+            )
+            .unwrap();
+
+        let rating = controller.rate_move(Direction::Down).unwrap();
+        assert_eq!(rating.played, Direction::Down);
+        assert!((0.0..=1.0).contains(&rating.loss));
+        assert_eq!(
+            controller.rate_move(rating.best).unwrap().grade(),
+            crate::MoveGrade::Best
+        );
+        assert!(controller.rate_move(Direction::Left).is_none());
+    }
 
-    pub fn random<T>() -> T
-    where
-        T: Copy + From<u64>,
-    {
-        let mut hasher = DefaultHasher::new();
-        SystemTime::now().hash(&mut hasher);
-        let hash = hasher.finish();
-        T::from(hash)
+    #[test]
+    fn test_minimax_assumes_the_worst_spawn() {
+        let mut game = Game::new(GameConfig::default()).unwrap();
+        game.load_from_state(
+            vec![2, 0, 0, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            Score::new(),
+            0,
+            GameState::Playing,
+        )
+        .unwrap();
+        let heuristic = HeuristicConfig::default();
+
+        // One ply and the adversary: the lowest evaluation of any spawn
+        let board = slide_board(game.rules(), game.board(), Direction::Down).unwrap();
+        let (worst_tile, worst_value) = spawns(&board)
+            .map(|(tile, next)| (tile, evaluate_board(&next, &heuristic)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap();
+        let values = MinimaxStrategy::default()
+            .with_max_depth(2)
+            .evaluate_moves(&game);
+        let down = values
+            .iter()
+            .find(|(direction, _)| *direction == Direction::Down)
+            .unwrap()
+            .1;
+        assert_eq!(down, worst_value);
+        let mut adversary = MinimaxStrategy::default().with_max_depth(1);
+        assert_eq!(
+            adversary.worst_spawn(game.rules(), &board),
+            Some(worst_tile)
+        );
+
+        // The worst case is never better than the expected case
+        let minimax = MinimaxStrategy::default().evaluate_moves(&game);
+        let expectimax = ExpectimaxStrategy::default()
+            .with_sample_threshold(16)
+            .with_probability_threshold(0.0)
+            .evaluate_moves(&game);
+        assert_eq!(minimax.len(), expectimax.len());
+        for ((direction, worst), (other, expected)) in minimax.iter().zip(&expectimax) {
+            assert_eq!(direction, other);
+            assert!(worst <= expected);
+        }
+
+        // The cruel variant places the adversary's tile
+        let expected = MinimaxStrategy::default()
+            .with_max_depth(ADVERSARY_DEPTH)
+            .worst_spawn(game.rules(), &board);
+        let result = game.make_adversarial_move(Direction::Down).unwrap();
+        assert!(result.moved);
+        assert_eq!(result.spawned, expected.into_iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_adversarial_move_with_gravity() {
+        let mut game = Game::new(GameConfig {
+            gravity: Some(Direction::Down),
+            ..GameConfig::default()
+        })
+        .unwrap();
+        game.load_from_state(
+            vec![2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4, 0, 0, 0, 0],
+            Score::new(),
+            0,
+            GameState::Playing,
+        )
+        .unwrap();
+
+        // The adversary places its tile on the board after the tiles fell
+        let mut settled = [0; 16];
+        settled[8] = 2;
+        settled[12] = 4;
+        let settled = Board::from_vec(settled.chunks(4).map(<[u64]>::to_vec).collect()).unwrap();
+        let expected = MinimaxStrategy::default()
+            .with_max_depth(ADVERSARY_DEPTH)
+            .worst_spawn(game.rules(), &settled);
+        let result = game.make_adversarial_move(Direction::Left).unwrap();
+        assert!(result.moved);
+        assert_eq!(result.spawned, expected.into_iter().collect::<Vec<_>>());
+        assert_eq!(game.board().get_tile(3, 0).unwrap().value, 4);
+        assert_eq!(game.board().get_tile(2, 0).unwrap().value, 2);
+    }
+
+    #[test]
+    fn test_search_control() {
+        let mut game = Game::new(GameConfig {
+            seed: Some(3),
+            ..GameConfig::default()
+        })
+        .unwrap();
+        for direction in [Direction::Left, Direction::Down].repeat(5) {
+            game.make_move(direction).unwrap();
+        }
+
+        for algorithm in [
+            AIAlgorithm::Expectimax,
+            AIAlgorithm::MCTS,
+            AIAlgorithm::Minimax,
+        ] {
+            let reports = Arc::new(std::sync::Mutex::new(Vec::new()));
+            let sink = reports.clone();
+            let token = CancellationToken::new();
+            let mut player = AIPlayer::new(algorithm)
+                .with_seed(1)
+                .with_cancellation(token.clone())
+                .with_progress(move |progress| sink.lock().unwrap().push(*progress));
+
+            let direction = player.get_best_move(&game).unwrap();
+            let reports = reports.lock().unwrap().clone();
+            assert!(!reports.is_empty(), "{:?} reported nothing", algorithm);
+            assert!(reports.windows(2).all(|w| w[0].nodes <= w[1].nodes));
+            assert_eq!(reports.last().unwrap().best, Some(direction));
+
+            // A cancelled search gives up
+            token.cancel();
+            assert!(matches!(
+                player.get_best_move(&game),
+                Err(GameError::Cancelled)
+            ));
+            assert!(matches!(
+                game.hint_with(&mut player),
+                Err(GameError::Cancelled)
+            ));
+            token.reset();
+            assert_eq!(player.get_best_move(&game).unwrap(), direction);
+        }
+    }
+
+    #[test]
+    fn test_depth_by_empty_cells() {
+        let policy = DepthPolicy::ByEmptyCells {
+            min_depth: 2,
+            max_depth: 6,
+        };
+        let mut board = Board::new(4).unwrap();
+        assert_eq!(DepthPolicy::Fixed.depth_for(&board, 3), 3);
+        assert_eq!(policy.depth_for(&board, 3), 2);
+        for cell in 0..8 {
+            board.set_tile(cell / 4, cell % 4, Tile::new(2)).unwrap();
+        }
+        assert_eq!(policy.depth_for(&board, 3), 4);
+        for cell in 8..16 {
+            board.set_tile(cell / 4, cell % 4, Tile::new(4)).unwrap();
+        }
+        assert_eq!(policy.depth_for(&board, 3), 6);
+
+        // Expectimax searches as deep as the policy says
+        let mut game = Game::new(GameConfig {
+            seed: Some(8),
+            ..GameConfig::default()
+        })
+        .unwrap();
+        for direction in [Direction::Left, Direction::Down].repeat(6) {
+            let _ = game.make_move(direction);
+        }
+        let depth = policy.depth_for(game.board(), 0);
+        let heuristic = HeuristicConfig {
+            depth: policy,
+            ..HeuristicConfig::default()
+        };
+        assert_eq!(
+            ExpectimaxStrategy::default()
+                .with_heuristic(heuristic)
+                .evaluate_moves(&game),
+            ExpectimaxStrategy::default()
+                .with_max_depth(depth)
+                .evaluate_moves(&game)
+        );
+    }
+
+    #[test]
+    fn test_probability_threshold() {
+        let mut game = Game::new(GameConfig {
+            seed: Some(3),
+            ..GameConfig::default()
+        })
+        .unwrap();
+        for direction in [Direction::Up, Direction::Left].repeat(3) {
+            let _ = game.make_move(direction);
+        }
+
+        // Pruning unlikely spawns searches fewer nodes
+        let mut full = ExpectimaxStrategy::default().with_probability_threshold(0.0);
+        let mut pruned = ExpectimaxStrategy::default().with_probability_threshold(0.02);
+        assert_eq!(
+            full.evaluate_moves(&game).len(),
+            pruned.evaluate_moves(&game).len()
+        );
+        assert!(pruned.nodes < full.nodes);
+
+        // Above 1 every move is evaluated without searching its spawns
+        let shallow = ExpectimaxStrategy::default()
+            .with_max_depth(1)
+            .evaluate_moves(&game);
+        assert_eq!(
+            ExpectimaxStrategy::default()
+                .with_probability_threshold(1.5)
+                .evaluate_moves(&game),
+            shallow
+        );
+    }
+
+    #[test]
+    fn test_incremental_search() {
+        let mut game = Game::new(GameConfig {
+            seed: Some(11),
+            ..GameConfig::default()
+        })
+        .unwrap();
+        for direction in [Direction::Right, Direction::Down].repeat(4) {
+            let _ = game.make_move(direction);
+        }
+
+        // Without a budget each step stops at its first look at the clock;
+        // stepping on still gets to the full depth and the same move
+        let mut search =
+            IncrementalSearch::new(&game, ExpectimaxStrategy::default().with_max_depth(6));
+        let mut steps = 0;
+        let chosen = loop {
+            steps += 1;
+            match search.step(Duration::ZERO) {
+                SearchStatus::Searching(progress) => {
+                    assert!(progress.depth <= 6);
+                    assert!(progress.best.is_some());
+                }
+                SearchStatus::Done(direction) => break direction,
+            }
+        };
+        assert!(steps > 1);
+        assert_eq!(search.step(Duration::ZERO), SearchStatus::Done(chosen));
+        assert_eq!(
+            chosen,
+            Some(
+                ExpectimaxStrategy::default()
+                    .with_max_depth(6)
+                    .choose(&game)
+            )
+        );
+
+        // One generous step searches to the full depth at once
+        let mut search =
+            IncrementalSearch::new(&game, ExpectimaxStrategy::default().with_max_depth(6));
+        assert_eq!(
+            search.step(Duration::from_secs(60)),
+            SearchStatus::Done(chosen)
+        );
     }
 }
@@ -0,0 +1,271 @@
+//! Move analysis: a move is compared with the best move the expectimax
+//! evaluator finds in the same position, either live through a
+//! [`MoveGrader`] or for every move of a replay.
+
+use crate::replay::{ReplayData, ReplayPlayer};
+use crate::{Direction, ExpectimaxStrategy, Game, GameResult, GameState};
+use serde::{Deserialize, Serialize};
+
+/// Search depth used by `analyze_replay`
+pub const DEFAULT_ANALYSIS_DEPTH: usize = 3;
+
+/// Share of the best evaluation a move may lose before it counts as a
+/// mistake
+pub const MISTAKE_THRESHOLD: f64 = 0.15;
+
+/// Share of the best evaluation a move may lose and still count as good
+pub const INACCURACY_THRESHOLD: f64 = 0.05;
+
+/// Verdict on a move, from best to worst
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum MoveGrade {
+    /// As good as the evaluator's choice
+    Best,
+    /// Loses less than `INACCURACY_THRESHOLD`
+    Good,
+    /// Loses less than `MISTAKE_THRESHOLD`
+    Inaccuracy,
+    /// Loses `MISTAKE_THRESHOLD` or more
+    Blunder,
+}
+
+impl MoveGrade {
+    /// Grade of a move losing `loss` of the best evaluation
+    pub fn from_loss(loss: f64) -> Self {
+        if loss <= 0.0 {
+            MoveGrade::Best
+        } else if loss < INACCURACY_THRESHOLD {
+            MoveGrade::Good
+        } else if loss < MISTAKE_THRESHOLD {
+            MoveGrade::Inaccuracy
+        } else {
+            MoveGrade::Blunder
+        }
+    }
+}
+
+impl std::fmt::Display for MoveGrade {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            MoveGrade::Best => "best",
+            MoveGrade::Good => "good",
+            MoveGrade::Inaccuracy => "inaccuracy",
+            MoveGrade::Blunder => "blunder",
+        };
+        f.write_str(name)
+    }
+}
+
+/// How one recorded move compares with the best alternative
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MoveAnalysis {
+    /// Index of the move in the replay
+    pub move_index: usize,
+    /// Direction that was played
+    pub played: Direction,
+    /// Direction the evaluator prefers
+    pub best: Direction,
+    /// Evaluation of the played move
+    pub played_value: f64,
+    /// Evaluation of the best move
+    pub best_value: f64,
+    /// Share of the best evaluation lost by the played move, from 0.0
+    /// (the best move) to 1.0
+    pub loss: f64,
+}
+
+impl MoveAnalysis {
+    /// Whether the played move falls far below the best alternative
+    pub fn is_mistake(&self) -> bool {
+        self.loss >= MISTAKE_THRESHOLD
+    }
+
+    /// Grade of the played move
+    pub fn grade(&self) -> MoveGrade {
+        MoveGrade::from_loss(self.loss)
+    }
+
+    /// Compare `played` with the best of the evaluated moves. `None` when
+    /// `played` was not evaluated, i.e. it does not change the board.
+    pub fn compare(
+        move_index: usize,
+        played: Direction,
+        values: &[(Direction, f64)],
+    ) -> Option<Self> {
+        let (best, best_value) = values.iter().copied().max_by(|a, b| a.1.total_cmp(&b.1))?;
+        let &(_, played_value) = values.iter().find(|(direction, _)| *direction == played)?;
+        let loss = if best_value > played_value {
+            ((best_value - played_value) / best_value.abs().max(f64::EPSILON)).min(1.0)
+        } else {
+            0.0
+        };
+        Some(MoveAnalysis {
+            move_index,
+            played,
+            best,
+            played_value,
+            best_value,
+            loss,
+        })
+    }
+}
+
+/// Grades moves as they are played, e.g. for an assist mode
+pub struct MoveGrader {
+    evaluator: ExpectimaxStrategy,
+}
+
+impl Default for MoveGrader {
+    fn default() -> Self {
+        Self::with_depth(DEFAULT_ANALYSIS_DEPTH)
+    }
+}
+
+impl MoveGrader {
+    /// Grader searching `depth` plies ahead
+    pub fn with_depth(depth: usize) -> Self {
+        Self {
+            evaluator: ExpectimaxStrategy::default().with_max_depth(depth.max(1)),
+        }
+    }
+
+    /// Judge `direction` in the current position of `game`, before it is
+    /// played. `None` when the move does not change the board.
+    pub fn grade(&mut self, game: &Game, direction: Direction) -> Option<MoveAnalysis> {
+        let values = self.evaluator.evaluate_moves(game);
+        MoveAnalysis::compare(game.moves() as usize, direction, &values)
+    }
+}
+
+/// Analysis of a whole replay
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayAnalysis {
+    /// One entry per recorded move
+    pub moves: Vec<MoveAnalysis>,
+    /// Average share of the best evaluation kept by the played moves, as a
+    /// percentage (100.0 when every move was the best one)
+    pub accuracy: f64,
+}
+
+impl ReplayAnalysis {
+    /// Moves flagged as mistakes
+    pub fn mistakes(&self) -> impl Iterator<Item = &MoveAnalysis> {
+        self.moves.iter().filter(|analysis| analysis.is_mistake())
+    }
+
+    /// Analysis of the move at `move_index`, if it was analyzed
+    pub fn move_at(&self, move_index: usize) -> Option<&MoveAnalysis> {
+        self.moves
+            .iter()
+            .find(|analysis| analysis.move_index == move_index)
+    }
+}
+
+/// Analyze every move of a replay at `DEFAULT_ANALYSIS_DEPTH`
+pub fn analyze_replay(replay: &ReplayData) -> GameResult<ReplayAnalysis> {
+    analyze_replay_with_depth(replay, DEFAULT_ANALYSIS_DEPTH)
+}
+
+/// Analyze every move of a replay, searching `depth` plies ahead. Deeper
+/// searches are slower but judge long-term plans better.
+pub fn analyze_replay_with_depth(replay: &ReplayData, depth: usize) -> GameResult<ReplayAnalysis> {
+    let mut grader = MoveGrader::with_depth(depth);
+    let mut player = ReplayPlayer::new(replay.clone())?;
+    let mut moves = Vec::with_capacity(replay.moves.len());
+
+    for (move_index, replay_move) in replay.moves.iter().enumerate() {
+        let mut game = player.current_game().clone();
+        if game.state() == GameState::Won {
+            game.continue_after_win()?;
+        }
+
+        let values = grader.evaluator.evaluate_moves(&game);
+        moves.extend(MoveAnalysis::compare(
+            move_index,
+            replay_move.direction,
+            &values,
+        ));
+
+        player.next_move()?;
+    }
+
+    let accuracy = if moves.is_empty() {
+        100.0
+    } else {
+        moves
+            .iter()
+            .map(|analysis| 1.0 - analysis.loss)
+            .sum::<f64>()
+            / moves.len() as f64
+            * 100.0
+    };
+    Ok(ReplayAnalysis { moves, accuracy })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameConfig, ReplayRecorder, Score};
+
+    #[test]
+    fn test_analyze_replay() {
+        let mut recorder = ReplayRecorder::new(GameConfig::default()).unwrap();
+        for direction in [Direction::Left, Direction::Up, Direction::Right]
+            .into_iter()
+            .cycle()
+            .take(30)
+        {
+            let _ = recorder.make_move(direction);
+        }
+        let replay = recorder.stop_recording();
+
+        let analysis = analyze_replay_with_depth(&replay, 2).unwrap();
+        assert_eq!(analysis.moves.len(), replay.moves.len());
+        assert!((0.0..=100.0).contains(&analysis.accuracy));
+        for (index, analysis) in analysis.moves.iter().enumerate() {
+            assert_eq!(analysis.move_index, index);
+            assert_eq!(analysis.played, replay.moves[index].direction);
+            assert!((0.0..=1.0).contains(&analysis.loss));
+            if analysis.played == analysis.best {
+                assert_eq!(analysis.loss, 0.0);
+            }
+        }
+        assert!(analysis.mistakes().all(|m| m.loss >= MISTAKE_THRESHOLD));
+
+        // An empty replay has nothing to get wrong
+        let empty = ReplayRecorder::new(GameConfig::default())
+            .unwrap()
+            .stop_recording();
+        assert_eq!(analyze_replay(&empty).unwrap().accuracy, 100.0);
+    }
+
+    #[test]
+    fn test_move_grader() {
+        assert_eq!(MoveGrade::from_loss(0.0), MoveGrade::Best);
+        assert_eq!(MoveGrade::from_loss(0.01), MoveGrade::Good);
+        assert_eq!(MoveGrade::from_loss(0.1), MoveGrade::Inaccuracy);
+        assert_eq!(MoveGrade::from_loss(MISTAKE_THRESHOLD), MoveGrade::Blunder);
+
+        let mut game = Game::new(GameConfig::default()).unwrap();
+        game.load_from_state(
+            vec![2, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            Score::new(),
+            5,
+            GameState::Playing,
+        )
+        .unwrap();
+
+        let mut grader = MoveGrader::with_depth(2);
+        let best = grader.grade(&game, Direction::Left).unwrap();
+        assert_eq!(best.move_index, 5);
+        let graded = [Direction::Left, Direction::Right, Direction::Down]
+            .map(|direction| grader.grade(&game, direction).unwrap());
+        assert!(graded.iter().all(|analysis| analysis.best == best.best));
+        assert!(graded
+            .iter()
+            .any(|analysis| analysis.grade() == MoveGrade::Best));
+
+        // Moving up changes nothing, so there is nothing to grade
+        assert!(grader.grade(&game, Direction::Up).is_none());
+    }
+}
@@ -0,0 +1,286 @@
+//! Automatic recording of every game a frontend plays.
+//!
+//! An [`AutoRecorder`] follows a [`Game`] through its events instead of
+//! owning it like a [`crate::ReplayRecorder`], so frontends keep driving the
+//! game as usual (moves, undo, AI moves, new games) and call
+//! [`AutoRecorder::observe`] once per action. Each finished game comes out
+//! as a replay, ready to be saved and pruned with a
+//! [`crate::ReplayRetention`].
+
+use crate::replay::{ReplayData, ReplayMetadata, ReplayMove, REPLAY_SCHEMA_VERSION};
+use crate::{Direction, Game, GameEvent, GameResult, GameState, Score, SpawnedTile};
+
+/// Records the games played on a `Game` from its events
+#[derive(Debug)]
+pub struct AutoRecorder {
+    /// Replay of the game in progress
+    replay: ReplayData,
+    /// Moves undone since they were recorded, most recent last
+    undone: Vec<ReplayMove>,
+    /// Whether the game in progress reached its target
+    won: bool,
+    /// Whether the game in progress was already handed out as finished
+    finished: bool,
+}
+
+impl AutoRecorder {
+    /// Start recording `game` from its current position, enabling its
+    /// events. Events queued before are dropped.
+    pub fn new(game: &mut Game) -> Self {
+        game.enable_events();
+        game.take_events();
+        Self {
+            replay: start_replay(game),
+            undone: Vec::new(),
+            won: game.state() == GameState::Won,
+            finished: false,
+        }
+    }
+
+    /// Record what happened to `game` since the last call, taking its
+    /// events. Returns the replays of the games that finished: lost, or
+    /// won and then replaced by a new game.
+    pub fn observe(&mut self, game: &mut Game) -> GameResult<Vec<ReplayData>> {
+        let mut finished = Vec::new();
+        let mut pending: Option<(Direction, Vec<SpawnedTile>)> = None;
+
+        for event in game.take_events() {
+            match event {
+                GameEvent::Moved { direction, .. } => {
+                    if let Some((direction, spawned)) = pending.take() {
+                        self.record_move(direction, spawned, game)?;
+                    }
+                    pending = Some((direction, Vec::new()));
+                }
+                GameEvent::TileSpawned { row, col, value } => match &mut pending {
+                    Some((_, spawned)) => spawned.push(SpawnedTile { row, col, value }),
+                    // Tiles of a new game, before its first move
+                    None => {
+                        if let Some(cell) = self
+                            .replay
+                            .initial_board
+                            .get_mut(row)
+                            .and_then(|cells| cells.get_mut(col))
+                        {
+                            *cell = value;
+                        }
+                    }
+                },
+                GameEvent::Won { .. } => self.won = true,
+                GameEvent::GameOver { .. } => {
+                    if let Some((direction, spawned)) = pending.take() {
+                        self.record_move(direction, spawned, game)?;
+                    }
+                    finished.extend(self.finish_game(GameState::GameOver));
+                }
+                GameEvent::Undone => {
+                    if let Some((direction, spawned)) = pending.take() {
+                        self.record_move(direction, spawned, game)?;
+                    }
+                    self.undone.extend(self.replay.moves.pop());
+                }
+                GameEvent::Redone => self.replay.moves.extend(self.undone.pop()),
+                GameEvent::NewGame => {
+                    if let Some((direction, spawned)) = pending.take() {
+                        self.record_move(direction, spawned, game)?;
+                    }
+                    if self.won {
+                        finished.extend(self.finish_game(GameState::Won));
+                    }
+                    let mut replay = start_replay(game);
+                    replay.initial_board = vec![vec![0; game.board().size()]; game.board().size()];
+                    replay.initial_rng = None;
+                    replay.final_score = 0;
+                    replay.total_moves = 0;
+                    self.replay = replay;
+                    self.undone.clear();
+                    self.won = false;
+                    self.finished = false;
+                }
+            }
+        }
+        if let Some((direction, spawned)) = pending {
+            self.record_move(direction, spawned, game)?;
+        }
+
+        // The generator of a new game is known once no move followed it
+        if self.replay.moves.is_empty() && game.board().to_vec() == self.replay.initial_board {
+            self.replay.initial_rng = Some(game.rng().clone());
+        }
+        Ok(finished)
+    }
+
+    /// The replay of the game in progress if it was won, e.g. to save it
+    /// when the frontend quits
+    pub fn finish(&mut self) -> Option<ReplayData> {
+        if self.won {
+            self.finish_game(GameState::Won)
+        } else {
+            None
+        }
+    }
+
+    /// Replay of the game in progress, so far
+    pub fn replay_data(&self) -> &ReplayData {
+        &self.replay
+    }
+
+    /// Play a move on a copy of the last recorded position to recover the
+    /// board it led to. The generator state is only known for a move that
+    /// left the board `game` has now.
+    fn record_move(
+        &mut self,
+        direction: Direction,
+        spawned: Vec<SpawnedTile>,
+        game: &Game,
+    ) -> GameResult<()> {
+        let (board_before, score_before, move_number) = match self.replay.moves.last() {
+            Some(last) => (
+                last.board_after.clone(),
+                last.score_after,
+                last.move_number + 1,
+            ),
+            None => (
+                self.replay.initial_board.clone(),
+                self.replay.final_score,
+                self.replay.total_moves,
+            ),
+        };
+
+        let mut replica = Game::new(self.replay.config.clone())?;
+        replica.load_from_state(
+            board_before.iter().flatten().copied().collect(),
+            Score::from_parts(score_before, score_before, 0),
+            move_number,
+            GameState::Playing,
+        )?;
+        replica.make_move_with_spawns(direction, &spawned)?;
+
+        let current = replica.board().to_vec() == game.board().to_vec();
+        let timestamp_ms = crate::get_current_time_ms();
+        self.replay.moves.push(ReplayMove {
+            direction,
+            board_before,
+            board_after: replica.board().to_vec(),
+            score_before,
+            score_after: if current {
+                game.score().current()
+            } else {
+                replica.score().current()
+            },
+            move_number,
+            timestamp: timestamp_ms / 1000,
+            timestamp_ms: Some(timestamp_ms),
+            board_hash: Some(replica.board().zobrist_hash()),
+            rng: current.then(|| game.rng().clone()),
+            spawned,
+        });
+        self.undone.clear();
+        Ok(())
+    }
+
+    /// Hand out the replay of the game in progress, once
+    fn finish_game(&mut self, state: GameState) -> Option<ReplayData> {
+        if self.finished || self.replay.moves.is_empty() {
+            return None;
+        }
+        self.finished = true;
+
+        let mut replay = self.replay.clone();
+        let last = &replay.moves[replay.moves.len() - 1];
+        replay.final_state = state;
+        replay.final_score = last.score_after;
+        replay.total_moves = last.move_number + 1;
+        replay.duration = crate::get_current_time().saturating_sub(replay.metadata.created_at);
+        Some(replay)
+    }
+}
+
+/// Empty replay of `game` from its current position
+fn start_replay(game: &Game) -> ReplayData {
+    ReplayData {
+        format_version: REPLAY_SCHEMA_VERSION,
+        config: game.config().clone(),
+        initial_board: game.board().to_vec(),
+        initial_rng: Some(game.rng().clone()),
+        moves: Vec::new(),
+        final_state: game.state(),
+        final_score: game.score().current(),
+        total_moves: game.moves(),
+        duration: 0,
+        metadata: ReplayMetadata {
+            auto_recorded: true,
+            ..ReplayMetadata::default()
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameConfig, ReplayPlayer};
+
+    /// Play until the game is lost, observing after every action
+    fn play_out(game: &mut Game, recorder: &mut AutoRecorder) -> Vec<ReplayData> {
+        let mut finished = Vec::new();
+        for direction in [
+            Direction::Left,
+            Direction::Down,
+            Direction::Right,
+            Direction::Up,
+        ]
+        .into_iter()
+        .cycle()
+        .take(5_000)
+        {
+            if game.state() == GameState::Won {
+                game.continue_after_win().unwrap();
+            }
+            if game.state() == GameState::GameOver {
+                break;
+            }
+            let _ = game.make_move(direction);
+            finished.extend(recorder.observe(game).unwrap());
+        }
+        finished
+    }
+
+    #[test]
+    fn test_records_finished_games() {
+        let mut game = Game::new(GameConfig {
+            seed: Some(11),
+            ..GameConfig::default()
+        })
+        .unwrap();
+        let mut recorder = AutoRecorder::new(&mut game);
+
+        // An undone move is dropped from the replay
+        game.make_move(Direction::Left).unwrap();
+        game.make_move(Direction::Up).unwrap();
+        game.undo().unwrap();
+        assert!(recorder.observe(&mut game).unwrap().is_empty());
+        assert_eq!(recorder.replay_data().moves.len(), game.moves() as usize);
+
+        let finished = play_out(&mut game, &mut recorder);
+        assert_eq!(finished.len(), 1);
+        let replay = &finished[0];
+        assert!(replay.metadata.auto_recorded);
+        assert_eq!(replay.final_state, GameState::GameOver);
+        assert_eq!(replay.final_score, game.score().current());
+        assert_eq!(replay.total_moves, game.moves());
+
+        // The replay plays back to the final position
+        let mut player = ReplayPlayer::new(replay.clone()).unwrap();
+        while player.next_move().unwrap() {}
+        assert_eq!(
+            player.current_game().board().to_vec(),
+            game.board().to_vec()
+        );
+
+        // A new game is recorded from its first tiles
+        game.new_game().unwrap();
+        assert!(recorder.observe(&mut game).unwrap().is_empty());
+        assert_eq!(recorder.replay_data().initial_board, game.board().to_vec());
+        assert!(recorder.replay_data().moves.is_empty());
+    }
+}
@@ -0,0 +1,154 @@
+//! Headless AI benchmarks: play many seeded games with one AI and compare
+//! the results, e.g. before and after changing a heuristic.
+
+use crate::{AIAlgorithm, AIPlayer, Direction, Game, GameConfig, GameResult, GameState};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Aggregate results of a benchmark
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkResults {
+    /// Name of the strategy that played
+    pub strategy: String,
+    pub games: usize,
+    /// Games that reached the win condition
+    pub wins: usize,
+    /// Share of the games won, from 0.0 to 1.0
+    pub win_rate: f64,
+    pub mean_score: f64,
+    pub median_score: f64,
+    pub best_score: u64,
+    /// Number of games by largest tile reached
+    pub max_tiles: BTreeMap<u64, usize>,
+    pub total_moves: u64,
+    /// Time spent playing, in milliseconds
+    pub duration_ms: u64,
+    /// Moves per second of playing time
+    pub moves_per_second: f64,
+}
+
+/// Plays games headlessly to measure an AI
+pub struct AIBenchmark;
+
+impl AIBenchmark {
+    /// Play `n_games` games with a built-in algorithm. Game `i` is seeded
+    /// with `seed + i`, so runs with the same seed play the same games.
+    pub fn run(
+        config: &GameConfig,
+        algorithm: AIAlgorithm,
+        n_games: usize,
+        seed: u64,
+    ) -> GameResult<BenchmarkResults> {
+        Self::run_player(config, &mut AIPlayer::new(algorithm), n_games, seed)
+    }
+
+    /// Play `n_games` games with a configured player, e.g. one with a
+    /// custom strategy or a time budget
+    pub fn run_player(
+        config: &GameConfig,
+        player: &mut AIPlayer,
+        n_games: usize,
+        seed: u64,
+    ) -> GameResult<BenchmarkResults> {
+        let mut scores = Vec::with_capacity(n_games);
+        let mut max_tiles = BTreeMap::new();
+        let mut wins = 0;
+        let mut total_moves = 0;
+        let start_ms = crate::get_current_time_ms();
+
+        for index in 0..n_games {
+            let mut game = Game::new(GameConfig {
+                seed: Some(seed.wrapping_add(index as u64)),
+                allow_undo: false,
+                ..config.clone()
+            })?;
+            let won = play_out(&mut game, player)?;
+
+            wins += won as usize;
+            total_moves += game.moves() as u64;
+            scores.push(game.score().current());
+            *max_tiles.entry(game.board().max_tile()).or_insert(0) += 1;
+        }
+
+        let duration_ms = crate::get_current_time_ms().saturating_sub(start_ms);
+        scores.sort_unstable();
+        let median_score = match scores.len() {
+            0 => 0.0,
+            len if len % 2 == 1 => scores[len / 2] as f64,
+            len => (scores[len / 2 - 1] + scores[len / 2]) as f64 / 2.0,
+        };
+        Ok(BenchmarkResults {
+            strategy: player.name().to_string(),
+            games: n_games,
+            wins,
+            win_rate: wins as f64 / n_games.max(1) as f64,
+            mean_score: scores.iter().sum::<u64>() as f64 / n_games.max(1) as f64,
+            median_score,
+            best_score: scores.last().copied().unwrap_or(0),
+            max_tiles,
+            total_moves,
+            duration_ms,
+            moves_per_second: total_moves as f64 * 1000.0 / duration_ms.max(1) as f64,
+        })
+    }
+}
+
+/// Let the player finish the game, keeping going after a win. Returns
+/// whether the game was won.
+fn play_out(game: &mut Game, player: &mut AIPlayer) -> GameResult<bool> {
+    let mut won = false;
+    loop {
+        if game.state() == GameState::Won {
+            won = true;
+            game.continue_after_win()?;
+        }
+        if game.state() != GameState::Playing {
+            return Ok(won);
+        }
+
+        // A strategy may settle on a move that changes nothing; play the
+        // first one that does instead, as `Game::hint` does
+        let suggested = player.get_best_move(game)?;
+        let mut moved = false;
+        for direction in [
+            suggested,
+            Direction::Up,
+            Direction::Down,
+            Direction::Left,
+            Direction::Right,
+        ] {
+            if game.make_move(direction)?.moved {
+                moved = true;
+                break;
+            }
+        }
+        if !moved {
+            return Ok(won);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_benchmark() {
+        let config = GameConfig {
+            board_size: 3,
+            ..GameConfig::default()
+        };
+        let results = AIBenchmark::run(&config, AIAlgorithm::Greedy, 5, 42).unwrap();
+        assert_eq!(results.strategy, "Greedy");
+        assert_eq!(results.games, 5);
+        assert_eq!(results.max_tiles.values().sum::<usize>(), 5);
+        assert!(results.best_score as f64 >= results.median_score);
+        assert!(results.mean_score > 0.0);
+        assert!(results.total_moves > 0);
+
+        // The same seed plays the same games
+        let again = AIBenchmark::run(&config, AIAlgorithm::Greedy, 5, 42).unwrap();
+        assert_eq!(again.total_moves, results.total_moves);
+        assert_eq!(again.max_tiles, results.max_tiles);
+    }
+}
@@ -0,0 +1,177 @@
+//! Persistent best score.
+//!
+//! A [`Game`](crate::Game) given a [`BestScoreStore`] starts from the stored
+//! best score and writes every new best back, so it survives restarts.
+
+use crate::{GameError, GameResult};
+use std::fmt::{self, Debug};
+
+/// Storage for the best score
+pub trait BestScoreStore: Debug + Send + Sync {
+    /// Best score saved so far (0 when nothing is saved yet)
+    fn load(&self) -> GameResult<u64>;
+
+    /// Save a new best score
+    fn save(&self, best: u64) -> GameResult<()>;
+}
+
+/// Best score kept in a text file
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone)]
+pub struct FileBestScoreStore {
+    path: std::path::PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileBestScoreStore {
+    /// Store the best score at `path`
+    pub fn new(path: impl Into<std::path::PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl BestScoreStore for FileBestScoreStore {
+    fn load(&self) -> GameResult<u64> {
+        if !self.path.exists() {
+            return Ok(0);
+        }
+        let content = std::fs::read_to_string(&self.path).map_err(|e| {
+            GameError::Serialization(format!("Failed to read best score file: {}", e))
+        })?;
+        content
+            .trim()
+            .parse()
+            .map_err(|e| GameError::Serialization(format!("Invalid best score file: {}", e)))
+    }
+
+    fn save(&self, best: u64) -> GameResult<()> {
+        std::fs::write(&self.path, best.to_string()).map_err(|e| {
+            GameError::Serialization(format!("Failed to write best score file: {}", e))
+        })
+    }
+}
+
+type LoadFn = Box<dyn Fn() -> Option<u64> + Send + Sync>;
+type SaveFn = Box<dyn Fn(u64) + Send + Sync>;
+
+/// Best score kept wherever a pair of callbacks puts it, such as the
+/// browser's `localStorage`
+pub struct CallbackBestScoreStore {
+    load: LoadFn,
+    save: SaveFn,
+}
+
+impl CallbackBestScoreStore {
+    /// Create a store from a loader (`None` when nothing is saved) and a saver
+    pub fn new(
+        load: impl Fn() -> Option<u64> + Send + Sync + 'static,
+        save: impl Fn(u64) + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            load: Box::new(load),
+            save: Box::new(save),
+        }
+    }
+}
+
+impl Debug for CallbackBestScoreStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallbackBestScoreStore")
+            .finish_non_exhaustive()
+    }
+}
+
+impl BestScoreStore for CallbackBestScoreStore {
+    fn load(&self) -> GameResult<u64> {
+        Ok((self.load)().unwrap_or(0))
+    }
+
+    fn save(&self, best: u64) -> GameResult<()> {
+        (self.save)(best);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Direction, Game, GameConfig, GameState, Score};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_file_store() {
+        let path = std::env::temp_dir().join(format!("rusty2048-best-{}", std::process::id()));
+        let store = FileBestScoreStore::new(&path);
+        assert_eq!(store.load().unwrap(), 0);
+        store.save(2048).unwrap();
+        assert_eq!(store.load().unwrap(), 2048);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_game_loads_and_saves_best_score() {
+        let saved = Arc::new(AtomicU64::new(100));
+        let store = {
+            let (load, save) = (saved.clone(), saved.clone());
+            CallbackBestScoreStore::new(
+                move || Some(load.load(Ordering::SeqCst)),
+                move |best| save.store(best, Ordering::SeqCst),
+            )
+        };
+
+        let mut game = Game::new(GameConfig::default()).unwrap();
+        game.set_best_score_store(Arc::new(store)).unwrap();
+        assert_eq!(game.score().best(), 100);
+
+        game.load_from_state(
+            vec![64, 64, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            Score::from_parts(90, 90, 0),
+            0,
+            GameState::Playing,
+        )
+        .unwrap();
+        assert_eq!(game.score().best(), 100);
+
+        game.make_move(Direction::Left).unwrap();
+        assert_eq!(game.score().best(), 218);
+        assert_eq!(saved.load(Ordering::SeqCst), 218);
+    }
+
+    #[test]
+    fn test_clones_do_not_save_best_score() {
+        let saved = Arc::new(AtomicU64::new(0));
+        let store = {
+            let save = saved.clone();
+            CallbackBestScoreStore::new(|| Some(0), move |best| save.store(best, Ordering::SeqCst))
+        };
+
+        let mut game = Game::new(GameConfig::default()).unwrap();
+        game.set_best_score_store(Arc::new(store)).unwrap();
+        game.load_from_state(
+            vec![2, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            Score::new(),
+            0,
+            GameState::Playing,
+        )
+        .unwrap();
+
+        // An AI's lookahead plays moves on clones of the game
+        let mut player = crate::AIPlayer::new(crate::AIAlgorithm::Greedy);
+        let best_move = player.get_best_move(&game).unwrap();
+        let mut lookahead = game.clone();
+        lookahead.make_move(Direction::Left).unwrap();
+        assert_eq!(lookahead.score().best(), 4);
+        assert_eq!(saved.load(Ordering::SeqCst), 0);
+
+        // Given the store again, a clone saves the best it already has
+        lookahead
+            .set_best_score_store(game.best_score_store().unwrap())
+            .unwrap();
+        assert_eq!(saved.load(Ordering::SeqCst), 4);
+
+        game.make_move(best_move).unwrap();
+        assert_eq!(saved.load(Ordering::SeqCst), game.score().best().max(4));
+    }
+}
@@ -0,0 +1,83 @@
+//! `rusty2048-train`: learn n-tuple weights by self-play. The weights file
+//! it writes is what the CLI loads through `RUSTY2048_NTUPLE_WEIGHTS`.
+
+use rusty2048_core::{NTupleNetwork, Trainer, TrainingConfig};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: rusty2048-train [--games <N>] [--output <FILE>] [--resume] [--size <N>] [--alpha <RATE>] [--lambda <DECAY>] [--seed <N>] [--report-every <N>] [--checkpoint-every <N>]";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let mut games = 100_000;
+    let mut output = PathBuf::from("ntuple-weights.bin");
+    let mut resume = false;
+    let mut size = 4;
+    let mut config = TrainingConfig {
+        checkpoint_every: 10_000,
+        ..TrainingConfig::default()
+    };
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--games" | "-n" => games = parse(arg, value()?)?,
+            "--output" | "-o" => output = PathBuf::from(value()?),
+            "--resume" => resume = true,
+            "--size" => size = parse(arg, value()?)?,
+            "--alpha" => config.learning_rate = parse(arg, value()?)?,
+            "--lambda" => config.lambda = parse::<f32>(arg, value()?)?.clamp(0.0, 1.0),
+            "--seed" => config.seed = parse(arg, value()?)?,
+            "--report-every" => config.report_every = parse(arg, value()?)?,
+            "--checkpoint-every" => config.checkpoint_every = parse(arg, value()?)?,
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            _ => return Err(format!("Unknown argument: {}\n{}", arg, USAGE)),
+        }
+    }
+    config.checkpoint_path = Some(output.clone());
+
+    let mut trainer = if resume {
+        Trainer::resume(&output, config).map_err(|e| e.to_string())?
+    } else {
+        let network = NTupleNetwork::standard(size).map_err(|e| e.to_string())?;
+        Trainer::new(network, config)
+    };
+
+    trainer
+        .train(games, |progress| {
+            println!(
+                "games {:>8}  avg score {:>9.0}  best {:>7}  max tile {:>5}  2048 rate {:>5.1}%",
+                progress.games,
+                progress.average_score,
+                progress.best_score,
+                progress.max_tile,
+                progress.win_rate * 100.0
+            );
+        })
+        .map_err(|e| e.to_string())?;
+
+    trainer.checkpoint(&output).map_err(|e| e.to_string())?;
+    println!("Saved weights to {}", output.display());
+    Ok(())
+}
+
+fn parse<T: std::str::FromStr>(arg: &str, value: &str) -> Result<T, String>
+where
+    T::Err: std::fmt::Display,
+{
+    value.parse().map_err(|e| format!("Invalid {}: {}", arg, e))
+}
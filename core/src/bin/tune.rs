@@ -0,0 +1,102 @@
+//! `rusty2048-tune`: tune the weights of a heuristic by a genetic
+//! algorithm, playing benchmark games with each candidate.
+
+use rusty2048_core::{AIAlgorithm, HeuristicConfig, Tuner, TuningConfig};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: rusty2048-tune [--generations <N>] [--output <FILE>] [--resume] [--start classic|snake] [--algorithm expectimax|mcts|minimax] [--depth <N>] [--size <N>] [--population <N>] [--elite <N>] [--games <N>] [--mutation <SCALE>] [--seed <N>]";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let mut generations = 20;
+    let mut output = PathBuf::from("heuristic-tuning.json");
+    let mut resume = false;
+    let mut start = HeuristicConfig::classic();
+    let mut config = TuningConfig::default();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--generations" | "-n" => generations = parse(arg, value()?)?,
+            "--output" | "-o" => output = PathBuf::from(value()?),
+            "--resume" => resume = true,
+            "--start" => {
+                start = match value()?.as_str() {
+                    "classic" => HeuristicConfig::classic(),
+                    "snake" => HeuristicConfig::snake(),
+                    other => return Err(format!("Unknown heuristic: {}", other)),
+                }
+            }
+            "--algorithm" | "-a" => {
+                config.algorithm = match value()?.as_str() {
+                    "expectimax" => AIAlgorithm::Expectimax,
+                    "mcts" => AIAlgorithm::MCTS,
+                    "minimax" => AIAlgorithm::Minimax,
+                    other => return Err(format!("Unknown algorithm: {}", other)),
+                }
+            }
+            "--depth" => config.max_depth = parse(arg, value()?)?,
+            "--size" => config.board_size = parse(arg, value()?)?,
+            "--population" => config.population = parse(arg, value()?)?,
+            "--elite" => config.elite = parse(arg, value()?)?,
+            "--games" => config.games = parse(arg, value()?)?,
+            "--mutation" => config.mutation = parse(arg, value()?)?,
+            "--seed" => config.seed = parse(arg, value()?)?,
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            _ => return Err(format!("Unknown argument: {}\n{}", arg, USAGE)),
+        }
+    }
+    config.checkpoint_path = Some(output.clone());
+
+    let mut tuner = if resume {
+        Tuner::resume(&output, config).map_err(|e| e.to_string())?
+    } else {
+        Tuner::new(start, config)
+    };
+
+    tuner
+        .run(generations, |progress| {
+            println!(
+                "generation {:>4}  best {:>9.0}  mean {:>9.0}  best so far {:>9.0}",
+                progress.generation,
+                progress.generation_best.fitness,
+                progress.mean_fitness,
+                progress.best.fitness
+            );
+        })
+        .map_err(|e| e.to_string())?;
+
+    if let Some(best) = tuner.best() {
+        let weights = serde_json::to_string_pretty(&best.heuristic).map_err(|e| e.to_string())?;
+        println!(
+            "Best weights after {} generations (mean score {:.0}):",
+            tuner.generation(),
+            best.fitness
+        );
+        println!("{}", weights);
+    }
+    println!("Saved checkpoint to {}", output.display());
+    Ok(())
+}
+
+fn parse<T: std::str::FromStr>(arg: &str, value: &str) -> Result<T, String>
+where
+    T::Err: std::fmt::Display,
+{
+    value.parse().map_err(|e| format!("Invalid {}: {}", arg, e))
+}
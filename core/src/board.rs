@@ -1,11 +1,12 @@
 use crate::error::{GameError, GameResult};
+use crate::zobrist;
 use serde::{Deserialize, Serialize};
 
 /// Represents a single tile on the game board
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Tile {
     /// Tile value (0 for empty, 2^n for filled tiles)
-    pub value: u32,
+    pub value: u64,
 }
 
 impl Tile {
@@ -15,7 +16,7 @@ impl Tile {
     }
 
     /// Create a new tile with a specific value
-    pub fn new(value: u32) -> Self {
+    pub fn new(value: u64) -> Self {
         Self { value }
     }
 
@@ -30,7 +31,7 @@ impl Tile {
     }
 
     /// Merge this tile with another tile
-    pub fn merge_with(&mut self, other: &Tile) -> u32 {
+    pub fn merge_with(&mut self, other: &Tile) -> u64 {
         if self.can_merge_with(other) {
             self.value *= 2;
             self.value
@@ -51,13 +52,38 @@ impl Tile {
 
 /// Game board representation
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "BoardData", into = "BoardData")]
 pub struct Board {
-    /// 2D grid of tiles
-    tiles: Vec<Vec<Tile>>,
+    /// Tiles in row-major order
+    tiles: Vec<Tile>,
     /// Board size (width = height)
     size: usize,
 }
 
+/// Serialized form of a board, a grid of rows
+#[derive(Serialize, Deserialize)]
+struct BoardData {
+    tiles: Vec<Vec<Tile>>,
+    size: usize,
+}
+
+impl TryFrom<BoardData> for Board {
+    type Error = GameError;
+
+    fn try_from(data: BoardData) -> GameResult<Self> {
+        Board::from_tiles(data.tiles)
+    }
+}
+
+impl From<Board> for BoardData {
+    fn from(board: Board) -> Self {
+        Self {
+            tiles: board.rows().map(<[Tile]>::to_vec).collect(),
+            size: board.size,
+        }
+    }
+}
+
 impl Board {
     /// Create a new empty board
     pub fn new(size: usize) -> GameResult<Self> {
@@ -65,7 +91,7 @@ impl Board {
             return Err(GameError::InvalidBoardSize { size });
         }
 
-        let tiles = vec![vec![Tile::empty(); size]; size];
+        let tiles = vec![Tile::empty(); size * size];
         Ok(Self { tiles, size })
     }
 
@@ -79,7 +105,7 @@ impl Board {
         if row >= self.size || col >= self.size {
             return Err(GameError::InvalidPosition { row, col });
         }
-        Ok(self.tiles[row][col])
+        Ok(self.tiles[row * self.size + col])
     }
 
     /// Set tile at position
@@ -87,7 +113,7 @@ impl Board {
         if row >= self.size || col >= self.size {
             return Err(GameError::InvalidPosition { row, col });
         }
-        self.tiles[row][col] = tile;
+        self.tiles[row * self.size + col] = tile;
         Ok(())
     }
 
@@ -96,22 +122,52 @@ impl Board {
         Ok(self.get_tile(row, col)?.is_empty())
     }
 
+    /// Iterate over all tiles with their (row, col), row by row
+    pub fn iter_tiles(&self) -> impl Iterator<Item = (usize, usize, Tile)> + '_ {
+        self.tiles
+            .iter()
+            .enumerate()
+            .map(move |(index, &tile)| (index / self.size, index % self.size, tile))
+    }
+
+    /// Iterate over the rows, top to bottom
+    pub fn rows(&self) -> impl Iterator<Item = &[Tile]> + '_ {
+        self.tiles.chunks(self.size)
+    }
+
+    /// Iterate over the columns, left to right; each column runs top to bottom
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = Tile> + '_> + '_ {
+        (0..self.size).map(move |col| self.tiles.iter().skip(col).step_by(self.size).copied())
+    }
+
+    /// All tiles in row-major order
+    pub fn as_flat_slice(&self) -> &[Tile] {
+        &self.tiles
+    }
+
+    /// Zobrist hash of the tiles, stable across runs and releases.
+    /// Equal boards hash equally; use it for transposition tables and
+    /// quick integrity checks.
+    pub fn zobrist_hash(&self) -> u64 {
+        self.tiles
+            .iter()
+            .enumerate()
+            .fold(zobrist::size_key(self.size), |hash, (cell, tile)| {
+                hash ^ zobrist::key(cell, tile.value)
+            })
+    }
+
     /// Get all empty positions
     pub fn empty_positions(&self) -> Vec<(usize, usize)> {
-        let mut positions = Vec::new();
-        for row in 0..self.size {
-            for col in 0..self.size {
-                if self.tiles[row][col].is_empty() {
-                    positions.push((row, col));
-                }
-            }
-        }
-        positions
+        self.iter_tiles()
+            .filter(|(_, _, tile)| tile.is_empty())
+            .map(|(row, col, _)| (row, col))
+            .collect()
     }
 
     /// Check if board is full
     pub fn is_full(&self) -> bool {
-        self.empty_positions().is_empty()
+        self.tiles.iter().all(|tile| !tile.is_empty())
     }
 
     /// Check if any moves are possible
@@ -122,31 +178,18 @@ impl Board {
         }
 
         // Check for possible merges
-        for row in 0..self.size {
-            for col in 0..self.size {
-                let current = self.tiles[row][col];
-
-                // Check right neighbor
-                if col + 1 < self.size && current.can_merge_with(&self.tiles[row][col + 1]) {
-                    return true;
-                }
-
+        self.iter_tiles().any(|(row, col, current)| {
+            // Check right neighbor
+            (col + 1 < self.size && current.can_merge_with(&self.tiles[row * self.size + col + 1]))
                 // Check bottom neighbor
-                if row + 1 < self.size && current.can_merge_with(&self.tiles[row + 1][col]) {
-                    return true;
-                }
-            }
-        }
-
-        false
+                || (row + 1 < self.size
+                    && current.can_merge_with(&self.tiles[(row + 1) * self.size + col]))
+        })
     }
 
     /// Get a copy of the current board state
     pub fn clone_board(&self) -> Self {
-        Self {
-            tiles: self.tiles.clone(),
-            size: self.size,
-        }
+        self.clone()
     }
 
     /// Create board from tile data
@@ -160,53 +203,37 @@ impl Board {
             return Err(GameError::InvalidBoardSize { size });
         }
 
-        Ok(Self { tiles, size })
+        Ok(Self {
+            tiles: tiles.into_iter().flatten().collect(),
+            size,
+        })
     }
 
     /// Get the maximum tile value on the board
-    pub fn max_tile(&self) -> u32 {
-        self.tiles
-            .iter()
-            .flat_map(|row| row.iter())
-            .map(|tile| tile.value)
-            .max()
-            .unwrap_or(0)
+    pub fn max_tile(&self) -> u64 {
+        self.tiles.iter().map(|tile| tile.value).max().unwrap_or(0)
     }
 
     /// Count tiles with a specific value
-    pub fn count_tiles(&self, value: u32) -> usize {
-        self.tiles
-            .iter()
-            .flat_map(|row| row.iter())
-            .filter(|tile| tile.value == value)
-            .count()
+    pub fn count_tiles(&self, value: u64) -> usize {
+        self.tiles.iter().filter(|tile| tile.value == value).count()
     }
 
-    /// Convert board to 2D vector of u32 values
-    pub fn to_vec(&self) -> Vec<Vec<u32>> {
-        self.tiles
-            .iter()
+    /// Convert board to 2D vector of u64 values
+    pub fn to_vec(&self) -> Vec<Vec<u64>> {
+        self.rows()
             .map(|row| row.iter().map(|tile| tile.value).collect())
             .collect()
     }
 
-    /// Create board from 2D vector of u32 values
-    pub fn from_vec(values: Vec<Vec<u32>>) -> GameResult<Self> {
-        if values.is_empty() || values[0].is_empty() {
-            return Err(GameError::InvalidBoardSize { size: 0 });
-        }
-
-        let size = values.len();
-        if values.iter().any(|row| row.len() != size) {
-            return Err(GameError::InvalidBoardSize { size });
-        }
-
-        let tiles = values
-            .iter()
-            .map(|row| row.iter().map(|&value| Tile::new(value)).collect())
-            .collect();
-
-        Ok(Self { tiles, size })
+    /// Create board from 2D vector of u64 values
+    pub fn from_vec(values: Vec<Vec<u64>>) -> GameResult<Self> {
+        Self::from_tiles(
+            values
+                .into_iter()
+                .map(|row| row.into_iter().map(Tile::new).collect())
+                .collect(),
+        )
     }
 }
 
@@ -259,4 +286,53 @@ mod tests {
         // Test max tile
         assert_eq!(board.max_tile(), 2);
     }
+
+    #[test]
+    fn test_traversal() {
+        let board = Board::from_vec(vec![vec![2, 4], vec![8, 0]]).unwrap();
+
+        let tiles: Vec<_> = board
+            .iter_tiles()
+            .map(|(row, col, tile)| (row, col, tile.value))
+            .collect();
+        assert_eq!(tiles, vec![(0, 0, 2), (0, 1, 4), (1, 0, 8), (1, 1, 0)]);
+
+        let rows: Vec<Vec<u64>> = board
+            .rows()
+            .map(|row| row.iter().map(|tile| tile.value).collect())
+            .collect();
+        assert_eq!(rows, board.to_vec());
+
+        let columns: Vec<Vec<u64>> = board
+            .columns()
+            .map(|column| column.map(|tile| tile.value).collect())
+            .collect();
+        assert_eq!(columns, vec![vec![2, 8], vec![4, 0]]);
+
+        assert_eq!(board.as_flat_slice().len(), 4);
+    }
+
+    #[test]
+    fn test_serde_keeps_grid_format() {
+        let board = Board::from_vec(vec![vec![2, 4], vec![8, 0]]).unwrap();
+        let json = serde_json::to_value(&board).unwrap();
+        assert_eq!(json["tiles"][1][0]["value"], 8);
+
+        let back: Board = serde_json::from_value(json).unwrap();
+        assert_eq!(back.to_vec(), board.to_vec());
+    }
+
+    #[test]
+    fn test_zobrist_hash() {
+        let board = Board::from_vec(vec![vec![2, 4], vec![0, 0]]).unwrap();
+        let same = Board::from_vec(vec![vec![2, 4], vec![0, 0]]).unwrap();
+        let moved = Board::from_vec(vec![vec![0, 0], vec![2, 4]]).unwrap();
+
+        assert_eq!(board.zobrist_hash(), same.zobrist_hash());
+        assert_ne!(board.zobrist_hash(), moved.zobrist_hash());
+        assert_ne!(
+            Board::new(3).unwrap().zobrist_hash(),
+            Board::new(4).unwrap().zobrist_hash()
+        );
+    }
 }
@@ -0,0 +1,219 @@
+use crate::{Game, GameConfig, GameError, GameResult, ReplayData};
+use serde::{Deserialize, Serialize};
+
+/// Seconds in a UTC day
+pub const SECONDS_PER_DAY: u64 = 86_400;
+
+/// The daily challenge: one seeded game per UTC day, the same for every player
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyChallenge {
+    /// Days since the Unix epoch (UTC)
+    pub day: u64,
+    /// Date as `YYYY-MM-DD`
+    pub date: String,
+    /// Seed of the day's game
+    pub seed: u64,
+}
+
+/// A finished daily challenge
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyResult {
+    pub day: u64,
+    pub date: String,
+    pub score: u64,
+    pub moves: u32,
+    pub max_tile: u64,
+    pub won: bool,
+    /// When the game finished (Unix timestamp)
+    pub completed_at: u64,
+}
+
+impl DailyChallenge {
+    /// Get the challenge of a day
+    pub fn for_day(day: u64) -> Self {
+        Self {
+            day,
+            date: date_string(day),
+            seed: day_seed(day),
+        }
+    }
+
+    /// Get the challenge of a UTC date written as `YYYY-MM-DD`
+    pub fn for_date(date: &str) -> GameResult<Self> {
+        parse_date(date)
+            .map(Self::for_day)
+            .ok_or_else(|| GameError::InvalidOperation(format!("Invalid date: {}", date)))
+    }
+
+    /// Get the challenge for a Unix timestamp
+    pub fn for_timestamp(timestamp: u64) -> Self {
+        Self::for_day(timestamp / SECONDS_PER_DAY)
+    }
+
+    /// Get today's challenge
+    pub fn today() -> Self {
+        Self::for_timestamp(crate::get_current_time())
+    }
+
+    /// Game configuration of the challenge (classic rules, no undo)
+    pub fn config(&self) -> GameConfig {
+        GameConfig {
+            allow_undo: false,
+            seed: Some(self.seed),
+            ..GameConfig::default()
+        }
+    }
+
+    /// Whether a game was set up as this challenge
+    pub fn matches_config(&self, config: &GameConfig) -> bool {
+        *config == self.config()
+    }
+
+    /// Check a replay tagged as a daily run against the challenge of its
+    /// date: same configuration and same starting board. Returns the
+    /// challenge it was played on.
+    pub fn validate_replay(replay: &ReplayData) -> GameResult<Self> {
+        let date = replay.metadata.daily.as_deref().ok_or_else(|| {
+            GameError::InvalidOperation("Replay is not a daily challenge".to_string())
+        })?;
+        let challenge = Self::for_date(date)?;
+
+        if !challenge.matches_config(&replay.config)
+            || challenge.new_game()?.board().to_vec() != replay.initial_board
+        {
+            return Err(GameError::InvalidOperation(format!(
+                "Replay was not played on the daily challenge of {}",
+                date
+            )));
+        }
+        Ok(challenge)
+    }
+
+    /// Start the challenge's game
+    pub fn new_game(&self) -> GameResult<Game> {
+        Game::new(self.config())
+    }
+
+    /// Summarize a finished game of this challenge
+    pub fn result(&self, game: &Game) -> DailyResult {
+        DailyResult {
+            day: self.day,
+            date: self.date.clone(),
+            score: game.score().current(),
+            moves: game.moves(),
+            max_tile: game.board().max_tile(),
+            won: game.state() == crate::GameState::Won,
+            completed_at: crate::get_current_time(),
+        }
+    }
+}
+
+/// Seed and game configuration of the daily challenge on a UTC date
+/// (`YYYY-MM-DD`), the same on every frontend
+pub fn daily_seed(date: &str) -> GameResult<(u64, GameConfig)> {
+    let challenge = DailyChallenge::for_date(date)?;
+    Ok((challenge.seed, challenge.config()))
+}
+
+/// Mix the day number into a well-spread seed (splitmix64)
+fn day_seed(day: u64) -> u64 {
+    let mut z = day.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Format days since the Unix epoch as a `YYYY-MM-DD` date
+pub fn date_string(day: u64) -> String {
+    // Civil-from-days conversion for the proleptic Gregorian calendar
+    let z = day + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + u64::from(m <= 2);
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+/// Parse a `YYYY-MM-DD` date into days since the Unix epoch
+pub fn parse_date(date: &str) -> Option<u64> {
+    let mut parts = date.splitn(3, '-').map(str::parse::<u64>);
+    let (y, m, d) = (
+        parts.next()?.ok()?,
+        parts.next()?.ok()?,
+        parts.next()?.ok()?,
+    );
+    if !(1970..=9999).contains(&y) || !(1..=12).contains(&m) || !(1..=31).contains(&d) {
+        return None;
+    }
+
+    // Days-from-civil, the inverse of `date_string`
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y / 400;
+    let yoe = y - era * 400;
+    let mp = if m > 2 { m - 3 } else { m + 9 };
+    let doy = (153 * mp + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let day = (era * 146_097 + doe).checked_sub(719_468)?;
+
+    // Reject days past the end of the month, such as 2025-02-30
+    (date_string(day) == date).then_some(day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReplayMetadata;
+
+    #[test]
+    fn test_date_string() {
+        assert_eq!(date_string(0), "1970-01-01");
+        assert_eq!(date_string(11_016), "2000-02-29");
+        assert_eq!(date_string(20_378), "2025-10-17");
+    }
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!(parse_date("1970-01-01"), Some(0));
+        assert_eq!(parse_date("2000-02-29"), Some(11_016));
+        assert_eq!(parse_date("2025-10-17"), Some(20_378));
+        assert_eq!(parse_date("2025-02-29"), None);
+        assert_eq!(parse_date("2025-13-01"), None);
+        assert_eq!(parse_date("yesterday"), None);
+    }
+
+    #[test]
+    fn test_daily_replay_validation() {
+        let (seed, config) = daily_seed("2025-10-17").unwrap();
+        let challenge = DailyChallenge::for_day(20_378);
+        assert_eq!(seed, challenge.seed);
+        assert!(challenge.matches_config(&config));
+
+        let mut recorder = crate::ReplayRecorder::new(config).unwrap();
+        let _ = recorder.make_move(crate::Direction::Left);
+        recorder.set_metadata(ReplayMetadata::default().with_daily(&challenge));
+        let mut replay = recorder.stop_recording();
+        assert_eq!(DailyChallenge::validate_replay(&replay).unwrap(), challenge);
+
+        replay.metadata.daily = Some("2025-10-18".to_string());
+        assert!(DailyChallenge::validate_replay(&replay).is_err());
+        replay.metadata.daily = None;
+        assert!(DailyChallenge::validate_replay(&replay).is_err());
+    }
+
+    #[test]
+    fn test_same_day_same_game() {
+        let morning = DailyChallenge::for_timestamp(20_377 * SECONDS_PER_DAY + 60);
+        let evening = DailyChallenge::for_timestamp(20_377 * SECONDS_PER_DAY + 80_000);
+        assert_eq!(morning, evening);
+        assert_ne!(morning.seed, DailyChallenge::for_day(20_378).seed);
+
+        let first = morning.new_game().unwrap();
+        let second = evening.new_game().unwrap();
+        assert_eq!(first.board().to_vec(), second.board().to_vec());
+        assert!(!first.config().allow_undo);
+    }
+}
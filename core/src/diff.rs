@@ -0,0 +1,229 @@
+//! Differences between two board snapshots.
+//!
+//! Frontends that only have snapshots, such as replay playback, use this to
+//! animate the step from one board to the next.
+
+use crate::game::line_cells;
+use crate::rules::{self, ClassicRules};
+use crate::{Board, Direction};
+use serde::{Deserialize, Serialize};
+
+/// One cell-level change between two boards
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TileChange {
+    /// A tile appeared in an empty cell
+    Added {
+        position: (usize, usize),
+        value: u64,
+    },
+    /// A tile disappeared
+    Removed {
+        position: (usize, usize),
+        value: u64,
+    },
+    /// A tile slid to another cell
+    Moved {
+        from: (usize, usize),
+        to: (usize, usize),
+        value: u64,
+    },
+    /// Two tiles merged into one; `value` is the merged value
+    Merged {
+        position: (usize, usize),
+        sources: [(usize, usize); 2],
+        value: u64,
+    },
+}
+
+impl TileChange {
+    fn is_added_or_removed(&self) -> bool {
+        matches!(self, TileChange::Added { .. } | TileChange::Removed { .. })
+    }
+}
+
+impl Board {
+    /// Changes that turn this board into `other`.
+    ///
+    /// When `other` is this board after a single move (under the classic
+    /// merge rules) plus spawned tiles, the slides and merges of that move
+    /// are reported along with the spawns as additions. Otherwise each
+    /// differing cell is reported as a removal and/or an addition.
+    pub fn diff(&self, other: &Board) -> Vec<TileChange> {
+        let mut best = self.cell_diff(other);
+        if self.size() == other.size() {
+            for direction in [
+                Direction::Up,
+                Direction::Down,
+                Direction::Left,
+                Direction::Right,
+            ] {
+                if let Some(changes) = self.move_diff(other, direction) {
+                    let unexplained = |changes: &[TileChange]| {
+                        changes.iter().filter(|c| c.is_added_or_removed()).count()
+                    };
+                    if unexplained(&changes) < unexplained(&best) {
+                        best = changes;
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    /// Cell by cell changes, without tracking tiles across cells
+    fn cell_diff(&self, other: &Board) -> Vec<TileChange> {
+        let size = self.size().max(other.size());
+        let value = |board: &Board, row, col| board.get_tile(row, col).map_or(0, |tile| tile.value);
+
+        let mut changes = Vec::new();
+        for row in 0..size {
+            for col in 0..size {
+                let (before, after) = (value(self, row, col), value(other, row, col));
+                if before == after {
+                    continue;
+                }
+                if before != 0 {
+                    changes.push(TileChange::Removed {
+                        position: (row, col),
+                        value: before,
+                    });
+                }
+                if after != 0 {
+                    changes.push(TileChange::Added {
+                        position: (row, col),
+                        value: after,
+                    });
+                }
+            }
+        }
+        changes
+    }
+
+    /// Changes made by a move in `direction`, if `other` can be this board
+    /// after that move and some spawns
+    fn move_diff(&self, other: &Board, direction: Direction) -> Option<Vec<TileChange>> {
+        let size = self.size();
+        let mut changes = Vec::new();
+        let mut moved = false;
+
+        for line in 0..size {
+            let cells = line_cells(size, line, direction);
+            let values: Vec<u64> = cells
+                .iter()
+                .map(|&(row, col)| self.get_tile(row, col).map_or(0, |tile| tile.value))
+                .collect();
+            let slid = rules::slide_line(&ClassicRules, &values);
+
+            for (to, &(row, col)) in cells.iter().enumerate() {
+                let after = other.get_tile(row, col).ok()?.value;
+                match slid.sources.get(to).map(Vec::as_slice) {
+                    Some(&[from]) => {
+                        if after != values[from] {
+                            return None;
+                        }
+                        if from != to {
+                            moved = true;
+                            changes.push(TileChange::Moved {
+                                from: cells[from],
+                                to: (row, col),
+                                value: values[from],
+                            });
+                        }
+                    }
+                    Some(&[first, second]) => {
+                        if after != slid.values[to] {
+                            return None;
+                        }
+                        moved = true;
+                        changes.push(TileChange::Merged {
+                            position: (row, col),
+                            sources: [cells[first], cells[second]],
+                            value: after,
+                        });
+                    }
+                    _ if after != 0 => changes.push(TileChange::Added {
+                        position: (row, col),
+                        value: after,
+                    }),
+                    _ => {}
+                }
+            }
+        }
+
+        moved.then_some(changes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_after_move() {
+        let before = Board::from_vec(vec![
+            vec![4, 0, 2, 2],
+            vec![0, 0, 0, 8],
+            vec![0; 4],
+            vec![0; 4],
+        ])
+        .unwrap();
+        // Left, then a 2 spawned in the bottom corner
+        let after = Board::from_vec(vec![
+            vec![4, 4, 0, 0],
+            vec![8, 0, 0, 0],
+            vec![0; 4],
+            vec![0, 0, 0, 2],
+        ])
+        .unwrap();
+
+        assert_eq!(
+            before.diff(&after),
+            vec![
+                TileChange::Merged {
+                    position: (0, 1),
+                    sources: [(0, 2), (0, 3)],
+                    value: 4,
+                },
+                TileChange::Moved {
+                    from: (1, 3),
+                    to: (1, 0),
+                    value: 8,
+                },
+                TileChange::Added {
+                    position: (3, 3),
+                    value: 2,
+                },
+            ]
+        );
+        assert!(before.diff(&before).is_empty());
+    }
+
+    #[test]
+    fn test_diff_without_a_move() {
+        let before = Board::from_vec(vec![vec![2, 0], vec![0, 0]]).unwrap();
+        let after = Board::from_vec(vec![vec![2, 0], vec![0, 2]]).unwrap();
+        assert_eq!(
+            before.diff(&after),
+            vec![TileChange::Added {
+                position: (1, 1),
+                value: 2,
+            }]
+        );
+
+        let undone = Board::from_vec(vec![vec![0, 4], vec![0, 0]]).unwrap();
+        assert_eq!(
+            before.diff(&undone),
+            vec![
+                TileChange::Removed {
+                    position: (0, 0),
+                    value: 2,
+                },
+                TileChange::Added {
+                    position: (0, 1),
+                    value: 4,
+                },
+            ]
+        );
+    }
+}
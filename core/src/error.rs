@@ -1,3 +1,4 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use thiserror::Error;
 
 /// Game-related errors
@@ -18,6 +19,9 @@ pub enum GameError {
     #[error("No undo available")]
     NoUndoAvailable,
 
+    #[error("No redo available")]
+    NoRedoAvailable,
+
     #[error("Serialization error: {0}")]
     Serialization(String),
 
@@ -26,7 +30,179 @@ pub enum GameError {
 
     #[error("Invalid operation: {0}")]
     InvalidOperation(String),
+
+    #[error("Data was written by a newer version (format {found}, this version supports up to {supported})")]
+    UnsupportedVersion { found: u32, supported: u32 },
+
+    #[error("Cancelled")]
+    Cancelled,
+}
+
+/// Stable identifier of a `GameError` kind, for frontends that branch on
+/// the kind of error instead of its message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    InvalidMove,
+    GameOver,
+    InvalidPosition,
+    InvalidBoardSize,
+    NoUndoAvailable,
+    NoRedoAvailable,
+    Serialization,
+    RngError,
+    InvalidOperation,
+    UnsupportedVersion,
+    Cancelled,
+}
+
+impl GameError {
+    /// Stable code of the error kind
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            GameError::InvalidMove(_) => ErrorCode::InvalidMove,
+            GameError::GameOver => ErrorCode::GameOver,
+            GameError::InvalidPosition { .. } => ErrorCode::InvalidPosition,
+            GameError::InvalidBoardSize { .. } => ErrorCode::InvalidBoardSize,
+            GameError::NoUndoAvailable => ErrorCode::NoUndoAvailable,
+            GameError::NoRedoAvailable => ErrorCode::NoRedoAvailable,
+            GameError::Serialization(_) => ErrorCode::Serialization,
+            GameError::RngError(_) => ErrorCode::RngError,
+            GameError::InvalidOperation(_) => ErrorCode::InvalidOperation,
+            GameError::UnsupportedVersion { .. } => ErrorCode::UnsupportedVersion,
+            GameError::Cancelled => ErrorCode::Cancelled,
+        }
+    }
+
+    fn details(&self) -> Option<ErrorDetails> {
+        let details = match self {
+            GameError::InvalidMove(reason)
+            | GameError::Serialization(reason)
+            | GameError::RngError(reason)
+            | GameError::InvalidOperation(reason) => ErrorDetails {
+                reason: Some(reason.clone()),
+                ..ErrorDetails::default()
+            },
+            GameError::InvalidPosition { row, col } => ErrorDetails {
+                row: Some(*row),
+                col: Some(*col),
+                ..ErrorDetails::default()
+            },
+            GameError::InvalidBoardSize { size } => ErrorDetails {
+                size: Some(*size),
+                ..ErrorDetails::default()
+            },
+            GameError::UnsupportedVersion { found, supported } => ErrorDetails {
+                found: Some(*found),
+                supported: Some(*supported),
+                ..ErrorDetails::default()
+            },
+            GameError::GameOver
+            | GameError::NoUndoAvailable
+            | GameError::NoRedoAvailable
+            | GameError::Cancelled => return None,
+        };
+        Some(details)
+    }
+}
+
+/// Serialized form of an error: `{code, message, details}`
+#[derive(Serialize, Deserialize)]
+struct ErrorData {
+    code: ErrorCode,
+    message: String,
+    #[serde(default)]
+    details: Option<ErrorDetails>,
+}
+
+/// Values carried by an error, depending on its code
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ErrorDetails {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    row: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    col: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    size: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    found: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    supported: Option<u32>,
+}
+
+impl Serialize for GameError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ErrorData {
+            code: self.code(),
+            message: self.to_string(),
+            details: self.details(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for GameError {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = ErrorData::deserialize(deserializer)?;
+        let details = data.details.unwrap_or_default();
+        // Fall back to the message for errors written without details
+        let reason = || details.reason.clone().unwrap_or(data.message.clone());
+
+        Ok(match data.code {
+            ErrorCode::InvalidMove => GameError::InvalidMove(reason()),
+            ErrorCode::GameOver => GameError::GameOver,
+            ErrorCode::InvalidPosition => GameError::InvalidPosition {
+                row: details.row.unwrap_or_default(),
+                col: details.col.unwrap_or_default(),
+            },
+            ErrorCode::InvalidBoardSize => GameError::InvalidBoardSize {
+                size: details.size.unwrap_or_default(),
+            },
+            ErrorCode::NoUndoAvailable => GameError::NoUndoAvailable,
+            ErrorCode::NoRedoAvailable => GameError::NoRedoAvailable,
+            ErrorCode::Serialization => GameError::Serialization(reason()),
+            ErrorCode::RngError => GameError::RngError(reason()),
+            ErrorCode::InvalidOperation => GameError::InvalidOperation(reason()),
+            ErrorCode::UnsupportedVersion => GameError::UnsupportedVersion {
+                found: details.found.unwrap_or_default(),
+                supported: details.supported.unwrap_or_default(),
+            },
+            ErrorCode::Cancelled => GameError::Cancelled,
+        })
+    }
 }
 
 /// Result type for game operations
 pub type GameResult<T> = Result<T, GameError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialized_errors() {
+        let json = serde_json::to_value(GameError::NoUndoAvailable).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "code": "no_undo_available",
+                "message": "No undo available",
+                "details": null,
+            })
+        );
+
+        let error = GameError::InvalidPosition { row: 1, col: 5 };
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["code"], "invalid_position");
+        assert_eq!(json["details"]["col"], 5);
+
+        let back: GameError = serde_json::from_value(json).unwrap();
+        assert_eq!(back.to_string(), error.to_string());
+        let back: GameError =
+            serde_json::from_str(r#"{"code": "invalid_move", "message": "Invalid move: stuck"}"#)
+                .unwrap();
+        assert_eq!(back.code(), ErrorCode::InvalidMove);
+    }
+}
@@ -0,0 +1,119 @@
+use crate::Direction;
+use serde::{Deserialize, Serialize};
+
+/// Something that happened in a game, for frontends that react to changes
+/// instead of polling the whole state
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GameEvent {
+    /// A move changed the board
+    Moved { direction: Direction, points: u64 },
+    /// A random tile was placed on the board
+    TileSpawned { row: usize, col: usize, value: u64 },
+    /// The target tile was reached
+    Won { max_tile: u64 },
+    /// No valid moves are left
+    GameOver { score: u64 },
+    /// The last move was undone
+    Undone,
+    /// An undone move was redone
+    Redone,
+    /// A new game was started
+    NewGame,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Game, GameConfig, GameState, Score};
+
+    fn game_with_board(board: Vec<u64>) -> Game {
+        let mut game = Game::new(GameConfig::default()).unwrap();
+        game.load_from_state(board, Score::new(), 0, GameState::Playing)
+            .unwrap();
+        game
+    }
+
+    #[test]
+    fn test_events_disabled_by_default() {
+        let mut game = game_with_board(vec![2, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        game.make_move(Direction::Left).unwrap();
+        assert!(game.take_events().is_empty());
+    }
+
+    #[test]
+    fn test_move_and_undo_events() {
+        let mut game = game_with_board(vec![2, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        game.enable_events();
+
+        game.make_move(Direction::Left).unwrap();
+        let events = game.take_events();
+        assert_eq!(
+            events[0],
+            GameEvent::Moved {
+                direction: Direction::Left,
+                points: 4
+            }
+        );
+        assert!(matches!(events[1], GameEvent::TileSpawned { .. }));
+        assert_eq!(events.len(), 2);
+
+        game.undo().unwrap();
+        assert_eq!(game.take_events(), vec![GameEvent::Undone]);
+        game.redo().unwrap();
+        assert_eq!(game.take_events(), vec![GameEvent::Redone]);
+        assert!(game.take_events().is_empty());
+    }
+
+    #[test]
+    fn test_new_game_event() {
+        let mut game = game_with_board(vec![2, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        game.enable_events();
+
+        game.new_game().unwrap();
+        let events = game.take_events();
+        assert_eq!(events[0], GameEvent::NewGame);
+        assert!(events[1..]
+            .iter()
+            .all(|event| matches!(event, GameEvent::TileSpawned { .. })));
+    }
+
+    #[test]
+    fn test_won_event() {
+        let mut game = game_with_board(vec![1024, 1024, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        game.enable_events();
+
+        game.make_move(Direction::Left).unwrap();
+        assert!(game
+            .take_events()
+            .contains(&GameEvent::Won { max_tile: 2048 }));
+    }
+
+    #[test]
+    fn test_game_over_event() {
+        // Moving right leaves one empty cell that no spawned tile can merge from
+        let mut game = game_with_board(vec![
+            16, 32, 64, 0, 128, 256, 512, 1024, 2, 4, 8, 16, 32, 64, 128, 256,
+        ]);
+        game.enable_events();
+
+        game.make_move(Direction::Right).unwrap();
+        assert_eq!(game.state(), GameState::GameOver);
+        assert_eq!(
+            game.take_events().last(),
+            Some(&GameEvent::GameOver { score: 0 })
+        );
+    }
+
+    #[test]
+    fn test_event_serialization() {
+        let event = GameEvent::TileSpawned {
+            row: 1,
+            col: 2,
+            value: 4,
+        };
+        let json = serde_json::to_string(&event).unwrap();
+        assert_eq!(json, r#"{"type":"tile_spawned","row":1,"col":2,"value":4}"#);
+        assert_eq!(serde_json::from_str::<GameEvent>(&json).unwrap(), event);
+    }
+}
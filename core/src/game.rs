@@ -1,6 +1,13 @@
 use crate::error::{GameError, GameResult};
-use crate::{board::Tile, Board, GameConfig, GameRng, GameStats, Score};
+use crate::moves::{MoveResult, SpawnedTile, TileMerge, TileMove};
+use crate::rules::{self, GameRules};
+use crate::{
+    board::Tile, BestScoreStore, Board, GameConfig, GameEvent, GameRng, GameStats, Score,
+    WinTrigger,
+};
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
 
 /// Game direction for moves
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -11,6 +18,25 @@ pub enum Direction {
     Right,
 }
 
+impl Direction {
+    /// Whether the direction moves tiles along rows
+    pub fn is_horizontal(&self) -> bool {
+        matches!(self, Direction::Left | Direction::Right)
+    }
+}
+
+impl std::fmt::Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Direction::Up => "up",
+            Direction::Down => "down",
+            Direction::Left => "left",
+            Direction::Right => "right",
+        };
+        f.write_str(name)
+    }
+}
+
 /// Game state
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum GameState {
@@ -19,8 +45,56 @@ pub enum GameState {
     GameOver,
 }
 
-/// Main game controller
+/// Serializable snapshot of an in-progress game, used to resume it later
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedGame {
+    /// Board tile values
+    pub board: Vec<Vec<u64>>,
+    /// Score tracker
+    pub score: Score,
+    /// Number of moves made
+    pub moves: u32,
+    /// Game state
+    pub state: GameState,
+    /// Game configuration
+    pub config: GameConfig,
+    /// Play time so far in seconds
+    pub elapsed: u64,
+    /// When the snapshot was taken (Unix timestamp)
+    pub saved_at: u64,
+    /// Random generator state, so upcoming tiles match the original game.
+    /// Missing in older saves, which resume with a fresh generator.
+    #[serde(default)]
+    pub rng: Option<GameRng>,
+    /// Whether the game was won and the player kept going
+    #[serde(default)]
+    pub keep_playing: bool,
+    /// Time taken for each move so far, in milliseconds
+    #[serde(default)]
+    pub move_times: Vec<u64>,
+    /// Which part of the win condition won the game
+    #[serde(default)]
+    pub won_by: Option<WinTrigger>,
+    /// `Board::zobrist_hash` of `board`, checked when resuming.
+    /// Missing in older saves.
+    #[serde(default)]
+    pub board_hash: Option<u64>,
+}
+
+/// Board and score at one point of the undo/redo history
 #[derive(Debug, Clone)]
+struct Snapshot {
+    board: Board,
+    score: Score,
+}
+
+/// Main game controller
+///
+/// Clones do not share the best score store: they are mostly hypothetical
+/// copies, such as an AI's lookahead, whose moves must not be saved as the
+/// player's best. Give a clone that becomes the played game the store again
+/// with `set_best_score_store`.
+#[derive(Debug)]
 pub struct Game {
     /// Game board
     board: Board,
@@ -30,35 +104,89 @@ pub struct Game {
     rng: GameRng,
     /// Game configuration
     config: GameConfig,
+    /// Rules named by `config.rules`
+    rules: Arc<dyn GameRules>,
     /// Current game state
     state: GameState,
     /// Number of moves made
     moves: u32,
     /// Game start time (Unix timestamp)
     start_time: u64,
-    /// Previous board state for undo
-    previous_board: Option<Board>,
-    /// Previous score for undo
-    previous_score: Option<Score>,
+    /// Set by `continue_after_win`; the game then runs until no moves are left
+    keep_playing: bool,
+    /// Which part of the win condition won the game
+    won_by: Option<WinTrigger>,
+    /// Time taken for each move in milliseconds, in move order
+    move_times: Vec<u64>,
+    /// When the current move's think time started (Unix milliseconds)
+    move_started_at: u64,
+    /// When the game was paused (Unix milliseconds); `None` while running
+    paused_at: Option<u64>,
+    /// States before the most recent moves, oldest first, for undo
+    undo_stack: VecDeque<Snapshot>,
+    /// Undone states, most recently undone last, for redo
+    redo_stack: Vec<Snapshot>,
+    /// Events not yet taken by the frontend; `None` while event tracking is off
+    events: Option<Vec<GameEvent>>,
+    /// Where the best score is persisted, if anywhere
+    best_score_store: Option<Arc<dyn BestScoreStore>>,
+}
+
+impl Clone for Game {
+    fn clone(&self) -> Self {
+        Self {
+            board: self.board.clone(),
+            score: self.score.clone(),
+            rng: self.rng.clone(),
+            config: self.config.clone(),
+            rules: self.rules.clone(),
+            state: self.state.clone(),
+            moves: self.moves,
+            start_time: self.start_time,
+            keep_playing: self.keep_playing,
+            won_by: self.won_by,
+            move_times: self.move_times.clone(),
+            move_started_at: self.move_started_at,
+            paused_at: self.paused_at,
+            undo_stack: self.undo_stack.clone(),
+            redo_stack: self.redo_stack.clone(),
+            events: self.events.clone(),
+            best_score_store: None,
+        }
+    }
 }
 
 impl Game {
     /// Create a new game with configuration
     pub fn new(config: GameConfig) -> GameResult<Self> {
+        if config.tiles_per_spawn == 0 {
+            return Err(GameError::InvalidOperation(
+                "At least one tile must spawn per move".to_string(),
+            ));
+        }
         let board = Board::new(config.board_size)?;
         let rng = GameRng::new(config.seed);
         let start_time = Self::get_current_time();
+        let rules = lookup_rules(&config)?;
 
         let mut game = Self {
             board,
             score: Score::new(),
             rng,
+            rules,
             config,
             state: GameState::Playing,
             moves: 0,
             start_time,
-            previous_board: None,
-            previous_score: None,
+            keep_playing: false,
+            won_by: None,
+            move_times: Vec::new(),
+            move_started_at: crate::get_current_time_ms(),
+            paused_at: None,
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+            events: None,
+            best_score_store: None,
         };
 
         // Add initial tiles
@@ -83,6 +211,39 @@ impl Game {
         &self.score
     }
 
+    /// Restore a best score persisted by the frontend
+    pub fn restore_best_score(&mut self, best: u64) {
+        self.score.restore_best(best);
+    }
+
+    /// Persist the best score in a store: the stored best is loaded now, a
+    /// higher best the game already has is saved, and every new best is
+    /// saved after the move that set it. The store is kept even when
+    /// loading fails.
+    pub fn set_best_score_store(&mut self, store: Arc<dyn BestScoreStore>) -> GameResult<()> {
+        let stored = store.load();
+        self.best_score_store = Some(store);
+        let stored = stored?;
+        self.score.restore_best(stored);
+        self.save_best_score(stored);
+        Ok(())
+    }
+
+    /// The store the best score is persisted in, if any
+    pub fn best_score_store(&self) -> Option<Arc<dyn BestScoreStore>> {
+        self.best_score_store.clone()
+    }
+
+    /// Save the best score if the move just made raised it. A failing store
+    /// never interrupts play; the best is saved again after the next move.
+    fn save_best_score(&self, previous_best: u64) {
+        if let Some(store) = &self.best_score_store {
+            if self.score.best() > previous_best {
+                let _ = store.save(self.score.best());
+            }
+        }
+    }
+
     /// Get game state
     pub fn state(&self) -> GameState {
         self.state.clone()
@@ -93,56 +254,294 @@ impl Game {
         self.moves
     }
 
+    /// Seed of the current game's tile sequence. A game started with this
+    /// seed in `GameConfig::seed` spawns the same tiles. `None` only for
+    /// games saved before seeds were stored.
+    pub fn seed(&self) -> Option<u64> {
+        self.rng.get_seed()
+    }
+
+    /// State of the random generator, for recording the exact point in the
+    /// tile sequence
+    pub fn rng(&self) -> &GameRng {
+        &self.rng
+    }
+
+    /// Continue the tile sequence from a recorded generator state
+    pub fn restore_rng(&mut self, rng: GameRng) {
+        self.rng = rng;
+    }
+
+    /// Time taken for each move in milliseconds, measured from the previous
+    /// action (move, undo, redo or game start)
+    pub fn move_times(&self) -> &[u64] {
+        &self.move_times
+    }
+
+    /// Whether the target was reached, including games continued after the win
+    pub fn has_won(&self) -> bool {
+        self.state == GameState::Won || self.keep_playing
+    }
+
+    /// Whether the game was won and continued with `continue_after_win`
+    pub fn keep_playing(&self) -> bool {
+        self.keep_playing
+    }
+
+    /// Keep playing a won game until no moves are left ("Keep going")
+    pub fn continue_after_win(&mut self) -> GameResult<()> {
+        if self.state != GameState::Won {
+            return Err(GameError::InvalidOperation(
+                "Only a won game can be continued".to_string(),
+            ));
+        }
+        self.keep_playing = true;
+        self.state = GameState::Playing;
+        Ok(())
+    }
+
+    /// Start queueing `GameEvent`s for `take_events`
+    pub fn enable_events(&mut self) {
+        self.events.get_or_insert_with(Vec::new);
+    }
+
+    /// Take the events queued since the last call
+    pub fn take_events(&mut self) -> Vec<GameEvent> {
+        self.events.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    fn emit(&mut self, event: GameEvent) {
+        if let Some(events) = &mut self.events {
+            events.push(event);
+        }
+    }
+
     /// Get game configuration
     pub fn config(&self) -> &GameConfig {
         &self.config
     }
 
+    /// Get the rules the game is played with
+    pub fn rules(&self) -> &dyn GameRules {
+        self.rules.as_ref()
+    }
+
     /// Check whether an undo action is currently available.
     pub fn can_undo(&self) -> bool {
-        self.config.allow_undo && self.previous_board.is_some() && self.previous_score.is_some()
+        self.undo_available() > 0
+    }
+
+    /// Get the number of moves that can currently be undone
+    pub fn undo_count(&self) -> usize {
+        self.undo_available()
+    }
+
+    /// Number of moves that can be undone in a row
+    pub fn undo_available(&self) -> usize {
+        if self.config.allow_undo {
+            self.undo_stack.len()
+        } else {
+            0
+        }
+    }
+
+    /// Check whether a redo action is currently available.
+    pub fn can_redo(&self) -> bool {
+        self.redo_available() > 0
+    }
+
+    /// Number of undone moves that can be redone in a row
+    pub fn redo_available(&self) -> usize {
+        if self.config.allow_undo {
+            self.redo_stack.len()
+        } else {
+            0
+        }
+    }
+
+    /// Stop the play clock, e.g. while the player is in a menu.
+    /// Moves are rejected until `resume` is called.
+    pub fn pause(&mut self) {
+        if self.paused_at.is_none() {
+            self.paused_at = Some(crate::get_current_time_ms());
+        }
+    }
+
+    /// Restart the play clock stopped by `pause`
+    pub fn resume(&mut self) {
+        if let Some(paused_at) = self.paused_at.take() {
+            let now = crate::get_current_time_ms();
+            // Shift the clocks so the pause does not count as play time
+            self.start_time += now / 1000 - paused_at / 1000;
+            self.move_started_at += now.saturating_sub(paused_at);
+        }
+    }
+
+    /// Whether the game is paused
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    /// Set the play time so far, e.g. of a game loaded with
+    /// `load_from_state` whose time the frontend kept
+    pub fn set_elapsed(&mut self, seconds: u64) {
+        let now = match self.paused_at {
+            Some(paused_at) => paused_at / 1000,
+            None => Self::get_current_time(),
+        };
+        self.start_time = now.saturating_sub(seconds);
+    }
+
+    /// Active play time in seconds, excluding pauses
+    fn elapsed(&self) -> u64 {
+        let now = match self.paused_at {
+            Some(paused_at) => paused_at / 1000,
+            None => Self::get_current_time(),
+        };
+        now.saturating_sub(self.start_time)
     }
 
     /// Get game statistics
     pub fn stats(&self) -> GameStats {
-        let current_time = Self::get_current_time();
+        let average_move_ms = (!self.move_times.is_empty())
+            .then(|| self.move_times.iter().sum::<u64>() as f64 / self.move_times.len() as f64);
 
         GameStats {
             score: self.score.current(),
             best_score: self.score.best(),
             moves: self.moves,
-            duration: current_time - self.start_time,
-            won: self.state == GameState::Won,
+            duration: self.elapsed(),
+            won: self.has_won(),
+            won_by: self.won_by,
             game_over: self.state == GameState::GameOver,
+            average_move_ms,
+            fastest_move_ms: self.move_times.iter().min().copied(),
+            slowest_move_ms: self.move_times.iter().max().copied(),
+            score_breakdown: self.score.breakdown().clone(),
         }
     }
 
-    /// Make a move in the specified direction
-    pub fn make_move(&mut self, direction: Direction) -> GameResult<bool> {
+    /// Make a move in the specified direction.
+    /// The result describes how tiles moved, merged and spawned.
+    pub fn make_move(&mut self, direction: Direction) -> GameResult<MoveResult> {
+        self.apply_move(direction, None)
+    }
+
+    /// Make a move, placing the given tiles instead of random ones (e.g. the
+    /// tiles recorded in a replay). The generator is not advanced.
+    pub fn make_move_with_spawns(
+        &mut self,
+        direction: Direction,
+        spawns: &[SpawnedTile],
+    ) -> GameResult<MoveResult> {
+        self.apply_move(direction, Some(spawns))
+    }
+
+    /// The board a move leaves before any tile spawns, settled under
+    /// gravity like a real move; `None` if nothing moves
+    pub(crate) fn board_after_move(&self, direction: Direction) -> GameResult<Option<Board>> {
+        let mut next = self.clone();
+        let result = next.perform_move(direction)?;
+        Ok(result.moved.then_some(next.board))
+    }
+
+    fn apply_move(
+        &mut self,
+        direction: Direction,
+        spawns: Option<&[SpawnedTile]>,
+    ) -> GameResult<MoveResult> {
         if self.state != GameState::Playing {
             return Err(GameError::GameOver);
         }
-
-        // Save previous state for undo
-        if self.config.allow_undo {
-            self.previous_board = Some(self.board.clone_board());
-            self.previous_score = Some(self.score.clone());
+        if self.is_paused() {
+            return Err(GameError::InvalidMove("Game is paused".to_string()));
         }
 
+        // Snapshot the current state for undo
+        let snapshot =
+            (self.config.allow_undo && self.config.max_undo_depth > 0).then(|| self.snapshot());
+
+        let previous_best = self.score.best();
+
         // Perform the move
-        let moved = self.perform_move(direction)?;
+        let mut result = self.perform_move(direction)?;
+
+        if result.moved {
+            result.bonuses =
+                self.score
+                    .apply_rules(&self.config.scoring, &result.merges, self.board.size());
+            result.points += result.bonuses.iter().map(|bonus| bonus.points).sum::<u64>();
+
+            self.emit(GameEvent::Moved {
+                direction,
+                points: result.points,
+            });
 
-        if moved {
+            // Only real moves can be undone, and they invalidate redo
+            if let Some(snapshot) = snapshot {
+                if self.undo_stack.len() >= self.config.max_undo_depth {
+                    self.undo_stack.pop_front();
+                }
+                self.undo_stack.push_back(snapshot);
+            }
+            self.redo_stack.clear();
             self.moves += 1;
+            self.record_move_time();
 
-            // Add a new random tile
-            self.add_random_tile()?;
+            // Add new tiles
+            result.spawned = match spawns {
+                Some(spawns) => self.place_tiles(spawns)?,
+                None => self.spawn_tiles()?,
+            };
 
             // Check game state
             self.update_game_state()?;
+            self.save_best_score(previous_best);
         }
 
-        Ok(moved)
+        Ok(result)
+    }
+
+    /// Like `make_move`, but a move that would change nothing is an error
+    /// explaining why, for UI messages and bot debugging
+    pub fn try_move(&mut self, direction: Direction) -> GameResult<MoveResult> {
+        self.check_move(direction)?;
+        self.make_move(direction)
+    }
+
+    /// Check whether a move is possible without making it. The error says
+    /// why not: the game is not being played, is paused, or no tile can
+    /// slide or merge in that direction.
+    pub fn check_move(&self, direction: Direction) -> GameResult<()> {
+        match self.state {
+            GameState::Playing => {}
+            GameState::Won => {
+                return Err(GameError::InvalidMove(
+                    "the game is won; continue playing to keep moving".to_string(),
+                ))
+            }
+            GameState::GameOver => return Err(GameError::GameOver),
+        }
+        if self.is_paused() {
+            return Err(GameError::InvalidMove("the game is paused".to_string()));
+        }
+
+        let size = self.board.size();
+        let tiles = self.board.as_flat_slice();
+        let slides = (0..size).any(|line| {
+            let values: Vec<u64> = line_cells(size, line, direction)
+                .into_iter()
+                .map(|(row, col)| tiles[row * size + col].value)
+                .collect();
+            rules::slide_line(self.rules.as_ref(), &values).values != values
+        });
+        if !slides {
+            return Err(GameError::InvalidMove(format!(
+                "no tiles can slide {}",
+                direction
+            )));
+        }
+        Ok(())
     }
 
     /// Undo the last move
@@ -150,14 +549,22 @@ impl Game {
         if !self.config.allow_undo {
             return Err(GameError::NoUndoAvailable);
         }
+        if self.is_paused() {
+            return Err(GameError::InvalidMove("Game is paused".to_string()));
+        }
 
-        if let (Some(prev_board), Some(prev_score)) =
-            (self.previous_board.take(), self.previous_score.take())
-        {
-            self.board = prev_board;
-            self.score = prev_score;
+        if let Some(previous) = self.undo_stack.pop_back() {
+            let current = self.restore(previous);
+            self.redo_stack.push(current);
+            self.score.apply_penalty(self.config.scoring.undo_penalty);
             self.moves = self.moves.saturating_sub(1);
+            self.move_times.pop();
+            self.move_started_at = crate::get_current_time_ms();
             self.state = GameState::Playing;
+            if !self.keep_playing {
+                self.won_by = None;
+            }
+            self.emit(GameEvent::Undone);
         } else {
             return Err(GameError::NoUndoAvailable);
         }
@@ -165,15 +572,71 @@ impl Game {
         Ok(())
     }
 
-    /// Start a new game
+    /// Redo the last undone move
+    pub fn redo(&mut self) -> GameResult<()> {
+        if !self.config.allow_undo {
+            return Err(GameError::NoRedoAvailable);
+        }
+        if self.is_paused() {
+            return Err(GameError::InvalidMove("Game is paused".to_string()));
+        }
+
+        if let Some(next) = self.redo_stack.pop() {
+            let current = self.restore(next);
+            self.undo_stack.push_back(current);
+            // The restored score predates the undo that is being redone
+            self.score.apply_penalty(self.config.scoring.undo_penalty);
+            self.moves += 1;
+            self.record_move_time();
+            self.emit(GameEvent::Redone);
+            self.update_game_state()?;
+        } else {
+            return Err(GameError::NoRedoAvailable);
+        }
+
+        Ok(())
+    }
+
+    /// Record the time since the previous action as the latest move's time
+    fn record_move_time(&mut self) {
+        let now = crate::get_current_time_ms();
+        self.move_times
+            .push(now.saturating_sub(self.move_started_at));
+        self.move_started_at = now;
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            board: self.board.clone_board(),
+            score: self.score.clone(),
+        }
+    }
+
+    /// Replace the board and score, returning the replaced ones
+    fn restore(&mut self, snapshot: Snapshot) -> Snapshot {
+        Snapshot {
+            board: std::mem::replace(&mut self.board, snapshot.board),
+            score: std::mem::replace(&mut self.score, snapshot.score),
+        }
+    }
+
+    /// Start a new game. Seeded games restart their sequence; others draw
+    /// a fresh seed.
     pub fn new_game(&mut self) -> GameResult<()> {
         self.board = Board::new(self.config.board_size)?;
+        self.rng = GameRng::new(self.config.seed);
         self.score.reset_current();
         self.state = GameState::Playing;
         self.moves = 0;
         self.start_time = Self::get_current_time();
-        self.previous_board = None;
-        self.previous_score = None;
+        self.keep_playing = false;
+        self.won_by = None;
+        self.move_times.clear();
+        self.move_started_at = crate::get_current_time_ms();
+        self.paused_at = None;
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.emit(GameEvent::NewGame);
 
         // Add initial tiles
         self.add_random_tile()?;
@@ -182,10 +645,87 @@ impl Game {
         Ok(())
     }
 
+    /// Take a snapshot of the game that can be resumed with `from_saved_state`
+    pub fn to_saved_state(&self) -> SavedGame {
+        let now = Self::get_current_time();
+        SavedGame {
+            board: self.board.to_vec(),
+            score: self.score.clone(),
+            moves: self.moves,
+            state: self.state.clone(),
+            config: self.config.clone(),
+            elapsed: self.elapsed(),
+            saved_at: now,
+            rng: Some(self.rng.clone()),
+            keep_playing: self.keep_playing,
+            move_times: self.move_times.clone(),
+            won_by: self.won_by,
+            board_hash: Some(self.board.zobrist_hash()),
+        }
+    }
+
+    /// Resume a game from a snapshot.
+    /// Upcoming tiles continue the original random sequence.
+    pub fn from_saved_state(saved: SavedGame) -> GameResult<Self> {
+        if saved.board.len() != saved.config.board_size
+            || saved
+                .board
+                .iter()
+                .any(|row| row.len() != saved.config.board_size)
+        {
+            return Err(GameError::InvalidBoardSize {
+                size: saved.board.len(),
+            });
+        }
+
+        let tiles = saved
+            .board
+            .iter()
+            .map(|row| row.iter().map(|&value| Tile::new(value)).collect())
+            .collect();
+
+        let board = Board::from_tiles(tiles)?;
+        if saved
+            .board_hash
+            .is_some_and(|hash| hash != board.zobrist_hash())
+        {
+            return Err(GameError::Serialization(
+                "Saved board does not match its checksum".to_string(),
+            ));
+        }
+        let rules = lookup_rules(&saved.config)?;
+        // Saves from before win triggers were recorded
+        let won = saved.state == GameState::Won || saved.keep_playing;
+        let won_by = saved.won_by.or_else(|| {
+            won.then(|| rules.won_by(&board, saved.score.current(), &saved.config))
+                .flatten()
+        });
+
+        Ok(Self {
+            board,
+            score: saved.score,
+            rng: saved.rng.unwrap_or_default(),
+            rules,
+            config: saved.config,
+            state: saved.state,
+            moves: saved.moves,
+            start_time: Self::get_current_time().saturating_sub(saved.elapsed),
+            keep_playing: saved.keep_playing,
+            won_by,
+            move_times: saved.move_times,
+            move_started_at: crate::get_current_time_ms(),
+            paused_at: None,
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+            events: None,
+            best_score_store: None,
+        })
+    }
+
     /// Load game from saved state
     pub fn load_from_state(
         &mut self,
-        board_data: Vec<u32>,
+        board_data: Vec<u64>,
         score: Score,
         moves: u32,
         state: GameState,
@@ -198,318 +738,212 @@ impl Game {
             });
         }
 
-        // Create board from data
-        let mut tiles = Vec::new();
-        for row in 0..self.config.board_size {
-            let mut row_tiles = Vec::new();
-            for col in 0..self.config.board_size {
-                let index = row * self.config.board_size + col;
-                row_tiles.push(Tile::new(board_data[index]));
-            }
-            tiles.push(row_tiles);
-        }
-
         // Update game state using the public method
-        self.board = Board::from_tiles(tiles)?;
+        self.board = Board::from_vec(
+            board_data
+                .chunks(self.config.board_size)
+                .map(<[u64]>::to_vec)
+                .collect(),
+        )?;
         self.score = score;
+        // A loaded score does not lower the stored best
+        if let Some(best) = self
+            .best_score_store
+            .as_ref()
+            .and_then(|store| store.load().ok())
+        {
+            self.score.restore_best(best);
+        }
         self.moves = moves;
+        self.won_by = self
+            .rules
+            .won_by(&self.board, self.score.current(), &self.config);
+        // A game still playing past the win condition was continued
+        self.keep_playing = state == GameState::Playing && self.won_by.is_some();
+        if state == GameState::GameOver {
+            self.won_by = None;
+        }
         self.state = state;
-        self.previous_board = None;
-        self.previous_score = None;
+        self.move_times.clear();
+        self.move_started_at = crate::get_current_time_ms();
+        self.paused_at = None;
+        self.undo_stack.clear();
+        self.redo_stack.clear();
 
         Ok(())
     }
 
+    /// Add `tiles_per_spawn` random tiles, as many as fit on the board
+    fn spawn_tiles(&mut self) -> GameResult<Vec<SpawnedTile>> {
+        let mut spawned = Vec::with_capacity(self.config.tiles_per_spawn);
+        for _ in 0..self.config.tiles_per_spawn {
+            match self.add_random_tile()? {
+                Some(tile) => spawned.push(tile),
+                None => break,
+            }
+        }
+        Ok(spawned)
+    }
+
+    /// Add the given tiles, which must land on empty cells
+    fn place_tiles(&mut self, spawns: &[SpawnedTile]) -> GameResult<Vec<SpawnedTile>> {
+        for tile in spawns {
+            if !self.board.is_empty(tile.row, tile.col)? {
+                return Err(GameError::InvalidPosition {
+                    row: tile.row,
+                    col: tile.col,
+                });
+            }
+            self.board
+                .set_tile(tile.row, tile.col, Tile::new(tile.value))?;
+            self.emit(GameEvent::TileSpawned {
+                row: tile.row,
+                col: tile.col,
+                value: tile.value,
+            });
+        }
+        Ok(spawns.to_vec())
+    }
+
     /// Add a random tile to the board
-    fn add_random_tile(&mut self) -> GameResult<()> {
+    fn add_random_tile(&mut self) -> GameResult<Option<SpawnedTile>> {
         let empty_positions = self.board.empty_positions();
         if empty_positions.is_empty() {
-            return Ok(());
+            return Ok(None);
         }
 
         let random_index = self.rng.gen_range(empty_positions.len());
         let (row, col) = empty_positions[random_index];
-        let value = self.rng.gen_tile_value();
+        let value = self.rules.spawn_value(&mut self.rng);
 
         self.board.set_tile(row, col, Tile::new(value))?;
-        Ok(())
+        self.emit(GameEvent::TileSpawned { row, col, value });
+        Ok(Some(SpawnedTile { row, col, value }))
     }
 
-    /// Perform a move in the specified direction
-    fn perform_move(&mut self, direction: Direction) -> GameResult<bool> {
-        let mut moved = false;
+    /// Slide and merge the tiles in the specified direction
+    fn perform_move(&mut self, direction: Direction) -> GameResult<MoveResult> {
+        let mut result = MoveResult::unmoved(direction);
         let size = self.board.size();
 
-        match direction {
-            Direction::Left => {
-                for row in 0..size {
-                    moved |= self.merge_row_left(row)?;
-                }
-            }
-            Direction::Right => {
-                for row in 0..size {
-                    moved |= self.merge_row_right(row)?;
-                }
-            }
-            Direction::Up => {
-                for col in 0..size {
-                    moved |= self.merge_col_up(col)?;
-                }
-            }
-            Direction::Down => {
-                for col in 0..size {
-                    moved |= self.merge_col_down(col)?;
-                }
-            }
-        }
-
-        Ok(moved)
-    }
-
-    /// Merge tiles in a row from left to right
-    fn merge_row_left(&mut self, row: usize) -> GameResult<bool> {
-        let mut moved = false;
-        let size = self.board.size();
-        let mut merged = vec![false; size];
-
-        // Move tiles to the left
-        for col in 1..size {
-            if !self.board.get_tile(row, col)?.is_empty() {
-                let mut target_col = col;
-                while target_col > 0 && self.board.get_tile(row, target_col - 1)?.is_empty() {
-                    let tile = self.board.get_tile(row, target_col)?;
-                    self.board.set_tile(row, target_col, Tile::empty())?;
-                    self.board.set_tile(row, target_col - 1, tile)?;
-                    target_col -= 1;
-                    moved = true;
-                }
-            }
-        }
+        for line in 0..size {
+            let cells = line_cells(size, line, direction);
+            let values = cells
+                .iter()
+                .map(|&(row, col)| self.board.get_tile(row, col).map(|tile| tile.value))
+                .collect::<GameResult<Vec<u64>>>()?;
 
-        // Merge adjacent tiles
-        for col in 0..size - 1 {
-            if merged[col] {
+            let slid = rules::slide_line(self.rules.as_ref(), &values);
+            if slid.values == values {
                 continue;
             }
-
-            let current = self.board.get_tile(row, col)?;
-            let next = self.board.get_tile(row, col + 1)?;
-
-            if current.can_merge_with(&next) {
-                let mut merged_tile = current;
-                let merge_score = merged_tile.merge_with(&next);
-                self.board.set_tile(row, col, merged_tile)?;
-                self.board.set_tile(row, col + 1, Tile::empty())?;
-                self.score.add_merge_points(merge_score);
-                merged[col + 1] = true;
-                moved = true;
+            result.moved = true;
+            for (&(row, col), &value) in cells.iter().zip(&slid.values) {
+                self.board.set_tile(row, col, Tile::new(value))?;
             }
-        }
 
-        // Move tiles again after merging
-        for col in 1..size {
-            if !self.board.get_tile(row, col)?.is_empty() {
-                let mut target_col = col;
-                while target_col > 0 && self.board.get_tile(row, target_col - 1)?.is_empty() {
-                    let tile = self.board.get_tile(row, target_col)?;
-                    self.board.set_tile(row, target_col, Tile::empty())?;
-                    self.board.set_tile(row, target_col - 1, tile)?;
-                    target_col -= 1;
-                    moved = true;
+            for (to, sources) in slid.sources.iter().enumerate() {
+                result
+                    .moves
+                    .extend(
+                        sources
+                            .iter()
+                            .filter(|&&from| from != to)
+                            .map(|&from| TileMove {
+                                from: cells[from],
+                                to: cells[to],
+                                value: values[from],
+                            }),
+                    );
+                if let [first, second] = sources[..] {
+                    let value = slid.values[to];
+                    let points = self.rules.merge_score(value);
+                    self.score.add_merge(value, points);
+                    result.points += points;
+                    result.merges.push(TileMerge {
+                        position: cells[to],
+                        sources: [cells[first], cells[second]],
+                        value,
+                    });
                 }
             }
         }
 
-        Ok(moved)
-    }
-
-    /// Merge tiles in a row from right to left
-    fn merge_row_right(&mut self, row: usize) -> GameResult<bool> {
-        let mut moved = false;
-        let size = self.board.size();
-        let mut merged = vec![false; size];
-
-        // Move tiles to the right
-        for col in (0..size - 1).rev() {
-            if !self.board.get_tile(row, col)?.is_empty() {
-                let mut target_col = col;
-                while target_col < size - 1 && self.board.get_tile(row, target_col + 1)?.is_empty()
-                {
-                    let tile = self.board.get_tile(row, target_col)?;
-                    self.board.set_tile(row, target_col, Tile::empty())?;
-                    self.board.set_tile(row, target_col + 1, tile)?;
-                    target_col += 1;
-                    moved = true;
-                }
-            }
+        if result.moved {
+            self.settle(direction, &mut result)?;
         }
 
-        // Merge adjacent tiles
-        for col in (1..size).rev() {
-            if merged[col] {
-                continue;
-            }
-
-            let current = self.board.get_tile(row, col)?;
-            let prev = self.board.get_tile(row, col - 1)?;
-
-            if current.can_merge_with(&prev) {
-                let mut merged_tile = current;
-                let merge_score = merged_tile.merge_with(&prev);
-                self.board.set_tile(row, col, merged_tile)?;
-                self.board.set_tile(row, col - 1, Tile::empty())?;
-                self.score.add_merge_points(merge_score);
-                merged[col - 1] = true;
-                moved = true;
-            }
-        }
-
-        // Move tiles again after merging
-        for col in (0..size - 1).rev() {
-            if !self.board.get_tile(row, col)?.is_empty() {
-                let mut target_col = col;
-                while target_col < size - 1 && self.board.get_tile(row, target_col + 1)?.is_empty()
-                {
-                    let tile = self.board.get_tile(row, target_col)?;
-                    self.board.set_tile(row, target_col, Tile::empty())?;
-                    self.board.set_tile(row, target_col + 1, tile)?;
-                    target_col += 1;
-                    moved = true;
-                }
-            }
-        }
-
-        Ok(moved)
+        Ok(result)
     }
 
-    /// Merge tiles in a column from top to bottom
-    fn merge_col_up(&mut self, col: usize) -> GameResult<bool> {
-        let mut moved = false;
-        let size = self.board.size();
-        let mut merged = vec![false; size];
-
-        // Move tiles up
-        for row in 1..size {
-            if !self.board.get_tile(row, col)?.is_empty() {
-                let mut target_row = row;
-                while target_row > 0 && self.board.get_tile(target_row - 1, col)?.is_empty() {
-                    let tile = self.board.get_tile(target_row, col)?;
-                    self.board.set_tile(target_row, col, Tile::empty())?;
-                    self.board.set_tile(target_row - 1, col, tile)?;
-                    target_row -= 1;
-                    moved = true;
-                }
-            }
-        }
-
-        // Merge adjacent tiles
-        for row in 0..size - 1 {
-            if merged[row] {
-                continue;
-            }
-
-            let current = self.board.get_tile(row, col)?;
-            let next = self.board.get_tile(row + 1, col)?;
-
-            if current.can_merge_with(&next) {
-                let mut merged_tile = current;
-                let merge_score = merged_tile.merge_with(&next);
-                self.board.set_tile(row, col, merged_tile)?;
-                self.board.set_tile(row + 1, col, Tile::empty())?;
-                self.score.add_merge_points(merge_score);
-                merged[row + 1] = true;
-                moved = true;
-            }
-        }
-
-        // Move tiles again after merging
-        for row in 1..size {
-            if !self.board.get_tile(row, col)?.is_empty() {
-                let mut target_row = row;
-                while target_row > 0 && self.board.get_tile(target_row - 1, col)?.is_empty() {
-                    let tile = self.board.get_tile(target_row, col)?;
-                    self.board.set_tile(target_row, col, Tile::empty())?;
-                    self.board.set_tile(target_row - 1, col, tile)?;
-                    target_row -= 1;
-                    moved = true;
-                }
-            }
+    /// Let tiles fall toward the gravity edge after a move across it.
+    /// Falling tiles never merge.
+    fn settle(&mut self, direction: Direction, result: &mut MoveResult) -> GameResult<()> {
+        let Some(gravity) = self.config.gravity else {
+            return Ok(());
+        };
+        if direction.is_horizontal() == gravity.is_horizontal() {
+            return Ok(());
         }
 
-        Ok(moved)
-    }
-
-    /// Merge tiles in a column from bottom to top
-    fn merge_col_down(&mut self, col: usize) -> GameResult<bool> {
-        let mut moved = false;
         let size = self.board.size();
-        let mut merged = vec![false; size];
-
-        // Move tiles down
-        for row in (0..size - 1).rev() {
-            if !self.board.get_tile(row, col)?.is_empty() {
-                let mut target_row = row;
-                while target_row < size - 1 && self.board.get_tile(target_row + 1, col)?.is_empty()
-                {
-                    let tile = self.board.get_tile(target_row, col)?;
-                    self.board.set_tile(target_row, col, Tile::empty())?;
-                    self.board.set_tile(target_row + 1, col, tile)?;
-                    target_row += 1;
-                    moved = true;
+        for line in 0..size {
+            let cells = line_cells(size, line, gravity);
+            let mut landed = 0;
+            for (index, &(row, col)) in cells.iter().enumerate() {
+                let value = self.board.get_tile(row, col)?.value;
+                if value == 0 {
+                    continue;
                 }
-            }
-        }
-
-        // Merge adjacent tiles
-        for row in (1..size).rev() {
-            if merged[row] {
-                continue;
-            }
-
-            let current = self.board.get_tile(row, col)?;
-            let prev = self.board.get_tile(row - 1, col)?;
-
-            if current.can_merge_with(&prev) {
-                let mut merged_tile = current;
-                let merge_score = merged_tile.merge_with(&prev);
-                self.board.set_tile(row, col, merged_tile)?;
-                self.board.set_tile(row - 1, col, Tile::empty())?;
-                self.score.add_merge_points(merge_score);
-                merged[row - 1] = true;
-                moved = true;
-            }
-        }
-
-        // Move tiles again after merging
-        for row in (0..size - 1).rev() {
-            if !self.board.get_tile(row, col)?.is_empty() {
-                let mut target_row = row;
-                while target_row < size - 1 && self.board.get_tile(target_row + 1, col)?.is_empty()
-                {
-                    let tile = self.board.get_tile(target_row, col)?;
-                    self.board.set_tile(target_row, col, Tile::empty())?;
-                    self.board.set_tile(target_row + 1, col, tile)?;
-                    target_row += 1;
-                    moved = true;
+                if index != landed {
+                    let (to_row, to_col) = cells[landed];
+                    self.board.set_tile(to_row, to_col, Tile::new(value))?;
+                    self.board.set_tile(row, col, Tile::new(0))?;
+                    result.drops.push(TileMove {
+                        from: (row, col),
+                        to: cells[landed],
+                        value,
+                    });
                 }
+                landed += 1;
             }
         }
 
-        Ok(moved)
+        Ok(())
     }
 
     /// Update game state based on current board
     fn update_game_state(&mut self) -> GameResult<()> {
+        let previous_state = self.state.clone();
+
         // Check if won
-        if self.board.max_tile() >= self.config.target_score && self.state == GameState::Playing {
-            self.state = GameState::Won;
+        if self.state == GameState::Playing && !self.keep_playing {
+            self.won_by = self
+                .rules
+                .won_by(&self.board, self.score.current(), &self.config);
+            if self.won_by.is_some() {
+                self.state = GameState::Won;
+            }
         }
 
         // Check if game over
-        if !self.board.has_valid_moves() {
+        if self.rules.is_lost(&self.board) {
             self.state = GameState::GameOver;
         }
 
+        if self.state != previous_state {
+            let event = match self.state {
+                GameState::Won => GameEvent::Won {
+                    max_tile: self.board.max_tile(),
+                },
+                GameState::GameOver => GameEvent::GameOver {
+                    score: self.score.current(),
+                },
+                GameState::Playing => return Ok(()),
+            };
+            self.emit(event);
+        }
+
         Ok(())
     }
 
@@ -520,9 +954,27 @@ impl Game {
     }
 }
 
+fn lookup_rules(config: &GameConfig) -> GameResult<Arc<dyn GameRules>> {
+    rules::rules_by_id(&config.rules)
+        .ok_or_else(|| GameError::InvalidOperation(format!("Unknown game rules: {}", config.rules)))
+}
+
+/// Cells of a row or column, ordered from the side tiles move towards
+pub(crate) fn line_cells(size: usize, line: usize, direction: Direction) -> Vec<(usize, usize)> {
+    (0..size)
+        .map(|i| match direction {
+            Direction::Left => (line, i),
+            Direction::Right => (line, size - 1 - i),
+            Direction::Up => (i, line),
+            Direction::Down => (size - 1 - i, line),
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{ScoringRules, WinCondition};
 
     #[test]
     fn test_game_creation() {
@@ -540,7 +992,7 @@ mod tests {
         let mut game = Game::new(config).unwrap();
 
         // Try to move left
-        let _moved = game.make_move(Direction::Left).unwrap();
+        game.make_move(Direction::Left).unwrap();
         // Should move if there are tiles that can be moved
     }
 
@@ -552,14 +1004,500 @@ mod tests {
         };
 
         let mut game = Game::new(config).unwrap();
+        // Start from a board where moving left always merges
+        game.load_from_state(
+            vec![2, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            Score::new(),
+            0,
+            GameState::Playing,
+        )
+        .unwrap();
         let initial_score = game.score().current();
 
         // Make a move
-        game.make_move(Direction::Left).unwrap();
+        assert!(game.make_move(Direction::Left).unwrap().moved);
 
         // Undo the move
         game.undo().unwrap();
 
         assert_eq!(game.score().current(), initial_score);
     }
+
+    #[test]
+    fn test_redo() {
+        let config = GameConfig {
+            allow_undo: true,
+            ..Default::default()
+        };
+        let mut game = Game::new(config).unwrap();
+        game.load_from_state(
+            vec![2, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            Score::new(),
+            0,
+            GameState::Playing,
+        )
+        .unwrap();
+        assert!(!game.can_undo());
+        assert_eq!(game.undo_count(), 0);
+
+        assert!(game.make_move(Direction::Left).unwrap().moved);
+        let after_move = game.board().to_vec();
+        assert_eq!(game.undo_count(), 1);
+        assert!(!game.can_redo());
+
+        game.undo().unwrap();
+        assert!(game.can_redo());
+        assert_eq!(game.moves(), 0);
+
+        game.redo().unwrap();
+        assert_eq!(game.board().to_vec(), after_move);
+        assert_eq!(game.score().current(), 4);
+        assert_eq!(game.moves(), 1);
+        assert!(game.can_undo());
+        assert!(!game.can_redo());
+    }
+
+    #[test]
+    fn test_undo_history_depth() {
+        let config = GameConfig {
+            max_undo_depth: 2,
+            seed: Some(7),
+            ..Default::default()
+        };
+        let mut game = Game::new(config).unwrap();
+        let mut boards = vec![game.board().to_vec()];
+        for direction in [
+            Direction::Left,
+            Direction::Up,
+            Direction::Right,
+            Direction::Down,
+        ]
+        .into_iter()
+        .cycle()
+        {
+            if boards.len() == 4 {
+                break;
+            }
+            if game.make_move(direction).unwrap().moved {
+                boards.push(game.board().to_vec());
+            }
+        }
+
+        // Only the last two moves are kept
+        assert_eq!(game.undo_available(), 2);
+        game.undo().unwrap();
+        game.undo().unwrap();
+        assert_eq!(game.board().to_vec(), boards[1]);
+        assert!(game.undo().is_err());
+
+        assert_eq!(game.redo_available(), 2);
+        game.redo().unwrap();
+        game.redo().unwrap();
+        assert_eq!(game.board().to_vec(), boards[3]);
+        assert_eq!(game.redo_available(), 0);
+        assert_eq!(game.moves(), 3);
+    }
+
+    #[test]
+    fn test_noop_move_keeps_undo_state() {
+        let config = GameConfig {
+            allow_undo: true,
+            ..Default::default()
+        };
+        let mut game = Game::new(config).unwrap();
+        game.load_from_state(
+            vec![2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            Score::new(),
+            0,
+            GameState::Playing,
+        )
+        .unwrap();
+
+        assert!(!game.make_move(Direction::Left).unwrap().moved);
+        assert!(!game.can_undo());
+    }
+
+    #[test]
+    fn test_saved_state_round_trip() {
+        let mut game = Game::new(GameConfig::default()).unwrap();
+        for direction in [Direction::Left, Direction::Up, Direction::Right] {
+            let _ = game.make_move(direction);
+        }
+
+        let json = serde_json::to_string(&game.to_saved_state()).unwrap();
+        let mut resumed = Game::from_saved_state(serde_json::from_str(&json).unwrap()).unwrap();
+
+        assert_eq!(resumed.board().to_vec(), game.board().to_vec());
+        assert_eq!(resumed.score().current(), game.score().current());
+        assert_eq!(resumed.moves(), game.moves());
+        assert_eq!(resumed.state(), game.state());
+        assert!(!resumed.can_undo());
+
+        // The resumed game spawns the same tiles as the original
+        for direction in [Direction::Down, Direction::Left, Direction::Up] {
+            let expected = game.make_move(direction).ok().map(|r| r.spawned);
+            let actual = resumed.make_move(direction).ok().map(|r| r.spawned);
+            assert_eq!(actual, expected);
+            assert_eq!(resumed.board().to_vec(), game.board().to_vec());
+        }
+
+        // A save whose board was edited is rejected
+        let mut tampered = game.to_saved_state();
+        tampered.board[0][0] += 2;
+        assert!(Game::from_saved_state(tampered).is_err());
+    }
+
+    #[test]
+    fn test_seed_replays_game() {
+        let mut game = Game::new(GameConfig::default()).unwrap();
+        let seed = game.seed().expect("new games have a seed");
+        let mut replayed = Game::new(GameConfig {
+            seed: Some(seed),
+            ..GameConfig::default()
+        })
+        .unwrap();
+
+        assert_eq!(replayed.board().to_vec(), game.board().to_vec());
+        for direction in [Direction::Left, Direction::Up, Direction::Right] {
+            let expected = game.make_move(direction).ok().map(|r| r.spawned);
+            assert_eq!(
+                replayed.make_move(direction).ok().map(|r| r.spawned),
+                expected
+            );
+        }
+
+        // Restarting a seeded game starts the same sequence again
+        let first_board = Game::new(replayed.config().clone())
+            .unwrap()
+            .board()
+            .to_vec();
+        replayed.new_game().unwrap();
+        assert_eq!(replayed.seed(), Some(seed));
+        assert_eq!(replayed.board().to_vec(), first_board);
+    }
+
+    #[test]
+    fn test_try_move_explains_failures() {
+        let mut game = Game::new(GameConfig::default()).unwrap();
+        game.load_from_state(
+            vec![2, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            Score::new(),
+            0,
+            GameState::Playing,
+        )
+        .unwrap();
+
+        match game.try_move(Direction::Left) {
+            Err(GameError::InvalidMove(reason)) => assert_eq!(reason, "no tiles can slide left"),
+            other => panic!("expected an invalid move, got {:?}", other),
+        }
+        assert!(game.check_move(Direction::Up).is_err());
+        assert!(game.try_move(Direction::Right).unwrap().moved);
+
+        game.pause();
+        assert!(matches!(
+            game.check_move(Direction::Left),
+            Err(GameError::InvalidMove(reason)) if reason.contains("paused")
+        ));
+    }
+
+    #[test]
+    fn test_scoring_rules() {
+        let mut game = Game::new(GameConfig {
+            scoring: ScoringRules {
+                corner_merge_bonus: 100,
+                undo_penalty: 3,
+                streak_bonus: 0,
+            },
+            ..GameConfig::default()
+        })
+        .unwrap();
+        game.load_from_state(
+            vec![2, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            Score::from_parts(10, 10, 0),
+            0,
+            GameState::Playing,
+        )
+        .unwrap();
+
+        let result = game.make_move(Direction::Left).unwrap();
+        assert_eq!(result.bonuses.len(), 1);
+        assert_eq!(result.points, 104);
+        assert_eq!(game.score().current(), 114);
+
+        // Bonuses are not part of the breakdown
+        assert_eq!(game.stats().score_breakdown.get(&4), Some(&4));
+
+        game.undo().unwrap();
+        assert_eq!(game.score().current(), 7);
+        assert!(game.stats().score_breakdown.is_empty());
+        game.redo().unwrap();
+        assert_eq!(game.score().current(), 111);
+
+        // Stats saved before the breakdown existed still load
+        let mut json = serde_json::to_value(game.stats()).unwrap();
+        json.as_object_mut().unwrap().remove("score_breakdown");
+        let stats: GameStats = serde_json::from_value(json).unwrap();
+        assert!(stats.score_breakdown.is_empty());
+    }
+
+    #[test]
+    fn test_move_result() {
+        let mut game = Game::new(GameConfig::default()).unwrap();
+        game.load_from_state(
+            vec![2, 2, 0, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            Score::new(),
+            0,
+            GameState::Playing,
+        )
+        .unwrap();
+
+        let result = game.make_move(Direction::Right).unwrap();
+        assert!(result.moved);
+        assert_eq!(result.points, 4);
+        assert_eq!(
+            result.moves,
+            vec![
+                TileMove {
+                    from: (0, 1),
+                    to: (0, 2),
+                    value: 2
+                },
+                TileMove {
+                    from: (0, 0),
+                    to: (0, 2),
+                    value: 2
+                },
+            ]
+        );
+        assert_eq!(
+            result.merges,
+            vec![TileMerge {
+                position: (0, 2),
+                sources: [(0, 1), (0, 0)],
+                value: 4
+            }]
+        );
+        let spawned = result.spawned[0];
+        assert_eq!(
+            game.board()
+                .get_tile(spawned.row, spawned.col)
+                .unwrap()
+                .value,
+            spawned.value
+        );
+    }
+
+    #[test]
+    fn test_continue_after_win() {
+        let config = GameConfig {
+            win_condition: WinCondition::Tile(8),
+            ..Default::default()
+        };
+        let mut game = Game::new(config).unwrap();
+        assert!(game.continue_after_win().is_err());
+        game.load_from_state(
+            vec![4, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            Score::new(),
+            0,
+            GameState::Playing,
+        )
+        .unwrap();
+
+        game.make_move(Direction::Left).unwrap();
+        assert_eq!(game.state(), GameState::Won);
+        assert!(matches!(
+            game.make_move(Direction::Right),
+            Err(GameError::GameOver)
+        ));
+
+        game.continue_after_win().unwrap();
+        assert_eq!(game.state(), GameState::Playing);
+        assert!(game.make_move(Direction::Right).unwrap().moved);
+        // Reaching the target again does not stop the game
+        assert_eq!(game.state(), GameState::Playing);
+        assert!(game.has_won());
+        assert!(game.stats().won);
+        assert_eq!(game.stats().won_by, Some(WinTrigger::Tile));
+    }
+
+    #[test]
+    fn test_win_by_score() {
+        let config = GameConfig {
+            win_condition: WinCondition::Either {
+                tile: 2048,
+                score: 8,
+            },
+            ..Default::default()
+        };
+        let mut game = Game::new(config).unwrap();
+        game.load_from_state(
+            vec![4, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            Score::new(),
+            0,
+            GameState::Playing,
+        )
+        .unwrap();
+        assert_eq!(game.stats().won_by, None);
+
+        game.make_move(Direction::Left).unwrap();
+        assert_eq!(game.state(), GameState::Won);
+        assert_eq!(game.stats().won_by, Some(WinTrigger::Score));
+
+        game.undo().unwrap();
+        assert_eq!(game.stats().won_by, None);
+    }
+
+    #[test]
+    fn test_tiles_per_spawn() {
+        let config = GameConfig {
+            tiles_per_spawn: 2,
+            ..Default::default()
+        };
+        let mut game = Game::new(config).unwrap();
+        game.load_from_state(
+            vec![2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            Score::new(),
+            0,
+            GameState::Playing,
+        )
+        .unwrap();
+        let result = game.make_move(Direction::Right).unwrap();
+        assert_eq!(result.spawned.len(), 2);
+        assert_eq!(game.board().empty_positions().len(), 13);
+
+        // Only one cell is free after sliding, so only one tile spawns
+        game.load_from_state(
+            vec![2, 4, 2, 4, 4, 2, 4, 2, 2, 4, 2, 4, 0, 2, 4, 8],
+            Score::new(),
+            0,
+            GameState::Playing,
+        )
+        .unwrap();
+        let result = game.make_move(Direction::Left).unwrap();
+        assert_eq!(result.spawned.len(), 1);
+        assert!(game.board().empty_positions().is_empty());
+
+        let invalid = GameConfig {
+            tiles_per_spawn: 0,
+            ..Default::default()
+        };
+        assert!(Game::new(invalid).is_err());
+    }
+
+    #[test]
+    fn test_move_times() {
+        let config = GameConfig {
+            allow_undo: true,
+            ..Default::default()
+        };
+        let mut game = Game::new(config).unwrap();
+        game.load_from_state(
+            vec![2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            Score::new(),
+            0,
+            GameState::Playing,
+        )
+        .unwrap();
+        assert!(game.stats().average_move_ms.is_none());
+
+        game.make_move(Direction::Right).unwrap();
+        game.make_move(Direction::Down).unwrap();
+        assert_eq!(game.move_times().len(), 2);
+
+        game.undo().unwrap();
+        assert_eq!(game.move_times().len(), 1);
+        game.redo().unwrap();
+        assert_eq!(game.move_times().len(), 2);
+
+        let stats = game.stats();
+        let fastest = stats.fastest_move_ms.unwrap();
+        let slowest = stats.slowest_move_ms.unwrap();
+        let average = stats.average_move_ms.unwrap();
+        assert!(fastest as f64 <= average && average <= slowest as f64);
+    }
+
+    #[test]
+    fn test_pause_resume() {
+        let mut game = Game::new(GameConfig::default()).unwrap();
+        game.load_from_state(
+            vec![2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            Score::new(),
+            0,
+            GameState::Playing,
+        )
+        .unwrap();
+
+        game.pause();
+        assert!(game.is_paused());
+        assert!(game.make_move(Direction::Right).is_err());
+        assert_eq!(game.moves(), 0);
+
+        game.resume();
+        assert!(!game.is_paused());
+        assert!(game.make_move(Direction::Right).unwrap().moved);
+
+        // Undo and redo wait for the game to resume too
+        game.pause();
+        assert!(game.undo().is_err());
+        assert_eq!(game.moves(), 1);
+        game.resume();
+        game.undo().unwrap();
+        game.pause();
+        assert!(game.redo().is_err());
+        assert_eq!(game.moves(), 0);
+        game.resume();
+        game.redo().unwrap();
+        assert_eq!(game.moves(), 1);
+    }
+
+    #[test]
+    fn test_set_elapsed() {
+        let mut game = Game::new(GameConfig::default()).unwrap();
+        game.set_elapsed(90);
+        let duration = game.stats().duration;
+        assert!((90..=91).contains(&duration));
+    }
+
+    #[test]
+    fn test_gravity() {
+        let config = GameConfig {
+            gravity: Some(Direction::Down),
+            ..Default::default()
+        };
+        let mut game = Game::new(config).unwrap();
+        game.load_from_state(
+            vec![2, 0, 0, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            Score::new(),
+            0,
+            GameState::Playing,
+        )
+        .unwrap();
+
+        let result = game.make_move(Direction::Left).unwrap();
+        assert_eq!(result.drops.len(), 2);
+        assert_eq!(game.board().get_tile(3, 0).unwrap().value, 2);
+        assert_eq!(game.board().get_tile(3, 1).unwrap().value, 4);
+
+        // Moves along the gravity axis do not settle
+        let result = game.make_move(Direction::Up).unwrap();
+        assert!(result.drops.is_empty());
+    }
+
+    #[test]
+    fn test_tiles_beyond_u32() {
+        let big = 1u64 << 32;
+        let mut game = Game::new(GameConfig::default()).unwrap();
+        game.load_from_state(
+            vec![big, big, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            Score::from_parts(u32::MAX as u64, 0, 0),
+            0,
+            GameState::Playing,
+        )
+        .unwrap();
+
+        game.make_move(Direction::Left).unwrap();
+        assert_eq!(game.board().max_tile(), big * 2);
+        assert_eq!(game.score().current(), u32::MAX as u64 + big * 2);
+    }
 }
@@ -0,0 +1,280 @@
+//! Invariant checks for games and boards played with the classic rules.
+//!
+//! Variants and frontends can run these after every move of their
+//! integration to catch broken rules early. With the `proptest` feature,
+//! [`strategies`] provides generators for boards, games and move sequences.
+
+use crate::{Board, Direction, Game, GameError};
+use thiserror::Error;
+
+/// A broken game invariant
+#[derive(Error, Debug)]
+pub enum InvariantViolation {
+    #[error("Tile at ({row}, {col}) has value {value}, which is not a power of two")]
+    InvalidTile { row: usize, col: usize, value: u64 },
+
+    #[error("Score changed by {actual} but the merges were worth {expected}")]
+    ScoreMismatch { expected: u64, actual: u64 },
+
+    #[error("Board after the move does not match the slid board plus one new tile")]
+    UnexpectedBoard,
+
+    #[error("The move filled {filled} cells; at most {allowed} tiles may appear")]
+    TooManyNewTiles { filled: usize, allowed: usize },
+
+    #[error("The move was reported as {reported} but the board says {actual}")]
+    MovedMismatch { reported: bool, actual: bool },
+
+    #[error("Undo did not restore the state before the move")]
+    UndoMismatch,
+
+    #[error(transparent)]
+    Game(#[from] GameError),
+}
+
+/// Result of an invariant check
+pub type InvariantResult<T> = Result<T, InvariantViolation>;
+
+/// Every tile is empty or a power of two of at least 2
+pub fn check_tiles(board: &Board) -> InvariantResult<()> {
+    for (row, values) in board.to_vec().iter().enumerate() {
+        for (col, &value) in values.iter().enumerate() {
+            if value != 0 && (value < 2 || !value.is_power_of_two()) {
+                return Err(InvariantViolation::InvalidTile { row, col, value });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Slide and merge a board without spawning a tile.
+/// Returns the resulting board and the points scored by the merges.
+pub fn slide(board: &[Vec<u64>], direction: Direction) -> (Vec<Vec<u64>>, u64) {
+    let size = board.len();
+    let mut result = vec![vec![0; size]; size];
+    let mut points = 0;
+
+    for line in 0..size {
+        // Cells of the line, ordered from the side the tiles move towards
+        let cells: Vec<(usize, usize)> = (0..size)
+            .map(|i| match direction {
+                Direction::Left => (line, i),
+                Direction::Right => (line, size - 1 - i),
+                Direction::Up => (i, line),
+                Direction::Down => (size - 1 - i, line),
+            })
+            .collect();
+
+        let mut tiles = cells
+            .iter()
+            .map(|&(row, col)| board[row][col])
+            .filter(|&value| value != 0)
+            .peekable();
+        let mut merged = Vec::with_capacity(size);
+        while let Some(value) = tiles.next() {
+            if tiles.peek() == Some(&value) {
+                tiles.next();
+                merged.push(value * 2);
+                points += value * 2;
+            } else {
+                merged.push(value);
+            }
+        }
+
+        for (&(row, col), value) in cells.iter().zip(merged) {
+            result[row][col] = value;
+        }
+    }
+
+    (result, points)
+}
+
+/// Check a move from `before` to `after`, as returned by `Game::make_move`:
+/// the score grows by exactly the merged values, the board is the slid board
+/// plus at most `tiles_per_spawn` new 2s or 4s in empty cells, and tiles stay
+/// valid.
+pub fn check_move(
+    before: &Game,
+    after: &Game,
+    direction: Direction,
+    moved: bool,
+) -> InvariantResult<()> {
+    check_tiles(after.board())?;
+
+    let board_before = before.board().to_vec();
+    let board_after = after.board().to_vec();
+    let (slid, points) = slide(&board_before, direction);
+
+    let actually_moved = slid != board_before;
+    if moved != actually_moved {
+        return Err(InvariantViolation::MovedMismatch {
+            reported: moved,
+            actual: actually_moved,
+        });
+    }
+
+    let gained = after
+        .score()
+        .current()
+        .wrapping_sub(before.score().current());
+    let expected = if moved { points } else { 0 };
+    if gained != expected {
+        return Err(InvariantViolation::ScoreMismatch {
+            expected,
+            actual: gained,
+        });
+    }
+
+    let mut new_tiles = 0;
+    for (slid_row, after_row) in slid.iter().zip(&board_after) {
+        for (&expected, &actual) in slid_row.iter().zip(after_row) {
+            if expected == actual {
+                continue;
+            }
+            if expected != 0 || !(actual == 2 || actual == 4) {
+                return Err(InvariantViolation::UnexpectedBoard);
+            }
+            new_tiles += 1;
+        }
+    }
+
+    // Merges only free cells, so the empty count can drop by at most the new tiles
+    let allowed = if moved {
+        after.config().tiles_per_spawn
+    } else {
+        0
+    };
+    if new_tiles > allowed {
+        return Err(InvariantViolation::TooManyNewTiles {
+            filled: new_tiles,
+            allowed,
+        });
+    }
+    Ok(())
+}
+
+/// Undo restores exactly the board, score and move count before the move
+pub fn check_undo(before: &Game, undone: &Game) -> InvariantResult<()> {
+    if before.board().to_vec() != undone.board().to_vec()
+        || before.score().current() != undone.score().current()
+        || before.moves() != undone.moves()
+    {
+        return Err(InvariantViolation::UndoMismatch);
+    }
+    Ok(())
+}
+
+/// Make a move and check every invariant, including that undo (when
+/// allowed) restores the previous state. Returns whether the board moved.
+pub fn checked_move(game: &mut Game, direction: Direction) -> InvariantResult<bool> {
+    let before = game.clone();
+    let moved = game.make_move(direction)?.moved;
+    check_move(&before, game, direction, moved)?;
+
+    if moved && game.config().allow_undo {
+        let after = game.clone();
+        game.undo()?;
+        check_undo(&before, game)?;
+        *game = after;
+    }
+    Ok(moved)
+}
+
+/// Proptest generators for boards, games and move sequences
+#[cfg(any(test, feature = "proptest"))]
+pub mod strategies {
+    use crate::{Direction, Game, GameConfig, GameState, Score, WinCondition};
+    use proptest::prelude::*;
+
+    /// A tile value: empty, or a power of two from 2 to 2048
+    pub fn tile() -> impl Strategy<Value = u64> {
+        prop_oneof![3 => Just(0u64), 5 => (1u32..=11).prop_map(|exp| 1 << exp)]
+    }
+
+    /// A board of the given size with arbitrary valid tiles
+    pub fn board(size: usize) -> impl Strategy<Value = Vec<Vec<u64>>> {
+        prop::collection::vec(prop::collection::vec(tile(), size), size)
+    }
+
+    /// Any move direction
+    pub fn direction() -> impl Strategy<Value = Direction> {
+        prop_oneof![
+            Just(Direction::Up),
+            Just(Direction::Down),
+            Just(Direction::Left),
+            Just(Direction::Right),
+        ]
+    }
+
+    /// A sequence of up to `max_len` moves
+    pub fn moves(max_len: usize) -> impl Strategy<Value = Vec<Direction>> {
+        prop::collection::vec(direction(), 0..=max_len)
+    }
+
+    /// A seeded game in progress on an arbitrary board of the given size
+    pub fn game(size: usize) -> impl Strategy<Value = Game> {
+        (board(size), any::<u64>()).prop_map(move |(values, seed)| {
+            let mut game = Game::new(GameConfig {
+                board_size: size,
+                win_condition: WinCondition::Tile(1 << 16),
+                allow_undo: true,
+                seed: Some(seed),
+                ..GameConfig::default()
+            })
+            .expect("valid configuration");
+            game.load_from_state(
+                values.into_iter().flatten().collect(),
+                Score::new(),
+                0,
+                GameState::Playing,
+            )
+            .expect("board matches the configured size");
+            game
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::strategies;
+    use super::*;
+    use crate::GameState;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_slide() {
+        let board = vec![
+            vec![2, 2, 2, 2],
+            vec![4, 0, 4, 8],
+            vec![0, 0, 0, 2],
+            vec![4, 4, 8, 0],
+        ];
+        let (slid, points) = slide(&board, Direction::Left);
+        assert_eq!(
+            slid,
+            vec![
+                vec![4, 4, 0, 0],
+                vec![8, 8, 0, 0],
+                vec![2, 0, 0, 0],
+                vec![8, 8, 0, 0],
+            ]
+        );
+        assert_eq!(points, 4 + 4 + 8 + 8);
+
+        let (slid, _) = slide(&board, Direction::Down);
+        assert_eq!(slid[3], vec![8, 4, 8, 2]);
+    }
+
+    proptest! {
+        #[test]
+        fn moves_keep_invariants(game in strategies::game(4), moves in strategies::moves(30)) {
+            let mut game = game;
+            for direction in moves {
+                if game.state() != GameState::Playing {
+                    break;
+                }
+                checked_move(&mut game, direction).unwrap();
+            }
+        }
+    }
+}
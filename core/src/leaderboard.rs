@@ -0,0 +1,246 @@
+use crate::daily::{date_string, SECONDS_PER_DAY};
+use crate::{Game, GameConfig, GameError, GameResult};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::fs;
+use std::path::Path;
+
+/// Default number of entries kept per board size and mode
+pub const DEFAULT_ENTRIES_PER_CATEGORY: usize = 10;
+
+/// Longest player name kept, in characters
+pub const MAX_NAME_LEN: usize = 20;
+
+/// A score on the local leaderboard
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    /// Player name
+    pub name: String,
+    pub score: u64,
+    pub max_tile: u64,
+    pub moves: u32,
+    /// Game duration in seconds
+    pub duration: u64,
+    pub board_size: usize,
+    /// Game mode identifier chosen by the frontend (e.g. "classic")
+    pub mode: String,
+    /// Identifier of the game's replay, if one was saved
+    #[serde(default)]
+    pub replay_id: Option<String>,
+    /// When the score was submitted (Unix timestamp)
+    pub achieved_at: u64,
+    /// Configuration the game was played with (missing in older entries)
+    #[serde(default)]
+    pub config: Option<GameConfig>,
+}
+
+impl LeaderboardEntry {
+    /// Entry for a game's current score, submitted now. The name is trimmed
+    /// and cut to `MAX_NAME_LEN` characters.
+    pub fn for_game(name: &str, game: &Game, mode: &str) -> Self {
+        Self {
+            name: name.trim().chars().take(MAX_NAME_LEN).collect(),
+            score: game.score().current(),
+            max_tile: game.board().max_tile(),
+            moves: game.moves(),
+            duration: game.stats().duration,
+            board_size: game.config().board_size,
+            mode: mode.to_string(),
+            replay_id: None,
+            achieved_at: crate::get_current_time(),
+            config: Some(game.config().clone()),
+        }
+    }
+
+    /// Set the identifier of the game's saved replay
+    pub fn with_replay_id(mut self, replay_id: impl Into<String>) -> Self {
+        self.replay_id = Some(replay_id.into());
+        self
+    }
+
+    /// Date the score was submitted, as `YYYY-MM-DD` (UTC)
+    pub fn date(&self) -> String {
+        date_string(self.achieved_at / SECONDS_PER_DAY)
+    }
+}
+
+/// Local top scores, ranked separately per board size and mode
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Leaderboard {
+    entries: Vec<LeaderboardEntry>,
+    max_per_category: usize,
+}
+
+impl Default for Leaderboard {
+    fn default() -> Self {
+        Self::new(DEFAULT_ENTRIES_PER_CATEGORY)
+    }
+}
+
+impl Leaderboard {
+    /// Create an empty leaderboard keeping `max_per_category` entries per size and mode
+    pub fn new(max_per_category: usize) -> Self {
+        Self {
+            entries: Vec::new(),
+            max_per_category,
+        }
+    }
+
+    /// Add a score. Returns its 1-based rank in its category, or `None` if it
+    /// did not make the cut.
+    pub fn submit(&mut self, entry: LeaderboardEntry) -> Option<usize> {
+        let rank = self.rank_for(entry.score, entry.board_size, &entry.mode)?;
+        let (board_size, mode) = (entry.board_size, entry.mode.clone());
+        self.entries.push(entry);
+        // Stable sort keeps earlier entries ahead on equal scores
+        self.entries.sort_by_key(|e| Reverse(e.score));
+
+        // Drop the entry that fell off the category
+        let mut kept = 0;
+        let max = self.max_per_category;
+        self.entries.retain(|e| {
+            if e.board_size != board_size || e.mode != mode {
+                return true;
+            }
+            kept += 1;
+            kept <= max
+        });
+        Some(rank)
+    }
+
+    /// Rank a score would take in a category, or `None` if it would not be kept
+    pub fn rank_for(&self, score: u64, board_size: usize, mode: &str) -> Option<usize> {
+        let rank = self
+            .top(board_size, mode)
+            .iter()
+            .take_while(|e| e.score >= score)
+            .count()
+            + 1;
+        (rank <= self.max_per_category).then_some(rank)
+    }
+
+    /// Entries of a category, best first
+    pub fn top(&self, board_size: usize, mode: &str) -> Vec<&LeaderboardEntry> {
+        self.entries
+            .iter()
+            .filter(|e| e.board_size == board_size && e.mode == mode)
+            .collect()
+    }
+
+    /// All entries, best first
+    pub fn entries(&self) -> &[LeaderboardEntry] {
+        &self.entries
+    }
+
+    /// Board sizes and modes that have entries
+    pub fn categories(&self) -> Vec<(usize, String)> {
+        let mut categories: Vec<(usize, String)> = self
+            .entries
+            .iter()
+            .map(|e| (e.board_size, e.mode.clone()))
+            .collect();
+        categories.sort();
+        categories.dedup();
+        categories
+    }
+
+    /// Remove the entries of one category
+    pub fn clear_category(&mut self, board_size: usize, mode: &str) {
+        self.entries
+            .retain(|e| e.board_size != board_size || e.mode != mode);
+    }
+
+    /// Remove all entries
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Load a leaderboard file; a missing file gives an empty leaderboard
+    pub fn load(path: impl AsRef<Path>) -> GameResult<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path).map_err(|e| {
+            GameError::InvalidOperation(format!("Failed to read leaderboard file: {}", e))
+        })?;
+        serde_json::from_str(&content).map_err(|e| {
+            GameError::InvalidOperation(format!("Failed to parse leaderboard file: {}", e))
+        })
+    }
+
+    /// Save the leaderboard to a file
+    pub fn save(&self, path: impl AsRef<Path>) -> GameResult<()> {
+        let content = serde_json::to_string_pretty(self).map_err(|e| {
+            GameError::InvalidOperation(format!("Failed to serialize leaderboard: {}", e))
+        })?;
+        fs::write(path, content).map_err(|e| {
+            GameError::InvalidOperation(format!("Failed to write leaderboard file: {}", e))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(score: u64, board_size: usize, mode: &str) -> LeaderboardEntry {
+        LeaderboardEntry {
+            name: "Player".to_string(),
+            score,
+            max_tile: 0,
+            moves: 0,
+            duration: 0,
+            board_size,
+            mode: mode.to_string(),
+            replay_id: None,
+            achieved_at: 0,
+            config: None,
+        }
+    }
+
+    #[test]
+    fn test_submit_ranks_per_category() {
+        let mut leaderboard = Leaderboard::new(2);
+        assert_eq!(leaderboard.submit(entry(100, 4, "classic")), Some(1));
+        assert_eq!(leaderboard.submit(entry(300, 4, "classic")), Some(1));
+        assert_eq!(leaderboard.submit(entry(50, 4, "hardcore")), Some(1));
+
+        // Category is full and 80 is lower than everything in it
+        assert_eq!(leaderboard.submit(entry(80, 4, "classic")), None);
+        assert_eq!(leaderboard.submit(entry(200, 4, "classic")), Some(2));
+
+        let scores: Vec<u64> = leaderboard
+            .top(4, "classic")
+            .iter()
+            .map(|e| e.score)
+            .collect();
+        assert_eq!(scores, vec![300, 200]);
+        assert_eq!(leaderboard.top(4, "hardcore").len(), 1);
+
+        leaderboard.clear_category(4, "classic");
+        assert_eq!(leaderboard.categories(), vec![(4, "hardcore".to_string())]);
+    }
+
+    #[test]
+    fn test_entry_for_game_and_file_round_trip() {
+        let game = Game::new(GameConfig::default()).unwrap();
+        let entry =
+            LeaderboardEntry::for_game("  A very long player name indeed ", &game, "classic")
+                .with_replay_id("replay-1");
+        assert_eq!(entry.name.chars().count(), MAX_NAME_LEN);
+        assert!(entry.name.starts_with("A very"));
+        assert_eq!(entry.config.as_ref(), Some(game.config()));
+        assert_eq!(entry.date().len(), 10);
+
+        let path =
+            std::env::temp_dir().join(format!("rusty2048-leaderboard-{}", std::process::id()));
+        assert!(Leaderboard::load(&path).unwrap().entries().is_empty());
+        let mut leaderboard = Leaderboard::default();
+        leaderboard.submit(entry.clone());
+        leaderboard.save(&path).unwrap();
+        assert_eq!(Leaderboard::load(&path).unwrap().entries(), &[entry]);
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -8,24 +8,96 @@
 //! - Random number generation with seed support
 
 pub mod ai;
+pub mod analysis;
+pub mod auto_record;
+pub mod benchmark;
+pub mod best_score;
 pub mod board;
+pub mod daily;
+pub mod diff;
 pub mod error;
+pub mod events;
 pub mod game;
+pub mod invariants;
+pub mod leaderboard;
+pub mod metrics;
+pub mod moves;
+pub mod ntuple;
+pub mod online;
+#[cfg(all(feature = "online", not(target_arch = "wasm32")))]
+pub mod online_client;
+pub mod position_code;
+pub mod race;
 pub mod replay;
+pub mod replay_stream;
 pub mod rng;
+pub mod rules;
 pub mod score;
+pub mod search;
 pub mod stats;
+pub mod symmetry;
+#[cfg(feature = "train")]
+pub mod train;
+pub mod transposition;
+#[cfg(feature = "tune")]
+pub mod tune;
+pub mod win;
+pub mod zobrist;
 
-pub use ai::{AIAlgorithm, AIGameController, AIPlayer};
+pub use ai::{
+    AIAlgorithm, AIGameController, AIPlayer, AIStrategy, DepthPolicy, ExpectimaxStrategy,
+    GreedyStrategy, HeuristicConfig, IncrementalSearch, MctsStrategy, MinimaxStrategy,
+    ADVERSARY_DEPTH, DEFAULT_PROBABILITY_THRESHOLD, DEFAULT_SAMPLE_THRESHOLD, MAX_ITERATIVE_DEPTH,
+};
+pub use analysis::{
+    analyze_replay, analyze_replay_with_depth, MoveAnalysis, MoveGrade, MoveGrader, ReplayAnalysis,
+    DEFAULT_ANALYSIS_DEPTH, INACCURACY_THRESHOLD, MISTAKE_THRESHOLD,
+};
+pub use auto_record::AutoRecorder;
+pub use benchmark::{AIBenchmark, BenchmarkResults};
+#[cfg(not(target_arch = "wasm32"))]
+pub use best_score::FileBestScoreStore;
+pub use best_score::{BestScoreStore, CallbackBestScoreStore};
 pub use board::Board;
-pub use error::{GameError, GameResult};
-pub use game::{Direction, Game, GameState};
+pub use daily::{daily_seed, DailyChallenge, DailyResult};
+pub use diff::TileChange;
+pub use error::{ErrorCode, GameError, GameResult};
+pub use events::GameEvent;
+pub use game::{Direction, Game, GameState, SavedGame};
+pub use leaderboard::{Leaderboard, LeaderboardEntry};
+pub use moves::{MoveResult, SpawnedTile, TileMerge, TileMove};
+pub use ntuple::{register_network, Afterstate, NTupleNetwork, NTupleStrategy};
+pub use online::{
+    GlobalEntry, GlobalLeaderboard, LeaderboardQuery, ScoreSubmission, SignedSubmission,
+    SubmitResponse,
+};
+#[cfg(all(feature = "online", not(target_arch = "wasm32")))]
+pub use online_client::OnlineLeaderboardClient;
+pub use race::{OpponentState, RaceMessage, RaceSession};
 pub use replay::{
-    ReplayData, ReplayManager, ReplayMetadata, ReplayMove, ReplayPlayer, ReplayRecorder,
+    PlaybackTiming, ReplayData, ReplayFile, ReplayManager, ReplayMetadata, ReplayMove,
+    ReplayPlayer, ReplayRecorder, ReplayRetention, ReplaySort, MAX_REAL_TIME_GAP_MS,
+    REPLAY_EXTENSION, REPLAY_FORMAT_VERSION, REPLAY_MAGIC, REPLAY_SCHEMA_VERSION,
 };
+pub use replay_stream::{recover_replay, ReplayStreamWriter, REPLAY_STREAM_EXTENSION};
 pub use rng::GameRng;
-pub use score::Score;
-pub use stats::{create_session_stats, GameSessionStats, StatisticsManager, StatisticsSummary};
+pub use rules::{register_rules, rules_by_id, ClassicRules, GameRules};
+pub use score::{BonusKind, Score, ScoreBonus, ScoringRules};
+pub use search::{
+    CancellationToken, ProgressCallback, SearchControl, SearchProgress, SearchStatus,
+};
+pub use stats::{
+    create_session_stats, stats_backup_path, ArchivedTotals, GameSessionStats, MergeReport,
+    PeriodTotals, PlayerType, PositionHeatmap, RetentionPolicy, SessionFilter, SessionSegment,
+    StatisticsManager, StatisticsSummary, StatsPeriod, STATS_SCHEMA_VERSION,
+};
+pub use symmetry::Symmetry;
+#[cfg(feature = "train")]
+pub use train::{Trainer, TrainingConfig, TrainingProgress};
+pub use transposition::{TranspositionStats, TranspositionTable, DEFAULT_TRANSPOSITION_CAPACITY};
+#[cfg(feature = "tune")]
+pub use tune::{Candidate, Tuner, TuningConfig, TuningProgress};
+pub use win::{WinCondition, WinTrigger};
 
 /// Get current time as Unix timestamp
 pub fn get_current_time() -> u64 {
@@ -43,43 +115,106 @@ pub fn get_current_time() -> u64 {
     }
 }
 
+/// Get current time in milliseconds since the Unix epoch
+pub fn get_current_time_ms() -> u64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        js_sys::Date::now() as u64
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use std::time::{SystemTime, UNIX_EPOCH};
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64
+    }
+}
+
 /// Game configuration
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct GameConfig {
     /// Board size (default: 4)
     pub board_size: usize,
-    /// Target score to win (default: 2048)
-    pub target_score: u32,
+    /// What has to be reached to win (default: the 2048 tile)
+    #[serde(
+        alias = "target_score",
+        deserialize_with = "win::deserialize_win_condition"
+    )]
+    pub win_condition: WinCondition,
     /// Whether to allow undo (default: true)
     pub allow_undo: bool,
+    /// How many moves can be undone in a row (default: 10)
+    #[serde(default = "default_max_undo_depth")]
+    pub max_undo_depth: usize,
+    /// Tiles spawned after every move (default: 1)
+    #[serde(default = "default_tiles_per_spawn")]
+    pub tiles_per_spawn: usize,
+    /// Edge tiles fall toward after every move across it, for the gravity
+    /// variant (default: none)
+    #[serde(default)]
+    pub gravity: Option<Direction>,
     /// Random seed for reproducible games
     pub seed: Option<u64>,
+    /// Id of the registered rules to play with (default: "classic")
+    #[serde(default = "rules::default_rules_id")]
+    pub rules: String,
+    /// Bonus and penalty rules on top of the merge points (default: none)
+    #[serde(default)]
+    pub scoring: ScoringRules,
 }
 
 impl Default for GameConfig {
     fn default() -> Self {
         Self {
             board_size: 4,
-            target_score: 2048,
+            win_condition: WinCondition::default(),
             allow_undo: true,
+            max_undo_depth: DEFAULT_MAX_UNDO_DEPTH,
+            tiles_per_spawn: 1,
+            gravity: None,
             seed: None,
+            rules: rules::default_rules_id(),
+            scoring: ScoringRules::default(),
         }
     }
 }
 
+/// Undo history kept by default
+pub const DEFAULT_MAX_UNDO_DEPTH: usize = 10;
+
+fn default_max_undo_depth() -> usize {
+    DEFAULT_MAX_UNDO_DEPTH
+}
+
+fn default_tiles_per_spawn() -> usize {
+    1
+}
+
 /// Game statistics
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Default)]
 pub struct GameStats {
     /// Current score
-    pub score: u32,
+    pub score: u64,
     /// Best score achieved
-    pub best_score: u32,
+    pub best_score: u64,
     /// Number of moves made
     pub moves: u32,
     /// Game duration in seconds
     pub duration: u64,
     /// Whether the game is won
     pub won: bool,
+    /// Which part of the win condition won the game
+    pub won_by: Option<WinTrigger>,
     /// Whether the game is over
     pub game_over: bool,
+    /// Average time between moves in milliseconds
+    pub average_move_ms: Option<f64>,
+    /// Shortest time between moves in milliseconds
+    pub fastest_move_ms: Option<u64>,
+    /// Longest time between moves in milliseconds
+    pub slowest_move_ms: Option<u64>,
+    /// Merge points by merged tile value, see `Score::breakdown`
+    #[serde(default)]
+    pub score_breakdown: std::collections::BTreeMap<u64, u64>,
 }
@@ -0,0 +1,154 @@
+//! Board quality metrics.
+//!
+//! These are the measurements the AI's evaluation is built from. They are
+//! unweighted, so charts and custom AIs can scale and combine them freely.
+
+use crate::board::Tile;
+use crate::{Board, Symmetry};
+
+/// Weight lost at each step along the snake of `Board::snake_weighting`
+const SNAKE_RATIO: f64 = 0.5;
+
+impl Board {
+    /// Number of empty cells
+    pub fn empty_count(&self) -> usize {
+        self.count_tiles(0)
+    }
+
+    /// How far rows and columns are from being sorted, as a penalty of zero
+    /// or less. Each line is measured in log2 steps against its better
+    /// direction, so a board whose every row and column is monotonic scores 0.
+    pub fn monotonicity(&self) -> f64 {
+        let line_penalty = |line: &[f64]| {
+            let (mut increases, mut decreases) = (0.0, 0.0);
+            for pair in line.windows(2) {
+                if pair[0] < pair[1] {
+                    increases += pair[1] - pair[0];
+                } else {
+                    decreases += pair[0] - pair[1];
+                }
+            }
+            f64::min(increases, decreases)
+        };
+        let exponent = |tile: Tile| {
+            if tile.is_empty() {
+                0.0
+            } else {
+                (tile.value as f64).log2()
+            }
+        };
+
+        let rows: f64 = self
+            .rows()
+            .map(|row| line_penalty(&row.iter().copied().map(exponent).collect::<Vec<_>>()))
+            .sum();
+        let columns: f64 = self
+            .columns()
+            .map(|column| line_penalty(&column.map(exponent).collect::<Vec<_>>()))
+            .sum();
+
+        -(rows + columns)
+    }
+
+    /// Negated sum of the differences between horizontally and vertically
+    /// adjacent tiles, ignoring empty cells. Closer to 0 means more merges
+    /// are lined up.
+    pub fn smoothness(&self) -> f64 {
+        let difference = |tile1: &Tile, tile2: &Tile| {
+            if !tile1.is_empty() && !tile2.is_empty() {
+                (tile1.value as f64 - tile2.value as f64).abs()
+            } else {
+                0.0
+            }
+        };
+
+        let horizontal: f64 = self
+            .rows()
+            .flat_map(|row| row.windows(2))
+            .map(|pair| difference(&pair[0], &pair[1]))
+            .sum();
+
+        // Each tile against the one below it
+        let tiles = self.as_flat_slice();
+        let vertical: f64 = tiles
+            .iter()
+            .zip(&tiles[self.size()..])
+            .map(|(tile1, tile2)| difference(tile1, tile2))
+            .sum();
+
+        -(horizontal + vertical)
+    }
+
+    /// Sum of the tile values in the four corners
+    pub fn corner_weighting(&self) -> f64 {
+        let last = self.size() - 1;
+        [(0, 0), (0, last), (last, 0), (last, last)]
+            .into_iter()
+            .filter_map(|(row, col)| self.get_tile(row, col).ok())
+            .map(|tile| tile.value as f64)
+            .sum()
+    }
+
+    /// Tile values weighted along a snake (boustrophedon) path, halving at
+    /// each step from the starting corner: the best of the eight paths
+    /// the board's symmetries give. Highest when the tiles are sorted
+    /// along the path, largest in the corner.
+    pub fn snake_weighting(&self) -> f64 {
+        let size = self.size();
+        let tiles = self.as_flat_slice();
+        let path: Vec<(usize, usize)> = (0..size)
+            .flat_map(|row| {
+                (0..size).map(move |i| (row, if row % 2 == 0 { i } else { size - 1 - i }))
+            })
+            .collect();
+
+        Symmetry::ALL
+            .into_iter()
+            .map(|symmetry| {
+                let mut weight = 1.0;
+                let mut sum = 0.0;
+                for &(row, col) in &path {
+                    let (row, col) = symmetry.apply(row, col, size);
+                    sum += tiles[row * size + col].value as f64 * weight;
+                    weight *= SNAKE_RATIO;
+                }
+                sum
+            })
+            .fold(0.0, f64::max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metrics() {
+        let sorted = Board::from_vec(vec![
+            vec![64, 32, 16, 8],
+            vec![32, 16, 8, 4],
+            vec![16, 8, 4, 2],
+            vec![8, 4, 2, 0],
+        ])
+        .unwrap();
+        assert_eq!(sorted.empty_count(), 1);
+        assert_eq!(sorted.monotonicity(), 0.0);
+        assert_eq!(sorted.corner_weighting(), 64.0 + 8.0 + 8.0);
+
+        let zigzag = Board::from_vec(vec![vec![2, 8, 2], vec![0, 0, 0], vec![0, 0, 4]]).unwrap();
+        // Row 0 rises and falls by two steps each; column 2 falls one step
+        // into the empty cell and rises two
+        assert_eq!(zigzag.monotonicity(), -3.0);
+        assert_eq!(zigzag.smoothness(), -12.0);
+        assert_eq!(zigzag.empty_count(), 5);
+
+        // Best path runs along the top row from the right, then zigzags
+        // down to the 4 in the bottom right corner
+        assert_eq!(
+            zigzag.snake_weighting(),
+            2.0 + 8.0 / 2.0 + 2.0 / 4.0 + 4.0 / 64.0
+        );
+        let snake = Board::from_vec(vec![vec![0, 0], vec![2, 4]]).unwrap();
+        assert_eq!(snake.snake_weighting(), 4.0 + 1.0);
+    }
+}
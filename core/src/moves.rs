@@ -0,0 +1,71 @@
+use crate::{Direction, ScoreBonus};
+use serde::{Deserialize, Serialize};
+
+/// A tile sliding to another cell during a move
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TileMove {
+    /// (row, col) before the move
+    pub from: (usize, usize),
+    /// (row, col) after the move
+    pub to: (usize, usize),
+    /// Value of the tile before any merge
+    pub value: u64,
+}
+
+/// Two tiles merged into one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TileMerge {
+    /// (row, col) of the merged tile
+    pub position: (usize, usize),
+    /// (row, col) of the two tiles before the move
+    pub sources: [(usize, usize); 2],
+    /// Value of the merged tile
+    pub value: u64,
+}
+
+/// A tile placed on the board after a move
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpawnedTile {
+    pub row: usize,
+    pub col: usize,
+    pub value: u64,
+}
+
+/// What a move did to the board, for animating it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveResult {
+    pub direction: Direction,
+    /// Whether any tile moved or merged
+    pub moved: bool,
+    /// Tiles that changed cell, including both tiles of every merge
+    pub moves: Vec<TileMove>,
+    pub merges: Vec<TileMerge>,
+    /// Tiles that fell toward the gravity edge after sliding, when
+    /// `GameConfig::gravity` is set
+    #[serde(default)]
+    pub drops: Vec<TileMove>,
+    /// Tiles added after the move; fewer than `GameConfig::tiles_per_spawn`
+    /// when the board filled up
+    pub spawned: Vec<SpawnedTile>,
+    /// Bonus points from `GameConfig::scoring`, already part of `points`
+    #[serde(default)]
+    pub bonuses: Vec<ScoreBonus>,
+    /// Points scored by the move
+    pub points: u64,
+}
+
+impl MoveResult {
+    /// Result of a move that changed nothing
+    pub fn unmoved(direction: Direction) -> Self {
+        Self {
+            direction,
+            moved: false,
+            moves: Vec::new(),
+            merges: Vec::new(),
+            drops: Vec::new(),
+            spawned: Vec::new(),
+            bonuses: Vec::new(),
+            points: 0,
+        }
+    }
+}
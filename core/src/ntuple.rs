@@ -0,0 +1,440 @@
+//! N-tuple network evaluation.
+//!
+//! An [`NTupleNetwork`] scores a board by looking up every tuple of cells
+//! (each row, each column and each 2x2 square in the standard layout) in a
+//! table of learned weights indexed by the tiles' exponents, and summing
+//! the weights. Networks trained by temporal-difference learning are the
+//! strongest known 2048 evaluators; [`NTupleStrategy`] plays the move whose
+//! points plus afterstate value are highest.
+//!
+//! Weights are loaded from a little-endian binary file:
+//!
+//! ```text
+//! magic "R2NT" | version u32 | board size u32 | tuple count u32
+//! per tuple: cell count u32 | cells u32 (row * size + col) ...
+//!            16^cells weights f32
+//! ```
+//!
+//! Frontends load a network once and [`register_network`] it, so
+//! `AIAlgorithm::NTuple` picks it up. Networks are trained with the
+//! `rusty2048-train` binary (`train` feature).
+
+use crate::ai::{evaluate_board, slide_board_scored, AIStrategy, HeuristicConfig};
+use crate::{Board, Direction, Game, GameError, GameResult};
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// First bytes of a weights file
+pub const NTUPLE_MAGIC: &[u8; 4] = b"R2NT";
+
+/// Version of the weights file format
+pub const NTUPLE_FORMAT_VERSION: u32 = 1;
+
+/// Distinct exponents a cell can take in a lookup; larger tiles share the
+/// last one
+const EXPONENTS: usize = 16;
+
+/// Most cells in a tuple, so a table stays at 16^6 entries or fewer
+const MAX_TUPLE_CELLS: usize = 6;
+
+/// Tuples of cells with a table of weights each
+#[derive(Debug, Clone, PartialEq)]
+pub struct NTupleNetwork {
+    size: usize,
+    /// Cells of each tuple, as `row * size + col`
+    tuples: Vec<Vec<usize>>,
+    /// `EXPONENTS^cells` weights per tuple
+    weights: Vec<Vec<f32>>,
+}
+
+impl NTupleNetwork {
+    /// Untrained network (all weights zero) over every row, column and 2x2
+    /// square of a board of the given size. Rows and columns are tuples of
+    /// `size` cells, so sizes above 4 are refused: their tables would take
+    /// tens of megabytes each.
+    pub fn standard(size: usize) -> GameResult<Self> {
+        if !(2..=4).contains(&size) {
+            return Err(GameError::InvalidOperation(format!(
+                "Standard n-tuple networks support boards of 2 to 4 cells per side, not {}",
+                size
+            )));
+        }
+        let mut tuples = Vec::new();
+        tuples.extend((0..size).map(|row| (0..size).map(|col| row * size + col).collect()));
+        tuples.extend((0..size).map(|col| (0..size).map(|row| row * size + col).collect()));
+        for row in 0..size - 1 {
+            for col in 0..size - 1 {
+                let cell = row * size + col;
+                tuples.push(vec![cell, cell + 1, cell + size, cell + size + 1]);
+            }
+        }
+        Self::with_tuples(size, tuples)
+    }
+
+    /// Untrained network over custom tuples of cells (`row * size + col`)
+    pub fn with_tuples(size: usize, tuples: Vec<Vec<usize>>) -> GameResult<Self> {
+        for tuple in &tuples {
+            if tuple.is_empty() || tuple.len() > MAX_TUPLE_CELLS {
+                return Err(GameError::InvalidOperation(format!(
+                    "A tuple needs 1 to {} cells, not {}",
+                    MAX_TUPLE_CELLS,
+                    tuple.len()
+                )));
+            }
+            if let Some(cell) = tuple.iter().find(|&&cell| cell >= size * size) {
+                return Err(GameError::InvalidOperation(format!(
+                    "Cell {} is outside a {}x{} board",
+                    cell, size, size
+                )));
+            }
+        }
+        let weights = tuples
+            .iter()
+            .map(|tuple| vec![0.0; EXPONENTS.pow(tuple.len() as u32)])
+            .collect();
+        Ok(Self {
+            size,
+            tuples,
+            weights,
+        })
+    }
+
+    /// Board size the network evaluates
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Cells of each tuple
+    pub fn tuples(&self) -> &[Vec<usize>] {
+        &self.tuples
+    }
+
+    /// Value of a board: the sum of every tuple's weight. Boards of
+    /// another size are worth 0.
+    pub fn evaluate(&self, board: &Board) -> f64 {
+        if board.size() != self.size {
+            return 0.0;
+        }
+        let tiles = board.as_flat_slice();
+        self.tuples
+            .iter()
+            .zip(&self.weights)
+            .map(|(tuple, weights)| weights[lookup_index(tuple, tiles)] as f64)
+            .sum()
+    }
+
+    /// Move every weight the board looks up by `delta`, e.g. for
+    /// temporal-difference training
+    pub fn update(&mut self, board: &Board, delta: f32) {
+        if board.size() != self.size {
+            return;
+        }
+        let tiles = board.as_flat_slice();
+        for (tuple, weights) in self.tuples.iter().zip(&mut self.weights) {
+            weights[lookup_index(tuple, tiles)] += delta;
+        }
+    }
+
+    /// The move whose points plus afterstate value are highest, or `None`
+    /// if no move changes the board
+    pub fn best_afterstate(&self, game: &Game) -> Option<Afterstate> {
+        best_afterstate(game, |after, points| points as f64 + self.evaluate(after))
+    }
+
+    /// Encode in the weights file format
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(NTUPLE_MAGIC);
+        bytes.extend_from_slice(&NTUPLE_FORMAT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(self.size as u32).to_le_bytes());
+        bytes.extend_from_slice(&(self.tuples.len() as u32).to_le_bytes());
+        for (tuple, weights) in self.tuples.iter().zip(&self.weights) {
+            bytes.extend_from_slice(&(tuple.len() as u32).to_le_bytes());
+            for &cell in tuple {
+                bytes.extend_from_slice(&(cell as u32).to_le_bytes());
+            }
+            for weight in weights {
+                bytes.extend_from_slice(&weight.to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    /// Decode the weights file format
+    pub fn from_bytes(bytes: &[u8]) -> GameResult<Self> {
+        let mut reader = Reader { bytes };
+        if reader.take(4)? != NTUPLE_MAGIC {
+            return Err(GameError::Serialization(
+                "Not an n-tuple weights file".to_string(),
+            ));
+        }
+        let version = reader.u32()?;
+        if version != NTUPLE_FORMAT_VERSION {
+            return Err(GameError::UnsupportedVersion {
+                found: version,
+                supported: NTUPLE_FORMAT_VERSION,
+            });
+        }
+        let size = reader.u32()? as usize;
+        let count = reader.u32()? as usize;
+
+        let mut tuples = Vec::new();
+        let mut weights = Vec::new();
+        for _ in 0..count {
+            let cells = reader.u32()? as usize;
+            if cells == 0 || cells > MAX_TUPLE_CELLS {
+                return Err(GameError::Serialization(format!(
+                    "Invalid n-tuple of {} cells",
+                    cells
+                )));
+            }
+            let tuple = (0..cells)
+                .map(|_| reader.u32().map(|cell| cell as usize))
+                .collect::<GameResult<Vec<_>>>()?;
+            let table = (0..EXPONENTS.pow(cells as u32))
+                .map(|_| reader.f32())
+                .collect::<GameResult<Vec<_>>>()?;
+            tuples.push(tuple);
+            weights.push(table);
+        }
+        if !reader.bytes.is_empty() {
+            return Err(GameError::Serialization(
+                "Trailing data after n-tuple weights".to_string(),
+            ));
+        }
+
+        let mut network =
+            Self::with_tuples(size, tuples).map_err(|e| GameError::Serialization(e.to_string()))?;
+        network.weights = weights;
+        Ok(network)
+    }
+
+    /// Load a weights file
+    pub fn load(path: impl AsRef<Path>) -> GameResult<Self> {
+        let path = path.as_ref();
+        let bytes = fs::read(path).map_err(|e| {
+            GameError::InvalidOperation(format!(
+                "Failed to read n-tuple weights {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Write a weights file
+    pub fn save(&self, path: impl AsRef<Path>) -> GameResult<()> {
+        let path = path.as_ref();
+        fs::write(path, self.to_bytes()).map_err(|e| {
+            GameError::InvalidOperation(format!(
+                "Failed to write n-tuple weights {}: {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+}
+
+/// A move and the board it leaves before a tile spawns
+#[derive(Debug, Clone)]
+pub struct Afterstate {
+    pub direction: Direction,
+    pub board: Board,
+    /// Points scored by the move's merges
+    pub points: u64,
+    /// Value the move was chosen by
+    pub value: f64,
+}
+
+/// The afterstate of `game` that `value` rates highest, the first one on
+/// ties
+fn best_afterstate(game: &Game, value: impl Fn(&Board, u64) -> f64) -> Option<Afterstate> {
+    let mut best: Option<Afterstate> = None;
+    for direction in [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ] {
+        let Some((board, points)) = slide_board_scored(game.rules(), game.board(), direction)
+        else {
+            continue;
+        };
+        let value = value(&board, points);
+        if best.as_ref().is_none_or(|best| value > best.value) {
+            best = Some(Afterstate {
+                direction,
+                board,
+                points,
+                value,
+            });
+        }
+    }
+    best
+}
+
+/// Index of a tuple's tiles in its table
+fn lookup_index(tuple: &[usize], tiles: &[crate::board::Tile]) -> usize {
+    tuple.iter().fold(0, |index, &cell| {
+        let value = tiles[cell].value;
+        let exponent = if value == 0 {
+            0
+        } else {
+            (value.ilog2() as usize).min(EXPONENTS - 1)
+        };
+        index * EXPONENTS + exponent
+    })
+}
+
+/// Reads the little-endian fields of a weights file
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, count: usize) -> GameResult<&'a [u8]> {
+        if self.bytes.len() < count {
+            return Err(GameError::Serialization(
+                "Truncated n-tuple weights".to_string(),
+            ));
+        }
+        let (taken, rest) = self.bytes.split_at(count);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn u32(&mut self) -> GameResult<u32> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn f32(&mut self) -> GameResult<f32> {
+        self.u32().map(f32::from_bits)
+    }
+}
+
+fn registry() -> &'static RwLock<Option<Arc<NTupleNetwork>>> {
+    static REGISTRY: OnceLock<RwLock<Option<Arc<NTupleNetwork>>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(None))
+}
+
+/// Make a network the one `AIAlgorithm::NTuple` plays with, replacing any
+/// network registered before
+pub fn register_network(network: Arc<NTupleNetwork>) {
+    *registry().write().unwrap_or_else(|e| e.into_inner()) = Some(network);
+}
+
+/// The network registered with `register_network`, if any
+pub fn registered_network() -> Option<Arc<NTupleNetwork>> {
+    registry().read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Plays the move whose points plus n-tuple value of the resulting board
+/// are highest. Without a network for the board's size it falls back to
+/// the snake heuristic.
+#[derive(Debug, Clone, Default)]
+pub struct NTupleStrategy {
+    network: Option<Arc<NTupleNetwork>>,
+}
+
+impl NTupleStrategy {
+    /// Play with the given network
+    pub fn new(network: Arc<NTupleNetwork>) -> Self {
+        Self {
+            network: Some(network),
+        }
+    }
+
+    /// Play with the registered network, if any
+    pub fn registered() -> Self {
+        Self {
+            network: registered_network(),
+        }
+    }
+
+    /// Network the strategy plays with
+    pub fn network(&self) -> Option<&Arc<NTupleNetwork>> {
+        self.network.as_ref()
+    }
+}
+
+impl AIStrategy for NTupleStrategy {
+    fn choose(&mut self, game: &Game) -> Direction {
+        let network = self
+            .network
+            .as_ref()
+            .filter(|network| network.size() == game.board().size());
+        let heuristic = HeuristicConfig::snake();
+        let best = best_afterstate(game, |after, points| match network {
+            Some(network) => points as f64 + network.evaluate(after),
+            None => evaluate_board(after, &heuristic),
+        });
+        best.map_or(Direction::Up, |afterstate| afterstate.direction)
+    }
+
+    fn name(&self) -> &str {
+        "N-Tuple"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameConfig, GameState, Score};
+
+    #[test]
+    fn test_network_round_trip() {
+        let mut network = NTupleNetwork::standard(4).unwrap();
+        // 4 rows, 4 columns and 9 squares
+        assert_eq!(network.tuples().len(), 17);
+
+        let board = Board::from_vec(vec![
+            vec![2, 4, 8, 16],
+            vec![0, 0, 0, 0],
+            vec![0, 0, 0, 0],
+            vec![0, 0, 0, 65536],
+        ])
+        .unwrap();
+        network.update(&board, 0.5);
+        assert_eq!(network.evaluate(&board), 17.0 * 0.5);
+        assert_eq!(network.evaluate(&Board::new(3).unwrap()), 0.0);
+
+        let decoded = NTupleNetwork::from_bytes(&network.to_bytes()).unwrap();
+        assert_eq!(decoded, network);
+
+        let mut bytes = network.to_bytes();
+        bytes.pop();
+        assert!(matches!(
+            NTupleNetwork::from_bytes(&bytes),
+            Err(GameError::Serialization(_))
+        ));
+        bytes[4] = 9;
+        assert!(matches!(
+            NTupleNetwork::from_bytes(&bytes),
+            Err(GameError::UnsupportedVersion { found: 9, .. })
+        ));
+        assert!(NTupleNetwork::standard(8).is_err());
+    }
+
+    #[test]
+    fn test_strategy_follows_the_network() {
+        let mut game = Game::new(GameConfig::default()).unwrap();
+        game.load_from_state(
+            vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2],
+            Score::new(),
+            0,
+            GameState::Playing,
+        )
+        .unwrap();
+
+        // A network that likes the tile in the top right corner
+        let mut network = NTupleNetwork::standard(4).unwrap();
+        let mut corner = Board::new(4).unwrap();
+        corner.set_tile(0, 3, crate::board::Tile::new(2)).unwrap();
+        network.update(&corner, 10.0);
+
+        let mut strategy = NTupleStrategy::new(Arc::new(network));
+        assert_eq!(strategy.choose(&game), Direction::Up);
+    }
+}
@@ -0,0 +1,219 @@
+//! Protocol of online leaderboards.
+//!
+//! A client submits a [`SignedSubmission`] as JSON with `POST {endpoint}/scores`
+//! and receives a [`SubmitResponse`]. `GET {endpoint}/leaderboard` with the
+//! query of a [`LeaderboardQuery`] returns a [`GlobalLeaderboard`].
+//!
+//! A submission is signed with HMAC-SHA256 over the exact bytes of its JSON
+//! `payload` string, using a key shared between the players of an endpoint
+//! and its server. Servers verify the signature before parsing the payload,
+//! so they never depend on how a client orders or formats fields.
+
+use crate::{Game, GameError, GameResult};
+use hmac_sha256::HMAC;
+use serde::{Deserialize, Serialize};
+
+/// Version of the protocol, sent with every submission
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// A finished game submitted to an online leaderboard
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoreSubmission {
+    /// Protocol version the client speaks
+    pub protocol_version: u32,
+    pub player_name: String,
+    pub score: u64,
+    pub max_tile: u64,
+    pub moves: u32,
+    /// Game duration in seconds
+    pub duration: u64,
+    pub board_size: usize,
+    /// Game mode identifier (e.g. "classic" or "daily")
+    pub mode: String,
+    /// Seed of a seeded game as a decimal string, since not every JSON
+    /// parser keeps 64-bit integers exact
+    #[serde(default)]
+    pub seed: Option<String>,
+    /// Hash of the game's replay, for servers that ask for it later
+    #[serde(default)]
+    pub replay_hash: Option<String>,
+    /// Version of the submitting client
+    pub client_version: String,
+    /// When the score was submitted (Unix timestamp)
+    pub submitted_at: u64,
+}
+
+impl ScoreSubmission {
+    /// Submission of a game's current score, made now
+    pub fn for_game(player_name: &str, game: &Game, mode: &str) -> Self {
+        Self {
+            protocol_version: PROTOCOL_VERSION,
+            player_name: player_name.trim().to_string(),
+            score: game.score().current(),
+            max_tile: game.board().max_tile(),
+            moves: game.moves(),
+            duration: game.stats().duration,
+            board_size: game.config().board_size,
+            mode: mode.to_string(),
+            seed: game.config().seed.map(|seed| seed.to_string()),
+            replay_hash: None,
+            client_version: env!("CARGO_PKG_VERSION").to_string(),
+            submitted_at: crate::get_current_time(),
+        }
+    }
+
+    /// Sign the submission with the endpoint's key
+    pub fn sign(&self, key: &[u8]) -> GameResult<SignedSubmission> {
+        let payload = serde_json::to_string(self)
+            .map_err(|e| GameError::Serialization(format!("Failed to encode submission: {}", e)))?;
+        let signature = to_hex(&HMAC::mac(&payload, key));
+        Ok(SignedSubmission { payload, signature })
+    }
+}
+
+/// A submission as sent over the wire
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedSubmission {
+    /// JSON of a [`ScoreSubmission`]
+    pub payload: String,
+    /// Lowercase hex HMAC-SHA256 of `payload`
+    pub signature: String,
+}
+
+impl SignedSubmission {
+    /// Check the signature and parse the submission
+    pub fn verify(&self, key: &[u8]) -> GameResult<ScoreSubmission> {
+        let expected = to_hex(&HMAC::mac(&self.payload, key));
+        if expected != self.signature.to_ascii_lowercase() {
+            return Err(GameError::InvalidOperation(
+                "Invalid submission signature".to_string(),
+            ));
+        }
+        serde_json::from_str(&self.payload)
+            .map_err(|e| GameError::Serialization(format!("Invalid submission: {}", e)))
+    }
+}
+
+/// Server answer to a submission
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubmitResponse {
+    /// Whether the score was recorded
+    pub accepted: bool,
+    /// 1-based rank of the score on its leaderboard, if it was ranked
+    #[serde(default)]
+    pub rank: Option<u64>,
+    /// Why the score was rejected, or any other note for the player
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// Which part of a global leaderboard to fetch
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeaderboardQuery {
+    /// Only scores on this board size
+    pub board_size: Option<usize>,
+    /// Only scores of this mode
+    pub mode: Option<String>,
+    /// At most this many entries, best first
+    pub limit: Option<usize>,
+}
+
+impl LeaderboardQuery {
+    /// URL query string, without the leading `?`
+    pub fn to_query_string(&self) -> String {
+        let mut params = Vec::new();
+        if let Some(board_size) = self.board_size {
+            params.push(format!("board_size={}", board_size));
+        }
+        if let Some(mode) = &self.mode {
+            params.push(format!("mode={}", percent_encode(mode)));
+        }
+        if let Some(limit) = self.limit {
+            params.push(format!("limit={}", limit));
+        }
+        params.join("&")
+    }
+}
+
+/// A ranked score on a global leaderboard
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GlobalEntry {
+    /// 1-based rank
+    pub rank: u64,
+    pub player_name: String,
+    pub score: u64,
+    pub max_tile: u64,
+    pub moves: u32,
+    pub board_size: usize,
+    pub mode: String,
+    /// When the score was submitted (Unix timestamp)
+    pub submitted_at: u64,
+}
+
+/// Scores fetched from a server, best first
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GlobalLeaderboard {
+    pub entries: Vec<GlobalEntry>,
+    /// Number of scores matching the query on the server
+    #[serde(default)]
+    pub total: Option<u64>,
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Percent-encode everything but unreserved URL characters
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GameConfig;
+
+    #[test]
+    fn test_signed_submission() {
+        let game = Game::new(GameConfig {
+            seed: Some(u64::MAX),
+            ..GameConfig::default()
+        })
+        .unwrap();
+        let submission = ScoreSubmission::for_game("Player", &game, "classic");
+        assert_eq!(submission.seed.as_deref(), Some("18446744073709551615"));
+
+        let signed = submission.sign(b"secret").unwrap();
+        assert_eq!(signed.signature.len(), 64);
+        assert_eq!(signed.verify(b"secret").unwrap(), submission);
+        assert!(signed.verify(b"other key").is_err());
+
+        let tampered = SignedSubmission {
+            payload: signed.payload.replace("\"Player\"", "\"Cheater\""),
+            ..signed
+        };
+        assert!(tampered.verify(b"secret").is_err());
+    }
+
+    #[test]
+    fn test_query_string() {
+        assert_eq!(LeaderboardQuery::default().to_query_string(), "");
+        let query = LeaderboardQuery {
+            board_size: Some(4),
+            mode: Some("daily run".to_string()),
+            limit: Some(50),
+        };
+        assert_eq!(
+            query.to_query_string(),
+            "board_size=4&mode=daily%20run&limit=50"
+        );
+    }
+}
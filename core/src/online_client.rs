@@ -0,0 +1,71 @@
+//! Blocking HTTP client for online leaderboards (`online` feature).
+//! See [`crate::online`] for the protocol.
+
+use crate::online::{GlobalLeaderboard, LeaderboardQuery, ScoreSubmission, SubmitResponse};
+use crate::{GameError, GameResult};
+use std::time::Duration;
+
+/// How long a request may take before it is abandoned
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Client of one user-configured leaderboard endpoint
+#[derive(Debug, Clone)]
+pub struct OnlineLeaderboardClient {
+    /// Base URL, without a trailing slash
+    endpoint: String,
+    /// Key submissions are signed with
+    key: Vec<u8>,
+    agent: ureq::Agent,
+}
+
+impl OnlineLeaderboardClient {
+    /// Create a client for `endpoint` (e.g. `https://example.com/api/2048`)
+    /// signing submissions with `key`
+    pub fn new(endpoint: &str, key: impl Into<Vec<u8>>) -> Self {
+        Self {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            key: key.into(),
+            agent: ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build(),
+        }
+    }
+
+    /// The endpoint's base URL
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// Sign and submit a score
+    pub fn submit(&self, submission: &ScoreSubmission) -> GameResult<SubmitResponse> {
+        let signed = submission.sign(&self.key)?;
+        self.agent
+            .post(&format!("{}/scores", self.endpoint))
+            .send_json(&signed)
+            .map_err(request_error)?
+            .into_json()
+            .map_err(response_error)
+    }
+
+    /// Fetch the global leaderboard
+    pub fn fetch(&self, query: &LeaderboardQuery) -> GameResult<GlobalLeaderboard> {
+        let mut url = format!("{}/leaderboard", self.endpoint);
+        let query = query.to_query_string();
+        if !query.is_empty() {
+            url = format!("{}?{}", url, query);
+        }
+
+        self.agent
+            .get(&url)
+            .call()
+            .map_err(request_error)?
+            .into_json()
+            .map_err(response_error)
+    }
+}
+
+fn request_error(error: ureq::Error) -> GameError {
+    GameError::InvalidOperation(format!("Leaderboard request failed: {}", error))
+}
+
+fn response_error(error: std::io::Error) -> GameError {
+    GameError::Serialization(format!("Invalid leaderboard response: {}", error))
+}
@@ -0,0 +1,132 @@
+//! Short text codes for board positions.
+//!
+//! A code is URL-safe base64 of the board size followed by run-length
+//! encoded tile exponents, row by row. Each run is one byte holding the
+//! exponent (0 for empty cells); runs longer than one cell set the high bit
+//! and are followed by a byte with the run length. A typical 4x4 position
+//! fits in about 16 characters.
+
+use crate::board::Tile;
+use crate::{Board, GameError, GameResult};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+
+/// Flag on an exponent byte that is followed by a run length
+const RUN_FLAG: u8 = 0x80;
+
+impl Board {
+    /// Encode the position as a short code for sharing in chat or URLs.
+    /// Fails for tiles that are not powers of two from 2 up.
+    pub fn encode(&self) -> GameResult<String> {
+        let size = u8::try_from(self.size())
+            .map_err(|_| GameError::InvalidBoardSize { size: self.size() })?;
+        let mut bytes = vec![size];
+
+        let mut exponents = self
+            .iter_tiles()
+            .map(|(row, col, tile)| match tile.value {
+                0 => Ok(0),
+                value if value >= 2 && value.is_power_of_two() => Ok(value.trailing_zeros() as u8),
+                _ => Err(GameError::Serialization(format!(
+                    "Tile {} at ({}, {}) cannot be encoded",
+                    tile.value, row, col
+                ))),
+            })
+            .peekable();
+
+        while let Some(exponent) = exponents.next() {
+            let exponent = exponent?;
+            let mut run = 1u8;
+            while run < u8::MAX
+                && exponents
+                    .peek()
+                    .is_some_and(|next| next.as_ref().ok() == Some(&exponent))
+            {
+                exponents.next();
+                run += 1;
+            }
+            if run == 1 {
+                bytes.push(exponent);
+            } else {
+                bytes.extend([exponent | RUN_FLAG, run]);
+            }
+        }
+
+        Ok(URL_SAFE_NO_PAD.encode(bytes))
+    }
+
+    /// Decode a position code written by [`Board::encode`]
+    pub fn decode(code: &str) -> GameResult<Board> {
+        let invalid = || GameError::Serialization("Invalid position code".to_string());
+        let bytes = URL_SAFE_NO_PAD.decode(code.trim()).map_err(|_| invalid())?;
+        let (&size, runs) = bytes.split_first().ok_or_else(invalid)?;
+        let size = size as usize;
+
+        let mut tiles = Vec::with_capacity(size * size);
+        let mut runs = runs.iter();
+        while let Some(&byte) = runs.next() {
+            let exponent = byte & !RUN_FLAG;
+            if exponent >= 64 {
+                return Err(invalid());
+            }
+            let run = if byte & RUN_FLAG != 0 {
+                *runs.next().ok_or_else(invalid)?
+            } else {
+                1
+            };
+            let value = if exponent == 0 { 0 } else { 1 << exponent };
+            tiles.extend(std::iter::repeat_n(Tile::new(value), run as usize));
+        }
+
+        if size == 0 || tiles.len() != size * size {
+            return Err(invalid());
+        }
+        Board::from_tiles(tiles.chunks(size).map(<[Tile]>::to_vec).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let board = Board::from_vec(vec![
+            vec![2, 4, 8, 16],
+            vec![0, 0, 0, 0],
+            vec![0, 0, 2048, 0],
+            vec![1 << 40, 0, 0, 2],
+        ])
+        .unwrap();
+
+        let code = board.encode().unwrap();
+        assert!(code.len() <= 20, "code {} is too long", code);
+        assert_eq!(Board::decode(&code).unwrap().to_vec(), board.to_vec());
+
+        let empty = Board::new(8).unwrap();
+        assert_eq!(
+            Board::decode(&empty.encode().unwrap()).unwrap().to_vec(),
+            empty.to_vec()
+        );
+    }
+
+    #[test]
+    fn test_invalid_codes() {
+        assert!(Board::decode("").is_err());
+        assert!(Board::decode("not a code!").is_err());
+        // A 4x4 header with a single cell
+        assert!(Board::decode(&URL_SAFE_NO_PAD.encode([4, 1])).is_err());
+        assert!(Board::from_vec(vec![vec![3]]).unwrap().encode().is_err());
+    }
+
+    #[test]
+    fn test_tile_of_one_is_rejected() {
+        // Exponent 0 means an empty cell, so a 1 would not survive a round trip
+        let board = Board::from_vec(vec![vec![1, 2], vec![0, 4]]).unwrap();
+        assert!(board.encode().is_err());
+
+        let board = Board::from_vec(vec![vec![2, 2], vec![0, 4]]).unwrap();
+        let code = board.encode().unwrap();
+        assert_eq!(Board::decode(&code).unwrap().to_vec(), board.to_vec());
+    }
+}
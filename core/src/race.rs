@@ -0,0 +1,296 @@
+use crate::board::Tile;
+use crate::{Direction, Game, GameConfig, GameError, GameResult, GameRng};
+use serde::{Deserialize, Serialize};
+
+/// Score gained in a single move that sends one garbage tile to the opponent
+pub const DEFAULT_GARBAGE_THRESHOLD: u64 = 64;
+
+/// Messages exchanged between race clients (usually through a relay server)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RaceMessage {
+    /// Ask to join a race room
+    Join { room: String, player: String },
+    /// Race starts; every player uses the same seed and configuration
+    Start { seed: u64, config: GameConfig },
+    /// Progress update after a move
+    Progress {
+        player: String,
+        score: u64,
+        max_tile: u64,
+        moves: u32,
+        board: Vec<Vec<u64>>,
+    },
+    /// Garbage tiles sent to the opponents
+    Garbage { player: String, count: u32 },
+    /// A player's game ended
+    Finished {
+        player: String,
+        score: u64,
+        won: bool,
+    },
+}
+
+/// Last known state of the opponent
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OpponentState {
+    pub name: String,
+    pub score: u64,
+    pub max_tile: u64,
+    pub moves: u32,
+    pub board: Vec<Vec<u64>>,
+    pub finished: bool,
+}
+
+/// One player's side of a head-to-head race.
+///
+/// The session is transport-agnostic: feed incoming messages to
+/// [`RaceSession::handle_message`] and send whatever
+/// [`RaceSession::drain_outbox`] returns.
+pub struct RaceSession {
+    /// Local player name
+    player: String,
+    /// Local game (created when the race starts)
+    game: Option<Game>,
+    /// RNG used to place incoming garbage tiles
+    garbage_rng: GameRng,
+    /// Last known opponent state
+    opponent: Option<OpponentState>,
+    /// Garbage tiles waiting to be dropped on the local board
+    pending_garbage: u32,
+    /// Score gained in one move that sends one garbage tile
+    garbage_threshold: u64,
+    /// Whether the local game has ended
+    finished: bool,
+    /// Messages waiting to be sent
+    outbox: Vec<RaceMessage>,
+}
+
+impl RaceSession {
+    /// Create a new race session for a player
+    pub fn new(player: &str) -> Self {
+        Self {
+            player: player.to_string(),
+            game: None,
+            garbage_rng: GameRng::new(None),
+            opponent: None,
+            pending_garbage: 0,
+            garbage_threshold: DEFAULT_GARBAGE_THRESHOLD,
+            finished: false,
+            outbox: Vec::new(),
+        }
+    }
+
+    /// Set the score gained in one move that sends one garbage tile
+    pub fn with_garbage_threshold(mut self, threshold: u64) -> Self {
+        self.garbage_threshold = threshold.max(1);
+        self
+    }
+
+    /// Queue a request to join a race room
+    pub fn join(&mut self, room: &str) {
+        self.outbox.push(RaceMessage::Join {
+            room: room.to_string(),
+            player: self.player.clone(),
+        });
+    }
+
+    /// Apply a message received from the network
+    pub fn handle_message(&mut self, message: RaceMessage) -> GameResult<()> {
+        match message {
+            RaceMessage::Start { seed, config } => {
+                let config = GameConfig {
+                    seed: Some(seed),
+                    ..config
+                };
+                self.game = Some(Game::new(config)?);
+                self.garbage_rng = GameRng::new(Some(seed.rotate_left(32)));
+                self.pending_garbage = 0;
+                self.finished = false;
+                self.publish_progress();
+            }
+            RaceMessage::Progress {
+                player,
+                score,
+                max_tile,
+                moves,
+                board,
+            } if player != self.player => {
+                let opponent = self.opponent.get_or_insert_with(OpponentState::default);
+                opponent.name = player;
+                opponent.score = score;
+                opponent.max_tile = max_tile;
+                opponent.moves = moves;
+                opponent.board = board;
+            }
+            RaceMessage::Garbage { player, count } if player != self.player => {
+                self.pending_garbage += count;
+            }
+            RaceMessage::Finished { player, score, .. } if player != self.player => {
+                let opponent = self.opponent.get_or_insert_with(OpponentState::default);
+                opponent.name = player;
+                opponent.score = score;
+                opponent.finished = true;
+            }
+            // Join requests are handled by the server, and our own messages may be echoed back
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Make a move on the local board, dropping pending garbage first
+    pub fn make_move(&mut self, direction: Direction) -> GameResult<bool> {
+        if self.finished {
+            return Err(GameError::GameOver);
+        }
+        let game = self
+            .game
+            .as_mut()
+            .ok_or_else(|| GameError::InvalidOperation("Race has not started".to_string()))?;
+
+        // Drop incoming garbage as 2-tiles on random empty cells
+        while self.pending_garbage > 0 {
+            let empty = game.board().empty_positions();
+            if empty.is_empty() {
+                break;
+            }
+            let (row, col) = empty[self.garbage_rng.gen_range(empty.len())];
+            game.board_mut().set_tile(row, col, Tile::new(2))?;
+            self.pending_garbage -= 1;
+        }
+        // Garbage that does not fit on the board is discarded
+        self.pending_garbage = 0;
+
+        let result = game.make_move(direction)?;
+        let (moved, gained) = (result.moved, result.points);
+
+        if gained >= self.garbage_threshold {
+            self.outbox.push(RaceMessage::Garbage {
+                player: self.player.clone(),
+                count: u32::try_from(gained / self.garbage_threshold).unwrap_or(u32::MAX),
+            });
+        }
+
+        let game = self.game.as_ref().expect("game exists while racing");
+        if !game.board().has_valid_moves() || game.state() != crate::GameState::Playing {
+            self.finished = true;
+            self.outbox.push(RaceMessage::Finished {
+                player: self.player.clone(),
+                score: game.score().current(),
+                won: game.state() == crate::GameState::Won,
+            });
+        }
+
+        if moved {
+            self.publish_progress();
+        }
+
+        Ok(moved)
+    }
+
+    /// Take all messages waiting to be sent
+    pub fn drain_outbox(&mut self) -> Vec<RaceMessage> {
+        std::mem::take(&mut self.outbox)
+    }
+
+    /// Get the local game (if the race has started)
+    pub fn game(&self) -> Option<&Game> {
+        self.game.as_ref()
+    }
+
+    /// Get the last known opponent state
+    pub fn opponent(&self) -> Option<&OpponentState> {
+        self.opponent.as_ref()
+    }
+
+    /// Get the local player name
+    pub fn player(&self) -> &str {
+        &self.player
+    }
+
+    /// Check whether the race has started
+    pub fn is_started(&self) -> bool {
+        self.game.is_some()
+    }
+
+    /// Check whether the local game has ended
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Number of garbage tiles waiting to drop on the local board
+    pub fn pending_garbage(&self) -> u32 {
+        self.pending_garbage
+    }
+
+    fn publish_progress(&mut self) {
+        if let Some(game) = &self.game {
+            self.outbox.push(RaceMessage::Progress {
+                player: self.player.clone(),
+                score: game.score().current(),
+                max_tile: game.board().max_tile(),
+                moves: game.moves(),
+                board: game.board().to_vec(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn start(session: &mut RaceSession) {
+        session
+            .handle_message(RaceMessage::Start {
+                seed: 42,
+                config: GameConfig::default(),
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn both_players_start_from_the_same_board() {
+        let mut alice = RaceSession::new("alice");
+        let mut bob = RaceSession::new("bob");
+        start(&mut alice);
+        start(&mut bob);
+
+        assert_eq!(
+            alice.game().unwrap().board().to_vec(),
+            bob.game().unwrap().board().to_vec()
+        );
+    }
+
+    #[test]
+    fn progress_and_garbage_reach_the_opponent() {
+        let mut alice = RaceSession::new("alice").with_garbage_threshold(1);
+        let mut bob = RaceSession::new("bob");
+        start(&mut alice);
+        start(&mut bob);
+        alice.drain_outbox();
+
+        for direction in [
+            Direction::Left,
+            Direction::Up,
+            Direction::Right,
+            Direction::Down,
+        ]
+        .iter()
+        .cycle()
+        .take(40)
+        {
+            let _ = alice.make_move(*direction);
+        }
+
+        for message in alice.drain_outbox() {
+            bob.handle_message(message).unwrap();
+        }
+
+        let opponent = bob.opponent().expect("bob should know about alice");
+        assert_eq!(opponent.name, "alice");
+        assert_eq!(opponent.score, alice.game().unwrap().score().current());
+        assert!(bob.pending_garbage() > 0);
+    }
+}
@@ -1,5 +1,31 @@
-use crate::{Direction, Game, GameConfig, GameError, GameResult, Score};
+use crate::replay_stream::ReplayStreamWriter;
+use crate::{
+    DailyChallenge, Direction, Game, GameConfig, GameError, GameResult, GameRng, Score, SpawnedTile,
+};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Magic bytes that start a binary replay
+pub const REPLAY_MAGIC: &[u8; 6] = b"R2048\0";
+/// Version of the binary replay format written by this build
+pub const REPLAY_FORMAT_VERSION: u8 = 1;
+/// Version of the replay data written by this build, stored in
+/// `ReplayData::format_version`.
+///
+/// Format 1 is every replay written before the version was recorded; its
+/// fields are a subset of format 2. Bump the version and add a step to
+/// `migrate_replay` whenever a change would alter what older replays mean.
+///
+/// Tile values and scores widened from `u32` to `u64` within format 2: both
+/// JSON and MessagePack store integers by value, not width, so every replay
+/// written with `u32` fields reads unchanged and means the same.
+pub const REPLAY_SCHEMA_VERSION: u32 = 2;
+/// File extension of binary replays
+pub const REPLAY_EXTENSION: &str = "r2048";
 
 /// A single move in the replay
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -7,32 +33,61 @@ pub struct ReplayMove {
     /// Direction of the move
     pub direction: Direction,
     /// Board state before the move
-    pub board_before: Vec<Vec<u32>>,
+    pub board_before: Vec<Vec<u64>>,
     /// Board state after the move
-    pub board_after: Vec<Vec<u32>>,
+    pub board_after: Vec<Vec<u64>>,
     /// Score before the move
-    pub score_before: u32,
+    pub score_before: u64,
     /// Score after the move
-    pub score_after: u32,
+    pub score_after: u64,
     /// Move number
     pub move_number: u32,
     /// Timestamp of the move
     pub timestamp: u64,
+    /// Time of the move in milliseconds since the Unix epoch; missing in
+    /// older replays
+    #[serde(default)]
+    pub timestamp_ms: Option<u64>,
+    /// `Board::zobrist_hash` of `board_after`; missing in older replays
+    #[serde(default)]
+    pub board_hash: Option<u64>,
+    /// Generator state after the move, so a game continued from this point
+    /// spawns the same tiles as the original; missing in older replays
+    #[serde(default)]
+    pub rng: Option<GameRng>,
+    /// Tiles added after the move, which playback places instead of
+    /// drawing new ones; empty in older replays
+    #[serde(default)]
+    pub spawned: Vec<SpawnedTile>,
+}
+
+impl ReplayMove {
+    /// Time of the move in milliseconds, falling back to the second
+    /// timestamp for older replays
+    pub fn time_ms(&self) -> u64 {
+        self.timestamp_ms.unwrap_or(self.timestamp * 1000)
+    }
 }
 
 /// Complete replay data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReplayData {
+    /// Version of the replay data (`REPLAY_SCHEMA_VERSION` once loaded)
+    #[serde(default = "unversioned")]
+    pub format_version: u32,
     /// Game configuration
     pub config: GameConfig,
     /// Initial board state
-    pub initial_board: Vec<Vec<u32>>,
+    pub initial_board: Vec<Vec<u64>>,
+    /// Generator state at the initial board; missing in older replays
+    #[serde(default)]
+    pub initial_rng: Option<GameRng>,
     /// All moves in the replay
     pub moves: Vec<ReplayMove>,
     /// Final game state
     pub final_state: crate::GameState,
     /// Final score
-    pub final_score: u32,
+    pub final_score: u64,
     /// Total moves
     pub total_moves: u32,
     /// Game duration
@@ -41,6 +96,125 @@ pub struct ReplayData {
     pub metadata: ReplayMetadata,
 }
 
+impl ReplayData {
+    /// Encode the replay in the compact binary format: `REPLAY_MAGIC`, the
+    /// format version byte, then zstd-compressed MessagePack. Fields are
+    /// stored by name, so replays stay readable as fields are added.
+    pub fn to_bytes(&self) -> GameResult<Vec<u8>> {
+        let packed = rmp_serde::to_vec_named(self)
+            .map_err(|e| GameError::Serialization(format!("Failed to encode replay: {}", e)))?;
+        let compressed = ruzstd::encoding::compress_to_vec(
+            packed.as_slice(),
+            ruzstd::encoding::CompressionLevel::Fastest,
+        );
+
+        let mut bytes = Vec::with_capacity(REPLAY_MAGIC.len() + 1 + compressed.len());
+        bytes.extend_from_slice(REPLAY_MAGIC);
+        bytes.push(REPLAY_FORMAT_VERSION);
+        bytes.extend_from_slice(&compressed);
+        Ok(bytes)
+    }
+
+    /// Decode a replay written by `to_bytes`, upgrading older versions
+    pub fn from_bytes(bytes: &[u8]) -> GameResult<Self> {
+        unpack(bytes).map(Self::upgraded)
+    }
+
+    /// Decode a replay in either format, telling binary replays from JSON
+    /// by their magic bytes. Older versions are upgraded; replays written
+    /// by a newer version fail with `GameError::UnsupportedVersion`.
+    pub fn decode(bytes: &[u8]) -> GameResult<Self> {
+        decode(bytes).map(Self::upgraded)
+    }
+
+    /// Bring a replay of an older version up to `REPLAY_SCHEMA_VERSION`
+    fn upgraded(mut self) -> Self {
+        for from in self.format_version..REPLAY_SCHEMA_VERSION {
+            migrate_replay(&mut self, from);
+        }
+        self.format_version = REPLAY_SCHEMA_VERSION;
+        self
+    }
+
+    /// Encode the replay in the format of a file name's extension: JSON for
+    /// `.json`, binary otherwise
+    fn encode_for(&self, path: &Path) -> GameResult<Vec<u8>> {
+        if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::to_vec_pretty(self)
+                .map_err(|e| GameError::Serialization(format!("Failed to encode replay: {}", e)))
+        } else {
+            self.to_bytes()
+        }
+    }
+}
+
+/// Upgrade a replay from format `from` to `from + 1`. Format 2 only added
+/// the version itself, so no step changes anything yet.
+fn migrate_replay(_replay: &mut ReplayData, _from: u32) {}
+
+fn unversioned() -> u32 {
+    1
+}
+
+/// Just the version of a replay, read before the rest so a replay from a
+/// newer version fails with a clear error rather than a parse error
+#[derive(Deserialize)]
+struct VersionProbe {
+    #[serde(default = "unversioned")]
+    format_version: u32,
+}
+
+fn check_version<E>(probe: Result<VersionProbe, E>) -> GameResult<()> {
+    match probe {
+        Ok(probe) if probe.format_version > REPLAY_SCHEMA_VERSION => {
+            Err(GameError::UnsupportedVersion {
+                found: probe.format_version,
+                supported: REPLAY_SCHEMA_VERSION,
+            })
+        }
+        // A replay that fails to parse gets its error from the full parse
+        _ => Ok(()),
+    }
+}
+
+/// Decode a binary replay, or the parts of it `T` holds
+fn unpack<T: DeserializeOwned>(bytes: &[u8]) -> GameResult<T> {
+    let body = bytes
+        .strip_prefix(REPLAY_MAGIC.as_slice())
+        .ok_or_else(|| GameError::Serialization("Not a binary replay".to_string()))?;
+    let (&version, compressed) = body
+        .split_first()
+        .ok_or_else(|| GameError::Serialization("Truncated replay".to_string()))?;
+    if version > REPLAY_FORMAT_VERSION {
+        return Err(GameError::UnsupportedVersion {
+            found: version.into(),
+            supported: REPLAY_FORMAT_VERSION.into(),
+        });
+    }
+
+    let invalid = |e: &dyn std::fmt::Display| {
+        GameError::Serialization(format!("Failed to decode replay: {}", e))
+    };
+    let mut packed = Vec::new();
+    ruzstd::decoding::StreamingDecoder::new(compressed)
+        .map_err(|e| invalid(&e))?
+        .read_to_end(&mut packed)
+        .map_err(|e| invalid(&e))?;
+    check_version(rmp_serde::from_slice(&packed))?;
+    rmp_serde::from_slice(&packed).map_err(|e| invalid(&e))
+}
+
+/// Decode a replay in either format, or the parts of it `T` holds
+fn decode<T: DeserializeOwned>(bytes: &[u8]) -> GameResult<T> {
+    if bytes.starts_with(REPLAY_MAGIC) {
+        unpack(bytes)
+    } else {
+        check_version(serde_json::from_slice(bytes))?;
+        serde_json::from_slice(bytes)
+            .map_err(|e| GameError::Serialization(format!("Failed to parse replay: {}", e)))
+    }
+}
+
 /// Replay metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReplayMetadata {
@@ -54,6 +228,13 @@ pub struct ReplayMetadata {
     pub version: String,
     /// Additional notes
     pub notes: Option<String>,
+    /// Date of the daily challenge this game was played as, `YYYY-MM-DD`
+    #[serde(default)]
+    pub daily: Option<String>,
+    /// Whether the replay was saved automatically; only these are removed
+    /// by `ReplayManager::apply_retention`
+    #[serde(default)]
+    pub auto_recorded: bool,
 }
 
 impl Default for ReplayMetadata {
@@ -64,6 +245,8 @@ impl Default for ReplayMetadata {
             player_name: None,
             version: env!("CARGO_PKG_VERSION").to_string(),
             notes: None,
+            daily: None,
+            auto_recorded: false,
         }
     }
 }
@@ -77,6 +260,8 @@ impl ReplayMetadata {
             player_name: None,
             version: env!("CARGO_PKG_VERSION").to_string(),
             notes: None,
+            daily: None,
+            auto_recorded: false,
         }
     }
 
@@ -91,6 +276,12 @@ impl ReplayMetadata {
         self.notes = Some(notes);
         self
     }
+
+    /// Tag the replay as a run of a daily challenge
+    pub fn with_daily(mut self, challenge: &DailyChallenge) -> Self {
+        self.daily = Some(challenge.date.clone());
+        self
+    }
 }
 
 /// Replay recorder for capturing game moves
@@ -101,17 +292,24 @@ pub struct ReplayRecorder {
     replay_data: ReplayData,
     /// Whether recording is active
     recording: bool,
+    /// File the replay is streamed to as it is recorded, if any
+    stream: Option<ReplayStreamWriter>,
 }
 
 impl ReplayRecorder {
     /// Create a new replay recorder
     pub fn new(config: GameConfig) -> GameResult<Self> {
-        let game = Game::new(config.clone())?;
-        let initial_board = game.board().to_vec();
+        Ok(Self::from_game(Game::new(config)?))
+    }
 
+    /// Record a game from its current position, e.g. one taken over from a
+    /// replay with `ReplayPlayer::take_over`
+    pub fn from_game(game: Game) -> Self {
         let replay_data = ReplayData {
-            config,
-            initial_board,
+            format_version: REPLAY_SCHEMA_VERSION,
+            config: game.config().clone(),
+            initial_board: game.board().to_vec(),
+            initial_rng: Some(game.rng().clone()),
             moves: Vec::new(),
             final_state: game.state(),
             final_score: game.score().current(),
@@ -120,14 +318,24 @@ impl ReplayRecorder {
             metadata: ReplayMetadata::default(),
         };
 
-        Ok(Self {
+        Self {
             game,
             replay_data,
             recording: true,
-        })
+            stream: None,
+        }
     }
 
-    /// Make a move and record it
+    /// Also write the replay to a file as it is recorded, so it can be
+    /// recovered with `recover_replay` if the program stops unexpectedly.
+    /// Moves recorded so far are written right away.
+    pub fn stream_to(&mut self, path: impl Into<PathBuf>) -> GameResult<()> {
+        self.stream = Some(ReplayStreamWriter::create(path, &self.replay_data)?);
+        Ok(())
+    }
+
+    /// Make a move and record it. The move is made even if streaming it
+    /// to disk fails; the error is still returned.
     pub fn make_move(&mut self, direction: Direction) -> GameResult<bool> {
         if !self.recording {
             return Err(GameError::InvalidOperation("Recording stopped".to_string()));
@@ -137,12 +345,12 @@ impl ReplayRecorder {
         let board_before = self.game.board().to_vec();
         let score_before = self.game.score().current();
         let move_number = self.game.moves();
-        let timestamp = crate::game::Game::get_current_time();
+        let timestamp_ms = crate::get_current_time_ms();
 
         // Make the move
-        let moved = self.game.make_move(direction)?;
+        let result = self.game.make_move(direction)?;
 
-        if moved {
+        if result.moved {
             // Record the move
             let move_record = ReplayMove {
                 direction,
@@ -151,16 +359,23 @@ impl ReplayRecorder {
                 score_before,
                 score_after: self.game.score().current(),
                 move_number,
-                timestamp,
+                timestamp: timestamp_ms / 1000,
+                timestamp_ms: Some(timestamp_ms),
+                board_hash: Some(self.game.board().zobrist_hash()),
+                rng: Some(self.game.rng().clone()),
+                spawned: result.spawned,
             };
 
+            if let Some(stream) = &mut self.stream {
+                stream.append_move(&move_record)?;
+            }
             self.replay_data.moves.push(move_record);
-            self.replay_data.total_moves = self.game.moves();
+            self.replay_data.total_moves = self.replay_data.moves.len() as u32;
             self.replay_data.final_state = self.game.state();
             self.replay_data.final_score = self.game.score().current();
         }
 
-        Ok(moved)
+        Ok(result.moved)
     }
 
     /// Stop recording and finalize replay
@@ -168,6 +383,11 @@ impl ReplayRecorder {
         self.recording = false;
         self.replay_data.duration =
             crate::game::Game::get_current_time() - self.replay_data.metadata.created_at;
+        if let Some(stream) = self.stream.take() {
+            // The replay is complete in memory; a stream left without its
+            // end can still be recovered
+            let _ = stream.finish(&self.replay_data);
+        }
         self.replay_data.clone()
     }
 
@@ -183,10 +403,29 @@ impl ReplayRecorder {
 
     /// Set replay metadata
     pub fn set_metadata(&mut self, metadata: ReplayMetadata) {
+        if let Some(stream) = &mut self.stream {
+            let _ = stream.update_metadata(&metadata);
+        }
         self.replay_data.metadata = metadata;
     }
 }
 
+/// Longest pause real-time playback waits between two moves, in
+/// milliseconds, so a game left idle does not stall its replay
+pub const MAX_REAL_TIME_GAP_MS: u64 = 5_000;
+
+/// How long playback waits between moves
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlaybackTiming {
+    /// The same interval before every move
+    #[default]
+    Fixed,
+    /// The time the player took in the original game, up to
+    /// `MAX_REAL_TIME_GAP_MS`
+    RealTime,
+}
+
 /// Replay player for playing back recorded games
 pub struct ReplayPlayer {
     /// Replay data to play
@@ -199,26 +438,24 @@ pub struct ReplayPlayer {
     playing: bool,
     /// Playback speed (1.0 = normal, 2.0 = 2x speed, etc.)
     speed: f32,
+    /// How long to wait between moves
+    timing: PlaybackTiming,
 }
 
 impl ReplayPlayer {
     /// Create a new replay player
     pub fn new(replay_data: ReplayData) -> GameResult<Self> {
-        let mut current_game = Game::new(replay_data.config.clone())?;
-        current_game.load_from_state(
-            flatten_board(&replay_data.initial_board),
-            Score::new(),
-            0,
-            crate::GameState::Playing,
-        )?;
-
-        Ok(Self {
+        let current_game = Game::new(replay_data.config.clone())?;
+        let mut player = Self {
             replay_data,
             current_move: 0,
             current_game,
             playing: false,
             speed: 1.0,
-        })
+            timing: PlaybackTiming::default(),
+        };
+        player.reset_game()?;
+        Ok(player)
     }
 
     /// Start playing the replay
@@ -247,7 +484,12 @@ impl ReplayPlayer {
         let replay_move = self.replay_data.moves[self.current_move].clone();
 
         self.current_move += 1;
-        self.restore_position(self.current_move, &replay_move)?;
+        if replay_move.spawned.is_empty() {
+            // Older replays only have snapshots of the board
+            self.restore_position(self.current_move, &replay_move)?;
+        } else {
+            self.apply_move(self.current_move, &replay_move)?;
+        }
 
         Ok(true)
     }
@@ -278,14 +520,39 @@ impl ReplayPlayer {
         Ok(true)
     }
 
+    /// Start a live game from the current position, to try another line.
+    /// The generator state is carried forward, so the same moves spawn the
+    /// same tiles as in the original game.
+    pub fn take_over(&self) -> GameResult<Game> {
+        let mut game = Game::new(self.replay_data.config.clone())?;
+        game.load_from_state(
+            flatten_board(&self.current_game.board().to_vec()),
+            self.current_game.score().clone(),
+            self.current_game.moves(),
+            self.current_game.state(),
+        )?;
+        game.restore_rng(self.current_game.rng().clone());
+        Ok(game)
+    }
+
     /// Reset game to initial state
     fn reset_game(&mut self) -> GameResult<()> {
+        // Replays of a game taken over mid-way start with points and moves
+        let (score, moves) = self
+            .replay_data
+            .moves
+            .first()
+            .map_or((0, 0), |first| (first.score_before, first.move_number));
         self.current_game.load_from_state(
             flatten_board(&self.replay_data.initial_board),
-            Score::new(),
-            0,
+            Score::from_parts(score, score, 0),
+            moves,
             crate::GameState::Playing,
-        )
+        )?;
+        if let Some(rng) = &self.replay_data.initial_rng {
+            self.current_game.restore_rng(rng.clone());
+        }
+        Ok(())
     }
 
     /// Reset game to specific move
@@ -341,6 +608,37 @@ impl ReplayPlayer {
         self.speed
     }
 
+    /// Set how long playback waits between moves
+    pub fn set_timing(&mut self, timing: PlaybackTiming) {
+        self.timing = timing;
+    }
+
+    /// Get how long playback waits between moves
+    pub fn timing(&self) -> PlaybackTiming {
+        self.timing
+    }
+
+    /// How long to wait before playing the next move, scaled by the speed:
+    /// `interval` with fixed timing, or the time between the moves in the
+    /// original game with real-time timing. `None` once the replay is
+    /// finished.
+    pub fn next_move_delay(&self, interval: Duration) -> Option<Duration> {
+        let next = self.replay_data.moves.get(self.current_move)?;
+        let delay = match self.timing {
+            PlaybackTiming::Fixed => interval,
+            PlaybackTiming::RealTime => {
+                let previous = match self.current_move.checked_sub(1) {
+                    Some(index) => self.replay_data.moves[index].time_ms(),
+                    None => self.replay_data.metadata.created_at * 1000,
+                };
+                let gap = next.time_ms().saturating_sub(previous);
+                Duration::from_millis(gap.min(MAX_REAL_TIME_GAP_MS))
+            }
+        };
+        let ms = delay.as_millis() as f64 / self.speed as f64;
+        Some(Duration::from_millis(ms.round() as u64))
+    }
+
     /// Get progress percentage
     pub fn progress(&self) -> f32 {
         if self.replay_data.moves.is_empty() {
@@ -350,6 +648,32 @@ impl ReplayPlayer {
         }
     }
 
+    /// Play a recorded move on the current position, placing the recorded
+    /// tiles, and check the result against the recorded board
+    fn apply_move(&mut self, move_index: usize, replay_move: &ReplayMove) -> GameResult<()> {
+        if self.current_game.state() == crate::GameState::Won {
+            self.current_game.continue_after_win()?;
+        }
+        self.current_game
+            .make_move_with_spawns(replay_move.direction, &replay_move.spawned)
+            .map_err(|e| {
+                GameError::Serialization(format!(
+                    "Replay move {} cannot be played: {}",
+                    move_index, e
+                ))
+            })?;
+        if self.current_game.board().to_vec() != replay_move.board_after {
+            return Err(GameError::Serialization(format!(
+                "Replay move {} does not match its recorded board",
+                move_index
+            )));
+        }
+        if let Some(rng) = &replay_move.rng {
+            self.current_game.restore_rng(rng.clone());
+        }
+        Ok(())
+    }
+
     fn restore_position(&mut self, move_index: usize, replay_move: &ReplayMove) -> GameResult<()> {
         let state = if move_index == self.replay_data.moves.len() {
             self.replay_data.final_state.clone()
@@ -368,20 +692,93 @@ impl ReplayPlayer {
         self.current_game.load_from_state(
             flatten_board(&replay_move.board_after),
             score,
-            move_index as u32,
+            replay_move.move_number + 1,
             state,
-        )
+        )?;
+        if let Some(rng) = &replay_move.rng {
+            self.current_game.restore_rng(rng.clone());
+        }
+
+        if replay_move
+            .board_hash
+            .is_some_and(|hash| hash != self.current_game.board().zobrist_hash())
+        {
+            return Err(GameError::Serialization(format!(
+                "Replay move {} does not match its checksum",
+                move_index
+            )));
+        }
+        Ok(())
     }
 }
 
-fn flatten_board(board: &[Vec<u32>]) -> Vec<u32> {
+fn flatten_board(board: &[Vec<u64>]) -> Vec<u64> {
     board.iter().flat_map(|row| row.iter().copied()).collect()
 }
 
-/// Replay manager for handling multiple replays
+/// A replay file found in a replay directory, described without loading
+/// its moves
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayFile {
+    pub path: PathBuf,
+    pub metadata: ReplayMetadata,
+    pub board_size: usize,
+    pub final_score: u64,
+    pub total_moves: u32,
+    /// Game duration in seconds
+    pub duration: u64,
+}
+
+/// The parts of a replay file listed by `ReplayManager::scan`
+#[derive(Deserialize)]
+struct ReplayHeader {
+    config: GameConfig,
+    final_score: u64,
+    total_moves: u32,
+    duration: u64,
+    metadata: ReplayMetadata,
+}
+
+/// Order of the files of a replay directory
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplaySort {
+    /// Most recently recorded first
+    #[default]
+    Newest,
+    /// Highest final score first
+    Score,
+}
+
+/// Which automatically saved replays a replay directory keeps: the best
+/// games by score plus the most recent ones
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayRetention {
+    /// Number of highest-scoring replays to keep
+    pub keep_best: usize,
+    /// Number of most recent replays to keep
+    pub keep_recent: usize,
+}
+
+impl Default for ReplayRetention {
+    fn default() -> Self {
+        Self {
+            keep_best: 10,
+            keep_recent: 20,
+        }
+    }
+}
+
+/// Replay manager for handling multiple replays, in memory or as the files
+/// of a replay directory
 pub struct ReplayManager {
     /// List of saved replays
     replays: Vec<ReplayData>,
+    /// Directory replay files are kept in, if any
+    dir: Option<PathBuf>,
+    /// Files found in `dir`, in `sort` order
+    files: Vec<ReplayFile>,
+    sort: ReplaySort,
 }
 
 impl ReplayManager {
@@ -389,7 +786,189 @@ impl ReplayManager {
     pub fn new() -> Self {
         Self {
             replays: Vec::new(),
+            dir: None,
+            files: Vec::new(),
+            sort: ReplaySort::default(),
+        }
+    }
+
+    /// Manage the replay files of a directory, creating it if needed
+    pub fn in_dir(dir: impl Into<PathBuf>) -> GameResult<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).map_err(|e| {
+            GameError::InvalidOperation(format!("Failed to create replay directory: {}", e))
+        })?;
+        let mut manager = Self {
+            dir: Some(dir),
+            ..Self::new()
+        };
+        manager.scan()?;
+        Ok(manager)
+    }
+
+    /// List the replay files (`.r2048` or `.json`) of the directory again.
+    /// Only their metadata is kept; files that are not replays are skipped.
+    pub fn scan(&mut self) -> GameResult<()> {
+        let dir = self.dir()?.to_path_buf();
+        let entries = fs::read_dir(&dir).map_err(|e| {
+            GameError::InvalidOperation(format!("Failed to read replay directory: {}", e))
+        })?;
+
+        self.files = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|ext| ext == REPLAY_EXTENSION || ext == "json")
+            })
+            .filter_map(|path| {
+                let header: ReplayHeader = decode(&fs::read(&path).ok()?).ok()?;
+                Some(ReplayFile {
+                    path,
+                    metadata: header.metadata,
+                    board_size: header.config.board_size,
+                    final_score: header.final_score,
+                    total_moves: header.total_moves,
+                    duration: header.duration,
+                })
+            })
+            .collect();
+        self.sort_files(self.sort);
+        Ok(())
+    }
+
+    /// Replay files of the directory, in the current sort order
+    pub fn files(&self) -> &[ReplayFile] {
+        &self.files
+    }
+
+    /// Current order of `files`
+    pub fn sort(&self) -> ReplaySort {
+        self.sort
+    }
+
+    /// Reorder `files`
+    pub fn sort_files(&mut self, sort: ReplaySort) {
+        self.sort = sort;
+        match sort {
+            ReplaySort::Newest => self.files.sort_by(|a, b| {
+                b.metadata
+                    .created_at
+                    .cmp(&a.metadata.created_at)
+                    .then_with(|| b.path.cmp(&a.path))
+            }),
+            ReplaySort::Score => self.files.sort_by(|a, b| {
+                b.final_score
+                    .cmp(&a.final_score)
+                    .then_with(|| b.metadata.created_at.cmp(&a.metadata.created_at))
+            }),
+        }
+    }
+
+    /// Load the replay of a file
+    pub fn load_file(&self, index: usize) -> GameResult<ReplayData> {
+        let file = self.file(index)?;
+        let bytes = fs::read(&file.path)
+            .map_err(|e| GameError::InvalidOperation(format!("Failed to read replay: {}", e)))?;
+        ReplayData::decode(&bytes)
+    }
+
+    /// Save a replay as a new `.r2048` file in the directory; returns its path
+    pub fn save_file(&mut self, replay: &ReplayData) -> GameResult<PathBuf> {
+        let dir = self.dir()?;
+        let created_at = replay.metadata.created_at;
+        let mut path = dir.join(format!("replay_{}.{}", created_at, REPLAY_EXTENSION));
+        let mut copy = 1;
+        while path.exists() {
+            path = dir.join(format!(
+                "replay_{}_{}.{}",
+                created_at, copy, REPLAY_EXTENSION
+            ));
+            copy += 1;
+        }
+
+        write_replay(&path, replay)?;
+        self.files.push(ReplayFile {
+            path: path.clone(),
+            metadata: replay.metadata.clone(),
+            board_size: replay.config.board_size,
+            final_score: replay.final_score,
+            total_moves: replay.total_moves,
+            duration: replay.duration,
+        });
+        self.sort_files(self.sort);
+        Ok(path)
+    }
+
+    /// Change the name of the replay in a file, keeping the file's format
+    pub fn rename_file(&mut self, index: usize, name: &str) -> GameResult<()> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(GameError::InvalidOperation(
+                "Replay name cannot be empty".to_string(),
+            ));
         }
+        let mut replay = self.load_file(index)?;
+        replay.metadata.name = name.to_string();
+        write_replay(&self.files[index].path, &replay)?;
+        self.files[index].metadata.name = replay.metadata.name;
+        Ok(())
+    }
+
+    /// Delete a replay file
+    pub fn delete_file(&mut self, index: usize) -> GameResult<ReplayFile> {
+        let file = self.file(index)?;
+        fs::remove_file(&file.path)
+            .map_err(|e| GameError::InvalidOperation(format!("Failed to delete replay: {}", e)))?;
+        Ok(self.files.remove(index))
+    }
+
+    /// Delete the automatically saved replays `retention` does not keep.
+    /// Replays saved by hand are never removed. Returns the number deleted.
+    pub fn apply_retention(&mut self, retention: ReplayRetention) -> GameResult<usize> {
+        let mut auto: Vec<&ReplayFile> = self
+            .files
+            .iter()
+            .filter(|file| file.metadata.auto_recorded)
+            .collect();
+        let mut keep: Vec<PathBuf> = Vec::new();
+        auto.sort_by_key(|file| std::cmp::Reverse(file.final_score));
+        keep.extend(
+            auto.iter()
+                .take(retention.keep_best)
+                .map(|f| f.path.clone()),
+        );
+        auto.sort_by_key(|file| std::cmp::Reverse(file.metadata.created_at));
+        keep.extend(
+            auto.iter()
+                .take(retention.keep_recent)
+                .map(|f| f.path.clone()),
+        );
+
+        let mut deleted = 0;
+        let mut index = 0;
+        while index < self.files.len() {
+            let file = &self.files[index];
+            if file.metadata.auto_recorded && !keep.contains(&file.path) {
+                self.delete_file(index)?;
+                deleted += 1;
+            } else {
+                index += 1;
+            }
+        }
+        Ok(deleted)
+    }
+
+    fn dir(&self) -> GameResult<&Path> {
+        self.dir.as_deref().ok_or_else(|| {
+            GameError::InvalidOperation("Replay manager has no directory".to_string())
+        })
+    }
+
+    fn file(&self, index: usize) -> GameResult<&ReplayFile> {
+        self.files
+            .get(index)
+            .ok_or_else(|| GameError::InvalidOperation("Replay index out of bounds".to_string()))
     }
 
     /// Add a replay to the manager
@@ -416,6 +995,14 @@ impl ReplayManager {
         }
     }
 
+    /// Replace the replay at an index, returning the old one; nothing
+    /// changes if the index is out of bounds
+    pub fn replace_replay(&mut self, index: usize, replay: ReplayData) -> Option<ReplayData> {
+        self.replays
+            .get_mut(index)
+            .map(|recorded| std::mem::replace(recorded, replay))
+    }
+
     /// Clear all replays
     pub fn clear_replays(&mut self) {
         self.replays.clear();
@@ -427,6 +1014,11 @@ impl ReplayManager {
     }
 }
 
+fn write_replay(path: &Path, replay: &ReplayData) -> GameResult<()> {
+    fs::write(path, replay.encode_for(path)?)
+        .map_err(|e| GameError::InvalidOperation(format!("Failed to write replay: {}", e)))
+}
+
 impl Default for ReplayManager {
     fn default() -> Self {
         Self::new()
@@ -474,4 +1066,368 @@ mod tests {
             recorded_move.score_after
         );
     }
+
+    #[test]
+    fn game_continued_from_replay_spawns_recorded_tiles() {
+        // Unseeded, so only the stored generator state can reproduce spawns
+        let mut recorder = ReplayRecorder::new(GameConfig::default()).unwrap();
+        for direction in [
+            Direction::Left,
+            Direction::Up,
+            Direction::Right,
+            Direction::Down,
+            Direction::Left,
+        ] {
+            let _ = recorder.make_move(direction);
+        }
+
+        let json = serde_json::to_string(&recorder.stop_recording()).unwrap();
+        let replay_data: ReplayData = serde_json::from_str(&json).unwrap();
+        let mut player = ReplayPlayer::new(replay_data.clone()).unwrap();
+
+        for (index, recorded) in replay_data.moves.iter().enumerate() {
+            player.go_to_move(index).unwrap();
+            let mut game = player.current_game().clone();
+            assert!(game.make_move(recorded.direction).unwrap().moved);
+            assert_eq!(game.board().to_vec(), recorded.board_after);
+        }
+    }
+
+    #[test]
+    fn gravity_replay_resimulates_from_saved_config() {
+        let config = GameConfig {
+            seed: Some(11),
+            gravity: Some(Direction::Down),
+            ..Default::default()
+        };
+        let mut recorder = ReplayRecorder::new(config).unwrap();
+        for direction in [Direction::Left, Direction::Right, Direction::Left] {
+            let _ = recorder.make_move(direction);
+        }
+
+        let json = serde_json::to_string(&recorder.stop_recording()).unwrap();
+        let replay_data: ReplayData = serde_json::from_str(&json).unwrap();
+        assert_eq!(replay_data.config.gravity, Some(Direction::Down));
+
+        // Replaying the moves on a fresh game with the stored config
+        // reproduces every recorded board, including the gravity drops
+        let mut game = Game::new(replay_data.config.clone()).unwrap();
+        assert_eq!(game.board().to_vec(), replay_data.initial_board);
+        for replay_move in &replay_data.moves {
+            game.make_move(replay_move.direction).unwrap();
+            assert_eq!(game.board().to_vec(), replay_move.board_after);
+        }
+    }
+
+    #[test]
+    fn replay_player_applies_recorded_spawns() {
+        let mut recorder = ReplayRecorder::new(GameConfig::default()).unwrap();
+        for direction in [
+            Direction::Left,
+            Direction::Up,
+            Direction::Right,
+            Direction::Down,
+        ]
+        .into_iter()
+        .cycle()
+        .take(12)
+        {
+            let _ = recorder.make_move(direction);
+        }
+        let mut replay_data = recorder.stop_recording();
+        assert!(replay_data.moves.iter().all(|m| m.spawned.len() == 1));
+
+        // Without generator states, the recorded spawns still reproduce every board
+        replay_data.initial_rng = None;
+        for replay_move in &mut replay_data.moves {
+            replay_move.rng = None;
+        }
+        let mut player = ReplayPlayer::new(replay_data.clone()).unwrap();
+        for replay_move in &replay_data.moves {
+            assert!(player.next_move().unwrap());
+            assert_eq!(
+                player.current_game().board().to_vec(),
+                replay_move.board_after
+            );
+            assert_eq!(
+                player.current_game().score().current(),
+                replay_move.score_after
+            );
+        }
+        assert!(!player.next_move().unwrap());
+
+        // A spawn that does not match the recorded board is reported
+        let spawn = &mut replay_data.moves[0].spawned[0];
+        spawn.value = if spawn.value == 2 { 4 } else { 2 };
+        let mut player = ReplayPlayer::new(replay_data).unwrap();
+        assert!(player.next_move().is_err());
+    }
+
+    #[test]
+    fn binary_replay_round_trip() {
+        let mut recorder = ReplayRecorder::new(GameConfig {
+            seed: Some(3),
+            ..Default::default()
+        })
+        .unwrap();
+        for direction in [Direction::Left, Direction::Up, Direction::Right]
+            .into_iter()
+            .cycle()
+            .take(60)
+        {
+            let _ = recorder.make_move(direction);
+        }
+        let replay_data = recorder.stop_recording();
+
+        let bytes = replay_data.to_bytes().unwrap();
+        assert!(bytes.starts_with(REPLAY_MAGIC));
+        let json = serde_json::to_vec(&replay_data).unwrap();
+        assert!(bytes.len() * 4 < json.len());
+
+        // Both formats are detected
+        for encoded in [&bytes, &json] {
+            let decoded = ReplayData::decode(encoded).unwrap();
+            assert_eq!(
+                serde_json::to_value(&decoded).unwrap(),
+                serde_json::to_value(&replay_data).unwrap()
+            );
+        }
+
+        let mut newer = bytes.clone();
+        newer[REPLAY_MAGIC.len()] = REPLAY_FORMAT_VERSION + 1;
+        assert!(matches!(
+            ReplayData::from_bytes(&newer),
+            Err(GameError::UnsupportedVersion { .. })
+        ));
+        assert!(ReplayData::from_bytes(&bytes[..bytes.len() / 2]).is_err());
+        assert!(ReplayData::from_bytes(&json).is_err());
+    }
+
+    #[test]
+    fn tiles_beyond_u32_round_trip() {
+        let mut recorder = ReplayRecorder::new(GameConfig {
+            seed: Some(5),
+            ..Default::default()
+        })
+        .unwrap();
+        let _ = recorder.make_move(Direction::Left);
+        let mut replay_data = recorder.stop_recording();
+        replay_data.initial_board[0][0] = 1 << 33;
+        replay_data.final_score = u32::MAX as u64 + 1;
+
+        let json = serde_json::to_vec(&replay_data).unwrap();
+        for encoded in [replay_data.to_bytes().unwrap(), json] {
+            let decoded = ReplayData::decode(&encoded).unwrap();
+            assert_eq!(decoded.format_version, REPLAY_SCHEMA_VERSION);
+            assert_eq!(decoded.initial_board[0][0], 1 << 33);
+            assert_eq!(decoded.final_score, u32::MAX as u64 + 1);
+        }
+    }
+
+    #[test]
+    fn take_over_branches_a_live_game() {
+        let mut recorder = ReplayRecorder::new(GameConfig::default()).unwrap();
+        for direction in [Direction::Left, Direction::Up, Direction::Right]
+            .into_iter()
+            .cycle()
+            .take(20)
+        {
+            let _ = recorder.make_move(direction);
+        }
+        let original = recorder.stop_recording();
+        let mut player = ReplayPlayer::new(original.clone()).unwrap();
+        player.go_to_move(5).unwrap();
+
+        // The same move spawns the same tile as in the original game
+        let mut game = player.take_over().unwrap();
+        assert_eq!(game.moves(), 5);
+        assert_eq!(game.score().current(), original.moves[4].score_after);
+        assert!(game.make_move(original.moves[5].direction).unwrap().moved);
+        assert_eq!(game.board().to_vec(), original.moves[5].board_after);
+
+        // A branch is recorded and played back from where it was taken over
+        let mut branch = ReplayRecorder::from_game(player.take_over().unwrap());
+        for direction in [Direction::Down, Direction::Left, Direction::Down] {
+            let _ = branch.make_move(direction);
+        }
+        let branch = branch.stop_recording();
+        assert_eq!(branch.total_moves, branch.moves.len() as u32);
+        let mut player = ReplayPlayer::new(branch.clone()).unwrap();
+        while player.next_move().unwrap() {}
+        assert_eq!(player.current_game().score().current(), branch.final_score);
+        assert_eq!(player.current_game().moves(), 5 + branch.moves.len() as u32);
+    }
+
+    #[test]
+    fn replay_directory() {
+        let dir = std::env::temp_dir().join(format!("rusty2048-replays-{}", std::process::id()));
+        let mut manager = ReplayManager::in_dir(&dir).unwrap();
+        assert!(manager.files().is_empty());
+
+        let mut replays = Vec::new();
+        for (i, moves) in [3, 12, 6].into_iter().enumerate() {
+            let mut recorder = ReplayRecorder::new(GameConfig::default()).unwrap();
+            for direction in [Direction::Left, Direction::Up, Direction::Right]
+                .into_iter()
+                .cycle()
+                .take(moves)
+            {
+                let _ = recorder.make_move(direction);
+            }
+            let mut replay = recorder.stop_recording();
+            replay.metadata.created_at = 1_000 + i as u64;
+            replays.push(replay);
+        }
+        manager.save_file(&replays[0]).unwrap();
+        manager.save_file(&replays[1]).unwrap();
+        // Older replays are JSON
+        fs::write(
+            dir.join("replay_1002.json"),
+            serde_json::to_string(&replays[2]).unwrap(),
+        )
+        .unwrap();
+        fs::write(dir.join("notes.txt"), "not a replay").unwrap();
+
+        let mut manager = ReplayManager::in_dir(&dir).unwrap();
+        let created: Vec<u64> = manager
+            .files()
+            .iter()
+            .map(|file| file.metadata.created_at)
+            .collect();
+        assert_eq!(created, vec![1_002, 1_001, 1_000]);
+        manager.sort_files(ReplaySort::Score);
+        let scores: Vec<u64> = manager.files().iter().map(|f| f.final_score).collect();
+        assert!(scores.windows(2).all(|pair| pair[0] >= pair[1]));
+
+        manager.sort_files(ReplaySort::Newest);
+        manager.rename_file(0, " Best run ").unwrap();
+        assert_eq!(manager.files()[0].metadata.name, "Best run");
+        let renamed: ReplayData =
+            serde_json::from_slice(&fs::read(dir.join("replay_1002.json")).unwrap()).unwrap();
+        assert_eq!(renamed.metadata.name, "Best run");
+        assert_eq!(renamed.moves.len(), replays[2].moves.len());
+        assert_eq!(
+            manager.load_file(1).unwrap().final_score,
+            replays[1].final_score
+        );
+
+        manager.delete_file(0).unwrap();
+        manager.scan().unwrap();
+        assert_eq!(manager.files().len(), 2);
+        assert!(manager.load_file(5).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn real_time_playback_delays() {
+        let mut recorder = ReplayRecorder::new(GameConfig::default()).unwrap();
+        for direction in [Direction::Left, Direction::Up, Direction::Right]
+            .into_iter()
+            .cycle()
+            .take(10)
+        {
+            let _ = recorder.make_move(direction);
+        }
+        let mut replay = recorder.stop_recording();
+        assert!(replay.moves.len() >= 3);
+        let start = replay.metadata.created_at * 1000;
+        replay.moves[0].timestamp_ms = Some(start + 400);
+        replay.moves[1].timestamp_ms = Some(start + 1_400);
+        // An old replay, with second timestamps only, and a long idle pause
+        replay.moves[2].timestamp_ms = None;
+        replay.moves[2].timestamp = replay.metadata.created_at + 60;
+
+        let interval = Duration::from_millis(500);
+        let mut player = ReplayPlayer::new(replay.clone()).unwrap();
+        assert_eq!(player.next_move_delay(interval), Some(interval));
+
+        player.set_timing(PlaybackTiming::RealTime);
+        assert_eq!(
+            player.next_move_delay(interval),
+            Some(Duration::from_millis(400))
+        );
+        player.next_move().unwrap();
+        player.set_speed(2.0);
+        assert_eq!(
+            player.next_move_delay(interval),
+            Some(Duration::from_millis(500))
+        );
+        player.next_move().unwrap();
+        assert_eq!(
+            player.next_move_delay(interval),
+            Some(Duration::from_millis(MAX_REAL_TIME_GAP_MS / 2))
+        );
+
+        player.go_to_move(replay.moves.len()).unwrap();
+        assert_eq!(player.next_move_delay(interval), None);
+    }
+
+    #[test]
+    fn replay_format_versions() {
+        let mut recorder = ReplayRecorder::new(GameConfig::default()).unwrap();
+        for direction in [Direction::Left, Direction::Right] {
+            let _ = recorder.make_move(direction);
+        }
+        let replay_data = recorder.stop_recording();
+        assert_eq!(replay_data.format_version, REPLAY_SCHEMA_VERSION);
+
+        // Replays written before the version was recorded are upgraded
+        let mut old = serde_json::to_value(&replay_data).unwrap();
+        old.as_object_mut().unwrap().remove("format_version");
+        let upgraded = ReplayData::decode(old.to_string().as_bytes()).unwrap();
+        assert_eq!(upgraded.format_version, REPLAY_SCHEMA_VERSION);
+        assert_eq!(upgraded.moves.len(), replay_data.moves.len());
+
+        // Replays from a newer version are refused, even if their fields
+        // changed in ways this build cannot parse
+        let mut newer = replay_data.clone();
+        newer.format_version = REPLAY_SCHEMA_VERSION + 1;
+        let mut newer_json = serde_json::to_value(&newer).unwrap();
+        newer_json["moves"] = serde_json::json!("moved elsewhere");
+        for encoded in [
+            newer.to_bytes().unwrap(),
+            newer_json.to_string().into_bytes(),
+        ] {
+            assert!(matches!(
+                ReplayData::decode(&encoded),
+                Err(GameError::UnsupportedVersion { found, supported })
+                    if found == REPLAY_SCHEMA_VERSION + 1 && supported == REPLAY_SCHEMA_VERSION
+            ));
+        }
+    }
+
+    #[test]
+    fn retention_keeps_best_and_recent() {
+        let dir = std::env::temp_dir().join(format!("rusty2048-retention-{}", std::process::id()));
+        let mut manager = ReplayManager::in_dir(&dir).unwrap();
+        let mut recorder = ReplayRecorder::new(GameConfig::default()).unwrap();
+        let _ = recorder.make_move(Direction::Left);
+        let template = recorder.stop_recording();
+
+        // Scores 500, 100, 400, 200, 300, oldest first
+        for (i, score) in [500, 100, 400, 200, 300].into_iter().enumerate() {
+            let mut replay = template.clone();
+            replay.final_score = score;
+            replay.metadata.created_at = 2_000 + i as u64;
+            replay.metadata.auto_recorded = true;
+            manager.save_file(&replay).unwrap();
+        }
+        // Saved by hand, so never removed
+        let mut kept = template.clone();
+        kept.final_score = 0;
+        kept.metadata.created_at = 1_000;
+        manager.save_file(&kept).unwrap();
+
+        let retention = ReplayRetention {
+            keep_best: 2,
+            keep_recent: 1,
+        };
+        assert_eq!(manager.apply_retention(retention).unwrap(), 2);
+        manager.scan().unwrap();
+        manager.sort_files(ReplaySort::Score);
+        let scores: Vec<u64> = manager.files().iter().map(|f| f.final_score).collect();
+        assert_eq!(scores, vec![500, 400, 300, 0]);
+        assert_eq!(manager.apply_retention(retention).unwrap(), 0);
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
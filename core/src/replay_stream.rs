@@ -0,0 +1,260 @@
+//! Crash-safe replay recording.
+//!
+//! A [`ReplayStreamWriter`] appends a replay to a JSON-lines file as it is
+//! recorded, flushing every line to disk. A game interrupted by a crash or
+//! power loss loses at most the move being written: [`recover_replay`]
+//! rebuilds the replay from whatever lines made it to disk.
+//!
+//! The first line starts the replay; every later line is a move, updated
+//! metadata, or the end of the replay:
+//!
+//! ```text
+//! {"start":{"config":{..},"initial_board":[..],"initial_rng":{..},"metadata":{..}}}
+//! {"move":{"direction":"Left","board_before":[..],..}}
+//! {"end":{"final_state":"GameOver","final_score":1024,"total_moves":2,"duration":30}}
+//! ```
+
+use crate::replay::{ReplayData, ReplayMetadata, ReplayMove, REPLAY_SCHEMA_VERSION};
+use crate::{GameConfig, GameError, GameResult, GameRng, GameState};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// File extension of replays being streamed
+pub const REPLAY_STREAM_EXTENSION: &str = "jsonl";
+
+/// One line of a replay stream. Externally tagged: internally tagged enums
+/// cannot hold the `u128` in a saved `GameRng`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum StreamRecord {
+    Start {
+        config: GameConfig,
+        initial_board: Vec<Vec<u64>>,
+        #[serde(default)]
+        initial_rng: Option<GameRng>,
+        metadata: ReplayMetadata,
+    },
+    Move(ReplayMove),
+    Metadata(ReplayMetadata),
+    End {
+        final_state: GameState,
+        final_score: u64,
+        total_moves: u32,
+        duration: u64,
+    },
+}
+
+/// Appends a replay to a file as it is recorded
+#[derive(Debug)]
+pub struct ReplayStreamWriter {
+    file: File,
+    path: PathBuf,
+}
+
+impl ReplayStreamWriter {
+    /// Create (or replace) the stream file at `path`, starting with the
+    /// configuration, initial position and metadata of `replay`, then
+    /// every move it already has
+    pub fn create(path: impl Into<PathBuf>, replay: &ReplayData) -> GameResult<Self> {
+        let path = path.into();
+        let file = File::create(&path).map_err(|e| write_error(&path, e))?;
+        let mut writer = Self { file, path };
+        writer.write(&StreamRecord::Start {
+            config: replay.config.clone(),
+            initial_board: replay.initial_board.clone(),
+            initial_rng: replay.initial_rng.clone(),
+            metadata: replay.metadata.clone(),
+        })?;
+        for replay_move in &replay.moves {
+            writer.append_move(replay_move)?;
+        }
+        Ok(writer)
+    }
+
+    /// Append a move and flush it to disk
+    pub fn append_move(&mut self, replay_move: &ReplayMove) -> GameResult<()> {
+        self.write(&StreamRecord::Move(replay_move.clone()))
+    }
+
+    /// Record new metadata, replacing the metadata written before
+    pub fn update_metadata(&mut self, metadata: &ReplayMetadata) -> GameResult<()> {
+        self.write(&StreamRecord::Metadata(metadata.clone()))
+    }
+
+    /// Mark the replay as complete with the final state of `replay`
+    pub fn finish(mut self, replay: &ReplayData) -> GameResult<()> {
+        self.write(&StreamRecord::End {
+            final_state: replay.final_state.clone(),
+            final_score: replay.final_score,
+            total_moves: replay.total_moves,
+            duration: replay.duration,
+        })
+    }
+
+    fn write(&mut self, record: &StreamRecord) -> GameResult<()> {
+        let mut line = serde_json::to_vec(record)
+            .map_err(|e| GameError::Serialization(format!("Failed to encode replay: {}", e)))?;
+        line.push(b'\n');
+        // A single write per line, synced, so a crash leaves at most one
+        // partial line at the end of the file
+        self.file
+            .write_all(&line)
+            .and_then(|_| self.file.sync_data())
+            .map_err(|e| write_error(&self.path, e))
+    }
+}
+
+/// Rebuild the replay of a stream file, finished or not.
+///
+/// A partial last line, as left by a crash, is ignored. An unfinished
+/// replay ends in the position of its last move, with the game still
+/// being played.
+pub fn recover_replay(path: impl AsRef<Path>) -> GameResult<ReplayData> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path).map_err(|e| {
+        GameError::InvalidOperation(format!(
+            "Failed to read replay stream {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+
+    let mut lines = content.lines().filter(|line| !line.trim().is_empty());
+    let mut replay = match lines.next().map(serde_json::from_str::<StreamRecord>) {
+        Some(Ok(StreamRecord::Start {
+            config,
+            initial_board,
+            initial_rng,
+            metadata,
+        })) => ReplayData {
+            format_version: REPLAY_SCHEMA_VERSION,
+            config,
+            initial_board,
+            initial_rng,
+            moves: Vec::new(),
+            final_state: GameState::Playing,
+            final_score: 0,
+            total_moves: 0,
+            duration: 0,
+            metadata,
+        },
+        _ => {
+            return Err(GameError::Serialization(
+                "Replay stream does not start with a replay".to_string(),
+            ))
+        }
+    };
+
+    let mut finished = false;
+    for line in lines {
+        let Ok(record) = serde_json::from_str::<StreamRecord>(line) else {
+            // Only the last line can be cut off by a crash
+            break;
+        };
+        match record {
+            StreamRecord::Move(replay_move) => replay.moves.push(replay_move),
+            StreamRecord::Metadata(metadata) => replay.metadata = metadata,
+            StreamRecord::End {
+                final_state,
+                final_score,
+                total_moves,
+                duration,
+            } => {
+                replay.final_state = final_state;
+                replay.final_score = final_score;
+                replay.total_moves = total_moves;
+                replay.duration = duration;
+                finished = true;
+            }
+            StreamRecord::Start { .. } => {
+                return Err(GameError::Serialization(
+                    "Replay stream starts more than once".to_string(),
+                ))
+            }
+        }
+    }
+
+    if !finished {
+        if let Some(last) = replay.moves.last() {
+            replay.final_score = last.score_after;
+            replay.duration = last.timestamp.saturating_sub(replay.metadata.created_at);
+        }
+        replay.total_moves = replay.moves.len() as u32;
+    }
+    Ok(replay)
+}
+
+fn write_error(path: &Path, error: std::io::Error) -> GameError {
+    GameError::InvalidOperation(format!(
+        "Failed to write replay stream {}: {}",
+        path.display(),
+        error
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Direction, ReplayRecorder};
+    use std::fs::OpenOptions;
+
+    #[test]
+    fn test_recover_interrupted_stream() {
+        let path = std::env::temp_dir().join(format!(
+            "rusty2048-stream-{}.{}",
+            std::process::id(),
+            REPLAY_STREAM_EXTENSION
+        ));
+        let mut recorder = ReplayRecorder::new(GameConfig::default()).unwrap();
+        recorder.stream_to(&path).unwrap();
+        for direction in [Direction::Left, Direction::Up, Direction::Right]
+            .into_iter()
+            .cycle()
+            .take(10)
+        {
+            let _ = recorder.make_move(direction);
+        }
+        recorder.set_metadata(ReplayMetadata::new("Interrupted".to_string()));
+        let expected = recorder.replay_data().clone();
+
+        // A crash in the middle of writing a move
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(br#"{"move":{"direction":"Le"#).unwrap();
+        drop(recorder);
+
+        let recovered = recover_replay(&path).unwrap();
+        assert_eq!(recovered.metadata.name, "Interrupted");
+        assert_eq!(recovered.moves.len(), expected.moves.len());
+        assert_eq!(recovered.final_score, expected.final_score);
+        assert_eq!(recovered.total_moves, expected.total_moves);
+        assert_eq!(recovered.final_state, GameState::Playing);
+        assert_eq!(
+            recovered.moves.last().map(|m| &m.board_after),
+            expected.moves.last().map(|m| &m.board_after)
+        );
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_finished_stream() {
+        let path = std::env::temp_dir().join(format!(
+            "rusty2048-stream-done-{}.{}",
+            std::process::id(),
+            REPLAY_STREAM_EXTENSION
+        ));
+        let mut recorder = ReplayRecorder::new(GameConfig::default()).unwrap();
+        let _ = recorder.make_move(Direction::Left);
+        // Moves made before streaming started are written too
+        recorder.stream_to(&path).unwrap();
+        let _ = recorder.make_move(Direction::Up);
+        let replay = recorder.stop_recording();
+
+        let recovered = recover_replay(&path).unwrap();
+        assert_eq!(recovered.moves.len(), replay.moves.len());
+        assert_eq!(recovered.duration, replay.duration);
+        assert_eq!(recovered.final_state, replay.final_state);
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,22 +1,31 @@
-use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
 
-/// Game random number generator with seed support
-#[derive(Debug, Clone)]
+/// Game random number generator with seed support.
+///
+/// Serializing it captures the exact position in the random sequence, so a
+/// deserialized copy draws the same values as the original.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GameRng {
-    rng: StdRng,
+    // Same generator as `StdRng`, which does not expose its state
+    rng: ChaCha12Rng,
+    /// Seed the sequence started from; unknown for generators saved before
+    /// seeds were stored
+    #[serde(default)]
+    seed: Option<u64>,
 }
 
 impl GameRng {
-    /// Create a new RNG with optional seed
+    /// Create a new RNG with optional seed; without one a seed is drawn
+    /// from entropy
     pub fn new(seed: Option<u64>) -> Self {
-        let rng = if let Some(seed) = seed {
-            StdRng::seed_from_u64(seed)
-        } else {
-            StdRng::from_entropy()
-        };
+        let seed = seed.unwrap_or_else(rand::random);
 
-        Self { rng }
+        Self {
+            rng: ChaCha12Rng::seed_from_u64(seed),
+            seed: Some(seed),
+        }
     }
 
     /// Generate a random value between 0 and max (exclusive)
@@ -32,8 +41,13 @@ impl GameRng {
         self.rng.gen_bool(probability)
     }
 
+    /// Generate a random value between 0.0 and 1.0 (exclusive)
+    pub fn gen_f64(&mut self) -> f64 {
+        self.rng.gen()
+    }
+
     /// Generate a random tile value (2 or 4 with 90/10 probability)
-    pub fn gen_tile_value(&mut self) -> u32 {
+    pub fn gen_tile_value(&mut self) -> u64 {
         if self.gen_bool(0.9) {
             2
         } else {
@@ -41,11 +55,9 @@ impl GameRng {
         }
     }
 
-    /// Get the current seed (if available)
+    /// Get the seed the sequence started from (if known)
     pub fn get_seed(&self) -> Option<u64> {
-        // Note: This is a simplified implementation
-        // In a real implementation, you'd need to store the seed separately
-        None
+        self.seed
     }
 }
 
@@ -70,6 +82,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_seed_is_stored() {
+        assert_eq!(GameRng::new(Some(42)).get_seed(), Some(42));
+
+        // A generated seed reproduces the sequence
+        let mut rng = GameRng::new(None);
+        let mut replayed = GameRng::new(rng.get_seed());
+        for _ in 0..10 {
+            assert_eq!(rng.gen_range(100), replayed.gen_range(100));
+        }
+    }
+
+    #[test]
+    fn test_serialized_rng_continues_sequence() {
+        let mut rng = GameRng::new(None);
+        rng.gen_range(100);
+
+        let json = serde_json::to_string(&rng).unwrap();
+        let mut restored: GameRng = serde_json::from_str(&json).unwrap();
+        for _ in 0..10 {
+            assert_eq!(rng.gen_range(100), restored.gen_range(100));
+        }
+    }
+
     #[test]
     fn test_tile_value_generation() {
         let mut rng = GameRng::new(Some(123));
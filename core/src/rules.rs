@@ -0,0 +1,229 @@
+//! Pluggable game rules.
+//!
+//! A [`GameRules`] implementation decides how tiles merge, what spawns and
+//! when a game is won or lost. Rules are registered under an id and chosen
+//! through `GameConfig::rules`, so games, replays and saved games of a
+//! variant work anywhere the variant's crate has called [`register_rules`].
+//! The board itself stays a square grid.
+
+use crate::{Board, GameConfig, GameRng, WinTrigger};
+use std::fmt::Debug;
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// Id of the built-in classic rules
+pub const CLASSIC_RULES: &str = "classic";
+
+/// Rules of a game variant
+pub trait GameRules: Debug + Send + Sync {
+    /// Unique id, stored in `GameConfig::rules`
+    fn id(&self) -> &str;
+
+    /// Human-readable name
+    fn name(&self) -> &str;
+
+    /// Value of the tile produced by merging two tiles, or `None` if they
+    /// do not merge. `first` is the tile closer to the move direction.
+    fn merge(&self, first: u64, second: u64) -> Option<u64> {
+        (first == second).then_some(first * 2)
+    }
+
+    /// Points scored for a merge that produced `merged`
+    fn merge_score(&self, merged: u64) -> u64 {
+        merged
+    }
+
+    /// Value of a newly spawned tile
+    fn spawn_value(&self, rng: &mut GameRng) -> u64 {
+        rng.gen_tile_value()
+    }
+
+    /// Which part of the win condition the board and score reach, if any
+    fn won_by(&self, board: &Board, score: u64, config: &GameConfig) -> Option<WinTrigger> {
+        config.win_condition.check(board.max_tile(), score)
+    }
+
+    /// Whether the board ends the game
+    fn is_lost(&self, board: &Board) -> bool {
+        !can_move(self, board)
+    }
+}
+
+/// The standard 2048 rules
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ClassicRules;
+
+impl GameRules for ClassicRules {
+    fn id(&self) -> &str {
+        CLASSIC_RULES
+    }
+
+    fn name(&self) -> &str {
+        "Classic"
+    }
+}
+
+/// Whether any move is possible under the given rules
+pub fn can_move<R: GameRules + ?Sized>(rules: &R, board: &Board) -> bool {
+    let size = board.size();
+    let tiles = board.as_flat_slice();
+    board.iter_tiles().any(|(row, col, tile)| {
+        let value = tile.value;
+        if value == 0 {
+            return true;
+        }
+        let mergeable = |other: u64| {
+            other != 0
+                && (rules.merge(value, other).is_some() || rules.merge(other, value).is_some())
+        };
+        (col + 1 < size && mergeable(tiles[row * size + col + 1].value))
+            || (row + 1 < size && mergeable(tiles[(row + 1) * size + col].value))
+    })
+}
+
+/// One line after sliding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlidLine {
+    /// New values of the line
+    pub values: Vec<u64>,
+    /// For each occupied cell of `values`, the input indices of the tiles
+    /// that ended there (two for a merge)
+    pub sources: Vec<Vec<usize>>,
+}
+
+impl SlidLine {
+    /// Values of the tiles produced by merges
+    pub fn merged_values(&self) -> impl Iterator<Item = u64> + '_ {
+        self.sources
+            .iter()
+            .zip(&self.values)
+            .filter(|(sources, _)| sources.len() > 1)
+            .map(|(_, &value)| value)
+    }
+}
+
+/// Slide one line of tiles towards its start, merging under the given rules
+pub fn slide_line<R: GameRules + ?Sized>(rules: &R, line: &[u64]) -> SlidLine {
+    let mut tiles = line
+        .iter()
+        .copied()
+        .enumerate()
+        .filter(|&(_, value)| value != 0)
+        .peekable();
+    let mut values = Vec::with_capacity(line.len());
+    let mut sources = Vec::new();
+
+    while let Some((index, value)) = tiles.next() {
+        match tiles
+            .peek()
+            .and_then(|&(next_index, next)| Some((next_index, rules.merge(value, next)?)))
+        {
+            Some((next_index, merged)) => {
+                tiles.next();
+                values.push(merged);
+                sources.push(vec![index, next_index]);
+            }
+            None => {
+                values.push(value);
+                sources.push(vec![index]);
+            }
+        }
+    }
+    values.resize(line.len(), 0);
+    SlidLine { values, sources }
+}
+
+fn registry() -> &'static RwLock<Vec<Arc<dyn GameRules>>> {
+    static REGISTRY: OnceLock<RwLock<Vec<Arc<dyn GameRules>>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(vec![Arc::new(ClassicRules)]))
+}
+
+/// Make rules available to `Game::new` under their id, replacing any rules
+/// registered with the same id
+pub fn register_rules(rules: Arc<dyn GameRules>) {
+    let mut registry = registry().write().unwrap_or_else(|e| e.into_inner());
+    registry.retain(|existing| existing.id() != rules.id());
+    registry.push(rules);
+}
+
+/// Look up registered rules by id
+pub fn rules_by_id(id: &str) -> Option<Arc<dyn GameRules>> {
+    registry()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .find(|rules| rules.id() == id)
+        .cloned()
+}
+
+/// All registered rules, classic first
+pub fn registered_rules() -> Vec<Arc<dyn GameRules>> {
+    registry().read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+pub(crate) fn default_rules_id() -> String {
+    CLASSIC_RULES.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Direction, Game, GameState, Score};
+
+    /// Only 2s spawn and merges score triple
+    #[derive(Debug)]
+    struct TripleScore;
+
+    impl GameRules for TripleScore {
+        fn id(&self) -> &str {
+            "test-triple-score"
+        }
+
+        fn name(&self) -> &str {
+            "Triple score"
+        }
+
+        fn merge_score(&self, merged: u64) -> u64 {
+            merged * 3
+        }
+
+        fn spawn_value(&self, _rng: &mut GameRng) -> u64 {
+            2
+        }
+    }
+
+    #[test]
+    fn test_registered_rules_drive_the_game() {
+        register_rules(Arc::new(TripleScore));
+        let mut game = Game::new(GameConfig {
+            rules: "test-triple-score".to_string(),
+            ..GameConfig::default()
+        })
+        .unwrap();
+        game.load_from_state(
+            vec![2, 2, 4, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            Score::new(),
+            0,
+            GameState::Playing,
+        )
+        .unwrap();
+
+        assert!(game.make_move(Direction::Left).unwrap().moved);
+        assert_eq!(game.score().current(), (4 + 8) * 3);
+        assert_eq!(game.board().count_tiles(4), 1);
+        assert_eq!(game.board().count_tiles(2), 1);
+
+        let unknown = GameConfig {
+            rules: "no-such-rules".to_string(),
+            ..GameConfig::default()
+        };
+        assert!(Game::new(unknown).is_err());
+    }
+
+    #[test]
+    fn test_slide_line() {
+        let slid = slide_line(&ClassicRules, &[2, 2, 2, 0, 4, 4]);
+        assert_eq!(slid.values, vec![4, 2, 8, 0, 0, 0]);
+        assert_eq!(slid.sources, vec![vec![0, 1], vec![2], vec![4, 5]]);
+        assert_eq!(slid.merged_values().collect::<Vec<_>>(), vec![4, 8]);
+    }
+}
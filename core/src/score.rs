@@ -1,14 +1,53 @@
+use crate::TileMerge;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Optional scoring rules on top of the merge points. All bonuses default
+/// to 0, which leaves classic scoring unchanged.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoringRules {
+    /// Points added for every merge that lands in a corner
+    pub corner_merge_bonus: u64,
+    /// Points taken away for every undo (or redo of an undone move)
+    pub undo_penalty: u64,
+    /// Points per move of the current merge streak: the n-th move in a row
+    /// that merges earns (n - 1) times this
+    pub streak_bonus: u64,
+}
+
+/// Why bonus points were awarded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BonusKind {
+    CornerMerge,
+    Streak,
+}
+
+/// Bonus points earned by a move, for "+bonus" indicators
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreBonus {
+    pub kind: BonusKind,
+    pub points: u64,
+    /// (row, col) the bonus is for; `None` for bonuses of the whole move
+    pub position: Option<(usize, usize)>,
+}
 
 /// Score tracking and calculation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Score {
     /// Current score
-    current: u32,
+    current: u64,
     /// Best score achieved
-    best: u32,
+    best: u64,
     /// Score gained from last move
-    last_move: u32,
+    last_move: u64,
+    /// Moves in a row that merged tiles
+    #[serde(default)]
+    streak: u32,
+    /// Merge points of the current game by merged tile value
+    #[serde(default)]
+    breakdown: BTreeMap<u64, u64>,
 }
 
 impl Score {
@@ -18,35 +57,56 @@ impl Score {
             current: 0,
             best: 0,
             last_move: 0,
+            streak: 0,
+            breakdown: BTreeMap::new(),
         }
     }
 
     /// Get current score
-    pub fn current(&self) -> u32 {
+    pub fn current(&self) -> u64 {
         self.current
     }
 
     /// Get best score
-    pub fn best(&self) -> u32 {
+    pub fn best(&self) -> u64 {
         self.best
     }
 
     /// Get score from last move
-    pub fn last_move(&self) -> u32 {
+    pub fn last_move(&self) -> u64 {
         self.last_move
     }
 
+    /// Moves in a row, up to the last one, that merged tiles
+    pub fn streak(&self) -> u32 {
+        self.streak
+    }
+
+    /// Points the current game scored by merging into each tile value,
+    /// e.g. how much came from making 8s, 16s, ... 2048s. Bonuses from
+    /// `ScoringRules` are not included.
+    pub fn breakdown(&self) -> &BTreeMap<u64, u64> {
+        &self.breakdown
+    }
+
     /// Restore a score snapshot from serialized state.
-    pub fn from_parts(current: u32, best: u32, last_move: u32) -> Self {
+    pub fn from_parts(current: u64, best: u64, last_move: u64) -> Self {
         Self {
             current,
             best,
             last_move,
+            streak: 0,
+            breakdown: BTreeMap::new(),
         }
     }
 
+    /// Restore a persisted best score (never lowers the current best)
+    pub fn restore_best(&mut self, best: u64) {
+        self.best = self.best.max(best);
+    }
+
     /// Add points from a merge
-    pub fn add_merge_points(&mut self, merged_value: u32) {
+    pub fn add_merge_points(&mut self, merged_value: u64) {
         self.last_move = merged_value;
         self.current += merged_value;
 
@@ -55,10 +115,70 @@ impl Score {
         }
     }
 
+    /// Add the points of a merge that produced a tile of `merged_value`
+    pub fn add_merge(&mut self, merged_value: u64, points: u64) {
+        *self.breakdown.entry(merged_value).or_default() += points;
+        self.add_merge_points(points);
+    }
+
+    /// Apply the scoring rules to a move that changed the board, given its
+    /// merges on a board of `size`. Returns the bonuses awarded.
+    pub fn apply_rules(
+        &mut self,
+        rules: &ScoringRules,
+        merges: &[TileMerge],
+        size: usize,
+    ) -> Vec<ScoreBonus> {
+        if merges.is_empty() {
+            self.streak = 0;
+            return Vec::new();
+        }
+        self.streak += 1;
+
+        let last = size - 1;
+        let mut bonuses: Vec<ScoreBonus> = merges
+            .iter()
+            .filter(|merge| {
+                let (row, col) = merge.position;
+                (row == 0 || row == last) && (col == 0 || col == last)
+            })
+            .map(|merge| ScoreBonus {
+                kind: BonusKind::CornerMerge,
+                points: rules.corner_merge_bonus,
+                position: Some(merge.position),
+            })
+            .collect();
+        if self.streak > 1 {
+            bonuses.push(ScoreBonus {
+                kind: BonusKind::Streak,
+                points: rules.streak_bonus * u64::from(self.streak - 1),
+                position: None,
+            });
+        }
+
+        bonuses.retain(|bonus| bonus.points > 0);
+        for bonus in &bonuses {
+            self.add_points(bonus.points);
+        }
+        bonuses
+    }
+
+    /// Take points away, never going below zero
+    pub fn apply_penalty(&mut self, points: u64) {
+        self.current = self.current.saturating_sub(points);
+    }
+
+    fn add_points(&mut self, points: u64) {
+        self.current += points;
+        self.best = self.best.max(self.current);
+    }
+
     /// Reset current score (for new game)
     pub fn reset_current(&mut self) {
         self.current = 0;
         self.last_move = 0;
+        self.streak = 0;
+        self.breakdown.clear();
     }
 
     /// Reset all scores
@@ -66,10 +186,12 @@ impl Score {
         self.current = 0;
         self.best = 0;
         self.last_move = 0;
+        self.streak = 0;
+        self.breakdown.clear();
     }
 
     /// Calculate score for a specific merge
-    pub fn calculate_merge_score(merged_value: u32) -> u32 {
+    pub fn calculate_merge_score(merged_value: u64) -> u64 {
         merged_value
     }
 }
@@ -123,6 +245,78 @@ mod tests {
         assert_eq!(Score::calculate_merge_score(2048), 2048);
     }
 
+    #[test]
+    fn test_breakdown() {
+        let mut score = Score::new();
+        score.add_merge(4, 4);
+        score.add_merge(8, 8);
+        score.add_merge(4, 4);
+
+        assert_eq!(score.current(), 16);
+        assert_eq!(
+            score.breakdown().iter().collect::<Vec<_>>(),
+            vec![(&4, &8), (&8, &8)]
+        );
+
+        score.reset_current();
+        assert!(score.breakdown().is_empty());
+    }
+
+    #[test]
+    fn test_scoring_rules() {
+        let rules = ScoringRules {
+            corner_merge_bonus: 10,
+            undo_penalty: 0,
+            streak_bonus: 5,
+        };
+        let merge = |position| TileMerge {
+            position,
+            sources: [position, position],
+            value: 4,
+        };
+        let mut score = Score::new();
+
+        let bonuses = score.apply_rules(&rules, &[merge((0, 3)), merge((1, 3))], 4);
+        assert_eq!(
+            bonuses,
+            vec![ScoreBonus {
+                kind: BonusKind::CornerMerge,
+                points: 10,
+                position: Some((0, 3)),
+            }]
+        );
+        assert_eq!(score.current(), 10);
+
+        // Second merging move in a row
+        let bonuses = score.apply_rules(&rules, &[merge((1, 1))], 4);
+        assert_eq!(bonuses[0].kind, BonusKind::Streak);
+        assert_eq!(score.current(), 15);
+
+        assert!(score.apply_rules(&rules, &[], 4).is_empty());
+        assert_eq!(score.streak(), 0);
+
+        score.apply_penalty(100);
+        assert_eq!(score.current(), 0);
+        assert_eq!(score.best(), 15);
+
+        // Classic scoring has no bonuses
+        assert!(Score::new()
+            .apply_rules(&ScoringRules::default(), &[merge((0, 0))], 4)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_restore_best() {
+        let mut score = Score::from_parts(0, 100, 0);
+
+        score.restore_best(50);
+        assert_eq!(score.best(), 100);
+
+        score.restore_best(500);
+        assert_eq!(score.best(), 500);
+        assert_eq!(score.current(), 0);
+    }
+
     #[test]
     fn test_restore_score_snapshot() {
         let score = Score::from_parts(128, 256, 16);
@@ -0,0 +1,148 @@
+//! Control over long AI searches: a [`CancellationToken`] stops a search
+//! from another thread, and a progress callback receives
+//! [`SearchProgress`] reports while it runs. Both are given to an
+//! `AIPlayer`, which passes them on to its strategy.
+
+use crate::Direction;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Shared flag asking a search to stop. Clones share the flag, so one can
+/// be kept to cancel a search running with another; tokens compare equal
+/// when they share it.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl PartialEq for CancellationToken {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for CancellationToken {}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask the searches using this token to stop
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether `cancel` was called since the token was created or reset
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Clear the flag, so the token can be used for the next search
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::Relaxed);
+    }
+}
+
+/// How far a running search got
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchProgress {
+    /// Nodes searched so far; simulations for MCTS
+    pub nodes: u64,
+    /// Depth being searched
+    pub depth: usize,
+    /// Best move found so far, if any
+    pub best: Option<Direction>,
+}
+
+/// Where an incremental search stands after a step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SearchStatus {
+    /// Out of budget for this step; step again to go on
+    Searching(SearchProgress),
+    /// Finished: the move to play, `None` if no move changes the board
+    Done(Option<Direction>),
+}
+
+/// Called with each progress report
+pub type ProgressCallback = Arc<dyn Fn(&SearchProgress) + Send + Sync>;
+
+/// Cancellation and progress reporting of a search. The default neither
+/// cancels nor reports.
+#[derive(Clone, Default)]
+pub struct SearchControl {
+    cancellation: Option<CancellationToken>,
+    progress: Option<ProgressCallback>,
+}
+
+impl fmt::Debug for SearchControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SearchControl")
+            .field("cancellation", &self.cancellation)
+            .field("progress", &self.progress.is_some())
+            .finish()
+    }
+}
+
+impl SearchControl {
+    /// Stop the search when `token` is cancelled
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Report progress to `callback`
+    pub fn with_progress(mut self, callback: ProgressCallback) -> Self {
+        self.progress = Some(callback);
+        self
+    }
+
+    /// Whether the search was asked to stop
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+    }
+
+    /// Pass a progress report on to the callback, if there is one
+    pub fn report(&self, progress: SearchProgress) {
+        if let Some(callback) = &self.progress {
+            callback(&progress);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_search_control() {
+        let token = CancellationToken::new();
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        let control = SearchControl::default()
+            .with_cancellation(token.clone())
+            .with_progress(Arc::new(move |progress: &SearchProgress| {
+                sink.lock().unwrap().push(*progress)
+            }));
+
+        assert!(!control.is_cancelled());
+        assert_eq!(token.clone(), token);
+        assert_ne!(CancellationToken::new(), token);
+        token.clone().cancel();
+        assert!(control.is_cancelled());
+        token.reset();
+        assert!(!control.is_cancelled());
+
+        let progress = SearchProgress {
+            nodes: 10,
+            depth: 2,
+            best: Some(Direction::Left),
+        };
+        control.report(progress);
+        SearchControl::default().report(progress);
+        assert_eq!(*reports.lock().unwrap(), vec![progress]);
+    }
+}
@@ -1,7 +1,9 @@
+use crate::daily::{date_string, SECONDS_PER_DAY};
 use crate::error::{GameError, GameResult};
+use crate::{Board, DailyChallenge, GameConfig, GameStats, ReplayAnalysis};
 use serde::{Deserialize, Serialize};
 use std::cmp::Reverse;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::Path;
 
@@ -11,13 +13,13 @@ pub struct GameSessionStats {
     /// Game session ID (timestamp)
     pub session_id: u64,
     /// Final score
-    pub final_score: u32,
+    pub final_score: u64,
     /// Number of moves made
     pub moves: u32,
     /// Game duration in seconds
     pub duration: u64,
     /// Maximum tile achieved
-    pub max_tile: u32,
+    pub max_tile: u64,
     /// Whether the game was won
     pub won: bool,
     /// Game end reason
@@ -30,6 +32,146 @@ pub struct GameSessionStats {
     pub avg_score_per_move: f64,
     /// Efficiency score (score / moves)
     pub efficiency: f64,
+    /// Average time between moves in milliseconds, if it was measured
+    #[serde(default)]
+    pub average_move_ms: Option<f64>,
+    /// Shortest time between moves in milliseconds
+    #[serde(default)]
+    pub fastest_move_ms: Option<u64>,
+    /// Longest time between moves in milliseconds
+    #[serde(default)]
+    pub slowest_move_ms: Option<u64>,
+    /// Date of the daily challenge this game was played as, `YYYY-MM-DD`
+    #[serde(default)]
+    pub daily: Option<String>,
+    /// Configuration the game was played with (missing in older sessions)
+    #[serde(default)]
+    pub config: Option<GameConfig>,
+    /// Who played the game
+    #[serde(default)]
+    pub player: PlayerType,
+    /// Tile values on the board when the game ended, row by row
+    #[serde(default)]
+    pub final_board: Option<Vec<Vec<u64>>>,
+    /// Accuracy of the moves against the AI evaluation, as a percentage,
+    /// if the game was analyzed
+    #[serde(default)]
+    pub accuracy: Option<f64>,
+}
+
+impl GameSessionStats {
+    /// Add the move timing of a game's statistics
+    pub fn with_move_timing(mut self, stats: &GameStats) -> Self {
+        self.average_move_ms = stats.average_move_ms;
+        self.fastest_move_ms = stats.fastest_move_ms;
+        self.slowest_move_ms = stats.slowest_move_ms;
+        self
+    }
+
+    /// Tag the session as a run of a daily challenge
+    pub fn with_daily(mut self, challenge: &DailyChallenge) -> Self {
+        self.daily = Some(challenge.date.clone());
+        self
+    }
+
+    /// Record the configuration the game was played with
+    pub fn with_config(mut self, config: &GameConfig) -> Self {
+        self.config = Some(config.clone());
+        self
+    }
+
+    /// Record the board the game ended with
+    pub fn with_final_board(mut self, board: &Board) -> Self {
+        self.final_board = Some(board.to_vec());
+        self
+    }
+
+    /// Record the accuracy of an analysis of the game
+    pub fn with_analysis(mut self, analysis: &ReplayAnalysis) -> Self {
+        self.accuracy = Some(analysis.accuracy);
+        self
+    }
+
+    /// Record who played the game
+    pub fn with_player(mut self, player: PlayerType) -> Self {
+        self.player = player;
+        self
+    }
+
+    /// The configuration segment of the session. Sessions recorded before
+    /// configurations were stored count as default games.
+    pub fn segment(&self) -> SessionSegment {
+        let default_config = GameConfig::default();
+        let config = self.config.as_ref().unwrap_or(&default_config);
+        SessionSegment {
+            board_size: config.board_size,
+            rules: config.rules.clone(),
+            seeded: config.seed.is_some(),
+            player: self.player,
+        }
+    }
+}
+
+/// Who played a game
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum PlayerType {
+    /// A person making the moves
+    #[default]
+    Human,
+    /// The AI playing on its own or assisting
+    Ai,
+}
+
+/// The configuration a group of sessions was played with
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct SessionSegment {
+    /// Board size
+    pub board_size: usize,
+    /// Id of the rules variant
+    pub rules: String,
+    /// Whether the game was played from a fixed seed
+    pub seeded: bool,
+    /// Who played the game
+    pub player: PlayerType,
+}
+
+/// Filter selecting sessions by configuration; `None` fields match anything
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionFilter {
+    /// Only games on this board size
+    pub board_size: Option<usize>,
+    /// Only games with these rules
+    pub rules: Option<String>,
+    /// Only seeded (or only unseeded) games
+    pub seeded: Option<bool>,
+    /// Only games played by this player type
+    pub player: Option<PlayerType>,
+}
+
+impl SessionFilter {
+    /// Filter matching exactly one segment
+    pub fn for_segment(segment: &SessionSegment) -> Self {
+        Self {
+            board_size: Some(segment.board_size),
+            rules: Some(segment.rules.clone()),
+            seeded: Some(segment.seeded),
+            player: Some(segment.player),
+        }
+    }
+
+    /// Whether a session passes the filter
+    pub fn matches(&self, session: &GameSessionStats) -> bool {
+        let segment = session.segment();
+        self.board_size
+            .is_none_or(|size| size == segment.board_size)
+            && self.rules.as_ref().is_none_or(|id| *id == segment.rules)
+            && self.seeded.is_none_or(|seeded| seeded == segment.seeded)
+            && self.player.is_none_or(|player| player == segment.player)
+    }
 }
 
 /// Game end reason
@@ -53,7 +195,7 @@ pub struct StatisticsSummary {
     /// Win rate percentage
     pub win_rate: f64,
     /// Highest score ever achieved
-    pub highest_score: u32,
+    pub highest_score: u64,
     /// Average score across all games
     pub average_score: f64,
     /// Total moves across all games
@@ -65,42 +207,347 @@ pub struct StatisticsSummary {
     /// Average game duration
     pub average_duration: f64,
     /// Highest tile ever achieved
-    pub highest_tile: u32,
+    pub highest_tile: u64,
     /// Tile distribution (how many times each tile was achieved)
-    pub tile_distribution: HashMap<u32, u32>,
+    pub tile_distribution: HashMap<u64, u32>,
     /// Score distribution (ranges)
     pub score_distribution: ScoreDistribution,
     /// Recent games (last 10)
     pub recent_games: Vec<GameSessionStats>,
+    /// Average time between moves across games with move timing, in milliseconds
+    #[serde(default)]
+    pub average_move_ms: Option<f64>,
+    /// Average accuracy of analyzed games, as a percentage
+    #[serde(default)]
+    pub average_accuracy: Option<f64>,
+}
+
+/// Where tiles ended up on one board size, counted over finished games
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PositionHeatmap {
+    /// Board size
+    pub board_size: usize,
+    /// Number of games counted
+    pub games: u32,
+    /// How often each cell held the game's max tile at the end, row by row
+    pub max_tile: Vec<Vec<u32>>,
+    /// How often each cell was occupied at the end, row by row
+    pub occupancy: Vec<Vec<u32>>,
+}
+
+impl PositionHeatmap {
+    fn new(board_size: usize) -> Self {
+        Self {
+            board_size,
+            games: 0,
+            max_tile: vec![vec![0; board_size]; board_size],
+            occupancy: vec![vec![0; board_size]; board_size],
+        }
+    }
+
+    fn add(&mut self, board: &[Vec<u64>]) {
+        let max_tile = board.iter().flatten().copied().max().unwrap_or(0);
+        self.games += 1;
+        for (row, values) in board.iter().enumerate() {
+            for (col, &value) in values.iter().enumerate() {
+                if value != 0 {
+                    self.occupancy[row][col] += 1;
+                    if value == max_tile {
+                        self.max_tile[row][col] += 1;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Calendar period that sessions are grouped into (UTC)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatsPeriod {
+    /// Single day
+    Day,
+    /// Week starting on Monday
+    Week,
+    /// Calendar month
+    Month,
+}
+
+impl StatsPeriod {
+    /// Start and (exclusive) end, as Unix timestamps, of the period containing `timestamp`
+    pub fn range_containing(self, timestamp: u64) -> (u64, u64) {
+        let day = timestamp / SECONDS_PER_DAY;
+        let (first_day, next_day) = match self {
+            StatsPeriod::Day => (day, day + 1),
+            // The Unix epoch was a Thursday
+            StatsPeriod::Week => {
+                let monday = day - (day + 3) % 7;
+                (monday, monday + 7)
+            }
+            StatsPeriod::Month => {
+                let first = first_of_month(day);
+                (first, first_of_month(first + 31))
+            }
+        };
+        (first_day * SECONDS_PER_DAY, next_day * SECONDS_PER_DAY)
+    }
+}
+
+/// First day of the month containing `day` (days since the Unix epoch)
+fn first_of_month(day: u64) -> u64 {
+    let day_of_month: u64 = date_string(day)[8..].parse().unwrap_or(1);
+    day + 1 - day_of_month
+}
+
+/// Totals of the games that ended within one period
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PeriodTotals {
+    /// First day of the period as `YYYY-MM-DD`
+    pub date: String,
+    /// Start of the period (Unix timestamp)
+    pub start: u64,
+    /// End of the period, exclusive (Unix timestamp)
+    pub end: u64,
+    /// Games played
+    pub games: u32,
+    /// Games won
+    pub games_won: u32,
+    /// Best score
+    pub best_score: u64,
+    /// Sum of all final scores
+    pub total_score: u64,
+    /// Highest tile reached
+    pub highest_tile: u64,
+    /// Play time in seconds
+    pub play_time: u64,
+}
+
+/// Outcome of `StatisticsManager::import_and_merge`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergeReport {
+    /// Sessions added
+    pub added: usize,
+    /// Sessions that were already recorded
+    pub duplicates: usize,
+    /// Added sessions whose ID was taken by a different game and got a new one
+    pub renumbered: usize,
+    /// Sessions whose timestamps were corrected for clock skew
+    pub adjusted: usize,
 }
 
 /// Score distribution by ranges
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct ScoreDistribution {
     /// Games with score 0-1000
-    pub low_score: u32,
+    pub low_score: u64,
     /// Games with score 1001-5000
-    pub medium_score: u32,
+    pub medium_score: u64,
     /// Games with score 5001-10000
-    pub high_score: u32,
+    pub high_score: u64,
     /// Games with score 10001+
-    pub very_high_score: u32,
+    pub very_high_score: u64,
+}
+
+impl ScoreDistribution {
+    /// Count a game's score in its range
+    fn add(&mut self, score: u64) {
+        match score {
+            0..=1000 => self.low_score += 1,
+            1001..=5000 => self.medium_score += 1,
+            5001..=10000 => self.high_score += 1,
+            _ => self.very_high_score += 1,
+        }
+    }
+}
+
+/// Which sessions to keep; older ones are compacted into `ArchivedTotals`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionPolicy {
+    /// Keep at most this many sessions, the most recent ones
+    pub max_sessions: Option<usize>,
+    /// Keep only sessions that ended within this many days
+    pub max_age_days: Option<u64>,
+}
+
+impl RetentionPolicy {
+    /// Keep every session
+    pub fn keep_all() -> Self {
+        Self::default()
+    }
+
+    /// Keep the last `count` sessions
+    pub fn last_sessions(count: usize) -> Self {
+        Self {
+            max_sessions: Some(count),
+            max_age_days: None,
+        }
+    }
+
+    /// Keep the sessions of the last `days` days
+    pub fn last_days(days: u64) -> Self {
+        Self {
+            max_sessions: None,
+            max_age_days: Some(days),
+        }
+    }
+}
+
+/// Totals of sessions removed by compaction, so the summary keeps counting them.
+/// Filtered, range and per-period queries only see the remaining sessions.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArchivedTotals {
+    /// Games compacted
+    pub games: u32,
+    /// Games won
+    pub games_won: u32,
+    /// Highest score
+    pub highest_score: u64,
+    /// Sum of all final scores
+    pub total_score: u64,
+    /// Sum of all moves
+    pub total_moves: u32,
+    /// Sum of all durations in seconds
+    pub total_play_time: u64,
+    /// Highest tile reached
+    pub highest_tile: u64,
+    /// How many games ended with each max tile
+    pub tile_distribution: HashMap<u64, u32>,
+    /// Games per score range
+    pub score_distribution: ScoreDistribution,
+    /// Summed move time of games with move timing, in milliseconds
+    pub timed_move_ms: f64,
+    /// Moves of games with move timing
+    pub timed_moves: u32,
+    /// Summed accuracy of analyzed games
+    pub total_accuracy: f64,
+    /// Games with an accuracy
+    pub analyzed_games: u32,
+}
+
+impl ArchivedTotals {
+    /// Count a session in the totals
+    fn add(&mut self, session: &GameSessionStats) {
+        self.games += 1;
+        self.games_won += u32::from(session.won);
+        self.highest_score = self.highest_score.max(session.final_score);
+        self.total_score += session.final_score;
+        self.total_moves += session.moves;
+        self.total_play_time += session.duration;
+        self.highest_tile = self.highest_tile.max(session.max_tile);
+        *self.tile_distribution.entry(session.max_tile).or_insert(0) += 1;
+        self.score_distribution.add(session.final_score);
+        if let Some(average_move_ms) = session.average_move_ms {
+            self.timed_move_ms += average_move_ms * session.moves as f64;
+            self.timed_moves += session.moves;
+        }
+        if let Some(accuracy) = session.accuracy {
+            self.total_accuracy += accuracy;
+            self.analyzed_games += 1;
+        }
+    }
 }
 
 /// Statistics manager for tracking and analyzing game data
 pub struct StatisticsManager {
-    /// Path to statistics file
-    stats_file: String,
+    /// Path to statistics file (`None` keeps statistics in memory only)
+    stats_file: Option<String>,
     /// All game sessions
     sessions: Vec<GameSessionStats>,
+    /// Totals of sessions removed by the retention policy
+    archived: ArchivedTotals,
+    retention: RetentionPolicy,
+}
+
+/// Version of the statistics file format written by this build.
+///
+/// Format 1 is a plain array of sessions. Format 2 is an object with the
+/// sessions, archived totals and retention policy; files written before the
+/// version was recorded in it are format 2 too. Bump the version and add a
+/// step to `migrate_stats` whenever a change to the sessions would stop
+/// older files from parsing. Widening scores and tiles from `u32` to `u64`
+/// needed no new format, as every older file still parses to the same
+/// values.
+pub const STATS_SCHEMA_VERSION: u32 = 2;
+
+/// Contents of a statistics file
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StatsData {
+    #[serde(default)]
+    schema_version: u32,
+    sessions: Vec<GameSessionStats>,
+    #[serde(default)]
+    archived: ArchivedTotals,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    retention: Option<RetentionPolicy>,
+}
+
+impl StatsData {
+    /// Parse statistics of any supported format, migrating older ones.
+    /// Returns the data and the format it was written in.
+    fn parse(content: &str) -> GameResult<(Self, u32)> {
+        let mut value: serde_json::Value = serde_json::from_str(content)
+            .map_err(|e| GameError::InvalidOperation(format!("Failed to parse stats: {}", e)))?;
+
+        let version = match &value {
+            serde_json::Value::Array(_) => 1,
+            serde_json::Value::Object(object) => match object.get("schema_version") {
+                Some(version) => version
+                    .as_u64()
+                    .and_then(|version| u32::try_from(version).ok())
+                    .ok_or_else(|| {
+                        GameError::InvalidOperation(format!(
+                            "Failed to parse stats: invalid schema version {}",
+                            version
+                        ))
+                    })?,
+                None => 2,
+            },
+            _ => {
+                return Err(GameError::InvalidOperation(
+                    "Failed to parse stats: not a statistics file".to_string(),
+                ))
+            }
+        };
+        if version > STATS_SCHEMA_VERSION {
+            return Err(GameError::UnsupportedVersion {
+                found: version,
+                supported: STATS_SCHEMA_VERSION,
+            });
+        }
+
+        for from in version..STATS_SCHEMA_VERSION {
+            value = migrate_stats(value, from);
+        }
+        let data = serde_json::from_value(value).map_err(|e| {
+            GameError::InvalidOperation(format!(
+                "Failed to parse stats (format {}): {}",
+                version, e
+            ))
+        })?;
+        Ok((data, version))
+    }
+}
+
+/// Upgrade statistics JSON from format `from` to `from + 1`
+fn migrate_stats(value: serde_json::Value, from: u32) -> serde_json::Value {
+    match from {
+        1 => serde_json::json!({ "sessions": value }),
+        _ => value,
+    }
 }
 
 impl StatisticsManager {
-    /// Create a new statistics manager
+    /// Create a new statistics manager.
+    ///
+    /// A file in an older format is migrated and rewritten, keeping the
+    /// original at [`stats_backup_path`]. A file written by a newer version
+    /// fails with [`GameError::UnsupportedVersion`] and is left untouched.
     pub fn new(stats_file: &str) -> GameResult<Self> {
         let mut manager = Self {
-            stats_file: stats_file.to_string(),
-            sessions: Vec::new(),
+            stats_file: Some(stats_file.to_string()),
+            ..Self::in_memory()
         };
 
         // Load existing statistics
@@ -109,134 +556,219 @@ impl StatisticsManager {
         Ok(manager)
     }
 
+    /// Stop saving to the statistics file and keep later changes in memory
+    /// only, e.g. while other data could not be read
+    pub fn detach_file(&mut self) {
+        self.stats_file = None;
+    }
+
+    /// Create a statistics manager that keeps sessions in memory only
+    pub fn in_memory() -> Self {
+        Self {
+            stats_file: None,
+            sessions: Vec::new(),
+            archived: ArchivedTotals::default(),
+            retention: RetentionPolicy::default(),
+        }
+    }
+
     /// Record a new game session
     pub fn record_session(&mut self, session: GameSessionStats) -> GameResult<()> {
         self.sessions.push(session);
+        self.compact_sessions(crate::get_current_time());
         self.save_statistics()?;
         Ok(())
     }
 
-    /// Get statistics summary
-    pub fn get_summary(&self) -> StatisticsSummary {
-        if self.sessions.is_empty() {
-            return StatisticsSummary {
-                total_games: 0,
-                games_won: 0,
-                win_rate: 0.0,
-                highest_score: 0,
-                average_score: 0.0,
-                total_moves: 0,
-                average_moves: 0.0,
-                total_play_time: 0,
-                average_duration: 0.0,
-                highest_tile: 0,
-                tile_distribution: HashMap::new(),
-                score_distribution: ScoreDistribution::default(),
-                recent_games: Vec::new(),
-            };
+    /// Replace session `session_id` with a later result of the same game,
+    /// e.g. the real end of a game continued after the win. The session
+    /// keeps its id and start time; it is recorded anew if it was compacted.
+    pub fn update_session(&mut self, session_id: u64, session: GameSessionStats) -> GameResult<()> {
+        match self
+            .sessions
+            .iter_mut()
+            .find(|recorded| recorded.session_id == session_id)
+        {
+            Some(recorded) => {
+                *recorded = GameSessionStats {
+                    session_id,
+                    start_time: recorded.start_time,
+                    ..session
+                };
+                self.save_statistics()
+            }
+            None => self.record_session(session),
         }
+    }
 
-        let total_games = self.sessions.len() as u32;
-        let games_won = self.sessions.iter().filter(|s| s.won).count() as u32;
-        let win_rate = (games_won as f64 / total_games as f64) * 100.0;
+    /// The retention policy; sessions are kept forever by default
+    pub fn retention(&self) -> RetentionPolicy {
+        self.retention
+    }
 
-        let highest_score = self
-            .sessions
-            .iter()
-            .map(|s| s.final_score)
-            .max()
-            .unwrap_or(0);
-        let average_score = self
-            .sessions
-            .iter()
-            .map(|s| s.final_score as f64)
-            .sum::<f64>()
-            / total_games as f64;
+    /// Change the retention policy and compact right away.
+    /// Returns the number of sessions compacted.
+    pub fn set_retention(&mut self, retention: RetentionPolicy) -> GameResult<usize> {
+        self.retention = retention;
+        let compacted = self.compact_sessions(crate::get_current_time());
+        self.save_statistics()?;
+        Ok(compacted)
+    }
 
-        let total_moves = self.sessions.iter().map(|s| s.moves).sum::<u32>();
-        let average_moves = total_moves as f64 / total_games as f64;
+    /// Compact sessions the retention policy no longer keeps, e.g. ones that
+    /// aged out since the last game. Returns the number of sessions compacted.
+    pub fn compact(&mut self) -> GameResult<usize> {
+        let compacted = self.compact_sessions(crate::get_current_time());
+        if compacted > 0 {
+            self.save_statistics()?;
+        }
+        Ok(compacted)
+    }
+
+    /// Totals of the sessions compacted so far
+    pub fn archived_totals(&self) -> &ArchivedTotals {
+        &self.archived
+    }
 
-        let total_play_time = self.sessions.iter().map(|s| s.duration).sum::<u64>();
-        let average_duration = total_play_time as f64 / total_games as f64;
+    /// Move the sessions the retention policy drops into the archived totals
+    fn compact_sessions(&mut self, now: u64) -> usize {
+        if self.retention == RetentionPolicy::keep_all() {
+            return 0;
+        }
 
-        let highest_tile = self.sessions.iter().map(|s| s.max_tile).max().unwrap_or(0);
+        let excess = self
+            .retention
+            .max_sessions
+            .map_or(0, |max| self.sessions.len().saturating_sub(max));
+        let cutoff = self
+            .retention
+            .max_age_days
+            .map(|days| now.saturating_sub(days * SECONDS_PER_DAY));
 
-        // Calculate tile distribution
-        let mut tile_distribution = HashMap::new();
-        for session in &self.sessions {
-            *tile_distribution.entry(session.max_tile).or_insert(0) += 1;
+        self.sessions.sort_by_key(|session| session.end_time);
+        let (dropped, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut self.sessions)
+            .into_iter()
+            .enumerate()
+            .partition(|(i, session)| {
+                *i < excess || cutoff.is_some_and(|cutoff| session.end_time < cutoff)
+            });
+        for (_, session) in &dropped {
+            self.archived.add(session);
         }
+        self.sessions = kept.into_iter().map(|(_, session)| session).collect();
+        dropped.len()
+    }
+
+    /// Get statistics summary
+    pub fn get_summary(&self) -> StatisticsSummary {
+        summarize(&self.sessions, &self.archived)
+    }
+
+    /// Get the statistics summary of the sessions passing a filter
+    pub fn get_summary_filtered(&self, filter: &SessionFilter) -> StatisticsSummary {
+        summarize(&self.filtered(filter), &ArchivedTotals::default())
+    }
 
-        // Calculate score distribution
-        let mut score_distribution = ScoreDistribution::default();
+    /// Configuration segments that have recorded sessions, with their game counts
+    pub fn get_segments(&self) -> Vec<(SessionSegment, u32)> {
+        let mut segments = BTreeMap::new();
         for session in &self.sessions {
-            match session.final_score {
-                0..=1000 => score_distribution.low_score += 1,
-                1001..=5000 => score_distribution.medium_score += 1,
-                5001..=10000 => score_distribution.high_score += 1,
-                _ => score_distribution.very_high_score += 1,
-            }
+            *segments.entry(session.segment()).or_insert(0) += 1;
         }
+        segments.into_iter().collect()
+    }
 
-        // Get recent games (last 10)
-        let mut recent_games = self.sessions.clone();
-        recent_games.sort_by_key(|session| Reverse(session.end_time));
-        recent_games.truncate(10);
-
-        StatisticsSummary {
-            total_games,
-            games_won,
-            win_rate,
-            highest_score,
-            average_score,
-            total_moves,
-            average_moves,
-            total_play_time,
-            average_duration,
-            highest_tile,
-            tile_distribution,
-            score_distribution,
-            recent_games,
+    /// Get the statistics summary of the games that ended in `[start, end)` (Unix timestamps)
+    pub fn get_summary_for_range(&self, start: u64, end: u64) -> StatisticsSummary {
+        let sessions: Vec<GameSessionStats> = self
+            .sessions
+            .iter()
+            .filter(|session| (start..end).contains(&session.end_time))
+            .cloned()
+            .collect();
+        summarize(&sessions, &ArchivedTotals::default())
+    }
+
+    /// Totals per period for every period with games, oldest first
+    pub fn get_totals(&self, period: StatsPeriod) -> Vec<PeriodTotals> {
+        let mut totals: BTreeMap<u64, PeriodTotals> = BTreeMap::new();
+        for session in &self.sessions {
+            let (start, end) = period.range_containing(session.end_time);
+            let entry = totals.entry(start).or_insert_with(|| PeriodTotals {
+                date: date_string(start / SECONDS_PER_DAY),
+                start,
+                end,
+                games: 0,
+                games_won: 0,
+                best_score: 0,
+                total_score: 0,
+                highest_tile: 0,
+                play_time: 0,
+            });
+            entry.games += 1;
+            entry.games_won += u32::from(session.won);
+            entry.best_score = entry.best_score.max(session.final_score);
+            entry.total_score += session.final_score;
+            entry.highest_tile = entry.highest_tile.max(session.max_tile);
+            entry.play_time += session.duration;
         }
+        totals.into_values().collect()
+    }
+
+    /// Totals per UTC day for every day with games, oldest first
+    pub fn get_daily_totals(&self) -> Vec<PeriodTotals> {
+        self.get_totals(StatsPeriod::Day)
     }
 
     /// Get score trend data (last N games)
-    pub fn get_score_trend(&self, count: usize) -> Vec<(u32, u32)> {
-        let mut recent_sessions = self.sessions.clone();
-        recent_sessions.sort_by_key(|session| Reverse(session.end_time));
-        recent_sessions.truncate(count);
-        recent_sessions.reverse();
+    pub fn get_score_trend(&self, count: usize) -> Vec<(u32, u64)> {
+        score_trend(self.sessions.clone(), count)
+    }
 
-        recent_sessions
-            .iter()
-            .enumerate()
-            .map(|(i, session)| (i as u32, session.final_score))
-            .collect()
+    /// Get score trend data of the last N games passing a filter
+    pub fn get_score_trend_filtered(
+        &self,
+        filter: &SessionFilter,
+        count: usize,
+    ) -> Vec<(u32, u64)> {
+        score_trend(self.filtered(filter), count)
     }
 
     /// Get efficiency trend data (last N games)
     pub fn get_efficiency_trend(&self, count: usize) -> Vec<(u32, f64)> {
-        let mut recent_sessions = self.sessions.clone();
-        recent_sessions.sort_by_key(|session| Reverse(session.end_time));
-        recent_sessions.truncate(count);
-        recent_sessions.reverse();
+        efficiency_trend(self.sessions.clone(), count)
+    }
+
+    /// Get efficiency trend data of the last N games passing a filter
+    pub fn get_efficiency_trend_filtered(
+        &self,
+        filter: &SessionFilter,
+        count: usize,
+    ) -> Vec<(u32, f64)> {
+        efficiency_trend(self.filtered(filter), count)
+    }
 
-        recent_sessions
+    /// Sessions passing a filter
+    fn filtered(&self, filter: &SessionFilter) -> Vec<GameSessionStats> {
+        self.sessions
             .iter()
-            .enumerate()
-            .map(|(i, session)| (i as u32, session.efficiency))
+            .filter(|session| filter.matches(session))
+            .cloned()
             .collect()
     }
 
     /// Get tile achievement data
-    pub fn get_tile_achievements(&self) -> Vec<(u32, u32)> {
-        let mut tile_counts: Vec<(u32, u32)> = self
+    pub fn get_tile_achievements(&self) -> Vec<(u64, u32)> {
+        let mut tile_counts: Vec<(u64, u32)> = self
             .sessions
             .iter()
-            .fold(HashMap::new(), |mut acc, session| {
-                *acc.entry(session.max_tile).or_insert(0) += 1;
-                acc
-            })
+            .fold(
+                self.archived.tile_distribution.clone(),
+                |mut acc, session| {
+                    *acc.entry(session.max_tile).or_insert(0) += 1;
+                    acc
+                },
+            )
             .into_iter()
             .collect();
 
@@ -244,30 +776,72 @@ impl StatisticsManager {
         tile_counts
     }
 
+    /// Heatmaps of the final boards, one per board size, smallest size first.
+    /// Sessions recorded without their final board are left out.
+    pub fn get_position_heatmap(&self) -> Vec<PositionHeatmap> {
+        let mut heatmaps: BTreeMap<usize, PositionHeatmap> = BTreeMap::new();
+        for board in self.sessions.iter().filter_map(|s| s.final_board.as_ref()) {
+            if board.is_empty() || board.iter().any(|row| row.len() != board.len()) {
+                continue;
+            }
+            heatmaps
+                .entry(board.len())
+                .or_insert_with(|| PositionHeatmap::new(board.len()))
+                .add(board);
+        }
+        heatmaps.into_values().collect()
+    }
+
     /// Load statistics from file
     fn load_statistics(&mut self) -> GameResult<()> {
-        if !Path::new(&self.stats_file).exists() {
+        let Some(stats_file) = &self.stats_file else {
+            return Ok(());
+        };
+        if !Path::new(stats_file).exists() {
             return Ok(());
         }
 
-        let content = fs::read_to_string(&self.stats_file).map_err(|e| {
+        let content = fs::read_to_string(stats_file).map_err(|e| {
             GameError::InvalidOperation(format!("Failed to read stats file: {}", e))
         })?;
 
-        self.sessions = serde_json::from_str(&content).map_err(|e| {
-            GameError::InvalidOperation(format!("Failed to parse stats file: {}", e))
-        })?;
+        let (data, version) = StatsData::parse(&content)?;
+        self.sessions = data.sessions;
+        self.archived = data.archived;
+        self.retention = data.retention.unwrap_or_default();
+
+        // Older builds cannot read the migrated file, so keep the original
+        if version < STATS_SCHEMA_VERSION {
+            fs::copy(stats_file, stats_backup_path(stats_file, version)).map_err(|e| {
+                GameError::InvalidOperation(format!("Failed to back up stats file: {}", e))
+            })?;
+            self.save_statistics()?;
+        }
 
         Ok(())
     }
 
+    /// Serialize the statistics in the stats file format
+    fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(&StatsData {
+            schema_version: STATS_SCHEMA_VERSION,
+            sessions: self.sessions.clone(),
+            archived: self.archived.clone(),
+            retention: Some(self.retention),
+        })
+    }
+
     /// Save statistics to file
     fn save_statistics(&self) -> GameResult<()> {
-        let content = serde_json::to_string_pretty(&self.sessions).map_err(|e| {
+        let Some(stats_file) = &self.stats_file else {
+            return Ok(());
+        };
+
+        let content = self.to_json().map_err(|e| {
             GameError::InvalidOperation(format!("Failed to serialize stats: {}", e))
         })?;
 
-        fs::write(&self.stats_file, content).map_err(|e| {
+        fs::write(stats_file, content).map_err(|e| {
             GameError::InvalidOperation(format!("Failed to write stats file: {}", e))
         })?;
 
@@ -277,23 +851,220 @@ impl StatisticsManager {
     /// Clear all statistics
     pub fn clear_statistics(&mut self) -> GameResult<()> {
         self.sessions.clear();
+        self.archived = ArchivedTotals::default();
         self.save_statistics()?;
         Ok(())
     }
 
     /// Export statistics to JSON
     pub fn export_statistics(&self) -> GameResult<String> {
-        serde_json::to_string_pretty(&self.sessions)
+        self.to_json()
             .map_err(|e| GameError::InvalidOperation(format!("Failed to export stats: {}", e)))
     }
+
+    /// Get all recorded sessions
+    pub fn sessions(&self) -> &[GameSessionStats] {
+        &self.sessions
+    }
+
+    /// Add sessions recorded elsewhere, skipping ones already present (same session ID).
+    /// Returns the number of sessions added.
+    pub fn merge_sessions(&mut self, sessions: Vec<GameSessionStats>) -> GameResult<usize> {
+        let before = self.sessions.len();
+        for session in sessions {
+            if !self
+                .sessions
+                .iter()
+                .any(|existing| existing.session_id == session.session_id)
+            {
+                self.sessions.push(session);
+            }
+        }
+        self.sessions.sort_by_key(|session| session.end_time);
+
+        let added = self.sessions.len() - before;
+        if added > 0 {
+            self.save_statistics()?;
+        }
+        Ok(added)
+    }
+
+    /// Merge the statistics file of another device into these statistics.
+    ///
+    /// A session already recorded is skipped; a different game whose ID is
+    /// taken gets the next free ID. Timestamps from a clock running ahead
+    /// are moved back so no game ends in the future, and start times are
+    /// corrected to lie before end times. Summaries and trends include the
+    /// merged sessions right away.
+    pub fn import_and_merge(&mut self, path: impl AsRef<Path>) -> GameResult<MergeReport> {
+        let content = fs::read_to_string(path).map_err(|e| {
+            GameError::InvalidOperation(format!("Failed to read stats file: {}", e))
+        })?;
+        // Totals the other device compacted are not merged, as they may
+        // include games that are recorded here too
+        let sessions = StatsData::parse(&content)?.0.sessions;
+
+        let now = crate::get_current_time();
+        let mut report = MergeReport::default();
+        for mut session in sessions {
+            if correct_clock_skew(&mut session, now) {
+                report.adjusted += 1;
+            }
+
+            // A renumbered game from an earlier merge still has its start time
+            if self.sessions.iter().any(|existing| {
+                (existing.session_id == session.session_id
+                    || existing.start_time == session.start_time)
+                    && is_same_game(existing, &session)
+            }) {
+                report.duplicates += 1;
+                continue;
+            }
+
+            let id_taken = |id| {
+                self.sessions
+                    .iter()
+                    .any(|existing| existing.session_id == id)
+            };
+            if id_taken(session.session_id) {
+                while id_taken(session.session_id) {
+                    session.session_id += 1;
+                }
+                report.renumbered += 1;
+            }
+            self.sessions.push(session);
+            report.added += 1;
+        }
+        self.sessions.sort_by_key(|session| session.end_time);
+
+        if report.added > 0 {
+            self.compact_sessions(now);
+            self.save_statistics()?;
+        }
+        Ok(report)
+    }
+
+    /// Replace all sessions with previously exported statistics JSON
+    pub fn import_statistics(&mut self, content: &str) -> GameResult<()> {
+        let (data, _) = StatsData::parse(content)?;
+        if let Some(retention) = data.retention {
+            self.retention = retention;
+        }
+        self.sessions = data.sessions;
+        self.archived = data.archived;
+        self.compact_sessions(crate::get_current_time());
+        self.save_statistics()
+    }
+
+    /// Replace all sessions, dropping the archived totals
+    pub fn replace_sessions(&mut self, sessions: Vec<GameSessionStats>) -> GameResult<()> {
+        self.sessions = sessions;
+        self.archived = ArchivedTotals::default();
+        self.compact_sessions(crate::get_current_time());
+        self.save_statistics()
+    }
+}
+
+/// Where the original of a stats file migrated from format `version` is kept
+/// (e.g. `stats.json.v1.bak`)
+pub fn stats_backup_path(stats_file: &str, version: u32) -> String {
+    format!("{}.v{}.bak", stats_file, version)
+}
+
+/// Whether two sessions with the same ID record the same game
+fn is_same_game(a: &GameSessionStats, b: &GameSessionStats) -> bool {
+    a.final_score == b.final_score
+        && a.moves == b.moves
+        && a.max_tile == b.max_tile
+        && a.duration == b.duration
+}
+
+/// Fix timestamps of a session recorded with a wrong clock; returns whether
+/// anything changed. The session ID keeps the original start time.
+fn correct_clock_skew(session: &mut GameSessionStats, now: u64) -> bool {
+    let mut adjusted = false;
+    if session.end_time > now {
+        let skew = session.end_time - now;
+        session.end_time = now;
+        session.start_time = session.start_time.saturating_sub(skew);
+        adjusted = true;
+    }
+    if session.start_time > session.end_time {
+        session.start_time = session.end_time.saturating_sub(session.duration);
+        adjusted = true;
+    }
+    adjusted
+}
+
+/// Summarize a set of sessions together with the totals of compacted ones
+fn summarize(sessions: &[GameSessionStats], archived: &ArchivedTotals) -> StatisticsSummary {
+    let mut totals = archived.clone();
+    for session in sessions {
+        totals.add(session);
+    }
+
+    let games = totals.games.max(1) as f64;
+    let average_move_ms =
+        (totals.timed_moves > 0).then(|| totals.timed_move_ms / totals.timed_moves as f64);
+    let average_accuracy =
+        (totals.analyzed_games > 0).then(|| totals.total_accuracy / totals.analyzed_games as f64);
+
+    // Get recent games (last 10)
+    let mut recent_games = sessions.to_vec();
+    recent_games.sort_by_key(|session| Reverse(session.end_time));
+    recent_games.truncate(10);
+
+    StatisticsSummary {
+        total_games: totals.games,
+        games_won: totals.games_won,
+        win_rate: (totals.games_won as f64 / games) * 100.0,
+        highest_score: totals.highest_score,
+        average_score: totals.total_score as f64 / games,
+        total_moves: totals.total_moves,
+        average_moves: totals.total_moves as f64 / games,
+        total_play_time: totals.total_play_time,
+        average_duration: totals.total_play_time as f64 / games,
+        highest_tile: totals.highest_tile,
+        tile_distribution: totals.tile_distribution,
+        score_distribution: totals.score_distribution,
+        recent_games,
+        average_move_ms,
+        average_accuracy,
+    }
+}
+
+/// Scores of the last `count` sessions, oldest first
+fn score_trend(mut sessions: Vec<GameSessionStats>, count: usize) -> Vec<(u32, u64)> {
+    sessions.sort_by_key(|session| Reverse(session.end_time));
+    sessions.truncate(count);
+    sessions.reverse();
+
+    sessions
+        .iter()
+        .enumerate()
+        .map(|(i, session)| (i as u32, session.final_score))
+        .collect()
+}
+
+/// Efficiency of the last `count` sessions, oldest first
+fn efficiency_trend(mut sessions: Vec<GameSessionStats>, count: usize) -> Vec<(u32, f64)> {
+    sessions.sort_by_key(|session| Reverse(session.end_time));
+    sessions.truncate(count);
+    sessions.reverse();
+
+    sessions
+        .iter()
+        .enumerate()
+        .map(|(i, session)| (i as u32, session.efficiency))
+        .collect()
 }
 
 /// Helper function to create a game session from game stats
 pub fn create_session_stats(
-    final_score: u32,
+    final_score: u64,
     moves: u32,
     duration: u64,
-    max_tile: u32,
+    max_tile: u64,
     won: bool,
     start_time: u64,
     end_time: u64,
@@ -328,5 +1099,371 @@ pub fn create_session_stats(
         end_time,
         avg_score_per_move,
         efficiency,
+        average_move_ms: None,
+        fastest_move_ms: None,
+        slowest_move_ms: None,
+        daily: None,
+        config: None,
+        player: PlayerType::Human,
+        final_board: None,
+        accuracy: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filtered_queries() {
+        let mut manager = StatisticsManager::in_memory();
+        let large = GameConfig {
+            board_size: 5,
+            ..GameConfig::default()
+        };
+        // A session from before configurations were recorded
+        manager
+            .record_session(create_session_stats(100, 10, 5, 64, false, 1, 2))
+            .unwrap();
+        manager
+            .record_session(create_session_stats(500, 40, 20, 256, false, 3, 4).with_config(&large))
+            .unwrap();
+        manager
+            .record_session(
+                create_session_stats(900, 60, 30, 512, false, 5, 6)
+                    .with_config(&large)
+                    .with_player(PlayerType::Ai),
+            )
+            .unwrap();
+
+        let large_human = SessionFilter {
+            board_size: Some(5),
+            player: Some(PlayerType::Human),
+            ..SessionFilter::default()
+        };
+        let summary = manager.get_summary_filtered(&large_human);
+        assert_eq!(summary.total_games, 1);
+        assert_eq!(summary.highest_score, 500);
+
+        let classic = SessionFilter {
+            board_size: Some(4),
+            rules: Some(crate::rules::CLASSIC_RULES.to_string()),
+            seeded: Some(false),
+            ..SessionFilter::default()
+        };
+        assert_eq!(
+            manager.get_score_trend_filtered(&classic, 10),
+            vec![(0, 100)]
+        );
+        assert_eq!(
+            manager
+                .get_summary_filtered(&SessionFilter::default())
+                .total_games,
+            3
+        );
+
+        let segments = manager.get_segments();
+        assert_eq!(segments.len(), 3);
+        assert!(segments.iter().all(|(_, count)| *count == 1));
+        let (ai, _) = &segments[2];
+        assert_eq!(ai.player, PlayerType::Ai);
+        assert_eq!(
+            manager
+                .get_summary_filtered(&SessionFilter::for_segment(ai))
+                .highest_score,
+            900
+        );
+    }
+
+    #[test]
+    fn test_update_session() {
+        let mut manager = StatisticsManager::in_memory();
+        manager
+            .record_session(create_session_stats(100, 10, 5, 64, false, 1, 2))
+            .unwrap();
+        manager
+            .record_session(create_session_stats(2_500, 300, 60, 2048, true, 10, 70))
+            .unwrap();
+
+        // The won game was continued and ended later with a higher score
+        manager
+            .update_session(
+                10,
+                create_session_stats(9_000, 700, 200, 4096, true, 12, 212),
+            )
+            .unwrap();
+        let sessions = manager.sessions();
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[1].session_id, 10);
+        assert_eq!(sessions[1].start_time, 10);
+        assert_eq!(sessions[1].final_score, 9_000);
+        assert_eq!(sessions[1].max_tile, 4096);
+        let summary = manager.get_summary();
+        assert_eq!(summary.total_games, 2);
+        assert_eq!(summary.highest_score, 9_000);
+
+        // A session that is no longer kept is recorded as new
+        manager
+            .update_session(99, create_session_stats(300, 30, 10, 128, false, 99, 109))
+            .unwrap();
+        assert_eq!(manager.sessions().len(), 3);
+    }
+
+    #[test]
+    fn test_position_heatmap() {
+        let mut manager = StatisticsManager::in_memory();
+        let boards = [
+            vec![vec![8, 2], vec![0, 0]],
+            vec![vec![8, 0], vec![4, 8]],
+            vec![vec![2, 2, 0], vec![0, 4, 0], vec![0, 0, 0]],
+        ];
+        for board in boards {
+            let board = Board::from_vec(board).unwrap();
+            manager
+                .record_session(
+                    create_session_stats(0, 0, 0, 0, false, 0, 0).with_final_board(&board),
+                )
+                .unwrap();
+        }
+        // Sessions without a final board are not counted
+        manager
+            .record_session(create_session_stats(0, 0, 0, 0, false, 0, 0))
+            .unwrap();
+
+        let heatmaps = manager.get_position_heatmap();
+        assert_eq!(heatmaps.len(), 2);
+        assert_eq!(heatmaps[0].board_size, 2);
+        assert_eq!(heatmaps[0].games, 2);
+        assert_eq!(heatmaps[0].max_tile, vec![vec![2, 0], vec![0, 1]]);
+        assert_eq!(heatmaps[0].occupancy, vec![vec![2, 1], vec![1, 1]]);
+        assert_eq!(heatmaps[1].max_tile[1][1], 1);
+        assert_eq!(heatmaps[1].games, 1);
+    }
+
+    #[test]
+    fn test_period_totals() {
+        // 2025-10-15 was a Wednesday
+        let day = crate::daily::parse_date("2025-10-15").unwrap() * SECONDS_PER_DAY;
+        let (start, end) = StatsPeriod::Week.range_containing(day + 3600);
+        assert_eq!(date_string(start / SECONDS_PER_DAY), "2025-10-13");
+        assert_eq!(date_string(end / SECONDS_PER_DAY), "2025-10-20");
+        let (start, end) = StatsPeriod::Month.range_containing(day);
+        assert_eq!(date_string(start / SECONDS_PER_DAY), "2025-10-01");
+        assert_eq!(date_string(end / SECONDS_PER_DAY), "2025-11-01");
+        let (start, end) = StatsPeriod::Month.range_containing(day + 60 * SECONDS_PER_DAY);
+        assert_eq!(date_string(start / SECONDS_PER_DAY), "2025-12-01");
+        assert_eq!(date_string(end / SECONDS_PER_DAY), "2026-01-01");
+
+        let mut manager = StatisticsManager::in_memory();
+        for (score, end_time) in [
+            (100, day + 10),
+            (300, day + 20),
+            (200, day + SECONDS_PER_DAY),
+        ] {
+            manager
+                .record_session(create_session_stats(
+                    score,
+                    10,
+                    5,
+                    64,
+                    false,
+                    end_time - 5,
+                    end_time,
+                ))
+                .unwrap();
+        }
+
+        let daily = manager.get_daily_totals();
+        assert_eq!(daily.len(), 2);
+        assert_eq!(daily[0].date, "2025-10-15");
+        assert_eq!(
+            (daily[0].games, daily[0].best_score, daily[0].total_score),
+            (2, 300, 400)
+        );
+        assert_eq!(daily[1].date, "2025-10-16");
+
+        let weekly = manager.get_totals(StatsPeriod::Week);
+        assert_eq!(weekly.len(), 1);
+        assert_eq!((weekly[0].games, weekly[0].play_time), (3, 15));
+
+        let summary = manager.get_summary_for_range(day, day + SECONDS_PER_DAY);
+        assert_eq!(summary.total_games, 2);
+        assert_eq!(summary.highest_score, 300);
+    }
+
+    #[test]
+    fn test_import_and_merge() {
+        let mut manager = StatisticsManager::in_memory();
+        manager
+            .record_session(create_session_stats(100, 10, 5, 64, false, 1_000, 1_005))
+            .unwrap();
+
+        let now = crate::get_current_time();
+        let other_device = vec![
+            // Already recorded
+            create_session_stats(100, 10, 5, 64, false, 1_000, 1_005),
+            // Same ID, different game
+            create_session_stats(300, 30, 20, 128, false, 1_000, 1_020),
+            // Recorded with a clock an hour ahead
+            create_session_stats(500, 50, 60, 256, false, now + 3_540, now + 3_600),
+        ];
+        let path = std::env::temp_dir().join(format!("rusty2048-merge-{}", std::process::id()));
+        fs::write(&path, serde_json::to_string(&other_device).unwrap()).unwrap();
+
+        let report = manager.import_and_merge(&path).unwrap();
+        assert_eq!(
+            report,
+            MergeReport {
+                added: 2,
+                duplicates: 1,
+                renumbered: 1,
+                adjusted: 1,
+            }
+        );
+        let sessions = manager.sessions();
+        assert_eq!(sessions.len(), 3);
+        assert_eq!(sessions[1].session_id, 1_001);
+        assert!(sessions[2].end_time <= crate::get_current_time());
+        assert_eq!(sessions[2].end_time - sessions[2].start_time, 60);
+        assert_eq!(manager.get_summary().highest_score, 500);
+
+        // Merging again adds nothing
+        let report = manager.import_and_merge(&path).unwrap();
+        assert_eq!((report.added, report.duplicates), (0, 3));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_merge_applies_retention() {
+        let mut manager = StatisticsManager::in_memory();
+        manager
+            .set_retention(RetentionPolicy::last_sessions(2))
+            .unwrap();
+        manager
+            .record_session(create_session_stats(100, 10, 5, 64, false, 1_000, 1_005))
+            .unwrap();
+
+        let other_device = vec![
+            create_session_stats(200, 20, 10, 128, false, 2_000, 2_010),
+            create_session_stats(300, 30, 20, 256, false, 3_000, 3_020),
+        ];
+        let path =
+            std::env::temp_dir().join(format!("rusty2048-merge-retention-{}", std::process::id()));
+        fs::write(&path, serde_json::to_string(&other_device).unwrap()).unwrap();
+
+        assert_eq!(manager.import_and_merge(&path).unwrap().added, 2);
+        assert_eq!(manager.sessions().len(), 2);
+        assert_eq!(manager.archived_totals().games, 1);
+        assert_eq!(manager.get_summary().total_games, 3);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_retention_keeps_summary() {
+        let now = crate::get_current_time();
+        let mut manager = StatisticsManager::in_memory();
+        for (i, score) in [100u64, 2_000, 6_000, 300].into_iter().enumerate() {
+            let end_time = now - (10 - i as u64) * SECONDS_PER_DAY;
+            let mut session =
+                create_session_stats(score, 10, 60, 128, score > 5_000, end_time - 60, end_time);
+            // Only some games were analyzed
+            session.accuracy = (i % 2 == 0).then_some(80.0 + i as f64 * 5.0);
+            manager.record_session(session).unwrap();
+        }
+        let before = manager.get_summary();
+        assert_eq!(before.average_accuracy, Some(85.0));
+
+        assert_eq!(
+            manager
+                .set_retention(RetentionPolicy::last_sessions(2))
+                .unwrap(),
+            2
+        );
+        assert_eq!(manager.sessions().len(), 2);
+        assert_eq!(manager.archived_totals().games, 2);
+        // The two most recent games are kept
+        assert_eq!(manager.sessions()[0].final_score, 6_000);
+
+        let after = manager.get_summary();
+        assert_eq!(after.total_games, before.total_games);
+        assert_eq!(after.games_won, 1);
+        assert_eq!(after.highest_score, 6_000);
+        assert_eq!(after.average_score, before.average_score);
+        assert_eq!(after.total_play_time, before.total_play_time);
+        assert_eq!(after.score_distribution, before.score_distribution);
+        assert_eq!(after.average_accuracy, before.average_accuracy);
+        assert_eq!(manager.get_tile_achievements(), vec![(128, 4)]);
+
+        // Only the last game ended within 7 days
+        manager
+            .set_retention(RetentionPolicy::last_days(7))
+            .unwrap();
+        assert_eq!(manager.sessions().len(), 1);
+        manager
+            .record_session(create_session_stats(50, 5, 10, 16, false, now - 10, now))
+            .unwrap();
+        assert_eq!(manager.get_summary().total_games, 5);
+
+        // Archived totals and the policy survive an export
+        let mut restored = StatisticsManager::in_memory();
+        restored
+            .import_statistics(&manager.export_statistics().unwrap())
+            .unwrap();
+        assert_eq!(restored.retention(), RetentionPolicy::last_days(7));
+        assert_eq!(restored.get_summary().total_games, 5);
+        assert_eq!(restored.get_summary().highest_score, 6_000);
+    }
+
+    #[test]
+    fn test_schema_migration() {
+        let path = std::env::temp_dir().join(format!("rusty2048-schema-{}", std::process::id()));
+        let stats_file = path.to_string_lossy().to_string();
+        let legacy = vec![create_session_stats(100, 10, 5, 64, false, 1_000, 1_005)];
+        let legacy_json = serde_json::to_string(&legacy).unwrap();
+        fs::write(&path, &legacy_json).unwrap();
+
+        // A plain array is migrated, keeping the original as a backup
+        let manager = StatisticsManager::new(&stats_file).unwrap();
+        assert_eq!(manager.sessions().len(), 1);
+        let backup = stats_backup_path(&stats_file, 1);
+        assert_eq!(fs::read_to_string(&backup).unwrap(), legacy_json);
+        let migrated: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(migrated["schema_version"], STATS_SCHEMA_VERSION);
+        assert_eq!(migrated["sessions"].as_array().unwrap().len(), 1);
+
+        // Files of newer versions are refused and left alone
+        let newer = format!(r#"{{"schema_version": 99, "sessions": {}}}"#, legacy_json);
+        fs::write(&path, &newer).unwrap();
+        assert!(matches!(
+            StatisticsManager::new(&stats_file),
+            Err(GameError::UnsupportedVersion {
+                found: 99,
+                supported: STATS_SCHEMA_VERSION,
+            })
+        ));
+        assert_eq!(fs::read_to_string(&path).unwrap(), newer);
+
+        fs::remove_file(&path).unwrap();
+        fs::remove_file(&backup).unwrap();
+    }
+
+    #[test]
+    fn test_detach_file() {
+        let path = std::env::temp_dir().join(format!("rusty2048-detach-{}", std::process::id()));
+        let stats_file = path.to_string_lossy().to_string();
+        let mut manager = StatisticsManager::new(&stats_file).unwrap();
+        manager
+            .record_session(create_session_stats(100, 10, 5, 64, false, 1, 2))
+            .unwrap();
+        let saved = fs::read_to_string(&path).unwrap();
+
+        manager.detach_file();
+        manager
+            .record_session(create_session_stats(200, 20, 5, 128, false, 3, 4))
+            .unwrap();
+        assert_eq!(manager.sessions().len(), 2);
+        assert_eq!(fs::read_to_string(&path).unwrap(), saved);
+        fs::remove_file(&path).unwrap();
     }
 }
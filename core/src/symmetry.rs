@@ -0,0 +1,171 @@
+//! The eight symmetries of a square board.
+//!
+//! Rotating or reflecting a position does not change how good it is, so AI
+//! caches and opening books can store one canonical form per position.
+
+use crate::{Board, Direction};
+use serde::{Deserialize, Serialize};
+
+/// A rotation or reflection of the board
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Symmetry {
+    Identity,
+    /// Quarter turn clockwise
+    Rotate90,
+    Rotate180,
+    /// Quarter turn counterclockwise
+    Rotate270,
+    /// Mirror left to right
+    FlipHorizontal,
+    /// Mirror top to bottom
+    FlipVertical,
+    /// Mirror along the main diagonal
+    Transpose,
+    /// Mirror along the anti-diagonal
+    AntiTranspose,
+}
+
+impl Symmetry {
+    /// All eight symmetries, starting with the identity
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::Rotate90,
+        Symmetry::Rotate180,
+        Symmetry::Rotate270,
+        Symmetry::FlipHorizontal,
+        Symmetry::FlipVertical,
+        Symmetry::Transpose,
+        Symmetry::AntiTranspose,
+    ];
+
+    /// The symmetry that undoes this one
+    pub fn inverse(self) -> Self {
+        match self {
+            Symmetry::Rotate90 => Symmetry::Rotate270,
+            Symmetry::Rotate270 => Symmetry::Rotate90,
+            other => other,
+        }
+    }
+
+    /// Where the cell (row, col) of a board of the given size ends up
+    pub fn apply(self, row: usize, col: usize, size: usize) -> (usize, usize) {
+        let last = size - 1;
+        match self {
+            Symmetry::Identity => (row, col),
+            Symmetry::Rotate90 => (col, last - row),
+            Symmetry::Rotate180 => (last - row, last - col),
+            Symmetry::Rotate270 => (last - col, row),
+            Symmetry::FlipHorizontal => (row, last - col),
+            Symmetry::FlipVertical => (last - row, col),
+            Symmetry::Transpose => (col, row),
+            Symmetry::AntiTranspose => (last - col, last - row),
+        }
+    }
+
+    /// The move on the transformed board that matches `direction` on the
+    /// original one
+    pub fn apply_direction(self, direction: Direction) -> Direction {
+        let (row, col) = match direction {
+            Direction::Up => (-1, 0),
+            Direction::Down => (1, 0),
+            Direction::Left => (0, -1),
+            Direction::Right => (0, 1),
+        };
+        let (row, col) = match self {
+            Symmetry::Identity => (row, col),
+            Symmetry::Rotate90 => (col, -row),
+            Symmetry::Rotate180 => (-row, -col),
+            Symmetry::Rotate270 => (-col, row),
+            Symmetry::FlipHorizontal => (row, -col),
+            Symmetry::FlipVertical => (-row, col),
+            Symmetry::Transpose => (col, row),
+            Symmetry::AntiTranspose => (-col, -row),
+        };
+        match (row, col) {
+            (-1, 0) => Direction::Up,
+            (1, 0) => Direction::Down,
+            (0, -1) => Direction::Left,
+            _ => Direction::Right,
+        }
+    }
+}
+
+impl Board {
+    /// A copy of the board with a symmetry applied
+    pub fn transformed(&self, symmetry: Symmetry) -> Board {
+        let size = self.size();
+        let mut board = Board::new(size).expect("board size is valid");
+        for (row, col, tile) in self.iter_tiles() {
+            let (row, col) = symmetry.apply(row, col, size);
+            board
+                .set_tile(row, col, tile)
+                .expect("symmetries keep cells on the board");
+        }
+        board
+    }
+
+    /// The lexicographically smallest of the board's eight symmetric forms
+    /// (comparing tile values row by row), with the symmetry that produces
+    /// it from this board. Symmetric positions share one canonical form.
+    pub fn canonical(&self) -> (Board, Symmetry) {
+        Symmetry::ALL
+            .iter()
+            .map(|&symmetry| (self.transformed(symmetry), symmetry))
+            .min_by(|(a, _), (b, _)| {
+                let a = a.as_flat_slice().iter().map(|tile| tile.value);
+                a.cmp(b.as_flat_slice().iter().map(|tile| tile.value))
+            })
+            .expect("there are eight symmetries")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::invariants::slide;
+
+    fn sample() -> Board {
+        Board::from_vec(vec![vec![2, 4, 0], vec![0, 8, 0], vec![16, 0, 2]]).unwrap()
+    }
+
+    #[test]
+    fn test_symmetric_boards_share_canonical_form() {
+        let (canonical, symmetry) = sample().canonical();
+        assert_eq!(sample().transformed(symmetry).to_vec(), canonical.to_vec());
+
+        for symmetry in Symmetry::ALL {
+            let board = sample().transformed(symmetry);
+            assert_eq!(board.canonical().0.to_vec(), canonical.to_vec());
+            assert_eq!(
+                board.transformed(symmetry.inverse()).to_vec(),
+                sample().to_vec()
+            );
+        }
+    }
+
+    #[test]
+    fn test_moves_commute_with_symmetries() {
+        let board = sample();
+        for symmetry in Symmetry::ALL {
+            for direction in [
+                Direction::Up,
+                Direction::Down,
+                Direction::Left,
+                Direction::Right,
+            ] {
+                let (moved, _) = slide(&board.to_vec(), direction);
+                let (moved_transformed, _) = slide(
+                    &board.transformed(symmetry).to_vec(),
+                    symmetry.apply_direction(direction),
+                );
+                assert_eq!(
+                    Board::from_vec(moved)
+                        .unwrap()
+                        .transformed(symmetry)
+                        .to_vec(),
+                    moved_transformed
+                );
+            }
+        }
+    }
+}
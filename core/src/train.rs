@@ -0,0 +1,310 @@
+//! Self-play training of n-tuple networks (`train` feature).
+//!
+//! A [`Trainer`] plays games with its network (always taking the move whose
+//! points plus afterstate value are highest) and learns from them by
+//! temporal-difference learning on afterstates. At the end of each game the
+//! afterstates are updated from the last one backwards towards their
+//! λ-returns: `lambda` 0 is TD(0), higher values look further ahead.
+//!
+//! Checkpoints are ordinary weights files, loadable by
+//! [`NTupleNetwork::load`], next to a small JSON file with the number of
+//! games played so training can resume where it stopped.
+
+use crate::ntuple::NTupleNetwork;
+use crate::{Board, Game, GameConfig, GameError, GameResult, GameState};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Settings of a training run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrainingConfig {
+    /// Step size of the value updates, shared among the tuples
+    pub learning_rate: f32,
+    /// Trace decay: 0.0 for TD(0), up to 1.0 for Monte Carlo returns
+    pub lambda: f32,
+    /// Seed of the first game; game `n` is seeded with `seed + n`
+    pub seed: u64,
+    /// Games between progress reports
+    pub report_every: u64,
+    /// Games between checkpoints, 0 for none
+    pub checkpoint_every: u64,
+    /// Where checkpoints are written
+    pub checkpoint_path: Option<PathBuf>,
+}
+
+impl Default for TrainingConfig {
+    fn default() -> Self {
+        Self {
+            learning_rate: 0.1,
+            lambda: 0.0,
+            seed: 0,
+            report_every: 1_000,
+            checkpoint_every: 0,
+            checkpoint_path: None,
+        }
+    }
+}
+
+/// How the games since the last report went
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TrainingProgress {
+    /// Games played in total
+    pub games: u64,
+    /// Games covered by this report
+    pub window: u64,
+    pub average_score: f64,
+    pub best_score: u64,
+    /// Largest tile reached
+    pub max_tile: u64,
+    /// Share of the games that reached 2048
+    pub win_rate: f64,
+}
+
+/// Outcome of one training game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EpisodeResult {
+    pub score: u64,
+    pub max_tile: u64,
+    pub moves: u32,
+}
+
+/// Saved next to a checkpoint's weights
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CheckpointState {
+    games: u64,
+}
+
+/// Learns n-tuple weights by self-play
+#[derive(Debug, Clone)]
+pub struct Trainer {
+    network: NTupleNetwork,
+    config: TrainingConfig,
+    /// Games played so far, including before a resume
+    games: u64,
+}
+
+impl Trainer {
+    /// Start training `network` from scratch
+    pub fn new(network: NTupleNetwork, config: TrainingConfig) -> Self {
+        Self {
+            network,
+            config,
+            games: 0,
+        }
+    }
+
+    /// Resume from a checkpoint written by `checkpoint`
+    pub fn resume(path: impl AsRef<Path>, config: TrainingConfig) -> GameResult<Self> {
+        let path = path.as_ref();
+        let network = NTupleNetwork::load(path)?;
+        let state_path = state_path(path);
+        let state: CheckpointState = fs::read_to_string(&state_path)
+            .map_err(|e| {
+                GameError::InvalidOperation(format!(
+                    "Failed to read {}: {}",
+                    state_path.display(),
+                    e
+                ))
+            })
+            .and_then(|json| {
+                serde_json::from_str(&json).map_err(|e| {
+                    GameError::Serialization(format!("Invalid training checkpoint: {}", e))
+                })
+            })?;
+        Ok(Self {
+            network,
+            config,
+            games: state.games,
+        })
+    }
+
+    /// The network being trained
+    pub fn network(&self) -> &NTupleNetwork {
+        &self.network
+    }
+
+    /// Stop training and keep the network
+    pub fn into_network(self) -> NTupleNetwork {
+        self.network
+    }
+
+    /// Games played so far
+    pub fn games(&self) -> u64 {
+        self.games
+    }
+
+    /// Play `games` games, learning from each. `on_progress` is called
+    /// every `report_every` games; checkpoints are written every
+    /// `checkpoint_every` games and once more at the end.
+    pub fn train(
+        &mut self,
+        games: u64,
+        mut on_progress: impl FnMut(&TrainingProgress),
+    ) -> GameResult<()> {
+        let mut window = Vec::new();
+        for _ in 0..games {
+            window.push(self.play_episode()?);
+
+            if self.games.is_multiple_of(self.config.report_every) {
+                on_progress(&self.progress(&window));
+                window.clear();
+            }
+            if self.games.is_multiple_of(self.config.checkpoint_every) {
+                self.write_checkpoint()?;
+            }
+        }
+        if !window.is_empty() {
+            on_progress(&self.progress(&window));
+        }
+        if self.config.checkpoint_every > 0 {
+            self.write_checkpoint()?;
+        }
+        Ok(())
+    }
+
+    /// Play one game and learn from it
+    pub fn play_episode(&mut self) -> GameResult<EpisodeResult> {
+        let mut game = Game::new(GameConfig {
+            board_size: self.network.size(),
+            allow_undo: false,
+            seed: Some(self.config.seed.wrapping_add(self.games)),
+            ..GameConfig::default()
+        })?;
+
+        // Each afterstate with the points of the move that follows it
+        let mut afterstates: Vec<(Board, u64)> = Vec::new();
+        loop {
+            if game.state() == GameState::Won {
+                game.continue_after_win()?;
+            }
+            if game.state() != GameState::Playing {
+                break;
+            }
+            let Some(afterstate) = self.network.best_afterstate(&game) else {
+                break;
+            };
+            if let Some(last) = afterstates.last_mut() {
+                last.1 = afterstate.points;
+            }
+            game.make_move(afterstate.direction)?;
+            afterstates.push((afterstate.board, 0));
+        }
+
+        self.learn(&afterstates);
+        self.games += 1;
+        Ok(EpisodeResult {
+            score: game.score().current(),
+            max_tile: game.board().max_tile(),
+            moves: game.moves(),
+        })
+    }
+
+    /// Move each afterstate's value towards its λ-return, from the end of
+    /// the game backwards. The final afterstate is worth nothing.
+    fn learn(&mut self, afterstates: &[(Board, u64)]) {
+        let step = self.config.learning_rate / self.network.tuples().len().max(1) as f32;
+        let lambda = self.config.lambda as f64;
+
+        // λ-return of the afterstate after the one being updated
+        let mut next_return = 0.0;
+        let mut next_value = 0.0;
+        for (board, points) in afterstates.iter().rev() {
+            let target = *points as f64 + (1.0 - lambda) * next_value + lambda * next_return;
+            let value = self.network.evaluate(board);
+            self.network.update(board, step * (target - value) as f32);
+            next_return = target;
+            next_value = self.network.evaluate(board);
+        }
+    }
+
+    fn progress(&self, window: &[EpisodeResult]) -> TrainingProgress {
+        let count = window.len().max(1) as f64;
+        TrainingProgress {
+            games: self.games,
+            window: window.len() as u64,
+            average_score: window.iter().map(|r| r.score as f64).sum::<f64>() / count,
+            best_score: window.iter().map(|r| r.score).max().unwrap_or(0),
+            max_tile: window.iter().map(|r| r.max_tile).max().unwrap_or(0),
+            win_rate: window.iter().filter(|r| r.max_tile >= 2048).count() as f64 / count,
+        }
+    }
+
+    fn write_checkpoint(&self) -> GameResult<()> {
+        match &self.config.checkpoint_path {
+            Some(path) => self.checkpoint(path),
+            None => Ok(()),
+        }
+    }
+
+    /// Write the weights to `path` and the number of games played next to
+    /// them. The weights are written to a temporary file first, so an
+    /// interrupted checkpoint leaves the previous one intact.
+    pub fn checkpoint(&self, path: impl AsRef<Path>) -> GameResult<()> {
+        let path = path.as_ref();
+        let temporary = path.with_extension("tmp");
+        self.network.save(&temporary)?;
+        fs::rename(&temporary, path).map_err(|e| {
+            GameError::InvalidOperation(format!("Failed to write {}: {}", path.display(), e))
+        })?;
+
+        let state_path = state_path(path);
+        let json = serde_json::to_string(&CheckpointState { games: self.games })
+            .map_err(|e| GameError::Serialization(e.to_string()))?;
+        fs::write(&state_path, json).map_err(|e| {
+            GameError::InvalidOperation(format!("Failed to write {}: {}", state_path.display(), e))
+        })
+    }
+}
+
+/// Path of the JSON state saved next to a checkpoint
+fn state_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".json");
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_training_and_resume() {
+        let network = NTupleNetwork::standard(3).unwrap();
+        let path = std::env::temp_dir().join(format!("rusty2048-train-{}.bin", std::process::id()));
+        let config = TrainingConfig {
+            lambda: 0.5,
+            report_every: 5,
+            checkpoint_every: 10,
+            checkpoint_path: Some(path.clone()),
+            ..TrainingConfig::default()
+        };
+
+        let mut trainer = Trainer::new(network, config.clone());
+        let mut reports = Vec::new();
+        trainer
+            .train(12, |progress| reports.push(*progress))
+            .unwrap();
+        assert_eq!(
+            reports
+                .iter()
+                .map(|p| (p.games, p.window))
+                .collect::<Vec<_>>(),
+            vec![(5, 5), (10, 5), (12, 2)]
+        );
+        assert!(reports.iter().all(|p| p.average_score > 0.0));
+
+        // Learning moved the weights away from zero
+        let empty = Board::new(3).unwrap();
+        assert_ne!(trainer.network().evaluate(&empty), 0.0);
+
+        // The checkpoint is a plain weights file and resumes the count
+        let resumed = Trainer::resume(&path, config).unwrap();
+        assert_eq!(resumed.games(), 12);
+        assert_eq!(resumed.network(), trainer.network());
+        assert_eq!(NTupleNetwork::load(&path).unwrap(), *trainer.network());
+
+        fs::remove_file(&path).unwrap();
+        fs::remove_file(state_path(&path)).unwrap();
+    }
+}
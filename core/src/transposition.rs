@@ -0,0 +1,196 @@
+//! Transposition table for the AI searches.
+//!
+//! Different move orders often reach the same board, so a search stores the
+//! value of every node it finishes under the board's Zobrist hash and the
+//! remaining depth, and looks it up before searching the node again.
+//!
+//! The table is a fixed number of slots indexed by hash. When two nodes
+//! compete for a slot, the newcomer replaces an entry left by an earlier
+//! root search (an older generation) or one searched no deeper; otherwise
+//! the deeper entry of the current search is kept, as it saved more work.
+
+use serde::{Deserialize, Serialize};
+
+/// Slots of a table by default
+pub const DEFAULT_TRANSPOSITION_CAPACITY: usize = 1 << 14;
+
+/// Counters for tuning a table's capacity
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranspositionStats {
+    /// Lookups that found the node
+    pub hits: u64,
+    /// Lookups that did not
+    pub misses: u64,
+    /// Values stored
+    pub stores: u64,
+    /// Stores that evicted another node
+    pub replacements: u64,
+    /// Stores dropped to keep a deeper node of the current search
+    pub rejections: u64,
+}
+
+impl TranspositionStats {
+    /// Share of lookups that hit, from 0.0 to 1.0
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    hash: u64,
+    depth: usize,
+    is_maximizing: bool,
+    generation: u32,
+    value: f64,
+}
+
+/// Search values keyed by board hash, depth and node type
+#[derive(Debug, Clone)]
+pub struct TranspositionTable {
+    /// Allocated on the first store, so unused tables cost nothing
+    slots: Vec<Option<Entry>>,
+    capacity: usize,
+    generation: u32,
+    stats: TranspositionStats,
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        Self::new(DEFAULT_TRANSPOSITION_CAPACITY)
+    }
+}
+
+impl TranspositionTable {
+    /// Create a table with `capacity` slots; 0 disables it
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            slots: Vec::new(),
+            capacity,
+            generation: 0,
+            stats: TranspositionStats::default(),
+        }
+    }
+
+    /// Number of slots
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Start a new root search: entries stored so far can still be found,
+    /// but give way to the new search's entries
+    pub fn new_search(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    /// Value stored for a node, if any
+    pub fn get(&mut self, hash: u64, depth: usize, is_maximizing: bool) -> Option<f64> {
+        let value = self.slot(hash).and_then(|slot| {
+            self.slots
+                .get(slot)
+                .copied()
+                .flatten()
+                .filter(|entry| {
+                    entry.hash == hash
+                        && entry.depth == depth
+                        && entry.is_maximizing == is_maximizing
+                })
+                .map(|entry| entry.value)
+        });
+        match value {
+            Some(_) => self.stats.hits += 1,
+            None => self.stats.misses += 1,
+        }
+        value
+    }
+
+    /// Store the value of a node, following the replacement scheme
+    pub fn insert(&mut self, hash: u64, depth: usize, is_maximizing: bool, value: f64) {
+        let Some(slot) = self.slot(hash) else {
+            return;
+        };
+        if self.slots.is_empty() {
+            self.slots = vec![None; self.capacity];
+        }
+
+        if let Some(existing) = self.slots[slot] {
+            let same_node = existing.hash == hash && existing.is_maximizing == is_maximizing;
+            if !same_node && existing.generation == self.generation && existing.depth > depth {
+                self.stats.rejections += 1;
+                return;
+            }
+            if !same_node {
+                self.stats.replacements += 1;
+            }
+        }
+        self.slots[slot] = Some(Entry {
+            hash,
+            depth,
+            is_maximizing,
+            generation: self.generation,
+            value,
+        });
+        self.stats.stores += 1;
+    }
+
+    /// Counters since the table was created or last cleared
+    pub fn stats(&self) -> TranspositionStats {
+        self.stats
+    }
+
+    /// Forget every entry and reset the counters
+    pub fn clear(&mut self) {
+        self.slots = Vec::new();
+        self.generation = 0;
+        self.stats = TranspositionStats::default();
+    }
+
+    fn slot(&self, hash: u64) -> Option<usize> {
+        (self.capacity > 0).then(|| (hash % self.capacity as u64) as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replacement_scheme() {
+        let mut table = TranspositionTable::new(4);
+        assert_eq!(table.get(1, 2, true), None);
+        table.insert(1, 2, true, 10.0);
+        assert_eq!(table.get(1, 2, true), Some(10.0));
+        // Depth and node type are part of the key
+        assert_eq!(table.get(1, 1, true), None);
+        assert_eq!(table.get(1, 2, false), None);
+
+        // A shallower node of the same search keeps the deeper one
+        table.insert(5, 1, true, 20.0);
+        assert_eq!(table.get(1, 2, true), Some(10.0));
+        assert_eq!(table.get(5, 1, true), None);
+
+        // In a later search it replaces it
+        table.new_search();
+        table.insert(5, 1, true, 20.0);
+        assert_eq!(table.get(5, 1, true), Some(20.0));
+        assert_eq!(table.get(1, 2, true), None);
+
+        let stats = table.stats();
+        assert_eq!(stats.hits, 3);
+        assert_eq!(stats.misses, 5);
+        assert_eq!(stats.stores, 2);
+        assert_eq!(stats.replacements, 1);
+        assert_eq!(stats.rejections, 1);
+        assert_eq!(stats.hit_rate(), 3.0 / 8.0);
+
+        // A disabled table stores nothing
+        let mut disabled = TranspositionTable::new(0);
+        disabled.insert(1, 2, true, 10.0);
+        assert_eq!(disabled.get(1, 2, true), None);
+    }
+}
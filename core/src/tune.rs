@@ -0,0 +1,324 @@
+//! Tuning of heuristic weights by a genetic algorithm (`tune` feature).
+//!
+//! A [`Tuner`] keeps a population of [`HeuristicConfig`]s. Each generation
+//! every candidate plays the same seeded games through [`AIBenchmark`] and
+//! is scored by its mean score; the best `elite` candidates survive and
+//! the rest of the next generation is bred from them by uniform crossover
+//! and random mutation of each weight.
+//!
+//! Checkpoints are JSON files holding the population, the best candidate
+//! so far and the state of the random generator, so a resumed run goes on
+//! exactly as an uninterrupted one would have.
+
+use crate::{
+    AIAlgorithm, AIBenchmark, AIPlayer, GameConfig, GameError, GameResult, GameRng, HeuristicConfig,
+};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Settings of a tuning run
+#[derive(Debug, Clone, PartialEq)]
+pub struct TuningConfig {
+    /// Algorithm the weights are tuned for
+    pub algorithm: AIAlgorithm,
+    /// Search depth of the algorithm; shallow searches tune much faster
+    pub max_depth: usize,
+    pub board_size: usize,
+    /// Candidates per generation
+    pub population: usize,
+    /// Best candidates kept unchanged in the next generation
+    pub elite: usize,
+    /// Games each candidate plays per generation
+    pub games: usize,
+    /// Largest change of a weight by mutation, relative to the weight
+    pub mutation: f64,
+    /// Seed of the random generator and of the games; generation `n`
+    /// plays games seeded from `seed + n * games`
+    pub seed: u64,
+    /// Where a checkpoint is written after each generation
+    pub checkpoint_path: Option<PathBuf>,
+}
+
+impl Default for TuningConfig {
+    fn default() -> Self {
+        Self {
+            algorithm: AIAlgorithm::Expectimax,
+            max_depth: 2,
+            board_size: 4,
+            population: 12,
+            elite: 3,
+            games: 5,
+            mutation: 0.3,
+            seed: 0,
+            checkpoint_path: None,
+        }
+    }
+}
+
+/// A set of weights and how well it played
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Candidate {
+    pub heuristic: HeuristicConfig,
+    /// Mean score of its games
+    pub fitness: f64,
+}
+
+/// How a generation went
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TuningProgress {
+    /// Generations evaluated in total
+    pub generation: u64,
+    /// Best candidate of this generation
+    pub generation_best: Candidate,
+    /// Mean fitness of this generation
+    pub mean_fitness: f64,
+    /// Best candidate of any generation
+    pub best: Candidate,
+}
+
+/// Saved at each checkpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TunerState {
+    generation: u64,
+    population: Vec<HeuristicConfig>,
+    best: Option<Candidate>,
+    rng: GameRng,
+}
+
+/// Tunes heuristic weights by playing benchmark games
+#[derive(Debug, Clone)]
+pub struct Tuner {
+    config: TuningConfig,
+    state: TunerState,
+}
+
+impl Tuner {
+    /// Start from `initial` and mutations of it
+    pub fn new(initial: HeuristicConfig, config: TuningConfig) -> Self {
+        let mut rng = GameRng::new(Some(config.seed));
+        let mut population = vec![initial];
+        while population.len() < config.population.max(1) {
+            population.push(mutate(&initial, config.mutation, &mut rng));
+        }
+        Self {
+            config,
+            state: TunerState {
+                generation: 0,
+                population,
+                best: None,
+                rng,
+            },
+        }
+    }
+
+    /// Resume from a checkpoint written by `checkpoint`
+    pub fn resume(path: impl AsRef<Path>, config: TuningConfig) -> GameResult<Self> {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path).map_err(|e| {
+            GameError::InvalidOperation(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        let state: TunerState = serde_json::from_str(&json)
+            .map_err(|e| GameError::Serialization(format!("Invalid tuning checkpoint: {}", e)))?;
+        if state.population.is_empty() {
+            return Err(GameError::Serialization(
+                "Invalid tuning checkpoint: empty population".to_string(),
+            ));
+        }
+        Ok(Self { config, state })
+    }
+
+    /// Generations evaluated so far, including before a resume
+    pub fn generation(&self) -> u64 {
+        self.state.generation
+    }
+
+    /// Best candidate found so far
+    pub fn best(&self) -> Option<Candidate> {
+        self.state.best
+    }
+
+    /// Candidates of the next generation
+    pub fn population(&self) -> &[HeuristicConfig] {
+        &self.state.population
+    }
+
+    /// Evaluate and breed `generations` generations. `on_progress` is
+    /// called after each one, when a checkpoint is written too.
+    pub fn run(
+        &mut self,
+        generations: u64,
+        mut on_progress: impl FnMut(&TuningProgress),
+    ) -> GameResult<()> {
+        for _ in 0..generations {
+            let progress = self.step()?;
+            if let Some(path) = &self.config.checkpoint_path {
+                self.checkpoint(path)?;
+            }
+            on_progress(&progress);
+        }
+        Ok(())
+    }
+
+    /// Evaluate the current generation and breed the next one
+    pub fn step(&mut self) -> GameResult<TuningProgress> {
+        let game_config = GameConfig {
+            board_size: self.config.board_size,
+            ..GameConfig::default()
+        };
+        let seed = self
+            .config
+            .seed
+            .wrapping_add(self.state.generation * self.config.games as u64);
+
+        // The candidates play the same games, so luck favours none of them
+        let mut ranked = Vec::with_capacity(self.state.population.len());
+        for heuristic in &self.state.population {
+            let mut player = AIPlayer::new(self.config.algorithm)
+                .with_max_depth(self.config.max_depth)
+                .with_heuristic(*heuristic);
+            let results =
+                AIBenchmark::run_player(&game_config, &mut player, self.config.games, seed)?;
+            ranked.push(Candidate {
+                heuristic: *heuristic,
+                fitness: results.mean_score,
+            });
+        }
+        ranked.sort_by(|a, b| b.fitness.total_cmp(&a.fitness));
+
+        let generation_best = ranked[0];
+        let best = match self.state.best {
+            Some(best) if best.fitness >= generation_best.fitness => best,
+            _ => generation_best,
+        };
+        let mean_fitness = ranked
+            .iter()
+            .map(|candidate| candidate.fitness)
+            .sum::<f64>()
+            / ranked.len() as f64;
+
+        let parents = &ranked[..self.config.elite.clamp(1, ranked.len())];
+        let rng = &mut self.state.rng;
+        let mut population: Vec<_> = parents.iter().map(|parent| parent.heuristic).collect();
+        while population.len() < self.config.population.max(1) {
+            let a = &parents[rng.gen_range(parents.len())].heuristic;
+            let b = &parents[rng.gen_range(parents.len())].heuristic;
+            let child = crossover(a, b, rng);
+            population.push(mutate(&child, self.config.mutation, rng));
+        }
+
+        self.state.population = population;
+        self.state.best = Some(best);
+        self.state.generation += 1;
+        Ok(TuningProgress {
+            generation: self.state.generation,
+            generation_best,
+            mean_fitness,
+            best,
+        })
+    }
+
+    /// Write the tuner's state to `path`. It is written to a temporary
+    /// file first, so an interrupted checkpoint leaves the previous one
+    /// intact.
+    pub fn checkpoint(&self, path: impl AsRef<Path>) -> GameResult<()> {
+        let path = path.as_ref();
+        let temporary = path.with_extension("tmp");
+        let json = serde_json::to_string_pretty(&self.state)
+            .map_err(|e| GameError::Serialization(e.to_string()))?;
+        fs::write(&temporary, json)
+            .and_then(|()| fs::rename(&temporary, path))
+            .map_err(|e| {
+                GameError::InvalidOperation(format!("Failed to write {}: {}", path.display(), e))
+            })
+    }
+}
+
+/// The tuned weights of a heuristic
+fn weights(heuristic: &mut HeuristicConfig) -> [&mut f64; 6] {
+    [
+        &mut heuristic.position,
+        &mut heuristic.corner,
+        &mut heuristic.scattered,
+        &mut heuristic.smoothness,
+        &mut heuristic.monotonicity,
+        &mut heuristic.snake,
+    ]
+}
+
+/// Each weight from either parent
+fn crossover(a: &HeuristicConfig, b: &HeuristicConfig, rng: &mut GameRng) -> HeuristicConfig {
+    let mut child = *a;
+    let mut other = *b;
+    for (weight, other) in weights(&mut child).into_iter().zip(weights(&mut other)) {
+        if rng.gen_bool(0.5) {
+            *weight = *other;
+        }
+    }
+    child
+}
+
+/// Change each weight by up to `scale` of itself. Weights of 0 can grow
+/// too, and none turns negative, which would reverse what its term means.
+fn mutate(heuristic: &HeuristicConfig, scale: f64, rng: &mut GameRng) -> HeuristicConfig {
+    let mut mutated = *heuristic;
+    for weight in weights(&mut mutated) {
+        let change = (rng.gen_f64() * 2.0 - 1.0) * scale;
+        *weight = (*weight + change * weight.abs().max(0.1)).max(0.0);
+    }
+    mutated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tuning_and_resume() {
+        let path = std::env::temp_dir().join(format!("rusty2048-tune-{}.json", std::process::id()));
+        let config = TuningConfig {
+            algorithm: AIAlgorithm::Expectimax,
+            max_depth: 1,
+            board_size: 3,
+            population: 4,
+            elite: 2,
+            games: 2,
+            seed: 7,
+            checkpoint_path: Some(path.clone()),
+            ..TuningConfig::default()
+        };
+
+        let mut tuner = Tuner::new(HeuristicConfig::classic(), config.clone());
+        assert_eq!(tuner.population().len(), 4);
+        assert_eq!(tuner.population()[0], HeuristicConfig::classic());
+        let mut reports = Vec::new();
+        tuner.run(2, |progress| reports.push(*progress)).unwrap();
+        assert_eq!(
+            reports.iter().map(|p| p.generation).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        let best = tuner.best().unwrap();
+        assert!(reports.iter().all(|p| p.best.fitness <= best.fitness));
+        assert!(reports[1].generation_best.fitness >= reports[1].mean_fitness);
+
+        // The elites survive into the next generation
+        assert_eq!(tuner.population().len(), 4);
+        assert!(tuner
+            .population()
+            .contains(&reports[1].generation_best.heuristic));
+        assert!(tuner
+            .population()
+            .iter()
+            .all(|h| weights(&mut h.clone()).iter().all(|w| **w >= 0.0)));
+
+        // A resumed run carries on as the uninterrupted one does
+        let mut resumed = Tuner::resume(&path, config).unwrap();
+        assert_eq!(resumed.generation(), 2);
+        assert_eq!(resumed.best(), Some(best));
+        let next = tuner.step().unwrap();
+        assert_eq!(resumed.step().unwrap(), next);
+        assert_eq!(resumed.population(), tuner.population());
+
+        fs::remove_file(&path).unwrap();
+    }
+}
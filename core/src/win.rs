@@ -0,0 +1,129 @@
+//! Conditions for winning a game.
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// What a game has to reach to be won
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WinCondition {
+    /// Reach a tile of at least this value
+    Tile(u64),
+    /// Reach at least this score
+    Score(u64),
+    /// Reach the tile or the score, whichever comes first. Stored as
+    /// `both` before it was renamed.
+    #[serde(alias = "both")]
+    Either { tile: u64, score: u64 },
+}
+
+impl Default for WinCondition {
+    fn default() -> Self {
+        WinCondition::Tile(2048)
+    }
+}
+
+/// Which part of a [`WinCondition`] won a game
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WinTrigger {
+    Tile,
+    Score,
+}
+
+impl WinCondition {
+    /// Check the condition against a board's largest tile and the score.
+    /// The tile wins when both are reached at once.
+    pub fn check(&self, max_tile: u64, score: u64) -> Option<WinTrigger> {
+        let (tile_target, score_target) = match *self {
+            WinCondition::Tile(tile) => (Some(tile), None),
+            WinCondition::Score(score) => (None, Some(score)),
+            WinCondition::Either { tile, score } => (Some(tile), Some(score)),
+        };
+
+        if tile_target.is_some_and(|tile| max_tile >= tile) {
+            Some(WinTrigger::Tile)
+        } else if score_target.is_some_and(|target| score >= target) {
+            Some(WinTrigger::Score)
+        } else {
+            None
+        }
+    }
+
+    /// The tile to reach, if the condition has one
+    pub fn target_tile(&self) -> Option<u64> {
+        match *self {
+            WinCondition::Tile(tile) | WinCondition::Either { tile, .. } => Some(tile),
+            WinCondition::Score(_) => None,
+        }
+    }
+}
+
+/// Deserialize a win condition, also accepting the target tile number that
+/// configurations stored as `target_score` before win conditions existed
+pub(crate) fn deserialize_win_condition<'de, D>(deserializer: D) -> Result<WinCondition, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Stored {
+        TargetTile(u64),
+        Condition(WinCondition),
+    }
+
+    Ok(match Stored::deserialize(deserializer)? {
+        Stored::TargetTile(tile) => WinCondition::Tile(tile),
+        Stored::Condition(condition) => condition,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GameConfig;
+
+    #[test]
+    fn test_check() {
+        assert_eq!(
+            WinCondition::Tile(2048).check(2048, 0),
+            Some(WinTrigger::Tile)
+        );
+        assert_eq!(WinCondition::Tile(2048).check(1024, 50_000), None);
+        assert_eq!(
+            WinCondition::Score(10_000).check(512, 10_000),
+            Some(WinTrigger::Score)
+        );
+
+        let either = WinCondition::Either {
+            tile: 2048,
+            score: 10_000,
+        };
+        assert_eq!(either.check(1024, 10_000), Some(WinTrigger::Score));
+        assert_eq!(either.check(2048, 500), Some(WinTrigger::Tile));
+        assert_eq!(either.check(1024, 500), None);
+    }
+
+    #[test]
+    fn test_either_reads_old_name() {
+        let condition: WinCondition =
+            serde_json::from_str(r#"{"both":{"tile":2048,"score":10000}}"#).unwrap();
+        assert_eq!(
+            condition,
+            WinCondition::Either {
+                tile: 2048,
+                score: 10_000
+            }
+        );
+    }
+
+    #[test]
+    fn test_legacy_target_score() {
+        let mut json = serde_json::to_value(GameConfig::default()).unwrap();
+        let object = json.as_object_mut().unwrap();
+        object.remove("win_condition");
+        object.insert("target_score".to_string(), 512.into());
+
+        let config: GameConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(config.win_condition, WinCondition::Tile(512));
+    }
+}
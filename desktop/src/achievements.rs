@@ -0,0 +1,97 @@
+use crate::GameManager;
+use rusty2048_core::GameState;
+use rusty2048_shared::{Achievement, TranslationKey};
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Runtime, State};
+use tauri_plugin_notification::NotificationExt;
+
+/// File name of the achievement progress file inside the app data dir
+pub const ACHIEVEMENTS_FILE: &str = "achievements.json";
+/// Event emitted with each newly unlocked achievement
+pub const ACHIEVEMENT_EVENT: &str = "achievement";
+
+/// Achievement as presented to the UI, localized to the current language
+#[derive(Clone, Serialize)]
+pub struct AchievementView {
+    id: &'static str,
+    icon: &'static str,
+    title: &'static str,
+    description: &'static str,
+    unlocked: bool,
+    unlocked_at: Option<u64>,
+}
+
+impl GameManager {
+    /// Check achievements after a move of the main game and persist new unlocks.
+    /// Must run before the finished game is recorded as a session.
+    pub fn update_achievements(&mut self) -> Result<Vec<&'static Achievement>, String> {
+        let game = self.game();
+        let score = game.score().current();
+        let max_tile = game.board().max_tile();
+        let moves = game.moves();
+        let state = game.state();
+        let won = game.has_won();
+        let now = rusty2048_core::get_current_time();
+
+        let mut unlocked = self.achievements.update_in_game(score, max_tile, now);
+        if state != GameState::Playing && self.recorded_session.is_none() {
+            unlocked.extend(
+                self.achievements
+                    .record_game_end(score, max_tile, moves, won, now),
+            );
+        }
+
+        if !unlocked.is_empty() {
+            self.achievements_store
+                .save(&self.achievements)
+                .map_err(|e| format!("Failed to save achievements: {}", e))?;
+        }
+        Ok(unlocked)
+    }
+
+    fn achievement_view(&self, achievement: &'static Achievement) -> AchievementView {
+        let language = self.i18n.current_language();
+        AchievementView {
+            id: achievement.id,
+            icon: achievement.icon,
+            title: achievement.title(language),
+            description: achievement.description(language),
+            unlocked: self.achievements.is_unlocked(achievement.id),
+            unlocked_at: self.achievements.unlocked_at(achievement.id),
+        }
+    }
+}
+
+/// Show a system notification for each new unlock and tell the frontend,
+/// which lists it in the window too
+pub fn notify_unlocked<R: Runtime>(
+    app: &AppHandle<R>,
+    game_manager: &GameManager,
+    unlocked: &[&'static Achievement],
+) {
+    for achievement in unlocked {
+        let view = game_manager.achievement_view(achievement);
+        let _ = app
+            .notification()
+            .builder()
+            .title(game_manager.i18n.t(&TranslationKey::AchievementUnlocked))
+            .body(format!(
+                "{} {}\n{}",
+                view.icon, view.title, view.description
+            ))
+            .show();
+        let _ = app.emit(ACHIEVEMENT_EVENT, view);
+    }
+}
+
+#[tauri::command]
+pub async fn get_achievements(
+    state: State<'_, Arc<Mutex<GameManager>>>,
+) -> Result<Vec<AchievementView>, String> {
+    let game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    Ok(Achievement::all()
+        .iter()
+        .map(|achievement| game_manager.achievement_view(achievement))
+        .collect())
+}
@@ -0,0 +1,146 @@
+use crate::sessions::{SessionId, MAIN_SESSION};
+use crate::GameManager;
+use rusty2048_core::{AIAlgorithm, AIPlayer, CancellationToken, Direction, GameState};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+
+/// Event emitted when autoplay stops on its own (e.g. the game ended)
+pub const AI_STOPPED_EVENT: &str = "ai-stopped";
+
+/// Parse an AI algorithm name coming from the frontend
+fn parse_algorithm(algorithm: &str) -> Result<AIAlgorithm, String> {
+    match algorithm {
+        "greedy" => Ok(AIAlgorithm::Greedy),
+        "expectimax" => Ok(AIAlgorithm::Expectimax),
+        "mcts" => Ok(AIAlgorithm::MCTS),
+        "minimax" => Ok(AIAlgorithm::Minimax),
+        "ntuple" => Ok(AIAlgorithm::NTuple),
+        _ => Err("Invalid AI algorithm".to_string()),
+    }
+}
+
+fn direction_name(direction: Direction) -> &'static str {
+    match direction {
+        Direction::Up => "up",
+        Direction::Down => "down",
+        Direction::Left => "left",
+        Direction::Right => "right",
+    }
+}
+
+impl GameManager {
+    /// Stop a running autoplay task, if any, cancelling its search.
+    /// Returns whether one was running.
+    pub fn stop_autoplay(&mut self) -> bool {
+        let Some(token) = self.autoplay.take() else {
+            return false;
+        };
+        token.cancel();
+        true
+    }
+}
+
+#[tauri::command]
+pub async fn get_hint(
+    state: State<'_, Arc<Mutex<GameManager>>>,
+    algorithm: String,
+    session: Option<SessionId>,
+) -> Result<String, String> {
+    let game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    let game = game_manager.session(session.unwrap_or(MAIN_SESSION))?;
+    let direction = game
+        .hint(parse_algorithm(&algorithm)?)
+        .map_err(|e| e.to_string())?;
+    Ok(direction_name(direction).to_string())
+}
+
+#[tauri::command]
+pub async fn start_ai_autoplay(
+    app: AppHandle,
+    state: State<'_, Arc<Mutex<GameManager>>>,
+    algorithm: String,
+    speed: u64,
+) -> Result<(), String> {
+    let algorithm = parse_algorithm(&algorithm)?;
+    let token = CancellationToken::new();
+    {
+        let mut game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+        game_manager.stop_autoplay();
+        game_manager.autoplay = Some(token.clone());
+    }
+
+    let manager = state.inner().clone();
+    let delay = Duration::from_millis(speed);
+
+    // AI search is CPU-bound, so it runs on its own thread
+    std::thread::spawn(move || {
+        let mut player = AIPlayer::new(algorithm).with_cancellation(token.clone());
+        while !token.is_cancelled() {
+            std::thread::sleep(delay);
+            if token.is_cancelled() {
+                break;
+            }
+
+            // Search a copy of the game without holding the lock, so
+            // manual moves can cancel the search
+            let Ok(game) = manager
+                .lock()
+                .map(|game_manager| game_manager.game().clone())
+            else {
+                break;
+            };
+            if game.state() != GameState::Playing {
+                break;
+            }
+            let Ok(direction) = game.hint_with(&mut player) else {
+                break;
+            };
+
+            let Ok(mut game_manager) = manager.lock() else {
+                break;
+            };
+            // Autoplay may have been stopped during the search
+            if token.is_cancelled() {
+                break;
+            }
+            // The game may have changed meanwhile, e.g. by an undo
+            if game_manager.game().board().as_flat_slice() != game.board().as_flat_slice() {
+                continue;
+            }
+            let moved = game_manager.game_mut().make_move(direction);
+            if !moved.is_ok_and(|result| result.moved) {
+                break;
+            }
+            game_manager.ai_played = true;
+            let Ok(unlocked) = game_manager.after_move() else {
+                break;
+            };
+
+            crate::achievements::notify_unlocked(&app, &game_manager, &unlocked);
+            crate::tray::refresh_tray(&app, &game_manager);
+            crate::events::publish(&app, &mut game_manager);
+        }
+
+        // Only clear the handle if it was not replaced by a newer task
+        if let Ok(mut game_manager) = manager.lock() {
+            if game_manager
+                .autoplay
+                .as_ref()
+                .is_some_and(|current| *current == token)
+            {
+                game_manager.autoplay = None;
+                let _ = app.emit(AI_STOPPED_EVENT, game_manager.get_state());
+            }
+        }
+    });
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn stop_ai_autoplay(state: State<'_, Arc<Mutex<GameManager>>>) -> Result<(), String> {
+    let mut game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    game_manager.stop_autoplay();
+    Ok(())
+}
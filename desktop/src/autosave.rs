@@ -0,0 +1,92 @@
+use crate::sessions::MAIN_SESSION;
+use crate::{events, GameManager, GameState};
+use rusty2048_core::{Game, SavedGame};
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, State};
+
+/// File name of the autosave inside the app data dir
+pub const AUTOSAVE_FILE: &str = "autosave.json";
+/// How often the in-progress game is saved in the background
+pub const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Summary of the autosaved game shown in the "Resume last game" prompt
+#[derive(Serialize)]
+pub struct SavedGameInfo {
+    score: u64,
+    moves: u32,
+    max_tile: u64,
+    saved_at: u64,
+}
+
+impl GameManager {
+    /// Save the in-progress game; finished games clear the autosave instead
+    pub fn autosave(&self) -> Result<(), String> {
+        match self.game().state() {
+            rusty2048_core::GameState::Playing if self.game().moves() > 0 => self
+                .autosave_store
+                .save(&self.game().to_saved_state())
+                .map_err(|e| format!("Failed to autosave: {}", e)),
+            // Nothing worth saving yet; keep the previous save for "Resume"
+            rusty2048_core::GameState::Playing => Ok(()),
+            _ => self
+                .autosave_store
+                .remove()
+                .map_err(|e| format!("Failed to remove autosave: {}", e)),
+        }
+    }
+
+    /// Replace the current game with the autosaved one
+    pub fn resume_autosave(&mut self) -> Result<(), String> {
+        let saved = self
+            .load_autosave()?
+            .ok_or_else(|| "No saved game".to_string())?;
+
+        let best_score = self.game().score().best();
+        let mut game = Game::from_saved_state(saved).map_err(|e| e.to_string())?;
+        game.restore_best_score(best_score);
+        self.replace_game(MAIN_SESSION, game)
+    }
+
+    fn load_autosave(&self) -> Result<Option<SavedGame>, String> {
+        self.autosave_store
+            .load()
+            .map_err(|e| format!("Failed to read autosave: {}", e))
+    }
+}
+
+/// Save the game periodically in the background
+pub fn spawn_autosave_task(manager: Arc<Mutex<GameManager>>) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(AUTOSAVE_INTERVAL);
+        let Ok(game_manager) = manager.lock() else {
+            break;
+        };
+        let _ = game_manager.autosave();
+    });
+}
+
+#[tauri::command]
+pub async fn get_saved_game(
+    state: State<'_, Arc<Mutex<GameManager>>>,
+) -> Result<Option<SavedGameInfo>, String> {
+    let game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    Ok(game_manager.load_autosave()?.map(|saved| SavedGameInfo {
+        score: saved.score.current(),
+        moves: saved.moves,
+        max_tile: saved.board.iter().flatten().copied().max().unwrap_or(0),
+        saved_at: saved.saved_at,
+    }))
+}
+
+#[tauri::command]
+pub async fn resume_saved_game(
+    app: AppHandle,
+    state: State<'_, Arc<Mutex<GameManager>>>,
+) -> Result<GameState, String> {
+    let mut game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    game_manager.resume_autosave()?;
+    events::publish(&app, &mut game_manager);
+    Ok(game_manager.get_state())
+}
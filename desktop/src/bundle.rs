@@ -0,0 +1,202 @@
+use crate::sessions::MAIN_SESSION;
+use crate::{events, menu, tray, GameManager, GameState};
+use rusty2048_core::{Game, GameSessionStats, SavedGame};
+use rusty2048_shared::{AchievementProgress, UserSettings};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_dialog::DialogExt;
+
+/// Version of the save bundle format written by this build
+/// (2: tiles and scores are u64)
+pub const BUNDLE_VERSION: u32 = 2;
+
+/// Everything needed to move a player to another machine
+#[derive(Serialize, Deserialize)]
+pub struct SaveBundle {
+    /// Bundle format version
+    pub version: u32,
+    /// Version of the app that wrote the bundle
+    pub app_version: String,
+    /// When the bundle was written (Unix timestamp)
+    pub exported_at: u64,
+    /// The game in progress, if any
+    pub game: Option<SavedGame>,
+    /// Recorded game sessions
+    pub sessions: Vec<GameSessionStats>,
+    /// Theme, language, best score and key bindings
+    pub settings: UserSettings,
+    /// Unlocked achievements; missing in bundles written before achievements existed
+    #[serde(default)]
+    pub achievements: AchievementProgress,
+}
+
+/// How an imported bundle is combined with local data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportMode {
+    /// Keep local data and add what is missing (sessions, best score)
+    Merge,
+    /// Discard local data and use the bundle
+    Replace,
+}
+
+impl SaveBundle {
+    /// Parse a bundle, rejecting versions this build does not understand
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        #[derive(Deserialize)]
+        struct VersionOnly {
+            version: Option<u32>,
+        }
+
+        let header: VersionOnly =
+            serde_json::from_str(json).map_err(|e| format!("Invalid save file: {}", e))?;
+        match header.version {
+            None | Some(0) => return Err("Not a Rusty2048 save file".to_string()),
+            Some(version) if version > BUNDLE_VERSION => {
+                return Err(format!(
+                "Save file version {} is newer than supported version {}; please update Rusty2048",
+                version, BUNDLE_VERSION
+            ))
+            }
+            Some(_) => {}
+        }
+
+        serde_json::from_str(json).map_err(|e| format!("Invalid save file: {}", e))
+    }
+}
+
+impl GameManager {
+    /// Collect game, statistics and settings into a bundle
+    pub fn export_bundle(&self) -> SaveBundle {
+        let game = (self.game().state() == rusty2048_core::GameState::Playing
+            && self.game().moves() > 0)
+            .then(|| self.game().to_saved_state());
+        let mut settings = self.settings.clone();
+        settings.best_score = settings.best_score.max(self.game().score().best());
+
+        SaveBundle {
+            version: BUNDLE_VERSION,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            exported_at: rusty2048_core::get_current_time(),
+            game,
+            sessions: self.stats.sessions().to_vec(),
+            settings,
+            achievements: self.achievements.clone(),
+        }
+    }
+
+    /// Apply an imported bundle
+    pub fn import_bundle(&mut self, bundle: SaveBundle, mode: ImportMode) -> Result<(), String> {
+        let best_score = match mode {
+            ImportMode::Replace => {
+                self.stats
+                    .replace_sessions(bundle.sessions)
+                    .map_err(|e| e.to_string())?;
+                self.settings = bundle.settings;
+                self.achievements = bundle.achievements;
+                self.settings.best_score
+            }
+            ImportMode::Merge => {
+                self.stats
+                    .merge_sessions(bundle.sessions)
+                    .map_err(|e| e.to_string())?;
+                self.achievements.merge(bundle.achievements);
+                self.settings.best_score.max(bundle.settings.best_score)
+            }
+        };
+
+        // Merging never throws away a game in progress
+        let keep_current = mode == ImportMode::Merge && self.game().moves() > 0;
+        if let Some(saved) = bundle.game.filter(|_| !keep_current) {
+            let game = Game::from_saved_state(saved).map_err(|e| e.to_string())?;
+            self.replace_game(MAIN_SESSION, game)?;
+        }
+
+        self.settings.best_score = best_score;
+        self.achievements_store
+            .save(&self.achievements)
+            .map_err(|e| format!("Failed to save achievements: {}", e))?;
+        self.apply_settings();
+        self.save_settings()
+    }
+}
+
+/// Directory the file dialogs open in: the downloads folder, falling back to
+/// the app data dir
+fn export_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .download_dir()
+        .or_else(|_| app.path().app_data_dir())
+        .map_err(|e| e.to_string())
+}
+
+/// Ask where to save an exported file, suggesting `file_name` in `export_dir`.
+/// `None` if the player cancelled.
+pub fn pick_save_path(
+    app: &AppHandle,
+    file_name: &str,
+    filter: (&str, &[&str]),
+) -> Result<Option<PathBuf>, String> {
+    let mut dialog = app
+        .dialog()
+        .file()
+        .set_file_name(file_name)
+        .add_filter(filter.0, filter.1);
+    if let Ok(dir) = export_dir(app) {
+        dialog = dialog.set_directory(dir);
+    }
+    dialog
+        .blocking_save_file()
+        .map(|path| path.into_path().map_err(|e| e.to_string()))
+        .transpose()
+}
+
+/// Export the full state to a file the player picks; returns the path
+/// written, or `None` if the dialog was cancelled
+#[tauri::command]
+pub async fn export_save_bundle(
+    app: AppHandle,
+    state: State<'_, Arc<Mutex<GameManager>>>,
+) -> Result<Option<String>, String> {
+    let file_name = format!("rusty2048-save-{}.json", rusty2048_core::get_current_time());
+    let Some(path) = pick_save_path(&app, &file_name, ("Rusty2048 save", &["json"]))? else {
+        return Ok(None);
+    };
+
+    let game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    let json =
+        serde_json::to_string_pretty(&game_manager.export_bundle()).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write save file: {}", e))?;
+    Ok(Some(path.to_string_lossy().into_owned()))
+}
+
+/// Import a bundle from a file the player picks; `None` if the dialog was
+/// cancelled
+#[tauri::command]
+pub async fn import_save_bundle(
+    app: AppHandle,
+    state: State<'_, Arc<Mutex<GameManager>>>,
+    mode: ImportMode,
+) -> Result<Option<GameState>, String> {
+    let mut dialog = app.dialog().file().add_filter("Rusty2048 save", &["json"]);
+    if let Ok(dir) = export_dir(&app) {
+        dialog = dialog.set_directory(dir);
+    }
+    let Some(path) = dialog.blocking_pick_file() else {
+        return Ok(None);
+    };
+    let path = path.into_path().map_err(|e| e.to_string())?;
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+    let bundle = SaveBundle::from_json(&contents)?;
+    let mut game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    game_manager.import_bundle(bundle, mode)?;
+    // Key bindings and the best score may have changed
+    menu::refresh_menu(&app, &game_manager)?;
+    tray::refresh_tray(&app, &game_manager);
+    events::publish(&app, &mut game_manager);
+    Ok(Some(game_manager.get_state()))
+}
@@ -0,0 +1,108 @@
+use crate::sessions::MAIN_SESSION;
+use crate::{events, GameManager, GameState};
+use rusty2048_core::{DailyChallenge, DailyResult};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Runtime, State};
+
+/// File name of the daily challenge history inside the app data dir
+pub const DAILY_FILE: &str = "daily.json";
+/// Event emitted with the new `DailyChallenge` when the UTC day rolls over
+pub const DAILY_AVAILABLE_EVENT: &str = "daily-available";
+
+const DAY_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Finished daily challenges, oldest first
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DailyHistory {
+    pub results: Vec<DailyResult>,
+}
+
+/// Today's challenge and whether it was already played
+#[derive(Serialize)]
+pub struct DailyStatus {
+    pub challenge: DailyChallenge,
+    pub result: Option<DailyResult>,
+}
+
+impl GameManager {
+    /// Replace the main game with today's challenge
+    pub fn start_daily(&mut self) -> Result<(), String> {
+        let challenge = DailyChallenge::today();
+        let mut game = challenge.new_game().map_err(|e| e.to_string())?;
+        game.restore_best_score(self.game().score().best());
+        self.replace_game(MAIN_SESSION, game)?;
+        self.daily = Some(challenge);
+        Ok(())
+    }
+
+    /// Record the daily result once the daily game has ended
+    pub fn record_daily_if_finished(&mut self) -> Result<(), String> {
+        if self.game().state() == rusty2048_core::GameState::Playing {
+            return Ok(());
+        }
+        let Some(challenge) = &self.daily else {
+            return Ok(());
+        };
+
+        // Only the first finished attempt of a day counts
+        if self.daily_result(challenge.day).is_none() {
+            let result = challenge.result(self.game());
+            self.daily_history.results.push(result);
+            self.daily_store
+                .save(&self.daily_history)
+                .map_err(|e| format!("Failed to save daily history: {}", e))?;
+        }
+        Ok(())
+    }
+
+    fn daily_result(&self, day: u64) -> Option<&DailyResult> {
+        self.daily_history.results.iter().find(|r| r.day == day)
+    }
+}
+
+/// Notify the frontend whenever a new daily challenge becomes available
+pub fn spawn_daily_watcher<R: Runtime>(app: AppHandle<R>) {
+    std::thread::spawn(move || {
+        let mut day = DailyChallenge::today().day;
+        loop {
+            std::thread::sleep(DAY_CHECK_INTERVAL);
+            let challenge = DailyChallenge::today();
+            if challenge.day != day {
+                day = challenge.day;
+                let _ = app.emit(DAILY_AVAILABLE_EVENT, challenge);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_daily_challenge(
+    state: State<'_, Arc<Mutex<GameManager>>>,
+) -> Result<DailyStatus, String> {
+    let game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    let challenge = DailyChallenge::today();
+    let result = game_manager.daily_result(challenge.day).cloned();
+    Ok(DailyStatus { challenge, result })
+}
+
+#[tauri::command]
+pub async fn start_daily_challenge(
+    app: AppHandle,
+    state: State<'_, Arc<Mutex<GameManager>>>,
+) -> Result<GameState, String> {
+    let mut game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    game_manager.start_daily()?;
+    events::publish(&app, &mut game_manager);
+    Ok(game_manager.get_state())
+}
+
+#[tauri::command]
+pub async fn get_daily_history(
+    state: State<'_, Arc<Mutex<GameManager>>>,
+) -> Result<Vec<DailyResult>, String> {
+    let game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    Ok(game_manager.daily_history.results.clone())
+}
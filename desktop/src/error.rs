@@ -0,0 +1,42 @@
+use rusty2048_core::GameError;
+use serde::{Serialize, Serializer};
+
+/// Error returned by the game commands. Game errors reach the frontend as
+/// `{code, message, details}` so it can tell them apart; anything else is
+/// reported with the code `"other"`.
+#[derive(Debug)]
+pub enum CommandError {
+    Game(GameError),
+    Other(String),
+}
+
+impl From<GameError> for CommandError {
+    fn from(error: GameError) -> Self {
+        CommandError::Game(error)
+    }
+}
+
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        CommandError::Other(message)
+    }
+}
+
+impl Serialize for CommandError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Other<'a> {
+            code: &'static str,
+            message: &'a str,
+        }
+
+        match self {
+            CommandError::Game(error) => error.serialize(serializer),
+            CommandError::Other(message) => Other {
+                code: "other",
+                message,
+            }
+            .serialize(serializer),
+        }
+    }
+}
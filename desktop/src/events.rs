@@ -0,0 +1,19 @@
+use crate::GameManager;
+use rusty2048_core::GameEvent;
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// Event emitted with the new game state whenever the main game changes
+pub const STATE_CHANGED_EVENT: &str = "state-changed";
+/// Event emitted with the final `GameEvent` when the main game is won or lost
+pub const GAME_OVER_EVENT: &str = "game-over";
+
+/// Push the main game's queued events and its new state to the frontend
+pub fn publish<R: Runtime>(app: &AppHandle<R>, game_manager: &mut GameManager) {
+    let events = game_manager.game_mut().take_events();
+    for event in events {
+        if matches!(event, GameEvent::Won { .. } | GameEvent::GameOver { .. }) {
+            let _ = app.emit(GAME_OVER_EVENT, event);
+        }
+    }
+    let _ = app.emit(STATE_CHANGED_EVENT, game_manager.get_state());
+}
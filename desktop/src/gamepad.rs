@@ -0,0 +1,72 @@
+use gilrs::{Axis, Button, EventType, Gilrs};
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// Event emitted with the action of a gamepad press (`move_up`, `new_game`,
+/// `undo`, ...), run by the frontend like a key binding
+pub const GAMEPAD_EVENT: &str = "gamepad-action";
+
+/// How far the left stick must be pushed to count as a move
+const STICK_THRESHOLD: f32 = 0.6;
+
+/// Action of a button: D-pad to move, Start for a new game, B to undo and
+/// Y for a hint
+fn button_action(button: Button) -> Option<&'static str> {
+    match button {
+        Button::DPadUp => Some("move_up"),
+        Button::DPadDown => Some("move_down"),
+        Button::DPadLeft => Some("move_left"),
+        Button::DPadRight => Some("move_right"),
+        Button::Start => Some("new_game"),
+        Button::East => Some("undo"),
+        Button::North => Some("hint"),
+        _ => None,
+    }
+}
+
+/// Direction the left stick is pushed in, if it is pushed far enough
+fn stick_action(x: f32, y: f32) -> Option<&'static str> {
+    if x.abs().max(y.abs()) < STICK_THRESHOLD {
+        None
+    } else if x.abs() > y.abs() {
+        Some(if x < 0.0 { "move_left" } else { "move_right" })
+    } else {
+        Some(if y > 0.0 { "move_up" } else { "move_down" })
+    }
+}
+
+/// Listen to connected gamepads and forward their presses to the frontend
+pub fn spawn_gamepad_listener<R: Runtime>(app: AppHandle<R>) {
+    std::thread::spawn(move || {
+        let mut gilrs = match Gilrs::new() {
+            Ok(gilrs) => gilrs,
+            Err(e) => {
+                eprintln!("Gamepad input is unavailable: {}", e);
+                return;
+            }
+        };
+        // Holding the stick moves once, like holding a button
+        let mut stick = None;
+        loop {
+            let Some(event) = gilrs.next_event_blocking(None) else {
+                continue;
+            };
+            let action = match event.event {
+                EventType::ButtonPressed(button, _) => button_action(button),
+                EventType::AxisChanged(Axis::LeftStickX | Axis::LeftStickY, _, _) => {
+                    let gamepad = gilrs.gamepad(event.id);
+                    let pushed = stick_action(
+                        gamepad.value(Axis::LeftStickX),
+                        gamepad.value(Axis::LeftStickY),
+                    );
+                    let changed = pushed != stick;
+                    stick = pushed;
+                    pushed.filter(|_| changed)
+                }
+                _ => None,
+            };
+            if let Some(action) = action {
+                let _ = app.emit(GAMEPAD_EVENT, action);
+            }
+        }
+    });
+}
@@ -0,0 +1,39 @@
+use crate::GameManager;
+use rusty2048_shared::{Action, KeyBindings};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn get_key_bindings(
+    state: State<'_, Arc<Mutex<GameManager>>>,
+) -> Result<KeyBindings, String> {
+    let game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    Ok(game_manager.settings.key_bindings.clone())
+}
+
+#[tauri::command]
+pub async fn set_key_binding(
+    app: AppHandle,
+    state: State<'_, Arc<Mutex<GameManager>>>,
+    action: String,
+    keys: Vec<String>,
+) -> Result<KeyBindings, String> {
+    let action = Action::from_name(&action).ok_or_else(|| "Invalid action".to_string())?;
+    let mut game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    game_manager.settings.key_bindings.set(action, keys)?;
+    game_manager.save_settings()?;
+    crate::menu::refresh_menu(&app, &game_manager)?;
+    Ok(game_manager.settings.key_bindings.clone())
+}
+
+#[tauri::command]
+pub async fn reset_key_bindings(
+    app: AppHandle,
+    state: State<'_, Arc<Mutex<GameManager>>>,
+) -> Result<KeyBindings, String> {
+    let mut game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    game_manager.settings.key_bindings = KeyBindings::default();
+    game_manager.save_settings()?;
+    crate::menu::refresh_menu(&app, &game_manager)?;
+    Ok(game_manager.settings.key_bindings.clone())
+}
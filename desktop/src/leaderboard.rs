@@ -0,0 +1,126 @@
+use crate::sessions::MAIN_SESSION;
+use crate::{modes, GameManager};
+use rusty2048_core::{GameState, LeaderboardEntry};
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+/// File name of the local leaderboard inside the app data dir
+pub const LEADERBOARD_FILE: &str = "leaderboard.json";
+
+/// Outcome of submitting a score
+#[derive(Serialize)]
+pub struct SubmitResult {
+    /// 1-based rank in the board size and mode, if the score made the list
+    pub rank: Option<usize>,
+    pub entry: LeaderboardEntry,
+}
+
+/// One board size and mode with its entries, best first
+#[derive(Serialize)]
+pub struct LeaderboardCategory {
+    pub board_size: usize,
+    pub mode: String,
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+impl GameManager {
+    /// Submit the finished main game under a player name
+    pub fn submit_score(&mut self, name: &str) -> Result<SubmitResult, String> {
+        let game = self.session(MAIN_SESSION)?;
+        if game.state() == GameState::Playing {
+            return Err("The game is not finished yet".to_string());
+        }
+        if self.leaderboard_submitted {
+            return Err("This game was already submitted".to_string());
+        }
+
+        if name.trim().is_empty() {
+            return Err("Please enter a name".to_string());
+        }
+
+        // The desktop app does not keep replays of finished games yet
+        let entry = LeaderboardEntry::for_game(
+            name,
+            game,
+            modes::mode_id(game.config(), self.daily.is_some()),
+        );
+
+        let rank = self.leaderboard.submit(entry.clone());
+        self.leaderboard_submitted = true;
+        self.save_leaderboard()?;
+        Ok(SubmitResult { rank, entry })
+    }
+
+    fn save_leaderboard(&self) -> Result<(), String> {
+        self.leaderboard_store
+            .save(&self.leaderboard)
+            .map_err(|e| format!("Failed to save leaderboard: {}", e))
+    }
+}
+
+fn in_filter(
+    size: Option<usize>,
+    mode: Option<&str>,
+    board_size: usize,
+    category_mode: &str,
+) -> bool {
+    size.is_none_or(|size| size == board_size) && mode.is_none_or(|mode| mode == category_mode)
+}
+
+/// Leaderboard entries grouped by board size and mode. Pass `size` and `mode`
+/// to get a single category.
+#[tauri::command]
+pub async fn get_leaderboard(
+    state: State<'_, Arc<Mutex<GameManager>>>,
+    size: Option<usize>,
+    mode: Option<String>,
+) -> Result<Vec<LeaderboardCategory>, String> {
+    let game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    let leaderboard = &game_manager.leaderboard;
+
+    let categories = leaderboard
+        .categories()
+        .into_iter()
+        .filter(|(board_size, category_mode)| {
+            in_filter(size, mode.as_deref(), *board_size, category_mode)
+        })
+        .map(|(board_size, mode)| LeaderboardCategory {
+            entries: leaderboard
+                .top(board_size, &mode)
+                .into_iter()
+                .cloned()
+                .collect(),
+            board_size,
+            mode,
+        })
+        .collect();
+    Ok(categories)
+}
+
+#[tauri::command]
+pub async fn submit_score(
+    state: State<'_, Arc<Mutex<GameManager>>>,
+    name: String,
+) -> Result<SubmitResult, String> {
+    let mut game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    game_manager.submit_score(&name)
+}
+
+/// Remove leaderboard entries. Without `size` and `mode` everything is cleared.
+#[tauri::command]
+pub async fn clear_leaderboard(
+    state: State<'_, Arc<Mutex<GameManager>>>,
+    size: Option<usize>,
+    mode: Option<String>,
+) -> Result<(), String> {
+    let mut game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    for (board_size, category_mode) in game_manager.leaderboard.categories() {
+        if in_filter(size, mode.as_deref(), board_size, &category_mode) {
+            game_manager
+                .leaderboard
+                .clear_category(board_size, &category_mode);
+        }
+    }
+    game_manager.save_leaderboard()
+}
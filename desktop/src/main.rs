@@ -1,11 +1,43 @@
 // Prevents additional console window on Windows in release
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use rusty2048_core::{Direction, Game, GameConfig};
-use rusty2048_shared::{I18n, Language, Theme, TranslationKey};
+use error::CommandError;
+use rusty2048_core::{
+    Board, CancellationToken, Direction, Game, GameConfig, Leaderboard, MoveResult,
+    StatisticsManager, TileChange,
+};
+use rusty2048_shared::{
+    AchievementProgress, ConfigStore, I18n, Language, Theme, ThemeLoader, TranslationKey,
+    UserSettings,
+};
 use serde::{Deserialize, Serialize};
+use sessions::{SessionId, MAIN_SESSION};
+use std::collections::HashMap;
+use std::path::Path;
 use std::sync::{Arc, Mutex};
-use tauri::State;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+mod achievements;
+mod ai;
+mod autosave;
+mod bundle;
+mod daily;
+mod error;
+mod events;
+mod gamepad;
+mod keybindings;
+mod leaderboard;
+mod menu;
+mod migration;
+mod modes;
+mod sessions;
+mod settings;
+mod share;
+mod stats;
+mod sync;
+mod themes;
+mod tray;
+mod video;
 
 #[derive(Deserialize)]
 struct SetThemeArgs {
@@ -13,104 +45,354 @@ struct SetThemeArgs {
     theme_name: String,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 struct GameState {
-    board: Vec<Vec<u32>>,
-    score: u32,
-    best_score: u32,
+    session: SessionId,
+    board: Vec<Vec<u64>>,
+    score: u64,
+    best_score: u64,
     moves: u32,
     game_state: String,
-    max_tile: u32,
+    max_tile: u64,
     can_undo: bool,
+    undo_count: usize,
+    can_redo: bool,
+    /// Whether the play clock is stopped
+    paused: bool,
     theme: Theme,
+    /// Tile movements of the move that produced this state, for animations
+    last_move: Option<MoveResult>,
 }
 
 struct GameManager {
-    game: Game,
+    /// Open game sessions, always including `MAIN_SESSION`
+    games: HashMap<SessionId, Game>,
+    next_session: SessionId,
     theme: Theme,
     i18n: I18n,
+    stats: StatisticsManager,
+    /// Id of the session the current game was recorded as, once it ended.
+    /// A game continued after the win updates that session when it ends.
+    recorded_session: Option<u64>,
+    /// Whether the AI made moves in the current game
+    ai_played: bool,
+    /// Stops the AI autoplay task, if one is active
+    autoplay: Option<CancellationToken>,
+    settings: UserSettings,
+    settings_store: ConfigStore,
+    autosave_store: ConfigStore,
+    theme_loader: ThemeLoader,
+    achievements: AchievementProgress,
+    achievements_store: ConfigStore,
+    sync: sync::SyncSettings,
+    sync_store: ConfigStore,
+    /// Problem found while migrating or loading data at startup
+    data_error: Option<String>,
+    /// The daily challenge being played in the main session, if any
+    daily: Option<rusty2048_core::DailyChallenge>,
+    daily_history: daily::DailyHistory,
+    daily_store: ConfigStore,
+    leaderboard: Leaderboard,
+    leaderboard_store: ConfigStore,
+    /// Whether the current main game was already submitted to the leaderboard
+    leaderboard_submitted: bool,
 }
 
 impl GameManager {
-    fn new() -> Result<Self, Box<dyn std::error::Error>> {
+    fn new(data_dir: &Path) -> Result<Self, Box<dyn std::error::Error>> {
         let config = GameConfig::default();
-        let game = Game::new(config)?;
+        let mut game = Game::new(config)?;
+        game.enable_events();
         let theme = Theme::default();
         let i18n = I18n::new();
-        Ok(GameManager { game, theme, i18n })
+
+        std::fs::create_dir_all(data_dir)?;
+        let mut data_error = migration::migrate_data_dir(data_dir).err();
+
+        // Unreadable files are reported instead of being silently replaced
+        let stats_file = data_dir.join(stats::STATS_FILE);
+        let stats = StatisticsManager::new(&stats_file.to_string_lossy()).unwrap_or_else(|e| {
+            data_error.get_or_insert(e.to_string());
+            StatisticsManager::in_memory()
+        });
+        let (settings_store, settings) = Self::load_settings(data_dir);
+        let achievements_store = ConfigStore::in_dir(data_dir, achievements::ACHIEVEMENTS_FILE);
+        let achievements = achievements_store.load().unwrap_or_else(|e| {
+            data_error.get_or_insert(format!("Failed to read achievements: {}", e));
+            Default::default()
+        });
+        let daily_store = ConfigStore::in_dir(data_dir, daily::DAILY_FILE);
+        let daily_history = daily_store.load().unwrap_or_else(|e| {
+            data_error.get_or_insert(format!("Failed to read daily history: {}", e));
+            Default::default()
+        });
+        let leaderboard_store = ConfigStore::in_dir(data_dir, leaderboard::LEADERBOARD_FILE);
+        let leaderboard = leaderboard_store.load().unwrap_or_else(|e| {
+            data_error.get_or_insert(format!("Failed to read leaderboard: {}", e));
+            Default::default()
+        });
+        let sync_store = ConfigStore::in_dir(data_dir, sync::SYNC_FILE);
+        let sync = sync_store.load().unwrap_or_default();
+
+        let mut manager = GameManager {
+            games: HashMap::from([(MAIN_SESSION, game)]),
+            next_session: MAIN_SESSION + 1,
+            theme,
+            i18n,
+            stats,
+            recorded_session: None,
+            ai_played: false,
+            autoplay: None,
+            settings,
+            settings_store,
+            autosave_store: ConfigStore::in_dir(data_dir, autosave::AUTOSAVE_FILE),
+            theme_loader: ThemeLoader::new(data_dir.join(themes::THEMES_DIR)),
+            achievements,
+            achievements_store,
+            sync,
+            sync_store,
+            data_error,
+            daily: None,
+            daily_history,
+            daily_store,
+            leaderboard,
+            leaderboard_store,
+            leaderboard_submitted: false,
+        };
+        manager.apply_settings();
+        if manager.data_error.is_some() {
+            manager.protect_data();
+        }
+        Ok(manager)
     }
 
-    fn get_state(&self) -> GameState {
-        let board = self.game.board();
-        let size = board.size();
-        let mut board_data = vec![vec![0u32; size]; size];
-
-        for (row, row_data) in board_data.iter_mut().enumerate().take(size) {
-            for (col, cell) in row_data.iter_mut().enumerate().take(size) {
-                if let Ok(tile) = board.get_tile(row, col) {
-                    *cell = tile.value;
-                }
-            }
+    /// Stop writing to the data dir after a file could not be read or
+    /// migrated, so the player can still recover it. The game goes on with
+    /// what was loaded; statistics are kept in memory for this run.
+    fn protect_data(&mut self) {
+        for store in [
+            &mut self.settings_store,
+            &mut self.autosave_store,
+            &mut self.achievements_store,
+            &mut self.sync_store,
+            &mut self.daily_store,
+            &mut self.leaderboard_store,
+        ] {
+            store.set_read_only(true);
+        }
+        self.stats.detach_file();
+        if let Some(error) = &mut self.data_error {
+            error.push_str(" Nothing will be saved until this is fixed.");
         }
+    }
 
-        let game_state = match self.game.state() {
+    fn get_state(&self) -> GameState {
+        Self::state_of(MAIN_SESSION, self.game(), &self.theme)
+    }
+
+    fn session_state(&self, session: SessionId) -> Result<GameState, String> {
+        Ok(Self::state_of(session, self.session(session)?, &self.theme))
+    }
+
+    fn state_of(session: SessionId, game: &Game, theme: &Theme) -> GameState {
+        let board = game.board();
+        let game_state = match game.state() {
             rusty2048_core::GameState::Playing => "playing",
             rusty2048_core::GameState::Won => "won",
             rusty2048_core::GameState::GameOver => "game_over",
         };
 
         GameState {
-            board: board_data,
-            score: self.game.score().current(),
-            best_score: self.game.score().best(),
-            moves: self.game.moves(),
+            session,
+            board: board.to_vec(),
+            score: game.score().current(),
+            best_score: game.score().best(),
+            moves: game.moves(),
             game_state: game_state.to_string(),
-            max_tile: self.game.board().max_tile(),
-            can_undo: self.game.can_undo(),
-            theme: self.theme.clone(),
+            max_tile: board.max_tile(),
+            can_undo: game.can_undo(),
+            undo_count: game.undo_count(),
+            can_redo: game.can_redo(),
+            paused: game.is_paused(),
+            theme: theme.clone(),
+            last_move: None,
         }
     }
+
+    fn start_new_game(&mut self) -> Result<(), String> {
+        // Daily and seeded games are one-offs; the next game gets fresh tiles
+        if self.daily.is_some() || self.game().config().seed.is_some() {
+            let config = match self.daily {
+                Some(_) => GameConfig::default(),
+                None => GameConfig {
+                    seed: None,
+                    ..self.game().config().clone()
+                },
+            };
+            return self.start_game_with_config(MAIN_SESSION, config);
+        }
+
+        self.game_mut().new_game().map_err(|e| e.to_string())?;
+        self.recorded_session = None;
+        self.ai_played = false;
+        self.leaderboard_submitted = false;
+        Ok(())
+    }
+
+    fn set_theme_by_name(&mut self, theme_name: &str) -> Result<(), String> {
+        self.theme = self
+            .find_theme(theme_name)
+            .ok_or_else(|| "Invalid theme name".to_string())?;
+        self.save_settings()
+    }
+
+    fn set_language_code(&mut self, language_code: &str) -> Result<(), String> {
+        let language = Language::from_code(language_code)
+            .ok_or_else(|| "Invalid language code".to_string())?;
+        self.i18n.set_language(language);
+        self.settings.language = Some(language.code().to_string());
+        self.save_settings()
+    }
 }
 
 #[tauri::command]
 async fn make_move(
+    app: AppHandle,
     state: State<'_, Arc<Mutex<GameManager>>>,
     direction: String,
-) -> Result<GameState, String> {
+    session: Option<SessionId>,
+) -> Result<GameState, CommandError> {
     let dir = match direction.as_str() {
         "up" => Direction::Up,
         "down" => Direction::Down,
         "left" => Direction::Left,
         "right" => Direction::Right,
-        _ => return Err("Invalid direction".to_string()),
+        _ => return Err(CommandError::Other("Invalid direction".to_string())),
     };
 
+    let session = session.unwrap_or(MAIN_SESSION);
     let mut game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
-    game_manager
-        .game
-        .make_move(dir)
-        .map_err(|e| e.to_string())?;
-    Ok(game_manager.get_state())
+    // Moving by hand takes over from the AI, even mid-search
+    if session == MAIN_SESSION && game_manager.stop_autoplay() {
+        let _ = app.emit(ai::AI_STOPPED_EVENT, game_manager.get_state());
+    }
+    let result = game_manager.session_mut(session)?.make_move(dir)?;
+    if session == MAIN_SESSION {
+        let unlocked = game_manager.after_move()?;
+        achievements::notify_unlocked(&app, &game_manager, &unlocked);
+        tray::refresh_tray(&app, &game_manager);
+        events::publish(&app, &mut game_manager);
+    }
+    let mut game_state = game_manager.session_state(session)?;
+    game_state.last_move = Some(result);
+    Ok(game_state)
 }
 
 #[tauri::command]
-async fn get_state(state: State<'_, Arc<Mutex<GameManager>>>) -> Result<GameState, String> {
+async fn get_state(
+    state: State<'_, Arc<Mutex<GameManager>>>,
+    session: Option<SessionId>,
+) -> Result<GameState, String> {
     let game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
-    Ok(game_manager.get_state())
+    game_manager.session_state(session.unwrap_or(MAIN_SESSION))
+}
+
+#[tauri::command]
+async fn new_game(
+    app: AppHandle,
+    state: State<'_, Arc<Mutex<GameManager>>>,
+    session: Option<SessionId>,
+) -> Result<GameState, CommandError> {
+    let session = session.unwrap_or(MAIN_SESSION);
+    let mut game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    if session == MAIN_SESSION {
+        game_manager.start_new_game()?;
+        events::publish(&app, &mut game_manager);
+    } else {
+        game_manager.session_mut(session)?.new_game()?;
+    }
+    game_manager
+        .session_state(session)
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-async fn new_game(state: State<'_, Arc<Mutex<GameManager>>>) -> Result<GameState, String> {
+async fn undo(
+    app: AppHandle,
+    state: State<'_, Arc<Mutex<GameManager>>>,
+    session: Option<SessionId>,
+) -> Result<GameState, CommandError> {
+    let session = session.unwrap_or(MAIN_SESSION);
     let mut game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
-    game_manager.game.new_game().map_err(|e| e.to_string())?;
-    Ok(game_manager.get_state())
+    game_manager.session_mut(session)?.undo()?;
+    if session == MAIN_SESSION {
+        events::publish(&app, &mut game_manager);
+    }
+    game_manager
+        .session_state(session)
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-async fn undo(state: State<'_, Arc<Mutex<GameManager>>>) -> Result<GameState, String> {
+async fn redo(
+    app: AppHandle,
+    state: State<'_, Arc<Mutex<GameManager>>>,
+    session: Option<SessionId>,
+) -> Result<GameState, CommandError> {
+    let session = session.unwrap_or(MAIN_SESSION);
     let mut game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
-    game_manager.game.undo().map_err(|e| e.to_string())?;
-    Ok(game_manager.get_state())
+    game_manager.session_mut(session)?.redo()?;
+    if session == MAIN_SESSION {
+        events::publish(&app, &mut game_manager);
+    }
+    game_manager
+        .session_state(session)
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn continue_after_win(
+    app: AppHandle,
+    state: State<'_, Arc<Mutex<GameManager>>>,
+    session: Option<SessionId>,
+) -> Result<GameState, CommandError> {
+    let session = session.unwrap_or(MAIN_SESSION);
+    let mut game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    game_manager.session_mut(session)?.continue_after_win()?;
+    if session == MAIN_SESSION {
+        events::publish(&app, &mut game_manager);
+    }
+    game_manager
+        .session_state(session)
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn toggle_pause(
+    state: State<'_, Arc<Mutex<GameManager>>>,
+    session: Option<SessionId>,
+) -> Result<GameState, CommandError> {
+    let session = session.unwrap_or(MAIN_SESSION);
+    let mut game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    let game = game_manager.session_mut(session)?;
+    if game.is_paused() {
+        game.resume();
+    } else {
+        game.pause();
+    }
+    game_manager
+        .session_state(session)
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+async fn diff_boards(
+    before: Vec<Vec<u64>>,
+    after: Vec<Vec<u64>>,
+) -> Result<Vec<TileChange>, CommandError> {
+    let before = Board::from_vec(before)?;
+    let after = Board::from_vec(after)?;
+    Ok(before.diff(&after))
 }
 
 #[tauri::command]
@@ -119,29 +401,35 @@ async fn set_theme(
     args: SetThemeArgs,
 ) -> Result<GameState, String> {
     let mut mgr = state.lock().map_err(|_| "lock poisoned".to_string())?;
-    if let Some(theme) = Theme::by_name(&args.theme_name) {
-        mgr.theme = theme;
-        Ok(mgr.get_state())
-    } else {
-        Err("Invalid theme name".into())
-    }
+    mgr.set_theme_by_name(&args.theme_name)?;
+    Ok(mgr.get_state())
 }
 
 #[tauri::command]
-async fn get_available_themes() -> Vec<String> {
-    Theme::all_themes().iter().map(|t| t.name.clone()).collect()
+async fn get_available_themes(
+    state: State<'_, Arc<Mutex<GameManager>>>,
+) -> Result<Vec<String>, String> {
+    let game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    Ok(Theme::all_themes()
+        .into_iter()
+        .chain(game_manager.theme_loader.load_all())
+        .map(|t| t.name)
+        .collect())
 }
 
 #[tauri::command]
 async fn get_stats(state: State<'_, Arc<Mutex<GameManager>>>) -> Result<serde_json::Value, String> {
     let game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
-    let stats = game_manager.game.stats();
+    let stats = game_manager.game().stats();
     Ok(serde_json::json!({
         "duration": stats.duration,
-        "max_tile": game_manager.game.board().max_tile(),
-        "moves": game_manager.game.moves(),
-        "score": game_manager.game.score().current(),
-        "best_score": game_manager.game.score().best()
+        "max_tile": game_manager.game().board().max_tile(),
+        "moves": game_manager.game().moves(),
+        "score": game_manager.game().score().current(),
+        "best_score": game_manager.game().score().best(),
+        "score_breakdown": stats.score_breakdown,
+        // As a string, since seeds may not fit in a JS number
+        "seed": game_manager.game().seed().map(|seed| seed.to_string())
     }))
 }
 
@@ -162,12 +450,7 @@ async fn set_language(
     language_code: String,
 ) -> Result<(), String> {
     let mut game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
-    if let Some(language) = Language::from_code(&language_code) {
-        game_manager.i18n.set_language(language);
-        Ok(())
-    } else {
-        Err("Invalid language code".to_string())
-    }
+    game_manager.set_language_code(&language_code)
 }
 
 #[tauri::command]
@@ -192,17 +475,41 @@ async fn get_translation(
 }
 
 fn main() {
-    let game_manager = Arc::new(Mutex::new(
-        GameManager::new().expect("Failed to create game"),
-    ));
-
     tauri::Builder::default()
-        .manage(game_manager)
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
+        .setup(|app| {
+            let data_dir = app.path().app_data_dir()?;
+            let game_manager = GameManager::new(&data_dir)?;
+            app.set_menu(menu::build_menu(app.handle(), &game_manager)?)?;
+            tray::create_tray(app.handle(), &game_manager)?;
+
+            let game_manager = Arc::new(Mutex::new(game_manager));
+            autosave::spawn_autosave_task(game_manager.clone());
+            daily::spawn_daily_watcher(app.handle().clone());
+            gamepad::spawn_gamepad_listener(app.handle().clone());
+            app.manage(game_manager);
+            Ok(())
+        })
+        .on_menu_event(menu::handle_menu_event)
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::CloseRequested { .. } = event {
+                let state = window.state::<Arc<Mutex<GameManager>>>();
+                let saved = state.lock().map(|game_manager| game_manager.autosave());
+                if let Ok(Err(e)) = saved {
+                    eprintln!("{}", e);
+                }
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             make_move,
             get_state,
             new_game,
             undo,
+            redo,
+            continue_after_win,
+            toggle_pause,
+            diff_boards,
             set_theme,
             get_available_themes,
             get_stats,
@@ -210,7 +517,48 @@ fn main() {
             get_language,
             set_language,
             get_supported_languages,
-            get_translation
+            get_translation,
+            stats::record_session,
+            stats::get_statistics_summary,
+            stats::get_trends,
+            stats::get_statistics_segments,
+            stats::get_summary_for_range,
+            stats::get_period_totals,
+            stats::import_and_merge_statistics,
+            stats::get_stats_retention,
+            stats::set_stats_retention,
+            achievements::get_achievements,
+            ai::get_hint,
+            ai::start_ai_autoplay,
+            ai::stop_ai_autoplay,
+            autosave::get_saved_game,
+            autosave::resume_saved_game,
+            bundle::export_save_bundle,
+            bundle::import_save_bundle,
+            daily::get_daily_challenge,
+            daily::start_daily_challenge,
+            daily::get_daily_history,
+            migration::get_data_error,
+            modes::new_game_with_config,
+            modes::get_available_modes,
+            sync::get_sync_settings,
+            sync::set_sync_target,
+            sync::sync_now,
+            share::create_share_image,
+            video::export_replay_video,
+            sessions::create_game_session,
+            sessions::close_game_session,
+            sessions::list_game_sessions,
+            themes::import_theme,
+            themes::save_custom_theme,
+            themes::list_custom_themes,
+            themes::delete_custom_theme,
+            leaderboard::get_leaderboard,
+            leaderboard::submit_score,
+            leaderboard::clear_leaderboard,
+            keybindings::get_key_bindings,
+            keybindings::set_key_binding,
+            keybindings::reset_key_bindings
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
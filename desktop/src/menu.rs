@@ -0,0 +1,128 @@
+use crate::{events, GameManager};
+use rusty2048_shared::{Action, Language, Theme};
+use std::sync::{Arc, Mutex};
+use tauri::menu::{
+    AboutMetadataBuilder, Menu, MenuBuilder, MenuEvent, MenuItemBuilder, SubmenuBuilder,
+};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+/// Event emitted with the item id for menu actions handled by the frontend
+pub const MENU_ACTION_EVENT: &str = "menu-action";
+
+const THEME_PREFIX: &str = "theme:";
+const LANGUAGE_PREFIX: &str = "language:";
+
+/// Build the application menu; accelerators follow the user's key bindings
+pub fn build_menu<R: Runtime>(
+    app: &AppHandle<R>,
+    game_manager: &GameManager,
+) -> tauri::Result<Menu<R>> {
+    let bindings = &game_manager.settings.key_bindings;
+    let item = |id: &str, text: &str, action: Option<Action>| {
+        let mut builder = MenuItemBuilder::with_id(id, text);
+        if let Some(accelerator) = action.and_then(|action| bindings.accelerator(action)) {
+            builder = builder.accelerator(accelerator);
+        }
+        builder.build(app)
+    };
+
+    let game_menu = SubmenuBuilder::new(app, "Game")
+        .item(&item("new_game", "New Game", Some(Action::NewGame))?)
+        .item(&item("undo", "Undo", Some(Action::Undo))?)
+        .item(&item("redo", "Redo", Some(Action::Redo))?)
+        .separator()
+        .item(
+            &MenuItemBuilder::with_id("save", "Save")
+                .accelerator("CmdOrCtrl+S")
+                .build(app)?,
+        )
+        .item(
+            &MenuItemBuilder::with_id("open_replay", "Open Replay…")
+                .accelerator("CmdOrCtrl+O")
+                .build(app)?,
+        )
+        .separator()
+        .quit()
+        .build()?;
+
+    let mut theme_menu = SubmenuBuilder::new(app, "Theme");
+    for theme in Theme::all_themes()
+        .into_iter()
+        .chain(game_manager.theme_loader.load_all())
+    {
+        theme_menu = theme_menu.text(format!("{}{}", THEME_PREFIX, theme.name), &theme.name);
+    }
+
+    let mut language_menu = SubmenuBuilder::new(app, "Language");
+    for language in Language::all() {
+        language_menu = language_menu.text(
+            format!("{}{}", LANGUAGE_PREFIX, language.code()),
+            language.name(),
+        );
+    }
+
+    let view_menu = SubmenuBuilder::new(app, "View")
+        .item(&theme_menu.build()?)
+        .item(&language_menu.build()?)
+        .build()?;
+
+    let about = AboutMetadataBuilder::new()
+        .name(Some("Rusty2048"))
+        .version(Some(env!("CARGO_PKG_VERSION")))
+        .build();
+    let help_menu = SubmenuBuilder::new(app, "Help")
+        .about(Some(about))
+        .text("shortcuts", "Keyboard Shortcuts")
+        .build()?;
+
+    MenuBuilder::new(app)
+        .items(&[&game_menu, &view_menu, &help_menu])
+        .build()
+}
+
+/// Rebuild the menu after key bindings or custom themes changed
+pub fn refresh_menu<R: Runtime>(
+    app: &AppHandle<R>,
+    game_manager: &GameManager,
+) -> Result<(), String> {
+    build_menu(app, game_manager)
+        .and_then(|menu| app.set_menu(menu))
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Handle a menu click by running the same logic as the matching command
+pub fn handle_menu_event<R: Runtime>(app: &AppHandle<R>, event: MenuEvent) {
+    let id = event.id().as_ref();
+    // Tray items are handled by the tray's own listener
+    if id.starts_with("tray_") {
+        return;
+    }
+    let state = app.state::<Arc<Mutex<GameManager>>>();
+    let Ok(mut game_manager) = state.lock() else {
+        return;
+    };
+
+    let result = match id {
+        "new_game" => game_manager.start_new_game(),
+        "undo" => game_manager.game_mut().undo().map_err(|e| e.to_string()),
+        "redo" => game_manager.game_mut().redo().map_err(|e| e.to_string()),
+        "save" => game_manager.autosave(),
+        _ => {
+            if let Some(theme_name) = id.strip_prefix(THEME_PREFIX) {
+                game_manager.set_theme_by_name(theme_name)
+            } else if let Some(language_code) = id.strip_prefix(LANGUAGE_PREFIX) {
+                game_manager.set_language_code(language_code)
+            } else {
+                // Dialogs such as "Open Replay" and "Shortcuts" live in the frontend
+                let _ = app.emit(MENU_ACTION_EVENT, id);
+                return;
+            }
+        }
+    };
+
+    match result {
+        Ok(()) => events::publish(app, &mut game_manager),
+        Err(e) => eprintln!("Menu action {} failed: {}", id, e),
+    }
+}
@@ -0,0 +1,189 @@
+use crate::{achievements, autosave, daily, leaderboard, settings, stats, sync, GameManager};
+use rusty2048_shared::ConfigStore;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+/// Version of the on-disk data format written by this build
+pub const DATA_VERSION: u32 = 2;
+/// File recording the data format version inside the app data dir
+pub const VERSION_FILE: &str = "data_version.json";
+/// Directory for backups taken before a migration
+pub const BACKUP_DIR: &str = "backups";
+
+#[derive(Default, Serialize, Deserialize)]
+struct DataVersion {
+    version: u32,
+}
+
+/// Data files covered by the format version
+fn data_files() -> [&'static str; 7] {
+    [
+        stats::STATS_FILE,
+        settings::SETTINGS_FILE,
+        achievements::ACHIEVEMENTS_FILE,
+        autosave::AUTOSAVE_FILE,
+        sync::SYNC_FILE,
+        daily::DAILY_FILE,
+        leaderboard::LEADERBOARD_FILE,
+    ]
+}
+
+/// Bring the data dir up to `DATA_VERSION`, backing up the old files first.
+/// Returns the version migrated from, if a migration ran.
+pub fn migrate_data_dir(data_dir: &Path) -> Result<Option<u32>, String> {
+    let store = ConfigStore::in_dir(data_dir, VERSION_FILE);
+    let has_data = data_files().iter().any(|file| data_dir.join(file).exists());
+
+    let version = if store.path().exists() {
+        store
+            .load::<DataVersion>()
+            .map_err(|e| format!("Failed to read {}: {}", VERSION_FILE, e))?
+            .version
+    } else if has_data {
+        // Files written before the format was versioned
+        0
+    } else {
+        DATA_VERSION
+    };
+
+    if version > DATA_VERSION {
+        // Nothing is written while the data is unreadable, but keep a copy
+        // in case the player deletes the files to start over
+        let backup = backup_data_files(data_dir, version)?;
+        return Err(format!(
+            "Your data was written by a newer version of Rusty2048 (format {}, this version supports {}). Please update Rusty2048; a copy of your data was kept in {}.",
+            version,
+            DATA_VERSION,
+            backup.display()
+        ));
+    }
+
+    let migrated = if version < DATA_VERSION {
+        let backup = backup_data_files(data_dir, version)?;
+        for from in version..DATA_VERSION {
+            run_migration(data_dir, from).map_err(|e| {
+                format!(
+                    "Could not upgrade your data from format {}: {}. The original files were kept in {}.",
+                    from,
+                    e,
+                    backup.display()
+                )
+            })?;
+        }
+        Some(version)
+    } else {
+        None
+    };
+
+    if migrated.is_some() || !store.path().exists() {
+        store
+            .save(&DataVersion {
+                version: DATA_VERSION,
+            })
+            .map_err(|e| format!("Failed to write {}: {}", VERSION_FILE, e))?;
+    }
+    Ok(migrated)
+}
+
+fn backup_data_files(data_dir: &Path, version: u32) -> Result<PathBuf, String> {
+    let backup = data_dir.join(BACKUP_DIR).join(format!(
+        "v{}-{}",
+        version,
+        rusty2048_core::get_current_time()
+    ));
+    std::fs::create_dir_all(&backup).map_err(|e| format!("Failed to create backup: {}", e))?;
+    for file in data_files() {
+        let source = data_dir.join(file);
+        if source.exists() {
+            std::fs::copy(&source, backup.join(file))
+                .map_err(|e| format!("Failed to back up {}: {}", file, e))?;
+        }
+    }
+    Ok(backup)
+}
+
+/// Upgrade the files from format `from` to `from + 1`
+fn run_migration(data_dir: &Path, from: u32) -> Result<(), String> {
+    match from {
+        0 => migrate_v0(data_dir),
+        // Format 1 stored tiles and scores as u32; they read as u64 unchanged.
+        // Format 2 may hold values that format 1 readers cannot parse.
+        1 => Ok(()),
+        _ => Err(format!("no migration from format {}", from)),
+    }
+}
+
+/// Format 0: unversioned files. Older releases wrote statistics without the
+/// derived fields, so fill them in; every other file must at least be JSON.
+fn migrate_v0(data_dir: &Path) -> Result<(), String> {
+    for file in data_files() {
+        let path = data_dir.join(file);
+        if !path.exists() {
+            continue;
+        }
+        let content = std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", file, e))?;
+        let mut value: Value = serde_json::from_str(&content)
+            .map_err(|e| format!("{} is not valid JSON: {}", file, e))?;
+
+        if file == stats::STATS_FILE {
+            let sessions = value
+                .as_array_mut()
+                .ok_or_else(|| format!("{} does not contain a list of games", file))?;
+            for session in sessions.iter_mut() {
+                upgrade_session_v0(session).map_err(|e| format!("{}: {}", file, e))?;
+            }
+            let content = serde_json::to_string_pretty(&value).map_err(|e| e.to_string())?;
+            std::fs::write(&path, content).map_err(|e| format!("{}: {}", file, e))?;
+        }
+    }
+    Ok(())
+}
+
+fn upgrade_session_v0(session: &mut Value) -> Result<(), String> {
+    let session = session
+        .as_object_mut()
+        .ok_or_else(|| "game entry is not an object".to_string())?;
+    let number = |session: &serde_json::Map<String, Value>, key: &str| {
+        session.get(key).and_then(Value::as_u64).unwrap_or(0)
+    };
+
+    let score = number(session, "final_score");
+    let moves = number(session, "moves");
+    let end_time = number(session, "end_time");
+    let won = session.get("won").and_then(Value::as_bool).unwrap_or(false);
+    let per_move = if moves > 0 {
+        score as f64 / moves as f64
+    } else {
+        0.0
+    };
+
+    let defaults = [
+        ("session_id", json!(end_time)),
+        ("final_score", json!(score)),
+        ("moves", json!(moves)),
+        ("duration", json!(0)),
+        ("max_tile", json!(0)),
+        ("won", json!(won)),
+        ("end_reason", json!(if won { "Won" } else { "GameOver" })),
+        ("start_time", json!(end_time)),
+        ("end_time", json!(end_time)),
+        ("avg_score_per_move", json!(per_move)),
+        ("efficiency", json!(per_move)),
+    ];
+    for (key, value) in defaults {
+        session.entry(key).or_insert(value);
+    }
+    Ok(())
+}
+
+/// Problem found while loading data at startup, for the frontend to show
+#[tauri::command]
+pub async fn get_data_error(
+    state: State<'_, Arc<Mutex<GameManager>>>,
+) -> Result<Option<String>, String> {
+    let game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    Ok(game_manager.data_error.clone())
+}
@@ -0,0 +1,149 @@
+use crate::sessions::{SessionId, MAIN_SESSION};
+use crate::{events, GameManager, GameState};
+use rusty2048_core::{Game, GameConfig, WinCondition};
+use serde::Serialize;
+use std::ops::RangeInclusive;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, State};
+
+/// Board sizes offered by the desktop UI
+pub const BOARD_SIZES: RangeInclusive<usize> = 3..=8;
+/// Target tiles offered by the desktop UI
+pub const TARGET_TILES: [u64; 6] = [256, 512, 1024, 2048, 4096, 8192];
+
+/// A selectable game mode
+#[derive(Clone, Serialize)]
+pub struct GameMode {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub allow_undo: bool,
+    pub tiles_per_spawn: usize,
+}
+
+const MODES: [GameMode; 3] = [
+    GameMode {
+        id: "classic",
+        name: "Classic",
+        description: "Standard rules with undo",
+        allow_undo: true,
+        tiles_per_spawn: 1,
+    },
+    GameMode {
+        id: "hardcore",
+        name: "Hardcore",
+        description: "No undo",
+        allow_undo: false,
+        tiles_per_spawn: 1,
+    },
+    GameMode {
+        id: "double",
+        name: "Double Spawn",
+        description: "Two tiles spawn after every move",
+        allow_undo: true,
+        tiles_per_spawn: 2,
+    },
+];
+
+/// Modes and board options for the new game dialog
+#[derive(Serialize)]
+pub struct GameOptions {
+    pub modes: Vec<GameMode>,
+    pub board_sizes: Vec<usize>,
+    pub target_tiles: Vec<u64>,
+}
+
+/// Build a core configuration, falling back to the defaults for missing values
+pub fn build_config(
+    size: Option<usize>,
+    target: Option<u64>,
+    mode: Option<&str>,
+    seed: Option<u64>,
+) -> Result<GameConfig, String> {
+    let defaults = GameConfig::default();
+
+    let board_size = size.unwrap_or(defaults.board_size);
+    if !BOARD_SIZES.contains(&board_size) {
+        return Err(format!(
+            "Board size must be between {} and {}",
+            BOARD_SIZES.start(),
+            BOARD_SIZES.end()
+        ));
+    }
+
+    let target_tile = target
+        .or(defaults.win_condition.target_tile())
+        .unwrap_or_default();
+    if !target_tile.is_power_of_two() || target_tile < 8 {
+        return Err("Target must be a power of two of at least 8".to_string());
+    }
+
+    let mode = mode.unwrap_or("classic");
+    let mode = MODES
+        .iter()
+        .find(|m| m.id == mode)
+        .ok_or_else(|| format!("Unknown game mode: {}", mode))?;
+
+    Ok(GameConfig {
+        board_size,
+        win_condition: WinCondition::Tile(target_tile),
+        allow_undo: mode.allow_undo,
+        tiles_per_spawn: mode.tiles_per_spawn,
+        seed,
+        ..defaults
+    })
+}
+
+/// Identifier of the mode a game is played in, as used by the leaderboard
+pub fn mode_id(config: &GameConfig, daily: bool) -> &'static str {
+    if daily {
+        return "daily";
+    }
+    MODES
+        .iter()
+        .find(|m| m.allow_undo == config.allow_undo && m.tiles_per_spawn == config.tiles_per_spawn)
+        .map_or("classic", |m| m.id)
+}
+
+impl GameManager {
+    /// Replace a session's game with a new one using the given configuration
+    pub fn start_game_with_config(
+        &mut self,
+        session: SessionId,
+        config: GameConfig,
+    ) -> Result<(), String> {
+        let best_score = self.session(session)?.score().best();
+        let mut game = Game::new(config).map_err(|e| e.to_string())?;
+        game.restore_best_score(best_score);
+        self.replace_game(session, game)
+    }
+}
+
+#[tauri::command]
+pub async fn new_game_with_config(
+    app: AppHandle,
+    state: State<'_, Arc<Mutex<GameManager>>>,
+    size: Option<usize>,
+    target: Option<u64>,
+    mode: Option<String>,
+    seed: Option<u64>,
+    session: Option<SessionId>,
+) -> Result<GameState, String> {
+    let config = build_config(size, target, mode.as_deref(), seed)?;
+    let session = session.unwrap_or(MAIN_SESSION);
+    let mut game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    game_manager.start_game_with_config(session, config)?;
+    if session == MAIN_SESSION {
+        events::publish(&app, &mut game_manager);
+    }
+    game_manager.session_state(session)
+}
+
+#[tauri::command]
+pub async fn get_available_modes() -> GameOptions {
+    GameOptions {
+        modes: MODES.to_vec(),
+        board_sizes: BOARD_SIZES.collect(),
+        target_tiles: TARGET_TILES.to_vec(),
+    }
+}
@@ -0,0 +1,108 @@
+use crate::{GameManager, GameState};
+use rusty2048_core::Game;
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+/// Handle of a game session owned by the `GameManager`
+pub type SessionId = u32;
+
+/// The main window's session. It always exists and is the only one that is
+/// autosaved, recorded in statistics and driven by the menu and tray.
+pub const MAIN_SESSION: SessionId = 0;
+
+impl GameManager {
+    /// The main session's game
+    pub fn game(&self) -> &Game {
+        &self.games[&MAIN_SESSION]
+    }
+
+    /// The main session's game, mutably
+    pub fn game_mut(&mut self) -> &mut Game {
+        self.games
+            .get_mut(&MAIN_SESSION)
+            .expect("main session always exists")
+    }
+
+    /// Look up a session's game
+    pub fn session(&self, id: SessionId) -> Result<&Game, String> {
+        self.games
+            .get(&id)
+            .ok_or_else(|| format!("Unknown game session {}", id))
+    }
+
+    /// Look up a session's game, mutably
+    pub fn session_mut(&mut self, id: SessionId) -> Result<&mut Game, String> {
+        self.games
+            .get_mut(&id)
+            .ok_or_else(|| format!("Unknown game session {}", id))
+    }
+
+    /// Swap in a different game for a session, e.g. a resumed or reconfigured one
+    pub fn replace_game(&mut self, id: SessionId, mut game: Game) -> Result<(), String> {
+        if id == MAIN_SESSION {
+            // The main game drives the frontend through events
+            game.enable_events();
+            self.recorded_session = None;
+            self.ai_played = false;
+            self.leaderboard_submitted = false;
+            self.daily = None;
+        }
+        *self.session_mut(id)? = game;
+        Ok(())
+    }
+
+    /// Start a new session with the same configuration as the main game
+    pub fn create_session(&mut self) -> Result<SessionId, String> {
+        let config = self.game().config().clone();
+        let game = Game::new(config).map_err(|e| e.to_string())?;
+        let id = self.next_session;
+        self.next_session += 1;
+        self.games.insert(id, game);
+        Ok(id)
+    }
+
+    /// Close a session; the main session cannot be closed
+    pub fn close_session(&mut self, id: SessionId) -> Result<(), String> {
+        if id == MAIN_SESSION {
+            return Err("The main game session cannot be closed".to_string());
+        }
+        self.games
+            .remove(&id)
+            .map(|_| ())
+            .ok_or_else(|| format!("Unknown game session {}", id))
+    }
+
+    /// IDs of all open sessions, main session first
+    pub fn session_ids(&self) -> Vec<SessionId> {
+        let mut ids: Vec<SessionId> = self.games.keys().copied().collect();
+        ids.sort_unstable();
+        ids
+    }
+}
+
+/// Open an extra game session, e.g. for a second window or a comparison view
+#[tauri::command]
+pub async fn create_game_session(
+    state: State<'_, Arc<Mutex<GameManager>>>,
+) -> Result<GameState, String> {
+    let mut game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    let id = game_manager.create_session()?;
+    game_manager.session_state(id)
+}
+
+#[tauri::command]
+pub async fn close_game_session(
+    state: State<'_, Arc<Mutex<GameManager>>>,
+    session: SessionId,
+) -> Result<(), String> {
+    let mut game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    game_manager.close_session(session)
+}
+
+#[tauri::command]
+pub async fn list_game_sessions(
+    state: State<'_, Arc<Mutex<GameManager>>>,
+) -> Result<Vec<SessionId>, String> {
+    let game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    Ok(game_manager.session_ids())
+}
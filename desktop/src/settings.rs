@@ -0,0 +1,53 @@
+use crate::GameManager;
+use rusty2048_shared::{Achievement, ConfigStore, Language, UserSettings};
+use std::path::Path;
+
+/// File name of the settings file inside the app data dir
+pub const SETTINGS_FILE: &str = "settings.json";
+
+impl GameManager {
+    /// Open the settings store, falling back to defaults if the file is unreadable
+    pub fn load_settings(data_dir: &Path) -> (ConfigStore, UserSettings) {
+        let store = ConfigStore::in_dir(data_dir, SETTINGS_FILE);
+        let settings = store.load().unwrap_or_default();
+        (store, settings)
+    }
+
+    /// Apply loaded settings to the game, theme and language
+    pub fn apply_settings(&mut self) {
+        if let Some(theme) = self.find_theme(&self.settings.theme) {
+            self.theme = theme;
+        }
+        // Until the user picks a language, follow the OS locale
+        let language = match self.settings.language.as_deref() {
+            Some(code) => Language::from_code(code),
+            None => Language::detect_system(),
+        };
+        if let Some(language) = language {
+            self.i18n.set_language(language);
+        }
+        let best_score = self.settings.best_score;
+        self.game_mut().restore_best_score(best_score);
+    }
+
+    /// Persist the current theme, the chosen language and the best score
+    pub fn save_settings(&mut self) -> Result<(), String> {
+        self.settings.theme = self.theme.name.clone();
+        self.settings.best_score = self.settings.best_score.max(self.game().score().best());
+        self.settings_store
+            .save(&self.settings)
+            .map_err(|e| format!("Failed to save settings: {}", e))
+    }
+
+    /// Bookkeeping after every move: check achievements, record finished games and
+    /// persist a new best score. Returns the newly unlocked achievements.
+    pub fn after_move(&mut self) -> Result<Vec<&'static Achievement>, String> {
+        let unlocked = self.update_achievements()?;
+        self.record_if_finished()?;
+        self.record_daily_if_finished()?;
+        if self.game().score().best() > self.settings.best_score {
+            self.save_settings()?;
+        }
+        Ok(unlocked)
+    }
+}
@@ -0,0 +1,86 @@
+use crate::sessions::{SessionId, MAIN_SESSION};
+use crate::GameManager;
+use base64::Engine;
+use rusty2048_shared::render::{self, Image};
+use serde::Serialize;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, State};
+
+/// Directory for generated share images inside the app cache dir
+pub const SHARE_DIR: &str = "share";
+
+/// A rendered result image
+#[derive(Serialize)]
+pub struct ShareImage {
+    /// Where the PNG was written
+    pub path: String,
+    /// The PNG as a `data:` URL, for previews and the share dialog
+    pub data_url: String,
+}
+
+/// Encode an image as PNG
+pub fn encode_png(image: &Image) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut bytes, image.width, image.height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&image.pixels))
+        .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+    Ok(bytes)
+}
+
+impl GameManager {
+    /// Render a session's board, score and theme
+    pub fn render_result(&self, session: SessionId) -> Result<Image, String> {
+        let game = self.session(session)?;
+        let caption = format!(
+            "Score {}  Max {}",
+            game.score().current(),
+            game.board().max_tile()
+        );
+        Ok(render::render_board(
+            &game.board().to_vec(),
+            &caption,
+            &self.theme,
+        ))
+    }
+}
+
+fn write_png(dir: &Path, bytes: &[u8]) -> Result<String, String> {
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let path = dir.join(format!(
+        "rusty2048-{}.png",
+        rusty2048_core::get_current_time()
+    ));
+    std::fs::write(&path, bytes).map_err(|e| format!("Failed to write image: {}", e))?;
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// Render the current result to a PNG for sharing
+#[tauri::command]
+pub async fn create_share_image(
+    app: AppHandle,
+    state: State<'_, Arc<Mutex<GameManager>>>,
+    session: Option<SessionId>,
+) -> Result<ShareImage, String> {
+    let image = {
+        let game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+        game_manager.render_result(session.unwrap_or(MAIN_SESSION))?
+    };
+    let bytes = encode_png(&image)?;
+
+    let dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| e.to_string())?
+        .join(SHARE_DIR);
+    let path = write_png(&dir, &bytes)?;
+    let data_url = format!(
+        "data:image/png;base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(&bytes)
+    );
+    Ok(ShareImage { path, data_url })
+}
@@ -0,0 +1,185 @@
+use crate::GameManager;
+use rusty2048_core::stats::{GameEndReason, PlayerType};
+use rusty2048_core::{
+    create_session_stats, GameState, MergeReport, PeriodTotals, RetentionPolicy, SessionFilter,
+    SessionSegment, StatisticsSummary, StatsPeriod,
+};
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+/// File name of the statistics file inside the app data dir
+pub const STATS_FILE: &str = "stats.json";
+
+/// Score and efficiency trends of the last games
+#[derive(Serialize)]
+pub struct Trends {
+    scores: Vec<u64>,
+    efficiency: Vec<f64>,
+    tiles: Vec<(u64, u32)>,
+}
+
+impl GameManager {
+    /// Record the current game as a session, or update its session if it
+    /// was recorded before
+    pub fn record_session(&mut self) -> Result<(), String> {
+        let stats = self.game().stats();
+        let end_time = rusty2048_core::get_current_time();
+        let mut session = create_session_stats(
+            stats.score,
+            stats.moves,
+            stats.duration,
+            self.game().board().max_tile(),
+            self.game().has_won(),
+            end_time.saturating_sub(stats.duration),
+            end_time,
+        )
+        .with_move_timing(&stats)
+        .with_config(self.game().config())
+        .with_final_board(self.game().board())
+        .with_player(if self.ai_played {
+            PlayerType::Ai
+        } else {
+            PlayerType::Human
+        });
+        if let Some(challenge) = &self.daily {
+            session = session.with_daily(challenge);
+        }
+        // A continued game stays recorded as won
+        if self.game().state() == GameState::Playing && !self.game().keep_playing() {
+            session.end_reason = GameEndReason::Abandoned;
+        }
+
+        match self.recorded_session {
+            Some(session_id) => self.stats.update_session(session_id, session),
+            None => {
+                self.recorded_session = Some(session.session_id);
+                self.stats.record_session(session)
+            }
+        }
+        .map_err(|e| e.to_string())
+    }
+
+    /// Record the session when the game has just ended. A game continued
+    /// after the win was recorded then and is updated when it really ends.
+    pub fn record_if_finished(&mut self) -> Result<(), String> {
+        let state = self.game().state();
+        let continued_end = state == GameState::GameOver && self.game().keep_playing();
+        if state != GameState::Playing && (self.recorded_session.is_none() || continued_end) {
+            self.record_session()?;
+        }
+        Ok(())
+    }
+}
+
+#[tauri::command]
+pub async fn record_session(state: State<'_, Arc<Mutex<GameManager>>>) -> Result<(), String> {
+    let mut game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    game_manager.record_session()
+}
+
+#[tauri::command]
+pub async fn get_statistics_summary(
+    state: State<'_, Arc<Mutex<GameManager>>>,
+    filter: Option<SessionFilter>,
+) -> Result<StatisticsSummary, String> {
+    let game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    Ok(game_manager
+        .stats
+        .get_summary_filtered(&filter.unwrap_or_default()))
+}
+
+/// Summary of the games that ended in `[start, end)`, as Unix timestamps;
+/// without a range, the current period (UTC) is used
+#[tauri::command]
+pub async fn get_summary_for_range(
+    state: State<'_, Arc<Mutex<GameManager>>>,
+    start: Option<u64>,
+    end: Option<u64>,
+    period: Option<StatsPeriod>,
+) -> Result<StatisticsSummary, String> {
+    let game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    let (start, end) = match (start, end, period) {
+        (Some(start), Some(end), _) => (start, end),
+        (_, _, Some(period)) => period.range_containing(rusty2048_core::get_current_time()),
+        _ => return Err("Either a range or a period is required".to_string()),
+    };
+    Ok(game_manager.stats.get_summary_for_range(start, end))
+}
+
+/// Totals of every period with games, oldest first
+#[tauri::command]
+pub async fn get_period_totals(
+    state: State<'_, Arc<Mutex<GameManager>>>,
+    period: StatsPeriod,
+) -> Result<Vec<PeriodTotals>, String> {
+    let game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    Ok(game_manager.stats.get_totals(period))
+}
+
+/// Merge a statistics file from another device (e.g. the CLI's `stats.json`)
+#[tauri::command]
+pub async fn import_and_merge_statistics(
+    state: State<'_, Arc<Mutex<GameManager>>>,
+    path: String,
+) -> Result<MergeReport, String> {
+    let mut game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    game_manager
+        .stats
+        .import_and_merge(&path)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_stats_retention(
+    state: State<'_, Arc<Mutex<GameManager>>>,
+) -> Result<RetentionPolicy, String> {
+    let game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    Ok(game_manager.stats.retention())
+}
+
+/// Change how many sessions are kept; returns how many were compacted
+#[tauri::command]
+pub async fn set_stats_retention(
+    state: State<'_, Arc<Mutex<GameManager>>>,
+    retention: RetentionPolicy,
+) -> Result<usize, String> {
+    let mut game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    game_manager
+        .stats
+        .set_retention(retention)
+        .map_err(|e| e.to_string())
+}
+
+/// Configurations that have recorded games, with their game counts
+#[tauri::command]
+pub async fn get_statistics_segments(
+    state: State<'_, Arc<Mutex<GameManager>>>,
+) -> Result<Vec<(SessionSegment, u32)>, String> {
+    let game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    Ok(game_manager.stats.get_segments())
+}
+
+#[tauri::command]
+pub async fn get_trends(
+    state: State<'_, Arc<Mutex<GameManager>>>,
+    count: usize,
+    filter: Option<SessionFilter>,
+) -> Result<Trends, String> {
+    let game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    let stats = &game_manager.stats;
+    let filter = filter.unwrap_or_default();
+    Ok(Trends {
+        scores: stats
+            .get_score_trend_filtered(&filter, count)
+            .into_iter()
+            .map(|(_, score)| score)
+            .collect(),
+        efficiency: stats
+            .get_efficiency_trend_filtered(&filter, count)
+            .into_iter()
+            .map(|(_, efficiency)| efficiency)
+            .collect(),
+        tiles: stats.get_tile_achievements(),
+    })
+}
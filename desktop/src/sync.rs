@@ -0,0 +1,325 @@
+use crate::bundle::{ImportMode, SaveBundle};
+use crate::{events, menu, tray, GameManager};
+use base64::Engine;
+use hmac_sha256::{Hash, HMAC};
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, State};
+
+/// File name of the sync settings inside the app data dir
+pub const SYNC_FILE: &str = "sync.json";
+/// File name of the bundle inside a sync folder or WebDAV collection
+pub const REMOTE_FILE: &str = "rusty2048-sync.json";
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Where the save bundle is synced to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SyncTarget {
+    /// A folder kept in sync by another tool (Dropbox, a network share, ...)
+    Folder { path: PathBuf },
+    /// An HTTP endpoint that answers GET with the last bundle and accepts PUT.
+    /// A token is only sent over https.
+    Http { url: String, token: Option<String> },
+    /// A WebDAV collection (Nextcloud, ownCloud, ...) the bundle is stored in
+    /// as `REMOTE_FILE`, with Basic authentication over https
+    #[serde(rename = "webdav")]
+    WebDav {
+        url: String,
+        username: String,
+        password: String,
+    },
+    /// An object in an S3-compatible bucket (AWS, MinIO, R2, ...), addressed
+    /// path-style as `endpoint/bucket/key` and signed with Signature V4
+    S3 {
+        endpoint: String,
+        region: String,
+        bucket: String,
+        #[serde(default = "default_s3_key")]
+        key: String,
+        access_key_id: String,
+        secret_access_key: String,
+    },
+}
+
+fn default_s3_key() -> String {
+    REMOTE_FILE.to_string()
+}
+
+/// Sync configuration and status
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyncSettings {
+    pub target: Option<SyncTarget>,
+    /// When the last successful sync finished (Unix timestamp)
+    pub last_synced: Option<u64>,
+}
+
+/// Outcome of a sync
+#[derive(Serialize)]
+pub struct SyncReport {
+    /// Whether a remote bundle existed and was merged
+    pub merged_remote: bool,
+    pub synced_at: u64,
+}
+
+impl SyncTarget {
+    /// Fetch the remote bundle, if there is one yet
+    fn pull(&self) -> Result<Option<String>, String> {
+        if let SyncTarget::Folder { path } = self {
+            return match std::fs::read_to_string(path.join(REMOTE_FILE)) {
+                Ok(contents) => Ok(Some(contents)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(format!("Failed to read sync file: {}", e)),
+            };
+        }
+
+        let response = match self.request("GET", b"")?.call() {
+            Ok(response) if response.status() == 204 => return Ok(None),
+            Ok(response) => response,
+            Err(ureq::Error::Status(404, _)) => return Ok(None),
+            Err(e) => return Err(request_error("GET", e)),
+        };
+        let mut body = Vec::new();
+        response
+            .into_reader()
+            .read_to_end(&mut body)
+            .map_err(|e| format!("Failed to download sync bundle: {}", e))?;
+        String::from_utf8(body)
+            .map(Some)
+            .map_err(|_| "The remote sync bundle is not UTF-8 JSON".to_string())
+    }
+
+    /// Upload the merged bundle
+    fn push(&self, contents: &str) -> Result<(), String> {
+        if let SyncTarget::Folder { path } = self {
+            return std::fs::create_dir_all(path)
+                .and_then(|_| std::fs::write(path.join(REMOTE_FILE), contents))
+                .map_err(|e| format!("Failed to write sync file: {}", e));
+        }
+
+        self.request("PUT", contents.as_bytes())?
+            .set("Content-Type", "application/json")
+            .send_bytes(contents.as_bytes())
+            .map(|_| ())
+            .map_err(|e| request_error("PUT", e))
+    }
+
+    /// A request to a remote target, authenticated for it. `body` is only
+    /// read to sign S3 requests.
+    fn request(&self, method: &str, body: &[u8]) -> Result<ureq::Request, String> {
+        let agent = ureq::AgentBuilder::new().timeout(HTTP_TIMEOUT).build();
+        match self {
+            SyncTarget::Folder { .. } => Err("A sync folder is not a remote target".to_string()),
+            SyncTarget::Http { url, token } => {
+                let request = agent.request(method, url);
+                match token {
+                    Some(token) => {
+                        require_https(url)?;
+                        Ok(request.set("Authorization", &format!("Bearer {}", token)))
+                    }
+                    None => Ok(request),
+                }
+            }
+            SyncTarget::WebDav {
+                url,
+                username,
+                password,
+            } => {
+                require_https(url)?;
+                let credentials = base64::engine::general_purpose::STANDARD
+                    .encode(format!("{}:{}", username, password));
+                let url = format!("{}/{}", url.trim_end_matches('/'), REMOTE_FILE);
+                Ok(agent
+                    .request(method, &url)
+                    .set("Authorization", &format!("Basic {}", credentials)))
+            }
+            SyncTarget::S3 {
+                endpoint,
+                region,
+                bucket,
+                key,
+                access_key_id,
+                secret_access_key,
+            } => {
+                require_https(endpoint)?;
+                let endpoint = endpoint.trim_end_matches('/');
+                let host = endpoint
+                    .strip_prefix("https://")
+                    .and_then(|rest| rest.split('/').next())
+                    .unwrap_or_default();
+                let path = format!("/{}/{}", uri_encode(bucket), uri_encode(key));
+                let now = rusty2048_core::get_current_time();
+                let date = rusty2048_core::daily::date_string(now / 86_400).replace('-', "");
+                let seconds = now % 86_400;
+                let timestamp = format!(
+                    "{}T{:02}{:02}{:02}Z",
+                    date,
+                    seconds / 3_600,
+                    seconds / 60 % 60,
+                    seconds % 60
+                );
+                let payload_hash = hex(&Hash::hash(body));
+
+                let canonical_request = format!(
+                    "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+                    method, path, host, payload_hash, timestamp, S3_SIGNED_HEADERS, payload_hash
+                );
+                let scope = format!("{}/{}/s3/aws4_request", date, region);
+                let string_to_sign = format!(
+                    "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+                    timestamp,
+                    scope,
+                    hex(&Hash::hash(canonical_request.as_bytes()))
+                );
+                let key = s3_signing_key(secret_access_key, &date, region);
+                let signature = hex(&HMAC::mac(string_to_sign.as_bytes(), key));
+
+                Ok(agent
+                    .request(method, &format!("{}{}", endpoint, path))
+                    .set("x-amz-content-sha256", &payload_hash)
+                    .set("x-amz-date", &timestamp)
+                    .set(
+                        "Authorization",
+                        &format!(
+                            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+                            access_key_id, scope, S3_SIGNED_HEADERS, signature
+                        ),
+                    ))
+            }
+        }
+    }
+}
+
+/// Headers covered by the S3 request signature
+const S3_SIGNED_HEADERS: &str = "host;x-amz-content-sha256;x-amz-date";
+
+/// Credentials must never travel in cleartext
+fn require_https(url: &str) -> Result<(), String> {
+    if url.starts_with("https://") {
+        Ok(())
+    } else {
+        Err(format!(
+            "Refusing to send sync credentials to {}; use an https:// URL",
+            url
+        ))
+    }
+}
+
+fn request_error(method: &str, error: ureq::Error) -> String {
+    match error {
+        ureq::Error::Status(status, _) => {
+            format!("Sync server answered {} with status {}", method, status)
+        }
+        ureq::Error::Transport(e) => format!("Sync request failed: {}", e),
+    }
+}
+
+/// The Signature V4 signing key of a day and region
+fn s3_signing_key(secret_access_key: &str, date: &str, region: &str) -> [u8; 32] {
+    let key = HMAC::mac(date, format!("AWS4{}", secret_access_key));
+    let key = HMAC::mac(region, key);
+    let key = HMAC::mac("s3", key);
+    HMAC::mac("aws4_request", key)
+}
+
+/// Percent-encode an S3 path segment as Signature V4 expects
+fn uri_encode(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Pull the remote bundle, merge it into local data and push the result back.
+/// Merging resolves conflicts: sessions are combined, the earliest achievement
+/// unlocks and the highest best score win, and local preferences are kept.
+fn sync(manager: &Arc<Mutex<GameManager>>, app: &AppHandle) -> Result<SyncReport, String> {
+    let target = manager
+        .lock()
+        .map_err(|_| "lock poisoned".to_string())?
+        .sync
+        .target
+        .clone()
+        .ok_or_else(|| "Sync is not configured".to_string())?;
+
+    // Network I/O happens without holding the lock
+    let remote = target.pull()?;
+    let merged_remote = remote.is_some();
+
+    let contents = {
+        let mut game_manager = manager.lock().map_err(|_| "lock poisoned".to_string())?;
+        if let Some(remote) = remote {
+            game_manager.import_bundle(SaveBundle::from_json(&remote)?, ImportMode::Merge)?;
+            menu::refresh_menu(app, &game_manager)?;
+            tray::refresh_tray(app, &game_manager);
+            events::publish(app, &mut game_manager);
+        }
+        serde_json::to_string_pretty(&game_manager.export_bundle()).map_err(|e| e.to_string())?
+    };
+
+    target.push(&contents)?;
+
+    let synced_at = rusty2048_core::get_current_time();
+    let mut game_manager = manager.lock().map_err(|_| "lock poisoned".to_string())?;
+    game_manager.sync.last_synced = Some(synced_at);
+    game_manager.save_sync_settings()?;
+    Ok(SyncReport {
+        merged_remote,
+        synced_at,
+    })
+}
+
+impl GameManager {
+    fn save_sync_settings(&self) -> Result<(), String> {
+        self.sync_store
+            .save(&self.sync)
+            .map_err(|e| format!("Failed to save sync settings: {}", e))
+    }
+}
+
+#[tauri::command]
+pub async fn get_sync_settings(
+    state: State<'_, Arc<Mutex<GameManager>>>,
+) -> Result<SyncSettings, String> {
+    let game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    Ok(game_manager.sync.clone())
+}
+
+/// Configure the sync target, or turn sync off with `None`
+#[tauri::command]
+pub async fn set_sync_target(
+    state: State<'_, Arc<Mutex<GameManager>>>,
+    target: Option<SyncTarget>,
+) -> Result<(), String> {
+    let mut game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    game_manager.sync = SyncSettings {
+        target,
+        last_synced: None,
+    };
+    game_manager.save_sync_settings()
+}
+
+#[tauri::command]
+pub async fn sync_now(
+    app: AppHandle,
+    state: State<'_, Arc<Mutex<GameManager>>>,
+) -> Result<SyncReport, String> {
+    let manager = state.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || sync(&manager, &app))
+        .await
+        .map_err(|e| e.to_string())?
+}
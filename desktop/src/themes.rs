@@ -0,0 +1,68 @@
+use crate::GameManager;
+use rusty2048_shared::Theme;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, State};
+
+/// Directory of custom themes inside the app data dir
+pub const THEMES_DIR: &str = "themes";
+
+impl GameManager {
+    /// Find a built-in or custom theme by name
+    pub fn find_theme(&self, name: &str) -> Option<Theme> {
+        Theme::by_name(name).or_else(|| self.theme_loader.by_name(name))
+    }
+}
+
+#[tauri::command]
+pub async fn import_theme(
+    app: AppHandle,
+    state: State<'_, Arc<Mutex<GameManager>>>,
+    path: String,
+) -> Result<Theme, String> {
+    let theme = Theme::load_file(Path::new(&path)).map_err(|e| e.to_string())?;
+    let game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    game_manager
+        .theme_loader
+        .save(&theme)
+        .map_err(|e| e.to_string())?;
+    crate::menu::refresh_menu(&app, &game_manager)?;
+    Ok(theme)
+}
+
+#[tauri::command]
+pub async fn save_custom_theme(
+    app: AppHandle,
+    state: State<'_, Arc<Mutex<GameManager>>>,
+    theme: Theme,
+) -> Result<Theme, String> {
+    let game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    game_manager
+        .theme_loader
+        .save(&theme)
+        .map_err(|e| e.to_string())?;
+    crate::menu::refresh_menu(&app, &game_manager)?;
+    Ok(theme)
+}
+
+#[tauri::command]
+pub async fn list_custom_themes(
+    state: State<'_, Arc<Mutex<GameManager>>>,
+) -> Result<Vec<Theme>, String> {
+    let game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    Ok(game_manager.theme_loader.load_all())
+}
+
+#[tauri::command]
+pub async fn delete_custom_theme(
+    app: AppHandle,
+    state: State<'_, Arc<Mutex<GameManager>>>,
+    name: String,
+) -> Result<(), String> {
+    let game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+    game_manager
+        .theme_loader
+        .delete(&name)
+        .map_err(|e| e.to_string())?;
+    crate::menu::refresh_menu(&app, &game_manager)
+}
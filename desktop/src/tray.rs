@@ -0,0 +1,78 @@
+use crate::{events, GameManager};
+use std::sync::{Arc, Mutex};
+use tauri::menu::{MenuBuilder, MenuEvent};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Manager, Runtime};
+
+const TRAY_ID: &str = "main";
+
+fn tooltip(best_score: u64) -> String {
+    format!("Rusty2048 — Best: {}", best_score)
+}
+
+/// Create the tray icon with quick-play entries
+pub fn create_tray<R: Runtime>(
+    app: &AppHandle<R>,
+    game_manager: &GameManager,
+) -> tauri::Result<()> {
+    let menu = MenuBuilder::new(app)
+        .text("tray_new_game", "New Game")
+        .text("tray_resume", "Resume")
+        .text("tray_daily", "Daily Challenge")
+        .separator()
+        .text("tray_quit", "Quit")
+        .build()?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .menu(&menu)
+        .tooltip(tooltip(game_manager.game().score().best()))
+        .on_menu_event(handle_tray_event);
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+    Ok(())
+}
+
+/// Update the tray tooltip with the current best score
+pub fn refresh_tray<R: Runtime>(app: &AppHandle<R>, game_manager: &GameManager) {
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        let _ = tray.set_tooltip(Some(tooltip(game_manager.game().score().best())));
+    }
+}
+
+fn show_main_window<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+fn handle_tray_event<R: Runtime>(app: &AppHandle<R>, event: MenuEvent) {
+    let id = event.id().as_ref();
+    if id == "tray_quit" {
+        app.exit(0);
+        return;
+    }
+
+    show_main_window(app);
+    let state = app.state::<Arc<Mutex<GameManager>>>();
+    let Ok(mut game_manager) = state.lock() else {
+        return;
+    };
+
+    let result = match id {
+        "tray_new_game" => game_manager.start_new_game(),
+        // A game in progress is simply brought back up
+        "tray_resume" if game_manager.game().moves() > 0 => return,
+        "tray_resume" => game_manager.resume_autosave(),
+        "tray_daily" => game_manager.start_daily(),
+        _ => return,
+    };
+
+    match result {
+        Ok(()) => events::publish(app, &mut game_manager),
+        Err(e) => eprintln!("Tray action {} failed: {}", id, e),
+    }
+}
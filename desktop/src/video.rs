@@ -0,0 +1,151 @@
+use crate::{bundle, GameManager};
+use rusty2048_core::ReplayData;
+use rusty2048_shared::export::{self, Frame, FINAL_FRAME_MS};
+use rusty2048_shared::render::Image;
+use serde::Deserialize;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, State};
+
+/// Default time each move is shown, in milliseconds
+pub const DEFAULT_FRAME_MS: u32 = 300;
+
+/// Output format of a replay video
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VideoFormat {
+    #[default]
+    Gif,
+    /// H.264 video, encoded by an `ffmpeg` found on the PATH
+    Mp4,
+}
+
+impl VideoFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            VideoFormat::Gif => "gif",
+            VideoFormat::Mp4 => "mp4",
+        }
+    }
+}
+
+/// Every position of a replay, starting with the initial board
+pub fn replay_frames(replay: &ReplayData) -> Vec<Frame> {
+    let mut frames = vec![Frame {
+        board: replay.initial_board.clone(),
+        caption: "Score 0".to_string(),
+    }];
+    frames.extend(replay.moves.iter().map(|m| Frame {
+        board: m.board_after.clone(),
+        caption: format!("Move {}  Score {}", m.move_number, m.score_after),
+    }));
+    frames
+}
+
+/// Encode frames as an MP4 by piping raw RGBA frames into `ffmpeg`
+pub fn encode_mp4(frames: &[Image], frame_ms: u32, path: &Path) -> Result<(), String> {
+    let first = frames.first().ok_or("The replay has no frames")?;
+    let fps = format!("1000/{}", frame_ms.max(1));
+    let size = format!("{}x{}", first.width, first.height);
+
+    let mut ffmpeg = Command::new("ffmpeg")
+        .args([
+            "-y",
+            "-loglevel",
+            "error",
+            "-f",
+            "rawvideo",
+            "-pix_fmt",
+            "rgba",
+        ])
+        .args(["-s", &size, "-framerate", &fps, "-i", "-"])
+        .args([
+            "-c:v",
+            "libx264",
+            "-pix_fmt",
+            "yuv420p",
+            "-movflags",
+            "+faststart",
+        ])
+        .arg(path)
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("MP4 export needs ffmpeg installed: {}", e))?;
+
+    {
+        let mut stdin = ffmpeg.stdin.take().ok_or("Failed to open ffmpeg input")?;
+        let final_repeats = (FINAL_FRAME_MS / frame_ms.max(1)).max(1);
+        let last = frames.len() - 1;
+        for (i, image) in frames.iter().enumerate() {
+            let repeats = if i == last { final_repeats } else { 1 };
+            for _ in 0..repeats {
+                stdin
+                    .write_all(&image.pixels)
+                    .map_err(|e| format!("Failed to write to ffmpeg: {}", e))?;
+            }
+        }
+    }
+
+    let output = ffmpeg.wait_with_output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(format!(
+            "ffmpeg failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Render a replay (as saved by the CLI) to a GIF or MP4 saved where the
+/// player picks; returns the path written, or `None` if the dialog was
+/// cancelled
+#[tauri::command]
+pub async fn export_replay_video(
+    app: AppHandle,
+    state: State<'_, Arc<Mutex<GameManager>>>,
+    contents: String,
+    format: Option<VideoFormat>,
+    frame_ms: Option<u32>,
+) -> Result<Option<String>, String> {
+    let replay = ReplayData::decode(contents.as_bytes())
+        .map_err(|e| format!("Invalid replay file: {}", e))?;
+    let theme = {
+        let game_manager = state.lock().map_err(|_| "lock poisoned".to_string())?;
+        game_manager.theme.clone()
+    };
+    let format = format.unwrap_or_default();
+    let frame_ms = frame_ms.unwrap_or(DEFAULT_FRAME_MS).max(20);
+
+    let file_name = format!(
+        "rusty2048-replay-{}.{}",
+        replay.metadata.created_at,
+        format.extension()
+    );
+    let filter_name = match format {
+        VideoFormat::Gif => "GIF animation",
+        VideoFormat::Mp4 => "MP4 video",
+    };
+    let filter = (filter_name, &[format.extension()][..]);
+    let Some(path) = bundle::pick_save_path(&app, &file_name, filter)? else {
+        return Ok(None);
+    };
+
+    // Rendering and encoding take a while for long games
+    tauri::async_runtime::spawn_blocking(move || {
+        let frames = export::render_frames(&replay_frames(&replay), &theme);
+        match format {
+            VideoFormat::Gif => {
+                let bytes = export::encode_gif(&frames, frame_ms)?;
+                std::fs::write(&path, bytes)
+                    .map_err(|e| format!("Failed to write video: {}", e))?;
+            }
+            VideoFormat::Mp4 => encode_mp4(&frames, frame_ms, &path)?,
+        }
+        Ok(Some(path.to_string_lossy().into_owned()))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,18 @@
+use rusty2048_core::{GameSessionStats, ReplayData, SavedGame};
+use rusty2048_shared::{AchievementProgress, UserSettings};
+use serde::{Deserialize, Serialize};
+
+/// Everything the engine needs persisted between launches.
+/// Frontends store it however suits the platform (files, localStorage, ...).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EngineData {
+    pub settings: UserSettings,
+    pub achievements: AchievementProgress,
+    /// Finished games, oldest first
+    pub sessions: Vec<GameSessionStats>,
+    /// Replays of finished games, oldest first
+    pub replays: Vec<ReplayData>,
+    /// The game in progress, if any
+    pub game: Option<SavedGame>,
+}
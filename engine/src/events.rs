@@ -0,0 +1,22 @@
+use rusty2048_core::{Direction, GameEvent};
+use serde::Serialize;
+
+/// Something the frontend may want to react to (animations, sounds, toasts)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EngineEvent {
+    /// An event from the game itself
+    Game { event: GameEvent },
+    /// An achievement was unlocked
+    AchievementUnlocked { id: &'static str },
+    /// The game ended and was recorded in the statistics and replays
+    GameFinished {
+        score: u64,
+        max_tile: u64,
+        won: bool,
+    },
+    /// The AI's suggestion for the current position
+    Hint { direction: Direction },
+    /// The settings changed and should be persisted
+    SettingsChanged,
+}
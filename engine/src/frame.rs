@@ -0,0 +1,38 @@
+use rusty2048_core::{Game, GameState};
+use rusty2048_shared::Theme;
+use serde::Serialize;
+
+/// Everything a frontend needs to draw the current game
+#[derive(Debug, Clone, Serialize)]
+pub struct Frame {
+    /// Tile values by row, 0 for empty cells
+    pub board: Vec<Vec<u64>>,
+    pub score: u64,
+    pub best_score: u64,
+    pub moves: u32,
+    pub state: GameState,
+    pub max_tile: u64,
+    pub can_undo: bool,
+    pub can_redo: bool,
+    /// Play time so far in seconds
+    pub duration: u64,
+    pub theme: Theme,
+}
+
+impl Frame {
+    /// Capture the drawable state of a game
+    pub fn new(game: &Game, theme: &Theme) -> Self {
+        Self {
+            board: game.board().to_vec(),
+            score: game.score().current(),
+            best_score: game.score().best(),
+            moves: game.moves(),
+            state: game.state(),
+            max_tile: game.board().max_tile(),
+            can_undo: game.can_undo(),
+            can_redo: game.can_redo(),
+            duration: game.stats().duration,
+            theme: theme.clone(),
+        }
+    }
+}
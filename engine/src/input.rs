@@ -0,0 +1,47 @@
+use rusty2048_core::Direction;
+use rusty2048_shared::Action;
+use serde::{Deserialize, Serialize};
+
+/// A player input, independent of how the frontend received it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "direction", rename_all = "snake_case")]
+pub enum Input {
+    Move(Direction),
+    /// A move picked by the AI; the game then counts as played by it
+    AiMove(Direction),
+    Undo,
+    Redo,
+    NewGame,
+    /// Keep playing a won game
+    ContinueAfterWin,
+    /// Ask the AI for the best move; answered with `EngineEvent::Hint`
+    Hint,
+    /// Pause a running game or resume a paused one
+    TogglePause,
+}
+
+impl Input {
+    /// The input a key binding action stands for; `None` for actions the
+    /// frontend handles itself, such as switching themes
+    pub fn from_action(action: Action) -> Option<Self> {
+        Some(match action {
+            Action::MoveUp => Input::Move(Direction::Up),
+            Action::MoveDown => Input::Move(Direction::Down),
+            Action::MoveLeft => Input::Move(Direction::Left),
+            Action::MoveRight => Input::Move(Direction::Right),
+            Action::NewGame => Input::NewGame,
+            Action::Undo => Input::Undo,
+            Action::Redo => Input::Redo,
+            Action::Hint => Input::Hint,
+            Action::Pause => Input::TogglePause,
+            Action::ContinueAfterWin => Input::ContinueAfterWin,
+            _ => return None,
+        })
+    }
+}
+
+impl From<Direction> for Input {
+    fn from(direction: Direction) -> Self {
+        Input::Move(direction)
+    }
+}
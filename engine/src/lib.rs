@@ -0,0 +1,585 @@
+//! High-level engine for Rusty2048 frontends
+//!
+//! Wires the core game together with themes, translations, settings,
+//! statistics, replays and achievements, so that a frontend only translates
+//! its input into [`Input`] and draws [`Frame`]s. The web frontend runs on
+//! it; the CLI and desktop frontends still drive the core crate directly.
+
+pub mod data;
+pub mod events;
+pub mod frame;
+pub mod input;
+
+pub use data::EngineData;
+pub use events::EngineEvent;
+pub use frame::Frame;
+pub use input::Input;
+
+use rusty2048_core::{
+    analyze_replay, create_session_stats, AIAlgorithm, BestScoreStore, DailyChallenge, Direction,
+    Game, GameConfig, GameError, GameResult, GameState, MoveResult, PlayerType, ReplayData,
+    ReplayManager, ReplayMetadata, ReplayMove, Score, StatisticsManager, StatisticsSummary,
+    REPLAY_SCHEMA_VERSION,
+};
+use rusty2048_shared::{AchievementProgress, I18n, Language, Theme, UserSettings};
+use std::sync::Arc;
+
+/// One game session with everything around it
+pub struct Engine {
+    game: Game,
+    theme: Theme,
+    i18n: I18n,
+    settings: UserSettings,
+    stats: StatisticsManager,
+    achievements: AchievementProgress,
+    replays: ReplayManager,
+    /// Replay of the current game; undo and redo move its last moves in and out
+    recording: ReplayData,
+    undone_moves: Vec<ReplayMove>,
+    /// Tile movements of the last move, for animations
+    last_move: Option<MoveResult>,
+    /// The daily challenge being played, if any
+    daily: Option<DailyChallenge>,
+    /// Whether the AI made moves in the current game
+    ai_played: bool,
+    /// Whether the current game is over; cleared when it is undone or
+    /// continued after the win
+    finished: bool,
+    /// Id of the session the current game was recorded as, once it ended
+    recorded_session: Option<u64>,
+    /// Index of the replay the current game was archived as, once it ended
+    recorded_replay: Option<usize>,
+    events: Vec<EngineEvent>,
+}
+
+impl Engine {
+    /// Create an engine with fresh data
+    pub fn new(config: GameConfig) -> GameResult<Self> {
+        Self::from_data(config, EngineData::default())
+    }
+
+    /// Create an engine from persisted data, resuming its saved game if any.
+    /// `config` is used when there is no saved game.
+    pub fn from_data(config: GameConfig, data: EngineData) -> GameResult<Self> {
+        let mut game = match data.game {
+            Some(saved) => Game::from_saved_state(saved)?,
+            None => Game::new(config)?,
+        };
+        game.restore_best_score(data.settings.best_score);
+        game.enable_events();
+
+        let mut stats = StatisticsManager::in_memory();
+        stats.replace_sessions(data.sessions)?;
+        let mut replays = ReplayManager::new();
+        for replay in data.replays {
+            replays.add_replay(replay);
+        }
+
+        let mut i18n = I18n::new();
+        if let Some(language) = data
+            .settings
+            .language
+            .as_deref()
+            .and_then(Language::from_code)
+        {
+            i18n.set_language(language);
+        }
+
+        Ok(Self {
+            recording: start_recording(&game),
+            theme: Theme::by_name(&data.settings.theme).unwrap_or_default(),
+            finished: game.state() != GameState::Playing,
+            game,
+            i18n,
+            settings: data.settings,
+            stats,
+            achievements: data.achievements,
+            replays,
+            undone_moves: Vec::new(),
+            last_move: None,
+            daily: None,
+            ai_played: false,
+            recorded_session: None,
+            recorded_replay: None,
+            events: Vec::new(),
+        })
+    }
+
+    /// Snapshot of everything that should be persisted
+    pub fn data(&self) -> EngineData {
+        EngineData {
+            settings: self.settings.clone(),
+            achievements: self.achievements.clone(),
+            sessions: self.stats.sessions().to_vec(),
+            replays: self.replays.get_replays().to_vec(),
+            game: Some(self.game.to_saved_state()),
+        }
+    }
+
+    /// Apply a player input. Returns whether the game changed.
+    pub fn handle_input(&mut self, input: Input) -> GameResult<bool> {
+        let changed = match input {
+            Input::Move(direction) => self.make_move(direction)?,
+            Input::AiMove(direction) => {
+                let moved = self.make_move(direction)?;
+                self.ai_played |= moved;
+                moved
+            }
+            Input::Undo => {
+                self.game.undo()?;
+                if let Some(replay_move) = self.recording.moves.pop() {
+                    self.undone_moves.push(replay_move);
+                }
+                true
+            }
+            Input::Redo => {
+                self.game.redo()?;
+                if let Some(replay_move) = self.undone_moves.pop() {
+                    self.recording.moves.push(replay_move);
+                }
+                true
+            }
+            Input::NewGame => {
+                self.new_game()?;
+                true
+            }
+            Input::ContinueAfterWin => {
+                self.game.continue_after_win()?;
+                true
+            }
+            Input::TogglePause => {
+                if self.game.is_paused() {
+                    self.game.resume();
+                } else {
+                    self.game.pause();
+                }
+                true
+            }
+            Input::Hint => {
+                let direction = self.game.hint(AIAlgorithm::Expectimax)?;
+                self.events.push(EngineEvent::Hint { direction });
+                false
+            }
+        };
+
+        if changed {
+            self.after_change();
+        }
+        Ok(changed)
+    }
+
+    /// Start a new game with the current configuration
+    pub fn new_game(&mut self) -> GameResult<()> {
+        self.game.new_game()?;
+        self.reset_recording();
+        Ok(())
+    }
+
+    /// Start a new game with a different configuration
+    pub fn new_game_with_config(&mut self, config: GameConfig) -> GameResult<()> {
+        let mut game = Game::new(config)?;
+        game.restore_best_score(self.game.score().best());
+        if let Some(store) = self.game.best_score_store() {
+            let _ = game.set_best_score_store(store);
+        }
+        game.enable_events();
+        self.game = game;
+        self.daily = None;
+        self.reset_recording();
+        Ok(())
+    }
+
+    /// Start the daily challenge of a date. New games keep dealing it until
+    /// another configuration is picked.
+    pub fn new_daily(&mut self, challenge: DailyChallenge) -> GameResult<()> {
+        self.new_game_with_config(challenge.config())?;
+        self.daily = Some(challenge);
+        Ok(())
+    }
+
+    /// Continue a game saved by the frontend in its own format
+    pub fn load_game(
+        &mut self,
+        board: Vec<u64>,
+        score: Score,
+        moves: u32,
+        state: GameState,
+    ) -> GameResult<()> {
+        self.game.load_from_state(board, score, moves, state)?;
+        self.reset_recording();
+        Ok(())
+    }
+
+    /// Set the play time of the current game, for frontends that keep
+    /// their own clock
+    pub fn set_elapsed(&mut self, seconds: u64) {
+        self.game.set_elapsed(seconds);
+    }
+
+    /// Keep the best score in `store` as well as in the settings
+    pub fn set_best_score_store(&mut self, store: Arc<dyn BestScoreStore>) -> GameResult<()> {
+        self.game.set_best_score_store(store)
+    }
+
+    /// Drawable state of the current game
+    pub fn frame(&self) -> Frame {
+        Frame::new(&self.game, &self.theme)
+    }
+
+    /// Take the events produced since the last call
+    pub fn take_events(&mut self) -> Vec<EngineEvent> {
+        self.drain_game_events();
+        std::mem::take(&mut self.events)
+    }
+
+    /// Get the current game
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// Tile movements of the last move, if any since the game started
+    pub fn last_move(&self) -> Option<&MoveResult> {
+        self.last_move.as_ref()
+    }
+
+    /// The daily challenge being played, if any
+    pub fn daily(&self) -> Option<&DailyChallenge> {
+        self.daily.as_ref()
+    }
+
+    /// Get the current theme
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// Get the translations in the current language
+    pub fn i18n(&self) -> &I18n {
+        &self.i18n
+    }
+
+    /// Get the user settings
+    pub fn settings(&self) -> &UserSettings {
+        &self.settings
+    }
+
+    /// Switch to a built-in theme by name
+    pub fn set_theme(&mut self, name: &str) -> GameResult<()> {
+        self.theme = Theme::by_name(name)
+            .ok_or_else(|| GameError::InvalidOperation(format!("Unknown theme: {}", name)))?;
+        self.settings.theme = self.theme.name.clone();
+        self.events.push(EngineEvent::SettingsChanged);
+        Ok(())
+    }
+
+    /// Switch the language by code (e.g. "en", "zh")
+    pub fn set_language(&mut self, code: &str) -> GameResult<()> {
+        let language = Language::from_code(code)
+            .ok_or_else(|| GameError::InvalidOperation(format!("Unknown language: {}", code)))?;
+        self.i18n.set_language(language);
+        self.settings.language = Some(language.code().to_string());
+        self.events.push(EngineEvent::SettingsChanged);
+        Ok(())
+    }
+
+    /// Summary of all finished games
+    pub fn statistics(&self) -> StatisticsSummary {
+        self.stats.get_summary()
+    }
+
+    /// Get the statistics manager, for trends and exports
+    pub fn stats(&self) -> &StatisticsManager {
+        &self.stats
+    }
+
+    /// Get the statistics manager, for imports and retention
+    pub fn stats_mut(&mut self) -> &mut StatisticsManager {
+        &mut self.stats
+    }
+
+    /// Replays of finished games, oldest first
+    pub fn replays(&self) -> &[ReplayData] {
+        self.replays.get_replays()
+    }
+
+    /// Replay of the current game so far
+    pub fn current_replay(&self) -> ReplayData {
+        let stats = self.game.stats();
+        let mut replay = self.recording.clone();
+        replay.final_state = self.game.state();
+        replay.final_score = stats.score;
+        replay.total_moves = replay.moves.len() as u32;
+        replay.duration = stats.duration;
+        if let Some(challenge) = &self.daily {
+            replay.metadata = replay.metadata.with_daily(challenge);
+        }
+        replay
+    }
+
+    /// Get the achievement progress
+    pub fn achievements(&self) -> &AchievementProgress {
+        &self.achievements
+    }
+
+    /// Get the achievement progress, e.g. to restore it
+    pub fn achievements_mut(&mut self) -> &mut AchievementProgress {
+        &mut self.achievements
+    }
+
+    fn make_move(&mut self, direction: Direction) -> GameResult<bool> {
+        let board_before = self.game.board().to_vec();
+        let score_before = self.game.score().current();
+        let move_number = self.game.moves();
+
+        let result = self.game.make_move(direction)?;
+        let moved = result.moved;
+        if moved {
+            self.undone_moves.clear();
+            self.recording.moves.push(ReplayMove {
+                direction,
+                board_before,
+                board_after: self.game.board().to_vec(),
+                score_before,
+                score_after: self.game.score().current(),
+                move_number,
+                timestamp: rusty2048_core::get_current_time(),
+                timestamp_ms: Some(rusty2048_core::get_current_time_ms()),
+                board_hash: Some(self.game.board().zobrist_hash()),
+                rng: Some(self.game.rng().clone()),
+                spawned: result.spawned.clone(),
+            });
+        }
+        self.last_move = Some(result);
+        Ok(moved)
+    }
+
+    /// Update achievements, best score and records after the game changed
+    fn after_change(&mut self) {
+        self.drain_game_events();
+        let now = rusty2048_core::get_current_time();
+        let score = self.game.score().current();
+        let max_tile = self.game.board().max_tile();
+
+        let mut unlocked = self.achievements.update_in_game(score, max_tile, now);
+        if self.game.state() == GameState::Playing {
+            // Undone or continued after the win; recorded again when it ends
+            self.finished = false;
+        } else if !self.finished {
+            self.finished = true;
+            let won = self.game.has_won();
+            // A game that ends again counts once towards the achievements
+            if self.recorded_session.is_none() {
+                unlocked.extend(self.achievements.record_game_end(
+                    score,
+                    max_tile,
+                    self.game.moves(),
+                    won,
+                    now,
+                ));
+            }
+            self.record_finished_game(won, now);
+            self.events.push(EngineEvent::GameFinished {
+                score,
+                max_tile,
+                won,
+            });
+        }
+        self.events.extend(
+            unlocked
+                .into_iter()
+                .map(|achievement| EngineEvent::AchievementUnlocked { id: achievement.id }),
+        );
+
+        if self.game.score().best() > self.settings.best_score {
+            self.settings.best_score = self.game.score().best();
+            self.events.push(EngineEvent::SettingsChanged);
+        }
+    }
+
+    fn record_finished_game(&mut self, won: bool, now: u64) {
+        let stats = self.game.stats();
+        let mut session = create_session_stats(
+            stats.score,
+            stats.moves,
+            stats.duration,
+            self.game.board().max_tile(),
+            won,
+            now.saturating_sub(stats.duration),
+            now,
+        )
+        .with_move_timing(&stats)
+        .with_config(self.game.config())
+        .with_final_board(self.game.board())
+        .with_player(if self.ai_played {
+            PlayerType::Ai
+        } else {
+            PlayerType::Human
+        });
+        if let Some(challenge) = &self.daily {
+            session = session.with_daily(challenge);
+        }
+        let replay = self.current_replay();
+        if let Ok(analysis) = analyze_replay(&replay) {
+            session = session.with_analysis(&analysis);
+        }
+
+        // A game that ends again, after an undo or once continued after the
+        // win, replaces its session and replay. In-memory statistics cannot
+        // fail to save.
+        match self.recorded_session {
+            Some(session_id) => {
+                let _ = self.stats.update_session(session_id, session);
+            }
+            None => {
+                self.recorded_session = Some(session.session_id);
+                let _ = self.stats.record_session(session);
+            }
+        }
+        // The archived replay is only replaced if it is still this game's
+        let archived = self.recorded_replay.filter(|&index| {
+            self.replays.get_replay(index).is_some_and(|recorded| {
+                recorded.metadata.created_at == replay.metadata.created_at
+                    && recorded.initial_board == replay.initial_board
+            })
+        });
+        match archived {
+            Some(index) => {
+                self.replays.replace_replay(index, replay);
+            }
+            None => {
+                self.recorded_replay = Some(self.replays.replay_count());
+                self.replays.add_replay(replay);
+            }
+        }
+    }
+
+    fn reset_recording(&mut self) {
+        self.drain_game_events();
+        self.recording = start_recording(&self.game);
+        self.undone_moves.clear();
+        self.last_move = None;
+        self.ai_played = false;
+        self.finished = self.game.state() != GameState::Playing;
+        self.recorded_session = None;
+        self.recorded_replay = None;
+    }
+
+    fn drain_game_events(&mut self) {
+        self.events.extend(
+            self.game
+                .take_events()
+                .into_iter()
+                .map(|event| EngineEvent::Game { event }),
+        );
+    }
+}
+
+/// Start an empty replay from the game's current position
+fn start_recording(game: &Game) -> ReplayData {
+    ReplayData {
+        format_version: REPLAY_SCHEMA_VERSION,
+        config: game.config().clone(),
+        initial_board: game.board().to_vec(),
+        initial_rng: Some(game.rng().clone()),
+        moves: Vec::new(),
+        final_state: game.state(),
+        final_score: game.score().current(),
+        total_moves: game.moves(),
+        duration: 0,
+        metadata: ReplayMetadata::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seeded() -> GameConfig {
+        GameConfig {
+            seed: Some(7),
+            ..GameConfig::default()
+        }
+    }
+
+    /// Play moves until one changes the board
+    fn play_one(engine: &mut Engine) {
+        for direction in [
+            Direction::Up,
+            Direction::Left,
+            Direction::Down,
+            Direction::Right,
+        ] {
+            if engine.handle_input(Input::Move(direction)).unwrap() {
+                return;
+            }
+        }
+        panic!("no move possible");
+    }
+
+    #[test]
+    fn test_moves_are_recorded_through_undo_and_redo() {
+        let mut engine = Engine::new(seeded()).unwrap();
+        play_one(&mut engine);
+        play_one(&mut engine);
+        assert_eq!(engine.current_replay().moves.len(), 2);
+
+        engine.handle_input(Input::Undo).unwrap();
+        assert_eq!(engine.current_replay().moves.len(), 1);
+        engine.handle_input(Input::Redo).unwrap();
+        assert_eq!(engine.current_replay().moves.len(), 2);
+        assert_eq!(engine.frame().moves, 2);
+
+        let events = engine.take_events();
+        assert!(events.contains(&EngineEvent::Game {
+            event: rusty2048_core::GameEvent::Undone
+        }));
+    }
+
+    #[test]
+    fn test_game_undone_after_the_end_is_recorded_once() {
+        // A replay of an earlier game is kept as it is
+        let earlier = Engine::new(seeded()).unwrap().current_replay();
+        let data = EngineData {
+            replays: vec![earlier],
+            ..EngineData::default()
+        };
+        let mut engine = Engine::from_data(seeded(), data).unwrap();
+        let mut board = vec![0; 16];
+        board[0] = 1024;
+        board[1] = 1024;
+        engine
+            .load_game(board, Score::new(), 0, GameState::Playing)
+            .unwrap();
+
+        engine.handle_input(Input::AiMove(Direction::Left)).unwrap();
+        assert_eq!(engine.game().state(), GameState::Won);
+        engine.handle_input(Input::Undo).unwrap();
+        assert_eq!(engine.game().state(), GameState::Playing);
+        engine.handle_input(Input::Move(Direction::Left)).unwrap();
+
+        let finished = engine
+            .take_events()
+            .into_iter()
+            .filter(|event| matches!(event, EngineEvent::GameFinished { won: true, .. }))
+            .count();
+        assert_eq!(finished, 2);
+        assert_eq!(engine.stats().sessions().len(), 1);
+        assert_eq!(engine.replays().len(), 2);
+        assert!(engine.replays()[0].moves.is_empty());
+        assert_eq!(engine.replays()[1].moves.len(), 1);
+        // The AI's move was undone, but the game still counts as its
+        assert_eq!(engine.stats().sessions()[0].player, PlayerType::Ai);
+    }
+
+    #[test]
+    fn test_data_round_trip() {
+        let mut engine = Engine::new(seeded()).unwrap();
+        engine.set_theme("Dark").unwrap();
+        play_one(&mut engine);
+
+        let data = engine.data();
+        let restored = Engine::from_data(GameConfig::default(), data).unwrap();
+        assert_eq!(restored.theme().name, "Dark");
+        assert_eq!(restored.frame().board, engine.frame().board);
+        assert!(engine.set_language("xx").is_err());
+    }
+}
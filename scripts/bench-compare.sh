@@ -0,0 +1,80 @@
+#!/bin/bash
+
+# Rusty2048 core benchmark comparison
+#
+#   scripts/bench-compare.sh save [name]      Record a baseline (default: main)
+#   scripts/bench-compare.sh compare [name]   Compare the working tree against it
+#   scripts/bench-compare.sh ref <git-ref>    Benchmark <git-ref>, then compare
+#                                             the working tree against it
+#
+# Extra arguments after the name are passed to criterion, e.g. a filter:
+#   scripts/bench-compare.sh compare main make_move
+
+set -e
+
+RED='\033[0;31m'
+GREEN='\033[0;32m'
+BLUE='\033[0;34m'
+NC='\033[0m'
+
+print_status() {
+    echo -e "${BLUE}🚀${NC} $1"
+}
+
+print_success() {
+    echo -e "${GREEN}✅${NC} $1"
+}
+
+print_error() {
+    echo -e "${RED}❌${NC} $1"
+}
+
+ROOT="$(cd "$(dirname "$0")/.." && pwd)"
+# Share one target dir so every run sees the same criterion baselines
+export CARGO_TARGET_DIR="${CARGO_TARGET_DIR:-$ROOT/target}"
+
+run_bench() {
+    local dir="$1"
+    shift
+    (cd "$dir" && cargo bench -p rusty2048-core --bench core -- "$@")
+}
+
+command="$1"
+shift || true
+
+case "$command" in
+    save)
+        name="${1:-main}"
+        shift || true
+        print_status "Saving baseline '$name'"
+        run_bench "$ROOT" --save-baseline "$name" "$@"
+        print_success "Baseline '$name' saved in $CARGO_TARGET_DIR/criterion"
+        ;;
+    compare)
+        name="${1:-main}"
+        shift || true
+        print_status "Comparing against baseline '$name'"
+        run_bench "$ROOT" --baseline "$name" "$@"
+        ;;
+    ref)
+        ref="$1"
+        if [ -z "$ref" ]; then
+            print_error "Usage: $0 ref <git-ref> [criterion args]"
+            exit 1
+        fi
+        shift
+        worktree="$(mktemp -d)"
+        trap 'git -C "$ROOT" worktree remove --force "$worktree"' EXIT
+        git -C "$ROOT" worktree add --detach "$worktree" "$ref" > /dev/null
+
+        name="ref-$(git -C "$ROOT" rev-parse --short "$ref")"
+        print_status "Benchmarking $ref as baseline '$name'"
+        run_bench "$worktree" --save-baseline "$name" "$@"
+        print_status "Comparing working tree against $ref"
+        run_bench "$ROOT" --baseline "$name" "$@"
+        ;;
+    *)
+        sed -n '3,11p' "$0" | sed 's/^# \{0,1\}//'
+        exit 1
+        ;;
+esac
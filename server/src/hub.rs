@@ -0,0 +1,383 @@
+use crate::protocol::{ClientMessage, Incoming, Outgoing, ServerMessage, SessionId, SessionState};
+use rusty2048_core::{Game, GameConfig, GameError, GameResult, GameRng, RaceMessage};
+use std::collections::{HashMap, HashSet};
+
+/// Identifier of a connected client
+pub type ClientId = u64;
+
+/// Messages to deliver, in order
+pub type Outbox = Vec<(ClientId, Outgoing)>;
+
+/// Board sizes a client may ask for
+const BOARD_SIZES: std::ops::RangeInclusive<usize> = 3..=8;
+/// Most tiles a client may have spawned per move
+const MAX_TILES_PER_SPAWN: usize = 2;
+/// Deepest undo history a client may ask for
+const MAX_UNDO_DEPTH: usize = 100;
+/// Largest bonus or penalty of the scoring rules a client may ask for
+const MAX_SCORING_POINTS: u64 = 1 << 20;
+/// Games one client may host at once
+const MAX_SESSIONS_PER_CLIENT: usize = 4;
+
+struct HostedGame {
+    game: Game,
+    owner: ClientId,
+    spectators: HashSet<ClientId>,
+}
+
+/// Transport-independent server state: hosted games, spectators and races.
+///
+/// Feed it what clients send and deliver the returned messages.
+pub struct Hub {
+    games: HashMap<SessionId, HostedGame>,
+    next_session: SessionId,
+    /// First player of each race room, waiting for an opponent
+    waiting: HashMap<String, ClientId>,
+    /// Opponents of each racing client
+    races: HashMap<ClientId, Vec<ClientId>>,
+    /// Source of race seeds
+    rng: GameRng,
+}
+
+impl Default for Hub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hub {
+    pub fn new() -> Self {
+        Self {
+            games: HashMap::new(),
+            next_session: 1,
+            waiting: HashMap::new(),
+            races: HashMap::new(),
+            rng: GameRng::new(None),
+        }
+    }
+
+    /// Handle a message from a client
+    pub fn handle(&mut self, client: ClientId, message: Incoming) -> Outbox {
+        match message {
+            Incoming::Race(message) => self.handle_race(client, message),
+            Incoming::Client(message) => self
+                .handle_client(client, message)
+                .unwrap_or_else(|e| error(client, e.to_string())),
+        }
+    }
+
+    /// Forget everything about a client that went away
+    pub fn disconnect(&mut self, client: ClientId) -> Outbox {
+        self.games.retain(|_, hosted| hosted.owner != client);
+        for hosted in self.games.values_mut() {
+            hosted.spectators.remove(&client);
+        }
+        self.waiting.retain(|_, waiting| *waiting != client);
+
+        // Opponents keep racing; the leaver just stops sending progress
+        if let Some(opponents) = self.races.remove(&client) {
+            for opponent in opponents {
+                if let Some(theirs) = self.races.get_mut(&opponent) {
+                    theirs.retain(|c| *c != client);
+                }
+            }
+        }
+        Vec::new()
+    }
+
+    fn handle_client(&mut self, client: ClientId, message: ClientMessage) -> GameResult<Outbox> {
+        match message {
+            ClientMessage::CreateSession { config } => {
+                let hosting = self
+                    .games
+                    .values()
+                    .filter(|hosted| hosted.owner == client)
+                    .count();
+                if hosting >= MAX_SESSIONS_PER_CLIENT {
+                    return Err(GameError::InvalidOperation(format!(
+                        "At most {} sessions per player",
+                        MAX_SESSIONS_PER_CLIENT
+                    )));
+                }
+                let config = config.unwrap_or_default();
+                validate_config(&config)?;
+                let game = Game::new(config)?;
+                let session = self.next_session;
+                self.next_session += 1;
+                self.games.insert(
+                    session,
+                    HostedGame {
+                        game,
+                        owner: client,
+                        spectators: HashSet::new(),
+                    },
+                );
+                let mut outbox: Outbox =
+                    vec![(client, ServerMessage::SessionCreated { session }.into())];
+                outbox.extend(self.broadcast_state(session)?);
+                Ok(outbox)
+            }
+            ClientMessage::Move { session, direction } => {
+                self.owned_game(client, session)?.make_move(direction)?;
+                self.broadcast_state(session)
+            }
+            ClientMessage::Undo { session } => {
+                self.owned_game(client, session)?.undo()?;
+                self.broadcast_state(session)
+            }
+            ClientMessage::NewGame { session } => {
+                self.owned_game(client, session)?.new_game()?;
+                self.broadcast_state(session)
+            }
+            ClientMessage::ContinueAfterWin { session } => {
+                self.owned_game(client, session)?.continue_after_win()?;
+                self.broadcast_state(session)
+            }
+            ClientMessage::GetState { session } => {
+                let state = SessionState::new(session, &self.hosted(session)?.game);
+                Ok(vec![(client, ServerMessage::State(state).into())])
+            }
+            ClientMessage::Spectate { session } => {
+                let hosted = self.hosted_mut(session)?;
+                hosted.spectators.insert(client);
+                let state = SessionState::new(session, &hosted.game);
+                Ok(vec![(client, ServerMessage::State(state).into())])
+            }
+            ClientMessage::StopSpectating { session } => {
+                self.hosted_mut(session)?.spectators.remove(&client);
+                Ok(Vec::new())
+            }
+        }
+    }
+
+    /// Pair players by room and relay their race messages to each other
+    fn handle_race(&mut self, client: ClientId, message: RaceMessage) -> Outbox {
+        match message {
+            RaceMessage::Join { room, .. } => {
+                let opponent = match self.waiting.remove(&room) {
+                    Some(opponent) if opponent != client => opponent,
+                    _ => {
+                        self.waiting.insert(room, client);
+                        return Vec::new();
+                    }
+                };
+
+                self.races.insert(client, vec![opponent]);
+                self.races.insert(opponent, vec![client]);
+                let start = RaceMessage::Start {
+                    seed: self.rng.gen_range(u32::MAX as usize) as u64,
+                    config: GameConfig::default(),
+                };
+                vec![
+                    (opponent, Outgoing::Race(start.clone())),
+                    (client, Outgoing::Race(start)),
+                ]
+            }
+            // Only the server starts races
+            RaceMessage::Start { .. } => error(client, "Races are started by the server"),
+            message => self
+                .races
+                .get(&client)
+                .into_iter()
+                .flatten()
+                .map(|opponent| (*opponent, Outgoing::Race(message.clone())))
+                .collect(),
+        }
+    }
+
+    fn hosted(&self, session: SessionId) -> GameResult<&HostedGame> {
+        self.games
+            .get(&session)
+            .ok_or_else(|| GameError::InvalidOperation(format!("No session {}", session)))
+    }
+
+    fn hosted_mut(&mut self, session: SessionId) -> GameResult<&mut HostedGame> {
+        self.games
+            .get_mut(&session)
+            .ok_or_else(|| GameError::InvalidOperation(format!("No session {}", session)))
+    }
+
+    fn owned_game(&mut self, client: ClientId, session: SessionId) -> GameResult<&mut Game> {
+        let hosted = self.hosted_mut(session)?;
+        if hosted.owner != client {
+            return Err(GameError::InvalidOperation(format!(
+                "Session {} belongs to another player",
+                session
+            )));
+        }
+        Ok(&mut hosted.game)
+    }
+
+    /// Send a game's state to its owner and spectators
+    fn broadcast_state(&self, session: SessionId) -> GameResult<Outbox> {
+        let hosted = self.hosted(session)?;
+        let state = ServerMessage::State(SessionState::new(session, &hosted.game));
+        Ok(std::iter::once(hosted.owner)
+            .chain(hosted.spectators.iter().copied())
+            .map(|client| (client, state.clone().into()))
+            .collect())
+    }
+}
+
+/// Reject configurations no frontend offers, which would let a client
+/// make the server allocate or compute without bound
+fn validate_config(config: &GameConfig) -> GameResult<()> {
+    if !BOARD_SIZES.contains(&config.board_size) {
+        return Err(GameError::InvalidOperation(format!(
+            "Board size must be between {} and {}",
+            BOARD_SIZES.start(),
+            BOARD_SIZES.end()
+        )));
+    }
+    if !(1..=MAX_TILES_PER_SPAWN).contains(&config.tiles_per_spawn) {
+        return Err(GameError::InvalidOperation(format!(
+            "Tiles per spawn must be between 1 and {}",
+            MAX_TILES_PER_SPAWN
+        )));
+    }
+    if config.max_undo_depth > MAX_UNDO_DEPTH {
+        return Err(GameError::InvalidOperation(format!(
+            "Undo depth must be at most {}",
+            MAX_UNDO_DEPTH
+        )));
+    }
+    let scoring = &config.scoring;
+    if [
+        scoring.corner_merge_bonus,
+        scoring.undo_penalty,
+        scoring.streak_bonus,
+    ]
+    .iter()
+    .any(|points| *points > MAX_SCORING_POINTS)
+    {
+        return Err(GameError::InvalidOperation(format!(
+            "Scoring points must be at most {}",
+            MAX_SCORING_POINTS
+        )));
+    }
+    Ok(())
+}
+
+fn error(client: ClientId, message: impl Into<String>) -> Outbox {
+    vec![(
+        client,
+        ServerMessage::Error {
+            message: message.into(),
+        }
+        .into(),
+    )]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusty2048_core::Direction;
+
+    fn parse(json: &str) -> Incoming {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_spectators_follow_moves() {
+        let mut hub = Hub::new();
+        let created = hub.handle(1, parse(r#"{"type":"create_session"}"#));
+        assert!(matches!(
+            created[0].1,
+            Outgoing::Server(ServerMessage::SessionCreated { session: 1 })
+        ));
+        hub.handle(2, parse(r#"{"type":"spectate","session":1}"#));
+
+        // Only the owner may move
+        let denied = hub.handle(2, parse(r#"{"type":"move","session":1,"direction":"Up"}"#));
+        assert!(matches!(
+            denied[0],
+            (2, Outgoing::Server(ServerMessage::Error { .. }))
+        ));
+
+        for direction in [Direction::Up, Direction::Left, Direction::Down] {
+            let message = Incoming::Client(ClientMessage::Move {
+                session: 1,
+                direction,
+            });
+            let outbox = hub.handle(1, message);
+            if let [(1, _), (2, _)] = outbox.as_slice() {
+                return;
+            }
+        }
+        panic!("spectator did not receive the state");
+    }
+
+    #[test]
+    fn test_race_pairs_players_and_relays() {
+        let mut hub = Hub::new();
+        let join = r#"{"type":"join","room":"lobby","player":"a"}"#;
+        assert!(hub.handle(1, parse(join)).is_empty());
+        let started = hub.handle(2, parse(join));
+        assert_eq!(started.len(), 2);
+
+        let garbage = hub.handle(1, parse(r#"{"type":"garbage","player":"a","count":1}"#));
+        assert!(matches!(
+            garbage.as_slice(),
+            [(2, Outgoing::Race(RaceMessage::Garbage { count: 1, .. }))]
+        ));
+
+        hub.disconnect(2);
+        assert!(hub
+            .handle(1, parse(r#"{"type":"garbage","player":"a","count":1}"#))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_session_limits() {
+        let mut hub = Hub::new();
+        let mut create = |client, config| {
+            let outbox = hub.handle(
+                client,
+                Incoming::Client(ClientMessage::CreateSession {
+                    config: Some(config),
+                }),
+            );
+            !matches!(outbox[0].1, Outgoing::Server(ServerMessage::Error { .. }))
+        };
+
+        let default = GameConfig::default();
+        for config in [
+            GameConfig {
+                board_size: 100_000_000_000,
+                ..default.clone()
+            },
+            GameConfig {
+                board_size: 2,
+                ..default.clone()
+            },
+            GameConfig {
+                tiles_per_spawn: 1000,
+                ..default.clone()
+            },
+            GameConfig {
+                max_undo_depth: 1_000_000_000,
+                ..default.clone()
+            },
+        ] {
+            assert!(!create(1, config));
+        }
+
+        for _ in 0..MAX_SESSIONS_PER_CLIENT {
+            assert!(create(1, default.clone()));
+        }
+        assert!(!create(1, default.clone()));
+        // Others may still host
+        assert!(create(2, default.clone()));
+
+        // A player who leaves frees their slots
+        hub.disconnect(1);
+        let outbox = hub.handle(
+            1,
+            Incoming::Client(ClientMessage::CreateSession { config: None }),
+        );
+        assert!(matches!(
+            outbox[0].1,
+            Outgoing::Server(ServerMessage::SessionCreated { .. })
+        ));
+    }
+}
@@ -0,0 +1,148 @@
+//! WebSocket server for Rusty2048
+//!
+//! Hosts games for remote players and bots, streams them to spectators and
+//! pairs race clients. Every WebSocket message is one JSON object; see
+//! `protocol.rs` for the format.
+
+mod hub;
+mod protocol;
+
+use hub::{ClientId, Hub, Outbox};
+use protocol::Incoming;
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tungstenite::error::ProtocolError;
+use tungstenite::{Message, WebSocket};
+
+/// Address used when none is given on the command line
+const DEFAULT_ADDRESS: &str = "127.0.0.1:9048";
+/// How long a connection waits for input before flushing its outgoing messages
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+type ConnectionResult = Result<(), Box<tungstenite::Error>>;
+
+/// Hub plus the channels of the connected clients
+#[derive(Default)]
+struct Server {
+    hub: Hub,
+    clients: HashMap<ClientId, Sender<String>>,
+    next_client: ClientId,
+}
+
+impl Server {
+    fn connect(&mut self) -> (ClientId, Receiver<String>) {
+        let (sender, receiver) = mpsc::channel();
+        self.next_client += 1;
+        self.clients.insert(self.next_client, sender);
+        (self.next_client, receiver)
+    }
+
+    fn disconnect(&mut self, client: ClientId) {
+        self.clients.remove(&client);
+        let outbox = self.hub.disconnect(client);
+        self.deliver(outbox);
+    }
+
+    fn deliver(&self, outbox: Outbox) {
+        for (client, message) in outbox {
+            if let (Some(sender), Ok(json)) =
+                (self.clients.get(&client), serde_json::to_string(&message))
+            {
+                let _ = sender.send(json);
+            }
+        }
+    }
+}
+
+fn main() {
+    let address = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_ADDRESS.to_string());
+    let listener = match TcpListener::bind(&address) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Failed to listen on {}: {}", address, e);
+            std::process::exit(1);
+        }
+    };
+    println!("Rusty2048 server listening on ws://{}", address);
+
+    let server = Arc::new(Mutex::new(Server::default()));
+    for stream in listener.incoming().flatten() {
+        let server = server.clone();
+        std::thread::spawn(move || {
+            if let Err(e) = serve(stream, &server) {
+                eprintln!("Connection error: {}", e);
+            }
+        });
+    }
+}
+
+/// Run one client connection until it closes
+fn serve(stream: TcpStream, server: &Mutex<Server>) -> ConnectionResult {
+    let mut socket = tungstenite::accept(stream).map_err(|e| match e {
+        tungstenite::HandshakeError::Failure(e) => e,
+        tungstenite::HandshakeError::Interrupted(_) => {
+            tungstenite::Error::Io(ErrorKind::WouldBlock.into())
+        }
+    })?;
+    socket
+        .get_ref()
+        .set_read_timeout(Some(POLL_INTERVAL))
+        .map_err(tungstenite::Error::Io)?;
+
+    let (client, outgoing) = lock(server).connect();
+    let result = run_connection(&mut socket, client, &outgoing, server);
+    lock(server).disconnect(client);
+    result
+}
+
+fn run_connection(
+    socket: &mut WebSocket<TcpStream>,
+    client: ClientId,
+    outgoing: &Receiver<String>,
+    server: &Mutex<Server>,
+) -> ConnectionResult {
+    loop {
+        match socket.read() {
+            Ok(Message::Text(text)) => {
+                let mut server = lock(server);
+                let outbox = match serde_json::from_str::<Incoming>(&text) {
+                    Ok(message) => server.hub.handle(client, message),
+                    Err(e) => vec![(
+                        client,
+                        protocol::ServerMessage::Error {
+                            message: format!("Invalid message: {}", e),
+                        }
+                        .into(),
+                    )],
+                };
+                server.deliver(outbox);
+            }
+            Ok(Message::Close(_))
+            | Err(tungstenite::Error::ConnectionClosed)
+            | Err(tungstenite::Error::Protocol(ProtocolError::ResetWithoutClosingHandshake)) => {
+                return Ok(())
+            }
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        while let Ok(json) = outgoing.try_recv() {
+            socket.send(Message::Text(json))?;
+        }
+    }
+}
+
+fn lock(server: &Mutex<Server>) -> std::sync::MutexGuard<'_, Server> {
+    // A panicking connection thread must not take the whole server down
+    server
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
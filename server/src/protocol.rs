@@ -0,0 +1,93 @@
+use rusty2048_core::{Direction, Game, GameConfig, GameState, RaceMessage};
+use serde::{Deserialize, Serialize};
+
+/// Identifier of a game hosted by the server
+pub type SessionId = u64;
+
+/// Requests for hosted games; every message is a JSON object with a `type`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// Start a game owned by this connection (default configuration if omitted)
+    CreateSession {
+        #[serde(default)]
+        config: Option<GameConfig>,
+    },
+    /// Move in an owned game
+    Move {
+        session: SessionId,
+        direction: Direction,
+    },
+    /// Undo the last move of an owned game
+    Undo { session: SessionId },
+    /// Restart an owned game
+    NewGame { session: SessionId },
+    /// Keep playing an owned game after winning it
+    ContinueAfterWin { session: SessionId },
+    /// Get the current state of any game
+    GetState { session: SessionId },
+    /// Receive the state of a game after every change
+    Spectate { session: SessionId },
+    /// Stop receiving a game's updates
+    StopSpectating { session: SessionId },
+}
+
+/// Anything a client may send: session requests or race messages
+/// (the same JSON the race clients exchange with each other)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum Incoming {
+    Race(RaceMessage),
+    Client(ClientMessage),
+}
+
+/// Replies and updates for hosted games
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    SessionCreated { session: SessionId },
+    State(SessionState),
+    Error { message: String },
+}
+
+/// Snapshot of a hosted game
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionState {
+    pub session: SessionId,
+    pub board: Vec<Vec<u64>>,
+    pub score: u64,
+    pub best_score: u64,
+    pub moves: u32,
+    pub max_tile: u64,
+    pub state: GameState,
+    pub can_undo: bool,
+}
+
+impl SessionState {
+    pub fn new(session: SessionId, game: &Game) -> Self {
+        Self {
+            session,
+            board: game.board().to_vec(),
+            score: game.score().current(),
+            best_score: game.score().best(),
+            moves: game.moves(),
+            max_tile: game.board().max_tile(),
+            state: game.state(),
+            can_undo: game.can_undo(),
+        }
+    }
+}
+
+/// A message on its way to a client
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum Outgoing {
+    Server(ServerMessage),
+    Race(RaceMessage),
+}
+
+impl From<ServerMessage> for Outgoing {
+    fn from(message: ServerMessage) -> Self {
+        Outgoing::Server(message)
+    }
+}
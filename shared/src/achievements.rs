@@ -0,0 +1,259 @@
+use crate::Language;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Condition that unlocks an achievement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AchievementCondition {
+    /// Reach a tile of at least this value
+    ReachTile(u64),
+    /// Reach at least this score in a single game
+    ReachScore(u64),
+    /// Finish this many games
+    PlayGames(u32),
+    /// Win this many games
+    WinGames(u32),
+    /// Win a game using at most this many moves
+    WinWithinMoves(u32),
+}
+
+/// Achievement definition
+#[derive(Debug, Clone, Serialize)]
+pub struct Achievement {
+    pub id: &'static str,
+    pub icon: &'static str,
+    pub condition: AchievementCondition,
+    title_en: &'static str,
+    title_zh: &'static str,
+    description_en: &'static str,
+    description_zh: &'static str,
+}
+
+/// The achievement catalog shared by all frontends
+static ACHIEVEMENTS: &[Achievement] = &[
+    Achievement {
+        id: "first_game",
+        icon: "🎮",
+        condition: AchievementCondition::PlayGames(1),
+        title_en: "First Steps",
+        title_zh: "初次尝试",
+        description_en: "Finish your first game",
+        description_zh: "完成第一局游戏",
+    },
+    Achievement {
+        id: "tile_128",
+        icon: "🥉",
+        condition: AchievementCondition::ReachTile(128),
+        title_en: "Getting Started",
+        title_zh: "渐入佳境",
+        description_en: "Reach the 128 tile",
+        description_zh: "合成 128 方块",
+    },
+    Achievement {
+        id: "tile_512",
+        icon: "🥈",
+        condition: AchievementCondition::ReachTile(512),
+        title_en: "Halfway There",
+        title_zh: "半程",
+        description_en: "Reach the 512 tile",
+        description_zh: "合成 512 方块",
+    },
+    Achievement {
+        id: "tile_1024",
+        icon: "🥇",
+        condition: AchievementCondition::ReachTile(1024),
+        title_en: "So Close",
+        title_zh: "近在咫尺",
+        description_en: "Reach the 1024 tile",
+        description_zh: "合成 1024 方块",
+    },
+    Achievement {
+        id: "tile_2048",
+        icon: "🏆",
+        condition: AchievementCondition::ReachTile(2048),
+        title_en: "2048!",
+        title_zh: "2048！",
+        description_en: "Reach the 2048 tile",
+        description_zh: "合成 2048 方块",
+    },
+    Achievement {
+        id: "tile_4096",
+        icon: "👑",
+        condition: AchievementCondition::ReachTile(4096),
+        title_en: "Beyond the Goal",
+        title_zh: "超越目标",
+        description_en: "Reach the 4096 tile",
+        description_zh: "合成 4096 方块",
+    },
+    Achievement {
+        id: "score_10000",
+        icon: "⭐",
+        condition: AchievementCondition::ReachScore(10000),
+        title_en: "High Scorer",
+        title_zh: "高分玩家",
+        description_en: "Score 10,000 points in one game",
+        description_zh: "单局得分达到 10,000",
+    },
+    Achievement {
+        id: "score_50000",
+        icon: "🌟",
+        condition: AchievementCondition::ReachScore(50000),
+        title_en: "Score Master",
+        title_zh: "得分大师",
+        description_en: "Score 50,000 points in one game",
+        description_zh: "单局得分达到 50,000",
+    },
+    Achievement {
+        id: "games_50",
+        icon: "🎖️",
+        condition: AchievementCondition::PlayGames(50),
+        title_en: "Veteran",
+        title_zh: "老玩家",
+        description_en: "Finish 50 games",
+        description_zh: "完成 50 局游戏",
+    },
+    Achievement {
+        id: "wins_10",
+        icon: "🏅",
+        condition: AchievementCondition::WinGames(10),
+        title_en: "Champion",
+        title_zh: "冠军",
+        description_en: "Win 10 games",
+        description_zh: "赢得 10 局游戏",
+    },
+    Achievement {
+        id: "fast_win",
+        icon: "⚡",
+        condition: AchievementCondition::WinWithinMoves(1000),
+        title_en: "Efficient",
+        title_zh: "高效",
+        description_en: "Win a game in 1,000 moves or fewer",
+        description_zh: "在 1,000 步内获胜",
+    },
+];
+
+impl Achievement {
+    /// Get all achievements in the catalog
+    pub fn all() -> &'static [Achievement] {
+        ACHIEVEMENTS
+    }
+
+    /// Find an achievement by id
+    pub fn by_id(id: &str) -> Option<&'static Achievement> {
+        ACHIEVEMENTS.iter().find(|a| a.id == id)
+    }
+
+    /// Get the localized title
+    pub fn title(&self, language: Language) -> &'static str {
+        match language {
+            Language::English => self.title_en,
+            Language::Chinese => self.title_zh,
+        }
+    }
+
+    /// Get the localized description
+    pub fn description(&self, language: Language) -> &'static str {
+        match language {
+            Language::English => self.description_en,
+            Language::Chinese => self.description_zh,
+        }
+    }
+}
+
+/// Player progress towards the achievement catalog
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AchievementProgress {
+    /// Unlocked achievement ids with their unlock timestamps
+    pub unlocked: HashMap<String, u64>,
+    /// Number of finished games
+    pub games_played: u32,
+    /// Number of won games
+    pub games_won: u32,
+}
+
+impl AchievementProgress {
+    /// Create empty progress
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check whether an achievement is unlocked
+    pub fn is_unlocked(&self, id: &str) -> bool {
+        self.unlocked.contains_key(id)
+    }
+
+    /// Get the unlock timestamp of an achievement
+    pub fn unlocked_at(&self, id: &str) -> Option<u64> {
+        self.unlocked.get(id).copied()
+    }
+
+    /// Combine progress from another device, keeping the earliest unlock times
+    pub fn merge(&mut self, other: AchievementProgress) {
+        for (id, timestamp) in other.unlocked {
+            self.unlocked
+                .entry(id)
+                .and_modify(|existing| *existing = (*existing).min(timestamp))
+                .or_insert(timestamp);
+        }
+        self.games_played = self.games_played.max(other.games_played);
+        self.games_won = self.games_won.max(other.games_won);
+    }
+
+    /// Check tile and score achievements during a game.
+    /// Returns the achievements unlocked by this update.
+    pub fn update_in_game(
+        &mut self,
+        score: u64,
+        max_tile: u64,
+        timestamp: u64,
+    ) -> Vec<&'static Achievement> {
+        self.unlock_where(timestamp, |condition| match condition {
+            AchievementCondition::ReachTile(tile) => max_tile >= tile,
+            AchievementCondition::ReachScore(target) => score >= target,
+            _ => false,
+        })
+    }
+
+    /// Record a finished game and check all achievements.
+    /// Returns the achievements unlocked by this game.
+    pub fn record_game_end(
+        &mut self,
+        score: u64,
+        max_tile: u64,
+        moves: u32,
+        won: bool,
+        timestamp: u64,
+    ) -> Vec<&'static Achievement> {
+        self.games_played += 1;
+        if won {
+            self.games_won += 1;
+        }
+
+        let (games_played, games_won) = (self.games_played, self.games_won);
+        self.unlock_where(timestamp, |condition| match condition {
+            AchievementCondition::ReachTile(tile) => max_tile >= tile,
+            AchievementCondition::ReachScore(target) => score >= target,
+            AchievementCondition::PlayGames(count) => games_played >= count,
+            AchievementCondition::WinGames(count) => games_won >= count,
+            AchievementCondition::WinWithinMoves(limit) => won && moves <= limit,
+        })
+    }
+
+    /// Unlock every locked achievement whose condition holds
+    fn unlock_where(
+        &mut self,
+        timestamp: u64,
+        is_met: impl Fn(AchievementCondition) -> bool,
+    ) -> Vec<&'static Achievement> {
+        let newly_unlocked: Vec<&'static Achievement> = ACHIEVEMENTS
+            .iter()
+            .filter(|a| !self.is_unlocked(a.id) && is_met(a.condition))
+            .collect();
+
+        for achievement in &newly_unlocked {
+            self.unlocked.insert(achievement.id.to_string(), timestamp);
+        }
+
+        newly_unlocked
+    }
+}
@@ -0,0 +1,109 @@
+use crate::KeyBindings;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// User settings persisted between launches
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UserSettings {
+    /// Selected theme name
+    pub theme: String,
+    /// Selected language code (`None` until the user picks one)
+    pub language: Option<String>,
+    /// Best score ever reached
+    pub best_score: u64,
+    /// Keyboard shortcuts
+    pub key_bindings: KeyBindings,
+}
+
+impl Default for UserSettings {
+    fn default() -> Self {
+        Self {
+            theme: "Classic".to_string(),
+            language: None,
+            best_score: 0,
+            key_bindings: KeyBindings::default(),
+        }
+    }
+}
+
+/// JSON file store for settings and other small documents
+#[derive(Debug, Clone)]
+pub struct ConfigStore {
+    path: PathBuf,
+    /// Refuse to write, leaving the file as it is
+    read_only: bool,
+}
+
+impl ConfigStore {
+    /// Create a store backed by a file
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            read_only: false,
+        }
+    }
+
+    /// Create a store for a file inside a directory
+    pub fn in_dir(dir: &Path, file_name: &str) -> Self {
+        Self::new(dir.join(file_name))
+    }
+
+    /// Get the file path of the store
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Make `save` and `remove` fail instead of touching the file, e.g.
+    /// while data that could not be read should be kept for recovery
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
+    fn check_writable(&self) -> io::Result<()> {
+        if self.read_only {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("{} is read-only", self.path.display()),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Load the stored value, or the default if the file does not exist yet
+    pub fn load<T: DeserializeOwned + Default>(&self) -> io::Result<T> {
+        if !self.path.exists() {
+            return Ok(T::default());
+        }
+
+        let content = fs::read_to_string(&self.path)?;
+        serde_json::from_str(&content).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Save a value, creating parent directories as needed.
+    /// The file is written to a temporary path first so a crash never leaves it half-written.
+    pub fn save<T: Serialize>(&self, value: &T) -> io::Result<()> {
+        self.check_writable()?;
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let content = serde_json::to_string_pretty(value)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, content)?;
+        fs::rename(&tmp_path, &self.path)
+    }
+
+    /// Delete the stored file (a missing file is not an error)
+    pub fn remove(&self) -> io::Result<()> {
+        self.check_writable()?;
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! Animated exports of a sequence of boards (e.g. a replay): GIFs drawn with
+//! the software renderer in [`crate::render`], and asciinema casts that play
+//! back in a terminal.
+
+use crate::render::{self, Image};
+use crate::Theme;
+use std::fmt::Write;
+
+/// How long the final position stays on screen, in milliseconds
+pub const FINAL_FRAME_MS: u32 = 2_000;
+
+/// Width of a tile in a cast, in terminal columns
+const CAST_CELL_WIDTH: usize = 7;
+/// Height of a tile in a cast, in terminal rows
+const CAST_CELL_HEIGHT: usize = 3;
+
+/// One position of an animation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub board: Vec<Vec<u64>>,
+    /// Line shown above the board (e.g. "Move 3  Score 120")
+    pub caption: String,
+}
+
+/// Draw every frame with the theme's colors
+pub fn render_frames(frames: &[Frame], theme: &Theme) -> Vec<Image> {
+    frames
+        .iter()
+        .map(|frame| render::render_board(&frame.board, &frame.caption, theme))
+        .collect()
+}
+
+/// Encode images as a looping animated GIF, showing each for `frame_ms`
+/// and the last one for `FINAL_FRAME_MS`
+pub fn encode_gif(images: &[Image], frame_ms: u32) -> Result<Vec<u8>, String> {
+    let first = images.first().ok_or("The replay has no frames")?;
+    let mut bytes = Vec::new();
+    {
+        let mut encoder =
+            gif::Encoder::new(&mut bytes, first.width as u16, first.height as u16, &[])
+                .map_err(|e| format!("Failed to encode GIF: {}", e))?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(|e| format!("Failed to encode GIF: {}", e))?;
+
+        for (i, image) in images.iter().enumerate() {
+            let mut pixels = image.pixels.clone();
+            let mut frame = gif::Frame::from_rgba_speed(
+                image.width as u16,
+                image.height as u16,
+                &mut pixels,
+                10,
+            );
+            // GIF delays are in hundredths of a second
+            let ms = if i + 1 == images.len() {
+                FINAL_FRAME_MS
+            } else {
+                frame_ms
+            };
+            frame.delay = (ms / 10).clamp(1, u16::MAX as u32) as u16;
+            encoder
+                .write_frame(&frame)
+                .map_err(|e| format!("Failed to encode GIF: {}", e))?;
+        }
+    }
+    Ok(bytes)
+}
+
+/// Encode frames as an asciinema cast (format version 2), drawing tiles
+/// with 24-bit terminal colors from the theme
+pub fn encode_asciicast(
+    frames: &[Frame],
+    frame_ms: u32,
+    theme: &Theme,
+    title: &str,
+) -> Result<String, String> {
+    let first = frames.first().ok_or("The replay has no frames")?;
+    let size = first.board.len();
+    let width = (size * (CAST_CELL_WIDTH + 1) + 1).max(40);
+    let height = size * (CAST_CELL_HEIGHT + 1) + 3;
+
+    let header = serde_json::json!({
+        "version": 2,
+        "width": width,
+        "height": height,
+        "title": title,
+        "env": { "TERM": "xterm-256color" },
+    });
+    let mut cast = header.to_string();
+    cast.push('\n');
+
+    let mut time = 0.0;
+    for frame in frames {
+        let event = serde_json::json!([time, "o", draw_frame(frame, theme)]);
+        cast.push_str(&event.to_string());
+        cast.push('\n');
+        time += frame_ms as f64 / 1000.0;
+    }
+    // Keep the final position on screen before the cast ends
+    time += (FINAL_FRAME_MS.saturating_sub(frame_ms)) as f64 / 1000.0;
+    cast.push_str(&serde_json::json!([time, "o", ""]).to_string());
+    cast.push('\n');
+    Ok(cast)
+}
+
+/// Terminal output drawing one frame from a cleared screen
+fn draw_frame(frame: &Frame, theme: &Theme) -> String {
+    let background = render::parse_hex_color(&theme.grid_background).unwrap_or([0xbb, 0xad, 0xa0]);
+    let gap_line = format!(
+        "{}{}\x1b[0m\r\n",
+        bg(background),
+        " ".repeat(frame.board.len() * (CAST_CELL_WIDTH + 1) + 1)
+    );
+    let mut out = String::from("\x1b[H\x1b[2J");
+    let _ = write!(out, "{}\x1b[0m\r\n\r\n", frame.caption);
+
+    for row in &frame.board {
+        out.push_str(&gap_line);
+        for line in 0..CAST_CELL_HEIGHT {
+            out.push_str(&bg(background));
+            out.push(' ');
+            for &value in row {
+                let color = render::tile_color(theme, value);
+                let text = if line == CAST_CELL_HEIGHT / 2 && value != 0 {
+                    value.to_string()
+                } else {
+                    String::new()
+                };
+                let label = render::label_color(theme, color);
+                let _ = write!(
+                    out,
+                    "{}\x1b[38;2;{};{};{}m{:^width$}{} ",
+                    bg(color),
+                    label[0],
+                    label[1],
+                    label[2],
+                    text,
+                    bg(background),
+                    width = CAST_CELL_WIDTH
+                );
+            }
+            out.push_str("\x1b[0m\r\n");
+        }
+    }
+    out.push_str(&gap_line);
+    out
+}
+
+/// Escape sequence setting a 24-bit background color
+fn bg(color: [u8; 3]) -> String {
+    format!("\x1b[48;2;{};{};{}m", color[0], color[1], color[2])
+}
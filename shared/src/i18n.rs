@@ -38,6 +38,25 @@ impl Language {
             _ => None,
         }
     }
+
+    /// Parse from a POSIX or BCP 47 locale such as `zh_CN.UTF-8` or `en-US`
+    pub fn from_locale(locale: &str) -> Option<Self> {
+        let language = locale
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        Self::from_code(&language)
+    }
+
+    /// Detect the language of the operating system from the locale environment variables
+    pub fn detect_system() -> Option<Self> {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|locale| Self::from_locale(&locale))
+    }
 }
 
 /// Translation keys
@@ -56,6 +75,7 @@ pub enum TranslationKey {
     YouWon,
     PressRToRestart,
     ContinuePlaying,
+    Paused,
 
     // Controls
     Controls,
@@ -140,11 +160,32 @@ pub enum TranslationKey {
     Success,
     Warning,
     Info,
+    AchievementUnlocked,
 
     // Time formatting
     Hours,
     Minutes,
     Seconds,
+
+    // Pause menu
+    MenuPaused,
+    Resume,
+    ChangeBoardSize,
+    Settings,
+    SaveAndQuit,
+    BoardSize,
+    BoardSizeFixed,
+    Theme,
+    AssistMode,
+    Charts,
+    Back,
+    On,
+    Off,
+    MenuMainHelp,
+    MenuPageHelp,
+
+    // Board view
+    TerminalTooSmall,
 }
 
 /// Translation data
@@ -179,7 +220,11 @@ impl TranslationData {
         );
         translations.insert(
             "continue_playing".to_string(),
-            "or continue playing".to_string(),
+            "or Enter to keep going".to_string(),
+        );
+        translations.insert(
+            "paused".to_string(),
+            "Paused - press Space to resume".to_string(),
         );
 
         // Controls
@@ -289,12 +334,51 @@ impl TranslationData {
         translations.insert("success".to_string(), "Success".to_string());
         translations.insert("warning".to_string(), "Warning".to_string());
         translations.insert("info".to_string(), "Info".to_string());
+        translations.insert(
+            "achievement_unlocked".to_string(),
+            "Achievement unlocked".to_string(),
+        );
 
         // Time formatting
         translations.insert("hours".to_string(), "h".to_string());
         translations.insert("minutes".to_string(), "m".to_string());
         translations.insert("seconds".to_string(), "s".to_string());
 
+        // Pause menu
+        translations.insert("menu_paused".to_string(), "Paused".to_string());
+        translations.insert("resume".to_string(), "Resume".to_string());
+        translations.insert(
+            "change_board_size".to_string(),
+            "Change Board Size".to_string(),
+        );
+        translations.insert("settings".to_string(), "Settings".to_string());
+        translations.insert("save_and_quit".to_string(), "Save & Quit".to_string());
+        translations.insert("board_size".to_string(), "Board Size".to_string());
+        translations.insert(
+            "board_size_fixed".to_string(),
+            "Fixed for the daily challenge".to_string(),
+        );
+        translations.insert("theme".to_string(), "Theme".to_string());
+        translations.insert("assist_mode".to_string(), "Assist Mode".to_string());
+        translations.insert("charts".to_string(), "Charts".to_string());
+        translations.insert("back".to_string(), "Back".to_string());
+        translations.insert("on".to_string(), "On".to_string());
+        translations.insert("off".to_string(), "Off".to_string());
+        translations.insert(
+            "menu_main_help".to_string(),
+            "↑↓ Select | Enter Confirm | Esc Resume".to_string(),
+        );
+        translations.insert(
+            "menu_page_help".to_string(),
+            "↑↓ Select | ←→ Change | Enter Confirm | Esc Back".to_string(),
+        );
+
+        // Board view
+        translations.insert(
+            "terminal_too_small".to_string(),
+            "Terminal too small: {width}x{height}, needs {needed_width}x{needed_height}. Enlarge the window or pick a smaller board.".to_string(),
+        );
+
         Self {
             language: Language::English,
             translations,
@@ -317,7 +401,11 @@ impl TranslationData {
         translations.insert("congratulations".to_string(), "🎉 恭喜！".to_string());
         translations.insert("you_won".to_string(), "你赢了！".to_string());
         translations.insert("press_r_to_restart".to_string(), "按R重新开始".to_string());
-        translations.insert("continue_playing".to_string(), "或继续游戏".to_string());
+        translations.insert(
+            "continue_playing".to_string(),
+            "或按回车继续游戏".to_string(),
+        );
+        translations.insert("paused".to_string(), "已暂停 - 按空格键继续".to_string());
 
         // Controls
         translations.insert("controls".to_string(), "控制".to_string());
@@ -411,12 +499,45 @@ impl TranslationData {
         translations.insert("success".to_string(), "成功".to_string());
         translations.insert("warning".to_string(), "警告".to_string());
         translations.insert("info".to_string(), "信息".to_string());
+        translations.insert("achievement_unlocked".to_string(), "成就解锁".to_string());
 
         // Time formatting
         translations.insert("hours".to_string(), "时".to_string());
         translations.insert("minutes".to_string(), "分".to_string());
         translations.insert("seconds".to_string(), "秒".to_string());
 
+        // Pause menu
+        translations.insert("menu_paused".to_string(), "已暂停".to_string());
+        translations.insert("resume".to_string(), "继续".to_string());
+        translations.insert("change_board_size".to_string(), "更改棋盘大小".to_string());
+        translations.insert("settings".to_string(), "设置".to_string());
+        translations.insert("save_and_quit".to_string(), "保存并退出".to_string());
+        translations.insert("board_size".to_string(), "棋盘大小".to_string());
+        translations.insert(
+            "board_size_fixed".to_string(),
+            "每日挑战的棋盘大小固定".to_string(),
+        );
+        translations.insert("theme".to_string(), "主题".to_string());
+        translations.insert("assist_mode".to_string(), "辅助模式".to_string());
+        translations.insert("charts".to_string(), "图表".to_string());
+        translations.insert("back".to_string(), "返回".to_string());
+        translations.insert("on".to_string(), "开".to_string());
+        translations.insert("off".to_string(), "关".to_string());
+        translations.insert(
+            "menu_main_help".to_string(),
+            "↑↓ 选择 | Enter 确认 | Esc 继续".to_string(),
+        );
+        translations.insert(
+            "menu_page_help".to_string(),
+            "↑↓ 选择 | ←→ 调整 | Enter 确认 | Esc 返回".to_string(),
+        );
+
+        // Board view
+        translations.insert(
+            "terminal_too_small".to_string(),
+            "终端窗口太小：{width}x{height}，需要 {needed_width}x{needed_height}。请放大窗口或选择更小的棋盘。".to_string(),
+        );
+
         Self {
             language: Language::Chinese,
             translations,
@@ -537,6 +658,7 @@ impl TranslationKey {
             TranslationKey::YouWon => "you_won",
             TranslationKey::PressRToRestart => "press_r_to_restart",
             TranslationKey::ContinuePlaying => "continue_playing",
+            TranslationKey::Paused => "paused",
             TranslationKey::Controls => "controls",
             TranslationKey::MoveTiles => "move_tiles",
             TranslationKey::Restart => "restart",
@@ -607,9 +729,26 @@ impl TranslationKey {
             TranslationKey::Success => "success",
             TranslationKey::Warning => "warning",
             TranslationKey::Info => "info",
+            TranslationKey::AchievementUnlocked => "achievement_unlocked",
             TranslationKey::Hours => "hours",
             TranslationKey::Minutes => "minutes",
             TranslationKey::Seconds => "seconds",
+            TranslationKey::MenuPaused => "menu_paused",
+            TranslationKey::Resume => "resume",
+            TranslationKey::ChangeBoardSize => "change_board_size",
+            TranslationKey::Settings => "settings",
+            TranslationKey::SaveAndQuit => "save_and_quit",
+            TranslationKey::BoardSize => "board_size",
+            TranslationKey::BoardSizeFixed => "board_size_fixed",
+            TranslationKey::Theme => "theme",
+            TranslationKey::AssistMode => "assist_mode",
+            TranslationKey::Charts => "charts",
+            TranslationKey::Back => "back",
+            TranslationKey::On => "on",
+            TranslationKey::Off => "off",
+            TranslationKey::MenuMainHelp => "menu_main_help",
+            TranslationKey::MenuPageHelp => "menu_page_help",
+            TranslationKey::TerminalTooSmall => "terminal_too_small",
         }
     }
 
@@ -627,6 +766,7 @@ impl TranslationKey {
             "you_won" => TranslationKey::YouWon,
             "press_r_to_restart" => TranslationKey::PressRToRestart,
             "continue_playing" => TranslationKey::ContinuePlaying,
+            "paused" => TranslationKey::Paused,
             "controls" => TranslationKey::Controls,
             "move_tiles" => TranslationKey::MoveTiles,
             "restart" => TranslationKey::Restart,
@@ -697,9 +837,26 @@ impl TranslationKey {
             "success" => TranslationKey::Success,
             "warning" => TranslationKey::Warning,
             "info" => TranslationKey::Info,
+            "achievement_unlocked" => TranslationKey::AchievementUnlocked,
             "hours" => TranslationKey::Hours,
             "minutes" => TranslationKey::Minutes,
             "seconds" => TranslationKey::Seconds,
+            "menu_paused" => TranslationKey::MenuPaused,
+            "resume" => TranslationKey::Resume,
+            "change_board_size" => TranslationKey::ChangeBoardSize,
+            "settings" => TranslationKey::Settings,
+            "save_and_quit" => TranslationKey::SaveAndQuit,
+            "board_size" => TranslationKey::BoardSize,
+            "board_size_fixed" => TranslationKey::BoardSizeFixed,
+            "theme" => TranslationKey::Theme,
+            "assist_mode" => TranslationKey::AssistMode,
+            "charts" => TranslationKey::Charts,
+            "back" => TranslationKey::Back,
+            "on" => TranslationKey::On,
+            "off" => TranslationKey::Off,
+            "menu_main_help" => TranslationKey::MenuMainHelp,
+            "menu_page_help" => TranslationKey::MenuPageHelp,
+            "terminal_too_small" => TranslationKey::TerminalTooSmall,
             _ => return None,
         })
     }
@@ -0,0 +1,459 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Game actions that can be bound to keys. The ones after `Pause` only
+/// exist in the terminal frontend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    NewGame,
+    Undo,
+    Redo,
+    Hint,
+    /// Pause or resume the play clock
+    Pause,
+    Quit,
+    /// Open or close the pause menu
+    Menu,
+    /// Keep going after winning
+    ContinueAfterWin,
+    NextTheme,
+    ThemeClassic,
+    ThemeDark,
+    ThemeNeon,
+    ThemeRetro,
+    ThemePastel,
+    ToggleThemeHelp,
+    NextLanguage,
+    ReplayMode,
+    /// Grade each move against the AI's choice
+    ToggleAssist,
+    ToggleCharts,
+    PrevChart,
+    NextChart,
+    ToggleAi,
+    ToggleAutoPlay,
+    PrevAlgorithm,
+    NextAlgorithm,
+    /// Shorten the delay between auto-played moves
+    SpeedUp,
+    SlowDown,
+}
+
+impl Action {
+    /// Get all bindable actions
+    pub fn all() -> Vec<Self> {
+        vec![
+            Action::MoveUp,
+            Action::MoveDown,
+            Action::MoveLeft,
+            Action::MoveRight,
+            Action::NewGame,
+            Action::Undo,
+            Action::Redo,
+            Action::Hint,
+            Action::Pause,
+            Action::Quit,
+            Action::Menu,
+            Action::ContinueAfterWin,
+            Action::NextTheme,
+            Action::ThemeClassic,
+            Action::ThemeDark,
+            Action::ThemeNeon,
+            Action::ThemeRetro,
+            Action::ThemePastel,
+            Action::ToggleThemeHelp,
+            Action::NextLanguage,
+            Action::ReplayMode,
+            Action::ToggleAssist,
+            Action::ToggleCharts,
+            Action::PrevChart,
+            Action::NextChart,
+            Action::ToggleAi,
+            Action::ToggleAutoPlay,
+            Action::PrevAlgorithm,
+            Action::NextAlgorithm,
+            Action::SpeedUp,
+            Action::SlowDown,
+        ]
+    }
+
+    /// Get the action name used in settings files and by frontends
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::MoveUp => "move_up",
+            Action::MoveDown => "move_down",
+            Action::MoveLeft => "move_left",
+            Action::MoveRight => "move_right",
+            Action::NewGame => "new_game",
+            Action::Undo => "undo",
+            Action::Redo => "redo",
+            Action::Hint => "hint",
+            Action::Pause => "pause",
+            Action::Quit => "quit",
+            Action::Menu => "menu",
+            Action::ContinueAfterWin => "continue_after_win",
+            Action::NextTheme => "next_theme",
+            Action::ThemeClassic => "theme_classic",
+            Action::ThemeDark => "theme_dark",
+            Action::ThemeNeon => "theme_neon",
+            Action::ThemeRetro => "theme_retro",
+            Action::ThemePastel => "theme_pastel",
+            Action::ToggleThemeHelp => "toggle_theme_help",
+            Action::NextLanguage => "next_language",
+            Action::ReplayMode => "replay_mode",
+            Action::ToggleAssist => "toggle_assist",
+            Action::ToggleCharts => "toggle_charts",
+            Action::PrevChart => "prev_chart",
+            Action::NextChart => "next_chart",
+            Action::ToggleAi => "toggle_ai",
+            Action::ToggleAutoPlay => "toggle_auto_play",
+            Action::PrevAlgorithm => "prev_algorithm",
+            Action::NextAlgorithm => "next_algorithm",
+            Action::SpeedUp => "speed_up",
+            Action::SlowDown => "slow_down",
+        }
+    }
+
+    /// Parse an action name
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all().into_iter().find(|action| action.name() == name)
+    }
+}
+
+/// Key bindings shared by all frontends.
+///
+/// Keys are written like `KeyboardEvent.key` values (`ArrowUp`, `w`), optionally
+/// prefixed with modifiers (`CmdOrCtrl+z`, `Alt+n`). Bindings that contain a
+/// modifier double as menu accelerators.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct KeyBindings {
+    bindings: BTreeMap<Action, Vec<String>>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self::from_defaults(&[
+            (Action::MoveUp, &["ArrowUp", "w"]),
+            (Action::MoveDown, &["ArrowDown", "s"]),
+            (Action::MoveLeft, &["ArrowLeft", "a"]),
+            (Action::MoveRight, &["ArrowRight", "d"]),
+            (Action::NewGame, &["CmdOrCtrl+n", "n"]),
+            (Action::Undo, &["CmdOrCtrl+z", "u"]),
+            (Action::Redo, &["CmdOrCtrl+y"]),
+            (Action::Hint, &["CmdOrCtrl+h", "h"]),
+            (Action::Pause, &["p"]),
+        ])
+    }
+}
+
+impl KeyBindings {
+    /// Bindings of each action to its keys, for frontends with their own
+    /// defaults
+    pub fn from_defaults(defaults: &[(Action, &[&str])]) -> Self {
+        Self {
+            bindings: defaults
+                .iter()
+                .map(|(action, keys)| (*action, keys.iter().map(|k| k.to_string()).collect()))
+                .collect(),
+        }
+    }
+
+    /// These bindings with the actions in `overrides` bound to exactly their
+    /// keys instead. Fails on a key that is not a valid [`KeyChord`] or that
+    /// is bound to two actions.
+    pub fn with_overrides(
+        &self,
+        overrides: &BTreeMap<Action, Vec<String>>,
+    ) -> Result<Self, String> {
+        let mut bindings = self.clone();
+        bindings.bindings.extend(
+            overrides
+                .iter()
+                .map(|(action, keys)| (*action, keys.clone())),
+        );
+
+        let mut seen: Vec<(KeyChord, Action)> = Vec::new();
+        for (action, keys) in &bindings.bindings {
+            for key in keys {
+                let chord: KeyChord = key.parse()?;
+                if let Some((_, other)) = seen.iter().find(|(seen, _)| *seen == chord) {
+                    return Err(format!(
+                        "{} is bound to both {} and {}",
+                        chord,
+                        other.name(),
+                        action.name()
+                    ));
+                }
+                seen.push((chord, *action));
+            }
+        }
+        Ok(bindings)
+    }
+
+    /// Find the action bound to a key chord
+    pub fn action_for_chord(&self, chord: &KeyChord) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(_, keys)| {
+                keys.iter()
+                    .any(|key| key.parse::<KeyChord>().as_ref() == Ok(chord))
+            })
+            .map(|(action, _)| *action)
+    }
+
+    /// Get the keys bound to an action
+    pub fn keys_for(&self, action: Action) -> &[String] {
+        self.bindings.get(&action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Find the action bound to a key
+    pub fn action_for(&self, key: &str) -> Option<Action> {
+        let key = normalize_key(key);
+        self.bindings
+            .iter()
+            .find(|(_, keys)| keys.iter().any(|k| normalize_key(k) == key))
+            .map(|(action, _)| *action)
+    }
+
+    /// Get the menu accelerator of an action (its first binding with a modifier)
+    pub fn accelerator(&self, action: Action) -> Option<&str> {
+        self.keys_for(action)
+            .iter()
+            .find(|key| key.contains('+') && key.len() > 1)
+            .map(String::as_str)
+    }
+
+    /// Replace the keys of an action.
+    /// Fails if a key is already bound to another action.
+    pub fn set(&mut self, action: Action, keys: Vec<String>) -> Result<(), String> {
+        for key in &keys {
+            if key.trim().is_empty() {
+                return Err("Key must not be empty".to_string());
+            }
+            if let Some(other) = self.action_for(key).filter(|other| *other != action) {
+                return Err(format!("{} is already bound to {}", key, other.name()));
+            }
+        }
+
+        self.bindings.insert(action, keys);
+        Ok(())
+    }
+}
+
+/// Normalize a key for comparison: modifiers in a fixed order, single characters lowercased
+fn normalize_key(key: &str) -> String {
+    let mut parts: Vec<&str> = key.split('+').collect();
+    // A trailing empty part means the key itself is "+"
+    let base = match parts.pop() {
+        Some("") => "+".to_string(),
+        Some(base) if base.chars().count() == 1 => base.to_lowercase(),
+        Some(base) => base.to_string(),
+        None => String::new(),
+    };
+
+    let mut modifiers: Vec<String> = parts
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .map(|part| match part.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" | "cmd" | "command" | "super" | "meta" | "cmdorctrl" => {
+                "CmdOrCtrl".to_string()
+            }
+            other => other.to_string(),
+        })
+        .collect();
+    modifiers.sort();
+    modifiers.dedup();
+    modifiers.push(base);
+    modifiers.join("+")
+}
+
+/// A key of a [`KeyChord`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+    /// A character; Shift is part of the character typed
+    Char(char),
+    Up,
+    Down,
+    Left,
+    Right,
+    Enter,
+    Esc,
+    Tab,
+    Backspace,
+    Delete,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    /// A function key, `F1` to `F12`
+    F(u8),
+}
+
+/// A key with the modifiers held with it.
+///
+/// Written as a key name (a character, `Up` or `ArrowUp`, `Enter`, `Space`,
+/// `Esc`, `Tab`, `Backspace`, `F1`...) optionally prefixed with `Ctrl+`
+/// (or `CmdOrCtrl+`) and `Alt+`. Letters match either case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub key: Key,
+    /// Ctrl, or Cmd on macOS
+    pub ctrl: bool,
+    pub alt: bool,
+}
+
+impl KeyChord {
+    /// Create a chord; letters are lowercased so that they match either case
+    pub fn new(key: Key, ctrl: bool, alt: bool) -> Self {
+        let key = match key {
+            Key::Char(c) => Key::Char(c.to_ascii_lowercase()),
+            key => key,
+        };
+        Self { key, ctrl, alt }
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = String;
+
+    fn from_str(chord: &str) -> Result<Self, String> {
+        // The last part is the key, which may itself be "+"
+        let (prefix, key) = match chord.strip_suffix("++") {
+            Some(prefix) => (Some(prefix), "+"),
+            None => match chord.rsplit_once('+') {
+                Some((prefix, key)) if !key.is_empty() => (Some(prefix), key),
+                _ => (None, chord),
+            },
+        };
+
+        let (mut ctrl, mut alt) = (false, false);
+        for modifier in prefix.into_iter().flat_map(|prefix| prefix.split('+')) {
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" | "cmd" | "command" | "cmdorctrl" => ctrl = true,
+                "alt" => alt = true,
+                _ => return Err(format!("Unknown modifier in key {}", chord)),
+            }
+        }
+
+        let mut chars = key.chars();
+        let key = match (chars.next(), chars.next()) {
+            (Some(c), None) => Key::Char(c),
+            _ => match key.to_ascii_lowercase().as_str() {
+                "up" | "arrowup" => Key::Up,
+                "down" | "arrowdown" => Key::Down,
+                "left" | "arrowleft" => Key::Left,
+                "right" | "arrowright" => Key::Right,
+                "enter" | "return" => Key::Enter,
+                "space" => Key::Char(' '),
+                "esc" | "escape" => Key::Esc,
+                "tab" => Key::Tab,
+                "backspace" => Key::Backspace,
+                "delete" => Key::Delete,
+                "home" => Key::Home,
+                "end" => Key::End,
+                "pageup" => Key::PageUp,
+                "pagedown" => Key::PageDown,
+                "comma" => Key::Char(','),
+                "plus" => Key::Char('+'),
+                name => match name.strip_prefix('f').map(str::parse) {
+                    Some(Ok(n)) if (1..=12).contains(&n) => Key::F(n),
+                    _ => return Err(format!("Unknown key: {}", chord)),
+                },
+            },
+        };
+        Ok(Self::new(key, ctrl, alt))
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            f.write_str("Ctrl+")?;
+        }
+        if self.alt {
+            f.write_str("Alt+")?;
+        }
+        match self.key {
+            Key::Char(' ') => f.write_str("Space"),
+            Key::Char(c) => write!(f, "{}", c),
+            Key::F(n) => write!(f, "F{}", n),
+            key => write!(f, "{:?}", key),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(text: &str) -> KeyChord {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_chords() {
+        assert_eq!(chord("q"), KeyChord::new(Key::Char('q'), false, false));
+        assert_eq!(chord("Q"), chord("q"));
+        assert_eq!(chord("Up"), KeyChord::new(Key::Up, false, false));
+        assert_eq!(chord("ArrowUp"), chord("up"));
+        assert_eq!(chord("Space"), KeyChord::new(Key::Char(' '), false, false));
+        assert_eq!(chord("comma"), chord(","));
+        assert_eq!(chord("F12"), KeyChord::new(Key::F(12), false, false));
+        assert_eq!(chord("Ctrl+z"), KeyChord::new(Key::Char('z'), true, false));
+        assert_eq!(chord("CmdOrCtrl+Z"), chord("ctrl+z"));
+        assert_eq!(chord("Alt+Ctrl+Left"), KeyChord::new(Key::Left, true, true));
+    }
+
+    #[test]
+    fn test_parse_plus() {
+        assert_eq!(chord("+"), KeyChord::new(Key::Char('+'), false, false));
+        assert_eq!(chord("Ctrl++"), KeyChord::new(Key::Char('+'), true, false));
+        assert_eq!(chord("plus"), chord("+"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!("Hyper+x".parse::<KeyChord>().is_err());
+        assert!("F13".parse::<KeyChord>().is_err());
+        assert!("Nope".parse::<KeyChord>().is_err());
+        assert!("".parse::<KeyChord>().is_err());
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        for text in ["Ctrl+Alt+x", "Space", "F5", "PageUp", "Esc", "Ctrl++"] {
+            assert_eq!(chord(text).to_string(), text);
+            assert_eq!(chord(&chord(text).to_string()), chord(text));
+        }
+    }
+
+    #[test]
+    fn test_overrides() {
+        let defaults =
+            KeyBindings::from_defaults(&[(Action::MoveUp, &["Up", "w"]), (Action::Quit, &["q"])]);
+        let overrides = BTreeMap::from([(Action::MoveUp, vec!["ArrowUp".to_string()])]);
+        let bindings = defaults.with_overrides(&overrides).unwrap();
+        assert_eq!(
+            bindings.action_for_chord(&chord("up")),
+            Some(Action::MoveUp)
+        );
+        assert_eq!(bindings.action_for_chord(&chord("w")), None);
+        assert_eq!(bindings.action_for_chord(&chord("Q")), Some(Action::Quit));
+
+        let overrides = BTreeMap::from([(Action::MoveUp, vec!["q".to_string()])]);
+        assert_eq!(
+            defaults.with_overrides(&overrides).unwrap_err(),
+            "q is bound to both move_up and quit"
+        );
+        let overrides = BTreeMap::from([(Action::MoveUp, vec!["Hyper+q".to_string()])]);
+        assert!(defaults.with_overrides(&overrides).is_err());
+    }
+}
@@ -5,8 +5,18 @@
 
 use serde::{Deserialize, Serialize};
 
+pub mod achievements;
+pub mod config_store;
+pub mod export;
 pub mod i18n;
+pub mod keybindings;
+pub mod render;
+pub mod theme_loader;
+pub use achievements::{Achievement, AchievementCondition, AchievementProgress};
+pub use config_store::{ConfigStore, UserSettings};
 pub use i18n::{I18n, Language, TranslationKey};
+pub use keybindings::{Action, Key, KeyBindings, KeyChord};
+pub use theme_loader::{ThemeError, ThemeLoader};
 
 /// Color theme for the game
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,6 +64,11 @@ impl Default for Theme {
 }
 
 impl Theme {
+    /// Index into `tile_colors` for a tile value, see [`tile_color_index`]
+    pub fn tile_color_index(&self, value: u64) -> usize {
+        tile_color_index(self.tile_colors.len(), value)
+    }
+
     /// Create a dark theme
     pub fn dark() -> Self {
         Self {
@@ -187,6 +202,17 @@ impl Theme {
     }
 }
 
+/// Index into a tile palette of `palette_len` colors for a tile value.
+/// Index 0 is the empty cell; tiles beyond the palette cycle through the
+/// tile colors again, so the huge tiles of big boards stay distinguishable.
+pub fn tile_color_index(palette_len: usize, value: u64) -> usize {
+    if value == 0 || palette_len < 2 {
+        return 0;
+    }
+    let exponent = value.trailing_zeros() as usize;
+    exponent.saturating_sub(1) % (palette_len - 1) + 1
+}
+
 /// Game configuration that can be shared across platforms
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SharedConfig {
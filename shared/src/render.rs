@@ -0,0 +1,228 @@
+//! Software rendering of boards into RGBA images, used for share cards and
+//! replay frames on platforms without a canvas.
+
+use crate::Theme;
+
+/// Size of a tile in pixels
+pub const CELL_SIZE: u32 = 80;
+/// Gap between tiles in pixels
+pub const GAP: u32 = 10;
+/// Margin around the grid in pixels
+pub const MARGIN: u32 = 20;
+/// Height of the caption area above the grid in pixels
+pub const HEADER_HEIGHT: u32 = 50;
+
+const GLYPH_WIDTH: u32 = 5;
+const GLYPH_HEIGHT: u32 = 7;
+const LIGHT_TEXT: [u8; 3] = [0xf9, 0xf6, 0xf2];
+
+/// An RGBA image
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    /// Row-major RGBA pixels
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    /// Create an image filled with one color
+    pub fn new(width: u32, height: u32, color: [u8; 3]) -> Self {
+        let pixels = [color[0], color[1], color[2], 0xff]
+            .into_iter()
+            .cycle()
+            .take((width * height * 4) as usize)
+            .collect();
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    fn set_pixel(&mut self, x: u32, y: u32, color: [u8; 3]) {
+        if x < self.width && y < self.height {
+            let index = ((y * self.width + x) * 4) as usize;
+            self.pixels[index..index + 3].copy_from_slice(&color);
+        }
+    }
+
+    /// Fill a rectangle with rounded corners
+    pub fn fill_rounded_rect(
+        &mut self,
+        x: u32,
+        y: u32,
+        w: u32,
+        h: u32,
+        radius: u32,
+        color: [u8; 3],
+    ) {
+        let radius = radius.min(w / 2).min(h / 2) as i64;
+        for dy in 0..h {
+            for dx in 0..w {
+                // Distance into the corner square, if the pixel is in one
+                let cx = (radius - dx as i64)
+                    .max(dx as i64 - (w as i64 - 1 - radius))
+                    .max(0);
+                let cy = (radius - dy as i64)
+                    .max(dy as i64 - (h as i64 - 1 - radius))
+                    .max(0);
+                if cx * cx + cy * cy <= radius * radius {
+                    self.set_pixel(x + dx, y + dy, color);
+                }
+            }
+        }
+    }
+
+    /// Draw text with the built-in pixel font; `scale` is the size of one font pixel
+    pub fn draw_text(&mut self, text: &str, x: u32, y: u32, scale: u32, color: [u8; 3]) {
+        for (i, c) in text.chars().enumerate() {
+            let left = x + i as u32 * (GLYPH_WIDTH + 1) * scale;
+            for (row, bits) in glyph(c).iter().enumerate() {
+                for col in 0..GLYPH_WIDTH {
+                    if bits & (1 << (GLYPH_WIDTH - 1 - col)) != 0 {
+                        for sy in 0..scale {
+                            for sx in 0..scale {
+                                self.set_pixel(
+                                    left + col * scale + sx,
+                                    y + row as u32 * scale + sy,
+                                    color,
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Width in pixels of text drawn with `draw_text`
+pub fn text_width(text: &str, scale: u32) -> u32 {
+    let len = text.chars().count() as u32;
+    (len * (GLYPH_WIDTH + 1)).saturating_sub(1) * scale
+}
+
+/// Parse `#rgb` or `#rrggbb`
+pub fn parse_hex_color(color: &str) -> Option<[u8; 3]> {
+    let hex = color.strip_prefix('#')?;
+    let channel = |s: &str| u8::from_str_radix(s, 16).ok();
+    match hex.len() {
+        6 => Some([
+            channel(&hex[0..2])?,
+            channel(&hex[2..4])?,
+            channel(&hex[4..6])?,
+        ]),
+        3 => {
+            let mut rgb = [0; 3];
+            for (i, c) in hex.chars().enumerate() {
+                rgb[i] = channel(&c.to_string())? * 17;
+            }
+            Some(rgb)
+        }
+        _ => None,
+    }
+}
+
+/// Pick the theme color for a tile value
+pub fn tile_color(theme: &Theme, value: u64) -> [u8; 3] {
+    theme
+        .tile_colors
+        .get(theme.tile_color_index(value))
+        .and_then(|color| parse_hex_color(color))
+        .unwrap_or([0xcd, 0xc1, 0xb4])
+}
+
+/// Color for the number on a tile: light text on dark tiles, theme text on
+/// light ones
+pub fn label_color(theme: &Theme, tile_color: [u8; 3]) -> [u8; 3] {
+    let luminance =
+        0.299 * tile_color[0] as f64 + 0.587 * tile_color[1] as f64 + 0.114 * tile_color[2] as f64;
+    if luminance > 160.0 {
+        parse_hex_color(&theme.text_color).unwrap_or([0x77, 0x6e, 0x65])
+    } else {
+        LIGHT_TEXT
+    }
+}
+
+/// Render a board with a caption line (e.g. "SCORE 2048") above it
+pub fn render_board(board: &[Vec<u64>], caption: &str, theme: &Theme) -> Image {
+    let size = board.len() as u32;
+    let grid = size * CELL_SIZE + (size + 1) * GAP;
+    let width = grid + 2 * MARGIN;
+    let height = HEADER_HEIGHT + grid + 2 * MARGIN;
+
+    let background = parse_hex_color(&theme.background).unwrap_or([0xfa, 0xf8, 0xef]);
+    let text_color = parse_hex_color(&theme.text_color).unwrap_or([0x77, 0x6e, 0x65]);
+    let mut image = Image::new(width, height, background);
+
+    let caption = caption.to_uppercase();
+    let caption_scale = ((width - 2 * MARGIN) / text_width(&caption, 1).max(1)).clamp(1, 4);
+    image.draw_text(
+        &caption,
+        (width.saturating_sub(text_width(&caption, caption_scale))) / 2,
+        MARGIN,
+        caption_scale,
+        parse_hex_color(&theme.title_color).unwrap_or(text_color),
+    );
+
+    let top = MARGIN + HEADER_HEIGHT;
+    let grid_color = parse_hex_color(&theme.grid_background).unwrap_or([0xbb, 0xad, 0xa0]);
+    image.fill_rounded_rect(MARGIN, top, grid, grid, GAP, grid_color);
+
+    for (row, cells) in board.iter().enumerate() {
+        for (col, &value) in cells.iter().enumerate() {
+            let x = MARGIN + GAP + col as u32 * (CELL_SIZE + GAP);
+            let y = top + GAP + row as u32 * (CELL_SIZE + GAP);
+            let color = tile_color(theme, value);
+            image.fill_rounded_rect(x, y, CELL_SIZE, CELL_SIZE, GAP / 2, color);
+
+            if value != 0 {
+                let label = value.to_string();
+                let scale = (CELL_SIZE * 8 / 10 / text_width(&label, 1)).clamp(1, 6);
+                image.draw_text(
+                    &label,
+                    x + (CELL_SIZE - text_width(&label, scale)) / 2,
+                    y + (CELL_SIZE - GLYPH_HEIGHT * scale) / 2,
+                    scale,
+                    label_color(theme, color),
+                );
+            }
+        }
+    }
+
+    image
+}
+
+/// 5x7 glyphs for digits and the letters used in captions; other characters are blank
+fn glyph(c: char) -> [u8; 7] {
+    match c {
+        '0' => [0x0e, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0e],
+        '1' => [0x04, 0x0c, 0x04, 0x04, 0x04, 0x04, 0x0e],
+        '2' => [0x0e, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1f],
+        '3' => [0x1f, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0e],
+        '4' => [0x02, 0x06, 0x0a, 0x12, 0x1f, 0x02, 0x02],
+        '5' => [0x1f, 0x10, 0x1e, 0x01, 0x01, 0x11, 0x0e],
+        '6' => [0x06, 0x08, 0x10, 0x1e, 0x11, 0x11, 0x0e],
+        '7' => [0x1f, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0e, 0x11, 0x11, 0x0e, 0x11, 0x11, 0x0e],
+        '9' => [0x0e, 0x11, 0x11, 0x0f, 0x01, 0x02, 0x0c],
+        'A' => [0x0e, 0x11, 0x11, 0x1f, 0x11, 0x11, 0x11],
+        'B' => [0x1e, 0x11, 0x11, 0x1e, 0x11, 0x11, 0x1e],
+        'C' => [0x0e, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0e],
+        'E' => [0x1f, 0x10, 0x10, 0x1e, 0x10, 0x10, 0x1f],
+        'I' => [0x0e, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0e],
+        'L' => [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1f],
+        'M' => [0x11, 0x1b, 0x15, 0x15, 0x11, 0x11, 0x11],
+        'N' => [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+        'O' => [0x0e, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0e],
+        'R' => [0x1e, 0x11, 0x11, 0x1e, 0x14, 0x12, 0x11],
+        'S' => [0x0f, 0x10, 0x10, 0x0e, 0x01, 0x01, 0x1e],
+        'T' => [0x1f, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        'V' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x0a, 0x04],
+        'W' => [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0a],
+        'X' => [0x11, 0x11, 0x0a, 0x04, 0x0a, 0x11, 0x11],
+        ':' => [0x00, 0x0c, 0x0c, 0x00, 0x0c, 0x0c, 0x00],
+        _ => [0; 7],
+    }
+}
@@ -0,0 +1,178 @@
+use crate::Theme;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Minimum number of tile colors (empty cell plus 2 through 2048)
+pub const MIN_TILE_COLORS: usize = 12;
+
+/// Errors raised while loading a theme file
+#[derive(Debug)]
+pub enum ThemeError {
+    /// The file could not be read or written
+    Io(std::io::Error),
+    /// The file is not a valid theme document
+    Parse(String),
+    /// The theme parsed but failed validation
+    Invalid(String),
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThemeError::Io(e) => write!(f, "Theme file error: {}", e),
+            ThemeError::Parse(e) => write!(f, "Invalid theme file: {}", e),
+            ThemeError::Invalid(e) => write!(f, "Invalid theme: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ThemeError {}
+
+impl From<std::io::Error> for ThemeError {
+    fn from(e: std::io::Error) -> Self {
+        ThemeError::Io(e)
+    }
+}
+
+/// Check that a string is a `#rgb` or `#rrggbb` color
+fn is_hex_color(color: &str) -> bool {
+    match color.strip_prefix('#') {
+        Some(hex) => matches!(hex.len(), 3 | 6) && hex.chars().all(|c| c.is_ascii_hexdigit()),
+        None => false,
+    }
+}
+
+impl Theme {
+    /// Validate a theme's name and colors
+    pub fn validate(&self) -> Result<(), ThemeError> {
+        if self.name.trim().is_empty() {
+            return Err(ThemeError::Invalid("name must not be empty".to_string()));
+        }
+        if self.tile_colors.len() < MIN_TILE_COLORS {
+            return Err(ThemeError::Invalid(format!(
+                "expected at least {} tile colors, found {}",
+                MIN_TILE_COLORS,
+                self.tile_colors.len()
+            )));
+        }
+
+        let named_colors = [
+            ("background", &self.background),
+            ("grid_background", &self.grid_background),
+            ("text_color", &self.text_color),
+            ("title_color", &self.title_color),
+            ("score_color", &self.score_color),
+            ("best_score_color", &self.best_score_color),
+            ("moves_color", &self.moves_color),
+            ("time_color", &self.time_color),
+        ];
+        for (field, color) in named_colors {
+            if !is_hex_color(color) {
+                return Err(ThemeError::Invalid(format!(
+                    "{} is not a hex color: {}",
+                    field, color
+                )));
+            }
+        }
+        if let Some(color) = self.tile_colors.iter().find(|c| !is_hex_color(c)) {
+            return Err(ThemeError::Invalid(format!(
+                "tile color is not a hex color: {}",
+                color
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Parse and validate a theme from JSON
+    pub fn from_json(json: &str) -> Result<Self, ThemeError> {
+        let theme: Theme =
+            serde_json::from_str(json).map_err(|e| ThemeError::Parse(e.to_string()))?;
+        theme.validate()?;
+        Ok(theme)
+    }
+
+    /// Load and validate a theme file
+    pub fn load_file(path: &Path) -> Result<Self, ThemeError> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+}
+
+/// Directory of user-defined theme files (one JSON file per theme)
+#[derive(Debug, Clone)]
+pub struct ThemeLoader {
+    dir: PathBuf,
+}
+
+impl ThemeLoader {
+    /// Create a loader for a themes directory
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Get the themes directory
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Load all valid themes in the directory, skipping invalid files
+    pub fn load_all(&self) -> Vec<Theme> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+
+        let mut themes: Vec<Theme> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| Theme::load_file(&path).ok())
+            .collect();
+        themes.sort_by(|a, b| a.name.cmp(&b.name));
+        themes
+    }
+
+    /// Validate a theme and save it to the directory (replacing a theme with the same name)
+    pub fn save(&self, theme: &Theme) -> Result<PathBuf, ThemeError> {
+        theme.validate()?;
+        if Theme::all_themes().iter().any(|t| t.name == theme.name) {
+            return Err(ThemeError::Invalid(format!(
+                "{} is a built-in theme",
+                theme.name
+            )));
+        }
+
+        fs::create_dir_all(&self.dir)?;
+        let path = self.path_for(&theme.name);
+        let json =
+            serde_json::to_string_pretty(theme).map_err(|e| ThemeError::Parse(e.to_string()))?;
+        fs::write(&path, json)?;
+        Ok(path)
+    }
+
+    /// Delete a custom theme by name
+    pub fn delete(&self, name: &str) -> Result<(), ThemeError> {
+        fs::remove_file(self.path_for(name))?;
+        Ok(())
+    }
+
+    /// Find a custom theme by name
+    pub fn by_name(&self, name: &str) -> Option<Theme> {
+        Theme::load_file(&self.path_for(name)).ok()
+    }
+
+    /// File path for a theme name (unsafe characters are replaced)
+    fn path_for(&self, name: &str) -> PathBuf {
+        let file_name: String = name
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.dir.join(format!("{}.json", file_name))
+    }
+}
@@ -76,6 +76,23 @@ build_web() {
     echo "   Or use: npm run preview"
 }
 
+# Node.js build function (headless engine for bots, tests and server-side verification)
+build_node() {
+    print_status "Building Node.js module..."
+
+    # Check if wasm-pack is installed
+    if ! command_exists wasm-pack; then
+        print_warning "wasm-pack is not installed. Installing..."
+        cargo install wasm-pack
+    fi
+
+    wasm-pack build --target nodejs --out-dir pkg-node
+
+    print_success "Node.js module built successfully!"
+    echo "   Files location: pkg-node/"
+    echo "   Usage: const { Rusty2048Web } = require('./pkg-node/rusty2048_web.js')"
+}
+
 # Development server function
 dev_server() {
     print_status "Starting development server..."
@@ -118,11 +135,13 @@ show_usage() {
     echo "Options:"
     echo "  build     Build production version"
     echo "  dev       Start development server"
+    echo "  node      Build headless Node.js module"
     echo "  help      Show this help message"
     echo ""
     echo "Examples:"
     echo "  $0 build   # Build production version"
     echo "  $0 dev     # Start development server"
+    echo "  $0 node    # Build Node.js module"
 }
 
 # Main script
@@ -145,6 +164,9 @@ main() {
         "dev")
             dev_server
             ;;
+        "node")
+            build_node
+            ;;
         "help"|"-h"|"--help")
             show_usage
             ;;
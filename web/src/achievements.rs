@@ -0,0 +1,84 @@
+use crate::Rusty2048Web;
+use rusty2048_engine::EngineEvent;
+use rusty2048_shared::Achievement;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+/// Achievement as presented to the UI, localized to the current language
+#[derive(Serialize)]
+struct AchievementView {
+    id: &'static str,
+    icon: &'static str,
+    title: &'static str,
+    description: &'static str,
+    unlocked: bool,
+    unlocked_at: Option<u64>,
+}
+
+#[wasm_bindgen]
+impl Rusty2048Web {
+    /// Get the achievement catalog with unlocked flags
+    pub fn get_achievements(&self) -> Result<JsValue, JsValue> {
+        let views: Vec<AchievementView> = Achievement::all()
+            .iter()
+            .map(|achievement| self.achievement_view(achievement))
+            .collect();
+        serde_wasm_bindgen::to_value(&views).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Register a callback invoked with each newly unlocked achievement
+    pub fn set_achievement_callback(&mut self, callback: js_sys::Function) {
+        self.achievement_callback = Some(callback);
+    }
+
+    /// Restore achievement progress previously exported with `export_achievements`
+    pub fn load_achievements(&mut self, json: &str) -> Result<(), JsValue> {
+        *self.engine.achievements_mut() = serde_json::from_str(json)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse achievements: {}", e)))?;
+        Ok(())
+    }
+
+    /// Export achievement progress as JSON (for localStorage persistence)
+    pub fn export_achievements(&self) -> Result<String, JsValue> {
+        serde_json::to_string(self.engine.achievements())
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize achievements: {}", e)))
+    }
+}
+
+impl Rusty2048Web {
+    /// Notify the callback of the achievements the engine unlocked since
+    /// the last call
+    pub(crate) fn notify_achievements(&mut self) -> Result<(), JsValue> {
+        let unlocked: Vec<&'static Achievement> = self
+            .engine
+            .take_events()
+            .into_iter()
+            .filter_map(|event| match event {
+                EngineEvent::AchievementUnlocked { id } => Achievement::by_id(id),
+                _ => None,
+            })
+            .collect();
+
+        if let Some(callback) = &self.achievement_callback {
+            for achievement in unlocked {
+                let view = serde_wasm_bindgen::to_value(&self.achievement_view(achievement))
+                    .map_err(|e| JsValue::from_str(&e.to_string()))?;
+                callback.call1(&JsValue::NULL, &view)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn achievement_view(&self, achievement: &'static Achievement) -> AchievementView {
+        let language = self.engine.i18n().current_language();
+        AchievementView {
+            id: achievement.id,
+            icon: achievement.icon,
+            title: achievement.title(language),
+            description: achievement.description(language),
+            unlocked: self.engine.achievements().is_unlocked(achievement.id),
+            unlocked_at: self.engine.achievements().unlocked_at(achievement.id),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use crate::Rusty2048Web;
+use rusty2048_core::{AIAlgorithm, Direction, ExpectimaxStrategy, IncrementalSearch, SearchStatus};
+use rusty2048_engine::Input;
+use serde::Serialize;
+use std::time::Duration;
+use wasm_bindgen::prelude::*;
+
+/// Where a stepped AI search stands, for JS
+#[derive(Serialize)]
+struct AiSearchView {
+    done: bool,
+    depth: usize,
+    nodes: f64,
+    /// Best move so far, the move to play once done
+    best: Option<&'static str>,
+}
+
+#[wasm_bindgen]
+impl Rusty2048Web {
+    /// Get the move suggested by the AI ("greedy", "expectimax", "mcts",
+    /// "minimax" or "ntuple")
+    pub fn get_ai_move(&self, algorithm: &str) -> Result<String, JsValue> {
+        let direction = self
+            .engine
+            .game()
+            .hint(parse_algorithm(algorithm)?)
+            .map_err(crate::game_error)?;
+        Ok(direction_name(direction).to_string())
+    }
+
+    /// Let the AI make one move; returns whether the board changed
+    pub fn make_ai_move(&mut self, algorithm: &str) -> Result<bool, JsValue> {
+        let direction = self
+            .engine
+            .game()
+            .hint(parse_algorithm(algorithm)?)
+            .map_err(crate::game_error)?;
+        self.handle_input(Input::AiMove(direction))
+    }
+
+    /// Start an Expectimax search of the next move, `max_depth` plies
+    /// deep, to run with `step_ai_search` a slice at a time (e.g. once per
+    /// animation frame or worker tick) instead of blocking the page
+    pub fn start_ai_search(&mut self, max_depth: usize) {
+        let strategy = ExpectimaxStrategy::default().with_max_depth(max_depth);
+        self.ai_search = Some(IncrementalSearch::new(self.engine.game(), strategy));
+    }
+
+    /// Search for about `budget_ms` milliseconds; returns
+    /// `{done, depth, nodes, best}`. A search started before the game
+    /// changed restarts for the current position.
+    pub fn step_ai_search(&mut self, budget_ms: f64) -> Result<JsValue, JsValue> {
+        let search = self
+            .ai_search
+            .take()
+            .ok_or_else(|| JsValue::from_str("No AI search was started"))?;
+        let game = self.engine.game();
+        let mut search = if search.game().moves() == game.moves()
+            && search.game().board().as_flat_slice() == game.board().as_flat_slice()
+        {
+            search
+        } else {
+            IncrementalSearch::new(game, search.into_strategy())
+        };
+
+        let status = search.step(Duration::from_millis(budget_ms.max(0.0) as u64));
+        let progress = search.progress();
+        let view = AiSearchView {
+            done: matches!(status, SearchStatus::Done(_)),
+            depth: progress.depth,
+            nodes: progress.nodes as f64,
+            best: progress.best.map(direction_name),
+        };
+        self.ai_search = Some(search);
+        serde_wasm_bindgen::to_value(&view).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Play the move of a finished `step_ai_search` and start searching
+    /// the next one; returns whether the board changed
+    pub fn play_ai_search_move(&mut self) -> Result<bool, JsValue> {
+        let search = match self.ai_search.take() {
+            Some(search) if search.is_done() => search,
+            search => {
+                self.ai_search = search;
+                return Err(JsValue::from_str("The AI search has not finished"));
+            }
+        };
+        let moved = match search.best() {
+            Some(direction) => self.handle_input(Input::AiMove(direction))?,
+            None => false,
+        };
+        self.ai_search = Some(IncrementalSearch::new(
+            self.engine.game(),
+            search.into_strategy(),
+        ));
+        Ok(moved)
+    }
+}
+
+/// Parse an AI algorithm name coming from JS
+fn parse_algorithm(algorithm: &str) -> Result<AIAlgorithm, JsValue> {
+    match algorithm {
+        "greedy" => Ok(AIAlgorithm::Greedy),
+        "expectimax" => Ok(AIAlgorithm::Expectimax),
+        "mcts" => Ok(AIAlgorithm::MCTS),
+        "minimax" => Ok(AIAlgorithm::Minimax),
+        "ntuple" => Ok(AIAlgorithm::NTuple),
+        _ => Err(JsValue::from_str("Invalid AI algorithm")),
+    }
+}
+
+fn direction_name(direction: Direction) -> &'static str {
+    match direction {
+        Direction::Up => "up",
+        Direction::Down => "down",
+        Direction::Left => "left",
+        Direction::Right => "right",
+    }
+}
@@ -0,0 +1,181 @@
+use crate::Rusty2048Web;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+
+/// Length of the tile pop animation in milliseconds
+const ANIMATION_MS: f64 = 150.0;
+/// Height reserved above the grid for the score line
+const HEADER_HEIGHT: f64 = 48.0;
+/// Gap between tiles, relative to the grid size
+const GAP_RATIO: f64 = 0.03;
+
+/// Draws a game onto a `<canvas>` element.
+///
+/// Call `render` from a `requestAnimationFrame` loop; it returns `true`
+/// while an animation is still running.
+#[wasm_bindgen]
+pub struct CanvasRenderer {
+    canvas: HtmlCanvasElement,
+    context: CanvasRenderingContext2d,
+    /// Board drawn by the previous frame
+    previous_board: Vec<Vec<u64>>,
+    /// Cells whose value changed since the previous board
+    changed: Vec<(usize, usize)>,
+    /// When the current animation started (milliseconds)
+    animation_start: Option<f64>,
+}
+
+#[wasm_bindgen]
+impl CanvasRenderer {
+    /// Create a renderer for a canvas element
+    #[wasm_bindgen(constructor)]
+    pub fn new(canvas: HtmlCanvasElement) -> Result<CanvasRenderer, JsValue> {
+        let context = canvas
+            .get_context("2d")?
+            .ok_or_else(|| JsValue::from_str("Canvas 2D context is not available"))?
+            .dyn_into::<CanvasRenderingContext2d>()?;
+
+        Ok(CanvasRenderer {
+            canvas,
+            context,
+            previous_board: Vec::new(),
+            changed: Vec::new(),
+            animation_start: None,
+        })
+    }
+
+    /// Draw the current state of a game.
+    /// Returns `true` while an animation is in progress.
+    pub fn render(&mut self, game: &Rusty2048Web) -> Result<bool, JsValue> {
+        let now = js_sys::Date::now();
+        let board = game.engine.game().board().to_vec();
+
+        if board != self.previous_board {
+            self.changed = changed_cells(&self.previous_board, &board);
+            self.previous_board = board.clone();
+            self.animation_start = Some(now);
+        }
+
+        let progress = match self.animation_start {
+            Some(start) => ((now - start) / ANIMATION_MS).min(1.0),
+            None => 1.0,
+        };
+        if progress >= 1.0 {
+            self.animation_start = None;
+            self.changed.clear();
+        }
+
+        let theme = game.theme_palette();
+        let width = self.canvas.width() as f64;
+        let height = self.canvas.height() as f64;
+        let ctx = &self.context;
+
+        // Background and score line
+        ctx.set_fill_style_str(&theme.background);
+        ctx.fill_rect(0.0, 0.0, width, height);
+
+        ctx.set_fill_style_str(&theme.text_color);
+        ctx.set_font("bold 20px sans-serif");
+        ctx.set_text_align("left");
+        ctx.set_text_baseline("middle");
+        ctx.fill_text(
+            &format!("Score: {}", game.engine.game().score().current()),
+            0.0,
+            HEADER_HEIGHT / 2.0,
+        )?;
+        ctx.set_text_align("right");
+        ctx.fill_text(
+            &format!("Best: {}", game.engine.game().score().best()),
+            width,
+            HEADER_HEIGHT / 2.0,
+        )?;
+
+        // Grid
+        let size = board.len().max(1) as f64;
+        let grid = width.min(height - HEADER_HEIGHT).max(0.0);
+        let gap = grid * GAP_RATIO;
+        let cell = (grid - gap * (size + 1.0)) / size;
+        let left = (width - grid) / 2.0;
+        let top = HEADER_HEIGHT;
+
+        ctx.set_fill_style_str(&theme.grid_background);
+        fill_rounded(ctx, left, top, grid, grid, gap)?;
+
+        ctx.set_text_align("center");
+        for (row, cells) in board.iter().enumerate() {
+            for (col, &value) in cells.iter().enumerate() {
+                let x = left + gap + col as f64 * (cell + gap);
+                let y = top + gap + row as f64 * (cell + gap);
+
+                // Changed tiles pop in from a smaller size
+                let scale = if value != 0 && self.changed.contains(&(row, col)) {
+                    0.6 + 0.4 * ease_out(progress)
+                } else {
+                    1.0
+                };
+                let tile = cell * scale;
+                let offset = (cell - tile) / 2.0;
+
+                ctx.set_fill_style_str(tile_color(&theme.tile_colors, value));
+                fill_rounded(ctx, x + offset, y + offset, tile, tile, gap / 2.0)?;
+
+                if value != 0 {
+                    let digits = value.to_string().len().max(2) as f64;
+                    ctx.set_font(&format!("bold {}px sans-serif", tile * 1.1 / digits));
+                    ctx.set_fill_style_str(&theme.title_color);
+                    ctx.fill_text(&value.to_string(), x + cell / 2.0, y + cell / 2.0)?;
+                }
+            }
+        }
+
+        Ok(self.animation_start.is_some())
+    }
+
+    /// Forget the previous frame so the next render draws without animation
+    pub fn reset(&mut self) {
+        self.previous_board.clear();
+        self.changed.clear();
+        self.animation_start = None;
+    }
+}
+
+/// Cells that differ between two boards (all non-empty cells if the sizes differ)
+fn changed_cells(previous: &[Vec<u64>], current: &[Vec<u64>]) -> Vec<(usize, usize)> {
+    let mut changed = Vec::new();
+    for (row, cells) in current.iter().enumerate() {
+        for (col, &value) in cells.iter().enumerate() {
+            let before = previous.get(row).and_then(|r| r.get(col)).copied();
+            if before != Some(value) {
+                changed.push((row, col));
+            }
+        }
+    }
+    changed
+}
+
+/// Pick the theme color for a tile value
+fn tile_color(colors: &[String], value: u64) -> &str {
+    colors
+        .get(rusty2048_shared::tile_color_index(colors.len(), value))
+        .map(String::as_str)
+        .unwrap_or("#cdc1b4")
+}
+
+fn ease_out(t: f64) -> f64 {
+    1.0 - (1.0 - t).powi(3)
+}
+
+fn fill_rounded(
+    ctx: &CanvasRenderingContext2d,
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    radius: f64,
+) -> Result<(), JsValue> {
+    ctx.begin_path();
+    ctx.round_rect_with_f64(x, y, width, height, radius)?;
+    ctx.fill();
+    Ok(())
+}
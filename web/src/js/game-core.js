@@ -37,7 +37,7 @@ export class GameCore {
         const savedState = this.storage.loadGameState();
         if (savedState) {
             try {
-                // Convert Uint32Array to regular array if needed
+                // Convert Float64Array to regular array if needed
                 const boardArray = Array.isArray(savedState.board) ? savedState.board : Array.from(savedState.board);
                 
                 await this.game.load_game(
@@ -167,7 +167,7 @@ export class GameCore {
         const moves = this.game.get_moves();
         const state = await this.game.get_state();
 
-        // Convert Uint32Array to regular array for storage
+        // Convert Float64Array to regular array for storage
         const boardArray = Array.isArray(board) ? board : Array.from(board);
 
         const gameState = {
@@ -221,6 +221,7 @@ export class GameCore {
     async set_theme(themeName) { return await this.game.set_theme(themeName); }
     get_theme() { return this.game.get_theme(); }
     async undo() { return await this.game.undo(); }
+    async continue_after_win() { return await this.game.continue_after_win(); }
     async new_game() { return await this.game.new_game(); }
     async make_move(direction) { return await this.game.make_move(direction); }
 
@@ -155,6 +155,11 @@ export class EventManager {
             await this.game.updateDisplay();
         });
 
+        document.getElementById('keepGoing').addEventListener('click', async () => {
+            await this.game.continue_after_win();
+            await this.game.updateDisplay();
+        });
+
         document.getElementById('languageToggleMenu').addEventListener('click', async () => {
             await this.game.toggleLanguage();
             this.closeMenu();
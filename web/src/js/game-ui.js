@@ -80,11 +80,14 @@ export class UIManager {
 
             messageEl.style.display = 'none';
             messageEl.className = 'message';
+            const keepGoingBtn = document.getElementById('keepGoing');
+            if (keepGoingBtn) keepGoingBtn.style.display = 'none';
 
             if (state === 'won') {
                 messageEl.textContent = '🎉 Congratulations! You won!';
                 messageEl.classList.add('won');
                 messageEl.style.display = 'block';
+                if (keepGoingBtn) keepGoingBtn.style.display = 'inline-block';
                 this.animationManager.addAnimationClass(messageEl, 'win-animation');
             } else if (state === 'game_over') {
                 messageEl.textContent = '💀 Game Over!';
@@ -0,0 +1,167 @@
+use crate::stats::to_js_object;
+use crate::Rusty2048Web;
+use rusty2048_core::GameState;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use wasm_bindgen::prelude::*;
+
+/// Result payload sent to a leaderboard backend
+#[derive(Serialize)]
+struct ResultPayload {
+    player_name: String,
+    score: u64,
+    max_tile: u64,
+    moves: u32,
+    duration: u64,
+    board_size: usize,
+    seed: Option<u64>,
+    replay_hash: String,
+    version: String,
+    submitted_at: u64,
+    /// Keyed hash over all fields above, so a backend can reject tampered payloads
+    fingerprint: String,
+}
+
+/// A single entry of a leaderboard fetched from a backend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct LeaderboardEntry {
+    pub name: String,
+    pub score: u64,
+    #[serde(default)]
+    pub max_tile: u64,
+    #[serde(default)]
+    pub moves: u32,
+    #[serde(default)]
+    pub submitted_at: u64,
+}
+
+#[wasm_bindgen]
+impl Rusty2048Web {
+    /// Build a fingerprinted result payload for the current game.
+    ///
+    /// `secret` is an optional key shared with the backend; without it the
+    /// fingerprint only detects accidental corruption.
+    pub fn create_result_payload(
+        &self,
+        player_name: &str,
+        secret: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let stats = self.engine.game().stats();
+        let replay_hash = format!("{:016x}", fnv1a(self.export_replay_json(None)?.as_bytes()));
+
+        let mut payload = ResultPayload {
+            player_name: player_name.to_string(),
+            score: stats.score,
+            max_tile: self.engine.game().board().max_tile(),
+            moves: stats.moves,
+            duration: stats.duration,
+            board_size: self.engine.game().board().size(),
+            seed: self.engine.game().config().seed,
+            replay_hash,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            submitted_at: rusty2048_core::get_current_time(),
+            fingerprint: String::new(),
+        };
+
+        let signed = format!(
+            "{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}",
+            secret.unwrap_or_default(),
+            payload.player_name,
+            payload.score,
+            payload.max_tile,
+            payload.moves,
+            payload.duration,
+            payload.board_size,
+            payload.seed.map(|s| s.to_string()).unwrap_or_default(),
+            payload.replay_hash,
+            payload.version,
+            payload.submitted_at,
+        );
+        payload.fingerprint = format!("{:016x}", fnv1a(signed.as_bytes()));
+
+        serde_wasm_bindgen::to_value(&payload).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Load leaderboard JSON fetched from a backend (an array of entries)
+    pub fn set_leaderboard(&mut self, json: &str) -> Result<usize, JsValue> {
+        let mut entries: Vec<LeaderboardEntry> = serde_json::from_str(json)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse leaderboard: {}", e)))?;
+        entries.sort_by_key(|entry| Reverse(entry.score));
+
+        self.leaderboard = entries;
+        Ok(self.leaderboard.len())
+    }
+
+    /// Get the loaded leaderboard, sorted by score
+    pub fn get_leaderboard(&self) -> Result<JsValue, JsValue> {
+        serde_wasm_bindgen::to_value(&self.leaderboard)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Submit the finished game to the local leaderboard. `mode` defaults to
+    /// "daily" for daily challenges and "classic" otherwise. Returns the
+    /// 1-based rank, or `undefined` if the score did not make the list.
+    pub fn submit_local_score(
+        &mut self,
+        name: &str,
+        mode: Option<String>,
+    ) -> Result<Option<usize>, JsValue> {
+        if self.engine.game().state() == GameState::Playing {
+            return Err(JsValue::from_str("The game is not finished yet"));
+        }
+        if name.trim().is_empty() {
+            return Err(JsValue::from_str("Please enter a name"));
+        }
+
+        let mode = mode.unwrap_or_else(|| {
+            let mode = if self.engine.daily().is_some() {
+                "daily"
+            } else {
+                "classic"
+            };
+            mode.to_string()
+        });
+        let entry = rusty2048_core::LeaderboardEntry::for_game(name, self.engine.game(), &mode);
+        Ok(self.local_leaderboard.submit(entry))
+    }
+
+    /// Get the local leaderboard entries of a board size and mode, best first
+    pub fn get_local_leaderboard(&self, size: usize, mode: &str) -> Result<JsValue, JsValue> {
+        to_js_object(&self.local_leaderboard.top(size, mode))
+    }
+
+    /// Get the board sizes and modes on the local leaderboard as `[size, mode]` pairs
+    pub fn get_local_leaderboard_categories(&self) -> Result<JsValue, JsValue> {
+        to_js_object(&self.local_leaderboard.categories())
+    }
+
+    /// Restore a local leaderboard previously exported with `export_local_leaderboard`
+    pub fn load_local_leaderboard(&mut self, json: &str) -> Result<(), JsValue> {
+        self.local_leaderboard = serde_json::from_str(json)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse leaderboard: {}", e)))?;
+        Ok(())
+    }
+
+    /// Export the local leaderboard as JSON (for localStorage persistence)
+    pub fn export_local_leaderboard(&self) -> Result<String, JsValue> {
+        serde_json::to_string(&self.local_leaderboard)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Get the 1-based rank the current score would take on the loaded leaderboard
+    pub fn get_leaderboard_rank(&self) -> usize {
+        let score = self.engine.game().score().current();
+        self.leaderboard
+            .iter()
+            .take_while(|entry| entry.score >= score)
+            .count()
+            + 1
+    }
+}
+
+/// 64-bit FNV-1a hash (stable across platforms and releases)
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
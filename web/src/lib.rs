@@ -1,8 +1,27 @@
-use rusty2048_core::{Direction, Game, GameConfig, GameState};
-use rusty2048_shared::{I18n, Language, TranslationKey};
+use leaderboard::LeaderboardEntry;
+use rusty2048_core::{
+    Board, CallbackBestScoreStore, DailyChallenge, Direction, GameConfig, GameError, GameState,
+    IncrementalSearch, Leaderboard, ReplayPlayer,
+};
+use rusty2048_engine::{Engine, Input};
+use rusty2048_shared::{Language, TranslationKey};
 use serde::Serialize;
+use std::sync::Arc;
 use wasm_bindgen::prelude::*;
 
+mod achievements;
+mod ai;
+#[cfg(feature = "canvas")]
+mod canvas;
+mod leaderboard;
+mod race;
+mod replay;
+mod stats;
+
+#[cfg(feature = "canvas")]
+pub use canvas::CanvasRenderer;
+pub use race::RaceClient;
+
 // When the `wee_alloc` feature is enabled, use `wee_alloc` as the global
 // allocator.
 #[cfg(feature = "wee_alloc")]
@@ -11,9 +30,15 @@ static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
 #[wasm_bindgen]
 pub struct Rusty2048Web {
-    game: Game,
-    i18n: I18n,
+    /// The game with its statistics, replays and achievements
+    engine: Engine,
     current_theme: String,
+    replay_player: Option<ReplayPlayer>,
+    leaderboard: Vec<LeaderboardEntry>,
+    local_leaderboard: Leaderboard,
+    achievement_callback: Option<js_sys::Function>,
+    /// Search of the AI's next move, run in steps
+    ai_search: Option<IncrementalSearch>,
 }
 
 impl Default for Rusty2048Web {
@@ -26,48 +51,53 @@ impl Default for Rusty2048Web {
 impl Rusty2048Web {
     #[wasm_bindgen(constructor)]
     pub fn new() -> Self {
-        console_error_panic_hook::set_once();
+        Self::with_config(GameConfig::default())
+    }
 
-        let config = GameConfig::default();
-        let game = Game::new(config).expect("Failed to create game");
-        let mut i18n = I18n::new();
+    /// Create a game with a fixed seed (for bots and server-side verification)
+    pub fn new_seeded(seed: u64) -> Self {
+        Self::with_config(GameConfig {
+            seed: Some(seed),
+            ..GameConfig::default()
+        })
+    }
 
-        // Try to detect browser language
-        if let Some(window) = web_sys::window() {
-            let navigator = window.navigator();
-            if let Some(lang) = navigator.language() {
-                if let Some(language) = Language::from_code(&lang) {
-                    i18n.set_language(language);
-                }
-            }
+    /// Play the daily challenge of a UTC date (`YYYY-MM-DD`), today's by
+    /// default. Every frontend deals the same board for a date.
+    pub fn new_daily(date: Option<String>) -> Result<Rusty2048Web, JsValue> {
+        let challenge = match date {
+            Some(date) => DailyChallenge::for_date(&date),
+            None => Ok(DailyChallenge::today()),
         }
+        .map_err(crate::game_error)?;
 
-        Self {
-            game,
-            i18n,
-            current_theme: "Classic".to_string(),
-        }
+        let mut web = Self::with_config(challenge.config());
+        web.engine.new_daily(challenge).map_err(crate::game_error)?;
+        Ok(web)
+    }
+
+    /// Date of the daily challenge being played, if any
+    pub fn get_daily_date(&self) -> Option<String> {
+        self.engine.daily().map(|challenge| challenge.date.clone())
     }
 
     /// Get current language
     pub fn get_language(&self) -> String {
-        self.i18n.current_language().code().to_string()
+        self.engine.i18n().current_language().code().to_string()
     }
 
     /// Set language
     pub fn set_language(&mut self, language_code: &str) -> Result<(), JsValue> {
-        if let Some(language) = Language::from_code(language_code) {
-            self.i18n.set_language(language);
-            Ok(())
-        } else {
-            Err(JsValue::from_str("Invalid language code"))
-        }
+        self.engine
+            .set_language(language_code)
+            .map_err(|_| JsValue::from_str("Invalid language code"))
     }
 
     /// Get supported languages
     pub fn get_supported_languages(&self) -> JsValue {
         let languages: Vec<String> = self
-            .i18n
+            .engine
+            .i18n()
             .supported_languages()
             .iter()
             .map(|lang| lang.code().to_string())
@@ -79,19 +109,18 @@ impl Rusty2048Web {
     pub fn get_translation(&self, key: &str) -> String {
         let translation_key = TranslationKey::from_key(key).unwrap_or(TranslationKey::Help);
 
-        self.i18n.t(&translation_key)
+        self.engine.i18n().t(&translation_key)
     }
 
     pub fn new_game(&mut self) -> Result<(), JsValue> {
-        self.game
-            .new_game()
-            .map_err(|e| JsValue::from_str(&e.to_string()))
+        self.engine.new_game().map_err(crate::game_error)?;
+        Ok(())
     }
 
     /// Load game from saved state
     pub fn load_game(
         &mut self,
-        board_data: Vec<u32>,
+        board_data: Vec<f64>,
         score_data: JsValue,
         moves: u32,
         state: &str,
@@ -109,45 +138,49 @@ impl Rusty2048Web {
         };
 
         // Load the game state
-        self.game
-            .load_from_state(board_data, score, moves, game_state)
-            .map_err(|e| JsValue::from_str(&e.to_string()))
+        self.engine
+            .load_game(
+                board_data.into_iter().map(|value| value as u64).collect(),
+                score,
+                moves,
+                game_state,
+            )
+            .map_err(crate::game_error)?;
+        Ok(())
     }
 
+    /// Sessions are recorded automatically when a game ends, and updated
+    /// if it is undone or continued after the win and ends again
     pub fn make_move(&mut self, direction: &str) -> Result<bool, JsValue> {
-        let dir = match direction {
-            "up" => Direction::Up,
-            "down" => Direction::Down,
-            "left" => Direction::Left,
-            "right" => Direction::Right,
-            _ => return Err(JsValue::from_str("Invalid direction")),
-        };
+        self.handle_input(Input::Move(parse_direction(direction)?))
+    }
 
-        self.game
-            .make_move(dir)
-            .map_err(|e| JsValue::from_str(&e.to_string()))
+    /// Like `make_move`, but throws an error explaining why when the move
+    /// would change nothing (e.g. "no tiles can slide left")
+    pub fn try_move(&mut self, direction: &str) -> Result<(), JsValue> {
+        self.engine
+            .game()
+            .check_move(parse_direction(direction)?)
+            .map_err(crate::game_error)?;
+        self.make_move(direction).map(|_| ())
     }
 
-    pub fn get_board(&self) -> Vec<u32> {
-        let board = self.game.board();
-        let mut result = Vec::new();
-        for row in 0..board.size() {
-            for col in 0..board.size() {
-                if let Ok(tile) = board.get_tile(row, col) {
-                    result.push(tile.value);
-                }
-            }
-        }
-        result
+    /// Tile movements, merges and the spawned tile of the last move
+    pub fn get_last_move(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.engine.last_move()).unwrap()
+    }
+
+    pub fn get_board(&self) -> Vec<f64> {
+        board_to_js(self.engine.game().board())
     }
 
     pub fn get_score(&self) -> JsValue {
-        let score = self.game.score();
+        let score = self.engine.game().score();
         serde_wasm_bindgen::to_value(&score).unwrap()
     }
 
     pub fn get_state(&self) -> String {
-        match self.game.state() {
+        match self.engine.game().state() {
             GameState::Playing => "playing".to_string(),
             GameState::Won => "won".to_string(),
             GameState::GameOver => "game_over".to_string(),
@@ -155,13 +188,39 @@ impl Rusty2048Web {
     }
 
     pub fn get_moves(&self) -> u32 {
-        self.game.moves()
+        self.engine.game().moves()
+    }
+
+    /// Seed of the current game, as a string since it may not fit in a JS
+    /// number. Pass it to `new_seeded` to play the same tiles again.
+    pub fn get_seed(&self) -> Option<String> {
+        self.engine.game().seed().map(|seed| seed.to_string())
+    }
+
+    /// Get game statistics; the duration is play time in seconds, without
+    /// pauses. `score_breakdown` is a plain object keyed by merged tile value.
+    pub fn get_stats(&self) -> JsValue {
+        stats::to_js_object(&self.engine.game().stats()).unwrap()
+    }
+
+    /// Get elapsed play time in milliseconds, counted in whole seconds
+    pub fn get_elapsed_ms(&self) -> f64 {
+        self.engine.game().stats().duration as f64 * 1000.0
+    }
+
+    /// Restore elapsed play time after `load_game` (e.g. from localStorage)
+    pub fn set_elapsed_ms(&mut self, elapsed_ms: f64) {
+        self.engine
+            .set_elapsed((elapsed_ms.max(0.0) / 1000.0) as u64);
     }
 
     pub fn undo(&mut self) -> Result<(), JsValue> {
-        self.game
-            .undo()
-            .map_err(|e| JsValue::from_str(&e.to_string()))
+        self.handle_input(Input::Undo).map(|_| ())
+    }
+
+    /// Keep playing after reaching the target tile
+    pub fn continue_after_win(&mut self) -> Result<(), JsValue> {
+        self.handle_input(Input::ContinueAfterWin).map(|_| ())
     }
 
     pub fn set_theme(&mut self, theme_name: &str) -> Result<(), JsValue> {
@@ -172,17 +231,63 @@ impl Rusty2048Web {
 
     /// Get current theme information
     pub fn get_theme(&self) -> JsValue {
-        #[derive(Serialize)]
-        struct Theme {
-            background: String,
-            title_color: String,
-            text_color: String,
-            grid_background: String,
-            tile_colors: Vec<String>,
+        serde_wasm_bindgen::to_value(&self.theme_palette()).unwrap()
+    }
+}
+
+/// Colors of a web theme
+#[derive(Serialize)]
+pub(crate) struct Theme {
+    pub(crate) background: String,
+    pub(crate) title_color: String,
+    pub(crate) text_color: String,
+    pub(crate) grid_background: String,
+    pub(crate) tile_colors: Vec<String>,
+}
+
+impl Rusty2048Web {
+    /// Create the web game from a configuration.
+    /// Works without `window`/`navigator`, so the engine can run under Node.
+    fn with_config(config: GameConfig) -> Self {
+        console_error_panic_hook::set_once();
+
+        let mut engine = Engine::new(config).expect("Failed to create game");
+
+        // Try to detect browser language (there is no window under Node)
+        if let Some(window) = web_sys::window() {
+            let navigator = window.navigator();
+            if let Some(lang) = navigator.language() {
+                if let Some(language) = Language::from_code(&lang) {
+                    let _ = engine.set_language(language.code());
+                }
+            }
         }
 
+        let _ = engine.set_best_score_store(Arc::new(local_storage_best_score()));
+
+        Self {
+            engine,
+            current_theme: "Classic".to_string(),
+            replay_player: None,
+            leaderboard: Vec::new(),
+            local_leaderboard: Leaderboard::default(),
+            achievement_callback: None,
+            ai_search: None,
+        }
+    }
+
+    /// Apply an input to the engine, keeping the achievement callback in
+    /// step with it
+    pub(crate) fn handle_input(&mut self, input: Input) -> Result<bool, JsValue> {
+        let changed = self.engine.handle_input(input).map_err(crate::game_error)?;
+        self.notify_achievements()?;
+        Ok(changed)
+    }
+
+    /// Get the colors of the current theme
+    pub(crate) fn theme_palette(&self) -> Theme {
         // Get theme based on current theme name
-        let theme = match self.current_theme.as_str() {
+        match self.current_theme.as_str() {
             "Dark" => Theme {
                 background: "#1a1a1a".to_string(),
                 title_color: "#ffffff".to_string(),
@@ -284,12 +389,55 @@ impl Rusty2048Web {
                     "#edc22e".to_string(), // 2048
                 ],
             },
-        };
+        }
+    }
+}
 
-        serde_wasm_bindgen::to_value(&theme).unwrap()
+/// Parse a direction name coming from JS
+pub(crate) fn parse_direction(direction: &str) -> Result<Direction, JsValue> {
+    match direction {
+        "up" => Ok(Direction::Up),
+        "down" => Ok(Direction::Down),
+        "left" => Ok(Direction::Left),
+        "right" => Ok(Direction::Right),
+        _ => Err(JsValue::from_str("Invalid direction")),
     }
 }
 
+/// localStorage key of the best score
+const BEST_SCORE_KEY: &str = "rusty2048-best-score";
+
+/// Best score kept in the browser's localStorage; nothing is stored where
+/// there is no window (e.g. under Node)
+fn local_storage_best_score() -> CallbackBestScoreStore {
+    let storage = || web_sys::window()?.local_storage().ok()?;
+    CallbackBestScoreStore::new(
+        move || storage()?.get_item(BEST_SCORE_KEY).ok()??.parse().ok(),
+        move |best| {
+            if let Some(storage) = storage() {
+                let _ = storage.set_item(BEST_SCORE_KEY, &best.to_string());
+            }
+        },
+    )
+}
+
+/// Convert a game error into a `{code, message, details}` object that JS
+/// can branch on
+pub(crate) fn game_error(error: GameError) -> JsValue {
+    serde_wasm_bindgen::to_value(&error).unwrap_or_else(|_| JsValue::from_str(&error.to_string()))
+}
+
+/// Flatten a board for JS. Tiles go out as plain numbers rather than
+/// `BigInt`s; they stay exact up to 2^53.
+pub(crate) fn board_to_js(board: &Board) -> Vec<f64> {
+    board
+        .to_vec()
+        .into_iter()
+        .flatten()
+        .map(|value| value as f64)
+        .collect()
+}
+
 #[wasm_bindgen]
 pub fn init_panic_hook() {
     console_error_panic_hook::set_once();
@@ -0,0 +1,96 @@
+use crate::parse_direction;
+use rusty2048_core::{RaceMessage, RaceSession};
+use wasm_bindgen::prelude::*;
+
+/// Browser client for head-to-head races.
+///
+/// JS owns the WebSocket: pass a `send` callback that forwards outgoing
+/// JSON messages to the socket, and feed incoming socket messages to
+/// `handle_message`.
+#[wasm_bindgen]
+pub struct RaceClient {
+    session: RaceSession,
+    send: js_sys::Function,
+}
+
+#[wasm_bindgen]
+impl RaceClient {
+    #[wasm_bindgen(constructor)]
+    pub fn new(player_name: &str, send: js_sys::Function) -> Self {
+        Self {
+            session: RaceSession::new(player_name),
+            send,
+        }
+    }
+
+    /// Ask the server to join a race room
+    pub fn join(&mut self, room: &str) -> Result<(), JsValue> {
+        self.session.join(room);
+        self.flush()
+    }
+
+    /// Handle a JSON message received from the socket
+    pub fn handle_message(&mut self, json: &str) -> Result<(), JsValue> {
+        let message: RaceMessage = serde_json::from_str(json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid race message: {}", e)))?;
+        self.session
+            .handle_message(message)
+            .map_err(crate::game_error)?;
+        self.flush()
+    }
+
+    /// Make a move on the local board
+    pub fn make_move(&mut self, direction: &str) -> Result<bool, JsValue> {
+        let moved = self
+            .session
+            .make_move(parse_direction(direction)?)
+            .map_err(crate::game_error)?;
+        self.flush()?;
+        Ok(moved)
+    }
+
+    /// Get the local board as a flat array
+    pub fn get_board(&self) -> Vec<f64> {
+        self.session
+            .game()
+            .map(|game| crate::board_to_js(game.board()))
+            .unwrap_or_default()
+    }
+
+    /// Get the local score
+    pub fn get_score(&self) -> f64 {
+        self.session
+            .game()
+            .map(|game| game.score().current() as f64)
+            .unwrap_or(0.0)
+    }
+
+    /// Get the last known opponent state (or `null` before any update)
+    pub fn get_opponent(&self) -> Result<JsValue, JsValue> {
+        serde_wasm_bindgen::to_value(&self.session.opponent())
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Number of garbage tiles waiting to drop on the local board
+    pub fn pending_garbage(&self) -> u32 {
+        self.session.pending_garbage()
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.session.is_started()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.session.is_finished()
+    }
+
+    /// Send all queued messages through the JS callback
+    fn flush(&mut self) -> Result<(), JsValue> {
+        for message in self.session.drain_outbox() {
+            let json = serde_json::to_string(&message)
+                .map_err(|e| JsValue::from_str(&format!("Failed to encode message: {}", e)))?;
+            self.send.call1(&JsValue::NULL, &JsValue::from_str(&json))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+use crate::Rusty2048Web;
+use rusty2048_core::{Board, ReplayData, ReplayPlayer};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+/// Summary of an imported replay
+#[derive(Serialize)]
+struct ReplayInfo {
+    name: String,
+    player_name: Option<String>,
+    created_at: u64,
+    final_score: u64,
+    total_moves: usize,
+    current_move: usize,
+    progress: f32,
+}
+
+#[wasm_bindgen]
+impl Rusty2048Web {
+    /// Serialize the current game's replay as JSON
+    pub fn export_replay_json(&self, name: Option<String>) -> Result<String, JsValue> {
+        let mut replay = self.engine.current_replay();
+        if let Some(name) = name {
+            replay.metadata.name = name;
+        }
+        serde_json::to_string_pretty(&replay)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize replay: {}", e)))
+    }
+
+    /// Serialize the current game's replay into a downloadable JSON blob
+    pub fn export_replay(&self, name: Option<String>) -> Result<web_sys::Blob, JsValue> {
+        let json = self.export_replay_json(name)?;
+        let parts = js_sys::Array::of1(&JsValue::from_str(&json));
+        let options = web_sys::BlobPropertyBag::new();
+        options.set_type("application/json");
+        web_sys::Blob::new_with_str_sequence_and_options(&parts, &options)
+    }
+
+    /// Suggested file name for an exported replay, matching the CLI naming scheme
+    pub fn replay_file_name(&self) -> String {
+        format!(
+            "replay_{}.json",
+            self.engine.current_replay().metadata.created_at
+        )
+    }
+
+    /// Load a replay from the contents of a user-selected file
+    pub fn import_replay(&mut self, json: &str) -> Result<JsValue, JsValue> {
+        let replay_data = ReplayData::decode(json.as_bytes()).map_err(crate::game_error)?;
+        let player = ReplayPlayer::new(replay_data).map_err(crate::game_error)?;
+
+        self.replay_player = Some(player);
+        self.get_replay_info()
+    }
+
+    /// Get information about the loaded replay
+    pub fn get_replay_info(&self) -> Result<JsValue, JsValue> {
+        let player = self.loaded_replay()?;
+        let metadata = &player.replay_data().metadata;
+        let info = ReplayInfo {
+            name: metadata.name.clone(),
+            player_name: metadata.player_name.clone(),
+            created_at: metadata.created_at,
+            final_score: player.replay_data().final_score,
+            total_moves: player.total_moves(),
+            current_move: player.current_move_index(),
+            progress: player.progress(),
+        };
+        serde_wasm_bindgen::to_value(&info).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Step the loaded replay forward by one move
+    pub fn replay_next_move(&mut self) -> Result<bool, JsValue> {
+        self.loaded_replay_mut()?
+            .next_move()
+            .map_err(crate::game_error)
+    }
+
+    /// Step the loaded replay back by one move
+    pub fn replay_previous_move(&mut self) -> Result<bool, JsValue> {
+        self.loaded_replay_mut()?
+            .previous_move()
+            .map_err(crate::game_error)
+    }
+
+    /// Jump the loaded replay to a specific move
+    pub fn replay_go_to_move(&mut self, move_index: usize) -> Result<bool, JsValue> {
+        self.loaded_replay_mut()?
+            .go_to_move(move_index)
+            .map_err(crate::game_error)
+    }
+
+    /// Get the board of the loaded replay at its current position
+    pub fn get_replay_board(&self) -> Result<Vec<f64>, JsValue> {
+        Ok(crate::board_to_js(
+            self.loaded_replay()?.current_game().board(),
+        ))
+    }
+
+    /// Get the tile changes made by the move that led to the current replay
+    /// position, for animating playback. Empty at the start of the replay.
+    pub fn get_replay_changes(&self) -> Result<JsValue, JsValue> {
+        let player = self.loaded_replay()?;
+        let changes = match player.current_move_index().checked_sub(1) {
+            Some(index) => {
+                let before =
+                    Board::from_vec(player.replay_data().moves[index].board_before.clone())
+                        .map_err(crate::game_error)?;
+                before.diff(player.current_game().board())
+            }
+            None => Vec::new(),
+        };
+        serde_wasm_bindgen::to_value(&changes).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Get the score of the loaded replay at its current position
+    pub fn get_replay_score(&self) -> Result<f64, JsValue> {
+        Ok(self.loaded_replay()?.current_game().score().current() as f64)
+    }
+
+    /// Unload the current replay
+    pub fn close_replay(&mut self) {
+        self.replay_player = None;
+    }
+}
+
+impl Rusty2048Web {
+    fn loaded_replay(&self) -> Result<&ReplayPlayer, JsValue> {
+        self.replay_player
+            .as_ref()
+            .ok_or_else(|| JsValue::from_str("No replay loaded"))
+    }
+
+    fn loaded_replay_mut(&mut self) -> Result<&mut ReplayPlayer, JsValue> {
+        self.replay_player
+            .as_mut()
+            .ok_or_else(|| JsValue::from_str("No replay loaded"))
+    }
+}
@@ -0,0 +1,174 @@
+use crate::Rusty2048Web;
+use rusty2048_core::{RetentionPolicy, SessionFilter, StatsPeriod};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+/// Chart series in the `{ labels, data }` shape used by Chart.js and similar libraries
+#[derive(Serialize)]
+struct ChartSeries<T> {
+    labels: Vec<String>,
+    data: Vec<T>,
+}
+
+#[wasm_bindgen]
+impl Rusty2048Web {
+    /// Restore statistics previously exported with `export_statistics`
+    pub fn load_statistics(&mut self, json: &str) -> Result<(), JsValue> {
+        self.engine
+            .stats_mut()
+            .import_statistics(json)
+            .map_err(crate::game_error)
+    }
+
+    /// Export all recorded sessions as JSON (for localStorage persistence)
+    pub fn export_statistics(&self) -> Result<String, JsValue> {
+        self.engine
+            .stats()
+            .export_statistics()
+            .map_err(crate::game_error)
+    }
+
+    /// Clear all recorded sessions
+    pub fn clear_statistics(&mut self) -> Result<(), JsValue> {
+        self.engine
+            .stats_mut()
+            .clear_statistics()
+            .map_err(crate::game_error)
+    }
+
+    /// Keep only the last `max_sessions` games and/or the games of the last
+    /// `max_age_days` days; older games still count in the summary.
+    /// Returns how many sessions were compacted.
+    pub fn set_stats_retention(
+        &mut self,
+        max_sessions: Option<usize>,
+        max_age_days: Option<u32>,
+    ) -> Result<usize, JsValue> {
+        self.engine
+            .stats_mut()
+            .set_retention(RetentionPolicy {
+                max_sessions,
+                max_age_days: max_age_days.map(u64::from),
+            })
+            .map_err(crate::game_error)
+    }
+
+    /// Get the statistics summary as a plain object
+    pub fn get_statistics_summary(&self) -> Result<JsValue, JsValue> {
+        to_js_object(&self.engine.stats().get_summary())
+    }
+
+    /// Get the summary of the games matching a filter like
+    /// `{ board_size: 5, rules: "classic", seeded: false, player: "ai" }`;
+    /// omitted fields match any game
+    pub fn get_filtered_summary(&self, filter: JsValue) -> Result<JsValue, JsValue> {
+        to_js_object(
+            &self
+                .engine
+                .stats()
+                .get_summary_filtered(&parse_filter(filter)?),
+        )
+    }
+
+    /// Get the configurations that have recorded games as `[segment, count]` pairs
+    pub fn get_statistics_segments(&self) -> Result<JsValue, JsValue> {
+        to_js_object(&self.engine.stats().get_segments())
+    }
+
+    /// Get the score trend of the last `count` games as `{ labels, data }`
+    pub fn get_score_trend(&self, count: usize) -> Result<JsValue, JsValue> {
+        self.get_filtered_score_trend(JsValue::UNDEFINED, count)
+    }
+
+    /// Get the score trend of the last `count` games matching a filter
+    pub fn get_filtered_score_trend(
+        &self,
+        filter: JsValue,
+        count: usize,
+    ) -> Result<JsValue, JsValue> {
+        let trend = self
+            .engine
+            .stats()
+            .get_score_trend_filtered(&parse_filter(filter)?, count);
+        to_js_object(&ChartSeries {
+            labels: trend.iter().map(|(i, _)| format!("#{}", i + 1)).collect(),
+            data: trend.iter().map(|&(_, score)| score).collect(),
+        })
+    }
+
+    /// Get the efficiency trend of the last `count` games as `{ labels, data }`
+    pub fn get_efficiency_trend(&self, count: usize) -> Result<JsValue, JsValue> {
+        let trend = self.engine.stats().get_efficiency_trend(count);
+        to_js_object(&ChartSeries {
+            labels: trend.iter().map(|(i, _)| format!("#{}", i + 1)).collect(),
+            data: trend.iter().map(|&(_, efficiency)| efficiency).collect(),
+        })
+    }
+
+    /// Get the summary of the games that ended between two Unix timestamps
+    /// in seconds (`end` exclusive)
+    pub fn get_summary_for_range(&self, start: f64, end: f64) -> Result<JsValue, JsValue> {
+        to_js_object(
+            &self
+                .engine
+                .stats()
+                .get_summary_for_range(start as u64, end as u64),
+        )
+    }
+
+    /// Get the summary of the current "day", "week" or "month" (UTC)
+    pub fn get_current_period_summary(&self, period: &str) -> Result<JsValue, JsValue> {
+        let (start, end) =
+            parse_period(period)?.range_containing(rusty2048_core::get_current_time());
+        to_js_object(&self.engine.stats().get_summary_for_range(start, end))
+    }
+
+    /// Get the totals of every "day", "week" or "month" with games, oldest first
+    pub fn get_period_totals(&self, period: &str) -> Result<JsValue, JsValue> {
+        to_js_object(&self.engine.stats().get_totals(parse_period(period)?))
+    }
+
+    /// Get the games played on each of the last `count` days with games as `{ labels, data }`
+    pub fn get_games_per_day(&self, count: usize) -> Result<JsValue, JsValue> {
+        let totals = self.engine.stats().get_daily_totals();
+        let recent = &totals[totals.len().saturating_sub(count)..];
+        to_js_object(&ChartSeries {
+            labels: recent.iter().map(|day| day.date.clone()).collect(),
+            data: recent.iter().map(|day| day.games).collect(),
+        })
+    }
+
+    /// Get how often each max tile was reached as `{ labels, data }`
+    pub fn get_tile_achievements(&self) -> Result<JsValue, JsValue> {
+        let tiles = self.engine.stats().get_tile_achievements();
+        to_js_object(&ChartSeries {
+            labels: tiles.iter().map(|(tile, _)| tile.to_string()).collect(),
+            data: tiles.iter().map(|&(_, count)| count).collect(),
+        })
+    }
+}
+
+/// Parse a statistics period name coming from JS
+fn parse_period(period: &str) -> Result<StatsPeriod, JsValue> {
+    match period {
+        "day" => Ok(StatsPeriod::Day),
+        "week" => Ok(StatsPeriod::Week),
+        "month" => Ok(StatsPeriod::Month),
+        _ => Err(JsValue::from_str("Invalid statistics period")),
+    }
+}
+
+/// Parse a session filter; `undefined` and `null` match every game
+fn parse_filter(filter: JsValue) -> Result<SessionFilter, JsValue> {
+    if filter.is_undefined() || filter.is_null() {
+        return Ok(SessionFilter::default());
+    }
+    serde_wasm_bindgen::from_value(filter).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Serialize into plain JS objects (maps become objects rather than `Map`s)
+pub(crate) fn to_js_object<T: Serialize>(value: &T) -> Result<JsValue, JsValue> {
+    value
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map_err(|e| JsValue::from_str(&e.to_string()))
+}